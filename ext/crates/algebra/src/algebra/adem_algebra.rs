@@ -67,7 +67,9 @@ impl AdemBasisElement {
             .filter(|b| !matches!(b, PorBockstein::Bockstein(false)))
     }
 
-    fn excess(&self, p: ValidPrime) -> u32 {
+    /// The excess of the element. An admissible monomial of excess $e$ acts non-trivially on a
+    /// class of degree $n$ in an unstable module only if $e \leq n$.
+    pub fn excess(&self, p: ValidPrime) -> u32 {
         if self.degree < 2 {
             self.degree as u32 // special case these so that self.ps[0] is defined
         } else if p == 2 {
@@ -1383,10 +1385,11 @@ impl Bialgebra for AdemAlgebra {
             result.reverse();
             result
         } else {
+            // Sq^i need not have index 0 when the basis is sorted by excess
             elt.ps
                 .iter()
                 .rev()
-                .map(|i| (*i as i32, 0))
+                .map(|i| self.beps_pn(0, *i))
                 .collect::<Vec<_>>()
         }
     }
@@ -1409,9 +1412,13 @@ impl Bialgebra for AdemAlgebra {
                     .collect::<Vec<_>>()
             }
         } else {
-            assert_eq!(op_idx, 0);
-            (0..=op_deg)
-                .map(|j| (j, 0, op_deg - j, 0))
+            assert_eq!((op_deg, op_idx), self.beps_pn(0, op_deg as u32));
+            (0..=op_deg as u32)
+                .map(|j| {
+                    let first = self.beps_pn(0, j);
+                    let last = self.beps_pn(0, op_deg as u32 - j);
+                    (first.0, first.1, last.0, last.1)
+                })
                 .collect::<Vec<_>>()
         }
     }
@@ -1475,6 +1482,59 @@ mod tests {
         }
     }
 
+    #[rstest(p, max_degree, case(2, 16), case(3, 40))]
+    #[trace]
+    fn test_adem_bialgebra_unstable(p: u32, max_degree: i32) {
+        // The unstable basis is sorted by excess, so e.g. Sq^n need not have index 0. Compare
+        // against the stable basis, where the indices of the Steenrod powers are all 0.
+        let p = ValidPrime::new(p);
+        let stable = AdemAlgebra::new(p, false);
+        let unstable = AdemAlgebra::new(p, true);
+        stable.compute_basis(max_degree);
+        unstable.compute_basis(max_degree);
+
+        let name = |algebra: &AdemAlgebra, degree, idx| {
+            if degree == 0 {
+                String::from("1")
+            } else {
+                algebra.basis_element_to_string(degree, idx)
+            }
+        };
+        let to_stable = |degree, idx| {
+            stable
+                .basis_element_from_string(&unstable.basis_element_to_string(degree, idx))
+                .unwrap()
+        };
+
+        for degree in 1..=max_degree {
+            for idx in 0..unstable.dimension(degree) {
+                let (_, stable_idx) = to_stable(degree, idx);
+                let names = |algebra: &AdemAlgebra, idx| {
+                    algebra
+                        .decompose(degree, idx)
+                        .into_iter()
+                        .map(|(d, i)| name(algebra, d, i))
+                        .collect::<Vec<_>>()
+                };
+                assert_eq!(names(&unstable, idx), names(&stable, stable_idx));
+            }
+        }
+
+        let q = if p == 2 { 1 } else { 2 * p.as_i32() - 2 };
+        for n in 1..=max_degree / q {
+            let (degree, idx) = unstable.beps_pn(0, n as u32);
+            let (_, stable_idx) = to_stable(degree, idx);
+            let names = |algebra: &AdemAlgebra, idx| {
+                algebra
+                    .coproduct(degree, idx)
+                    .into_iter()
+                    .map(|(d1, i1, d2, i2)| (name(algebra, d1, i1), name(algebra, d2, i2)))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(names(&unstable, idx), names(&stable, stable_idx));
+        }
+    }
+
     #[test]
     fn try_beps_pn_adem() {
         let p = ValidPrime::new(2);
@@ -38,6 +38,15 @@ pub trait Algebra: std::fmt::Display + Send + Sync + 'static {
         0
     }
 
+    /// A description of the profile of the algebra if it is a proper sub-Hopf algebra, e.g. of the
+    /// Milnor algebra. The [`magic`](Algebra::magic) does not tell different profiles apart, so
+    /// this is recorded in the manifests of save directories as well.
+    ///
+    /// This defaults to `None`.
+    fn profile_description(&self) -> Option<String> {
+        None
+    }
+
     /// Returns the prime the algebra is over.
     fn prime(&self) -> ValidPrime;
 
@@ -54,6 +63,15 @@ pub trait Algebra: std::fmt::Display + Send + Sync + 'static {
     /// Returns the dimension of the algebra in degree `degree`.
     fn dimension(&self, degree: i32) -> usize;
 
+    /// Returns the weight of a basis element.
+    ///
+    /// This is only meaningful for algebras with an additional grading, such as the motivic
+    /// Steenrod algebras, and defaults to 0.
+    #[allow(unused_variables)]
+    fn basis_element_weight(&self, degree: i32, idx: usize) -> i32 {
+        0
+    }
+
     /// Computes the product `r * s` of two basis elements, and adds the
     /// result to `result`.
     ///
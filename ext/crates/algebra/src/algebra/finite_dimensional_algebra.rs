@@ -0,0 +1,956 @@
+//! Connected finite dimensional graded algebras, specified by a multiplication table or by
+//! generators and relations.
+//!
+//! The algebra is specified by a json object in one of two formats. The first lists a basis and
+//! the non-zero products of basis elements,
+//! ```json
+//! {
+//!     "name": "F_2[Z/4]",
+//!     "basis": {"x": 1, "x2": 2, "x3": 3},
+//!     "products": ["x * x = x2", "x * x2 = x3", "x2 * x = x3"]
+//! }
+//! ```
+//! where the unit `1` in degree 0 is implicit. The second lists generators and the relations
+//! between them,
+//! ```json
+//! {
+//!     "name": "E(1)",
+//!     "generators": {"Q0": 1, "Q1": 3},
+//!     "relations": ["Q0^2", "Q1^2", "Q0 Q1 + Q1 Q0"]
+//! }
+//! ```
+//! In the second case, the basis elements are named by monomials in the generators, such as
+//! `Q0 Q1`. Products and relations are written as sums of terms, where each term is an optional
+//! integer coefficient followed by a product of basis elements separated by spaces or `*`. A
+//! relation may also be written as an equation `lhs = rhs`.
+//!
+//! In either case, we compute a minimal set of generators and a complete set of relations, so
+//! that modules can be specified by the actions of the generators as usual.
+
+use anyhow::{Context, anyhow};
+use fp::{
+    matrix::{Matrix, Subspace},
+    prime::{Prime, ValidPrime},
+    vector::{FpSlice, FpSliceMut, FpVector},
+};
+use rustc_hash::FxHashMap as HashMap;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::algebra::{Algebra, GeneratedAlgebra};
+
+/// A sum of products of pairs of basis elements, in the format of
+/// [`GeneratedAlgebra::generating_relations`].
+type Relation = Vec<(u32, (i32, usize), (i32, usize))>;
+
+/// A linear combination of products of basis elements, as parsed from a string.
+type Expression = Vec<(u32, Vec<(i32, usize)>)>;
+
+/// If the degrees of the generators of an algebra specified by a presentation exceed this, we
+/// assume that the algebra is infinite dimensional.
+const DEFAULT_MAX_DEGREE: i32 = 256;
+
+/// A connected finite dimensional graded algebra over $\mathbb{F}_p$. See the
+/// [module-level documentation](self) for how to specify one.
+pub struct FiniteDimensionalAlgebra {
+    p: ValidPrime,
+    name: String,
+    /// The names of the basis elements in each degree. Degree 0 is spanned by the unit.
+    names: Vec<Vec<String>>,
+    name_to_idx: HashMap<String, (i32, usize)>,
+    /// `products[d1][d2][i1][i2]` is the product of the `i1`th basis element in degree `d1` and
+    /// the `i2`th basis element in degree `d2`. This is only populated when `d1 + d2` is in range.
+    products: Vec<Vec<Vec<Vec<FpVector>>>>,
+    generators: Vec<Vec<usize>>,
+    /// The decomposition of each basis element that is not a generator.
+    decompositions: Vec<Vec<Relation>>,
+    relations: Vec<Vec<Relation>>,
+    magic: u32,
+}
+
+impl std::fmt::Display for FiniteDimensionalAlgebra {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "finite dimensional algebra")
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+impl FiniteDimensionalAlgebra {
+    /// Whether the module specified by `json` is defined over its own finite dimensional algebra,
+    /// rather than over the Steenrod algebra.
+    pub fn is_defined_by_module(json: &Value) -> bool {
+        json["algebra"].is_object()
+    }
+
+    /// The algebra defined by the `algebra` field of the module specified by `json`, at the prime
+    /// given by its `p` field. Profiles are not supported.
+    pub fn from_module_json(json: &Value) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            Self::is_defined_by_module(json),
+            "The module does not define a finite dimensional algebra"
+        );
+        anyhow::ensure!(
+            json["profile"].is_null(),
+            "Profiles are not supported for finite dimensional algebras"
+        );
+        let p = ValidPrime::deserialize(&json["p"]).context("Invalid prime")?;
+        Self::from_json(p, &json["algebra"])
+    }
+
+    /// Parse an algebra in either of the formats in the [module-level documentation](self).
+    pub fn from_json(p: ValidPrime, json: &Value) -> anyhow::Result<Self> {
+        let name = json["name"].as_str().unwrap_or("").to_string();
+        if json.get("generators").is_some() {
+            Self::from_presentation(p, name, json)
+        } else if json.get("basis").is_some() {
+            Self::from_table(p, name, json)
+        } else {
+            Err(anyhow!(
+                "A finite dimensional algebra must specify either a basis or generators"
+            ))
+        }
+    }
+
+    fn from_table(p: ValidPrime, name: String, json: &Value) -> anyhow::Result<Self> {
+        let basis = json["basis"]
+            .as_object()
+            .ok_or_else(|| anyhow!("basis must be an object"))?;
+        let mut names = vec![vec!["1".to_string()]];
+        for (basis_name, degree) in basis {
+            let degree = degree
+                .as_i64()
+                .ok_or_else(|| anyhow!("Invalid degree of {basis_name}"))?
+                as i32;
+            anyhow::ensure!(
+                degree > 0,
+                "Basis element {basis_name} must have positive degree"
+            );
+            anyhow::ensure!(
+                !basis_name.contains([' ', '*', '^']),
+                "Invalid basis element name: {basis_name}"
+            );
+            if names.len() <= degree as usize {
+                names.resize(degree as usize + 1, Vec::new());
+            }
+            names[degree as usize].push(basis_name.clone());
+        }
+
+        let mut products = zero_products(p, &names);
+        let name_to_idx = name_to_idx(&names);
+        let lookup = |x: &str| name_to_idx.get(x).copied();
+        let entries = json["products"].as_array().map_or(&[][..], Vec::as_slice);
+        for entry in entries {
+            let entry = entry
+                .as_str()
+                .ok_or_else(|| anyhow!("Products must be strings"))?;
+            let (lhs, rhs) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid product: {entry}"))?;
+            let factors = parse_word(lhs.trim(), &lookup)?;
+            let &[(d1, i1), (d2, i2)] = factors.as_slice() else {
+                return Err(anyhow!("Invalid product: {entry}"));
+            };
+            let degree = d1 + d2;
+            anyhow::ensure!(
+                d1 > 0 && d2 > 0,
+                "Products with the unit are implicit: {entry}"
+            );
+            let target = products
+                .get_mut(d1 as usize)
+                .and_then(|x| x.get_mut(d2 as usize))
+                .ok_or_else(|| anyhow!("Product out of range: {entry}"))?;
+            for (c, word) in parse_expression(p, rhs.trim(), &lookup)? {
+                let &[(d, i)] = word.as_slice() else {
+                    return Err(anyhow!("Invalid product: {entry}"));
+                };
+                anyhow::ensure!(d == degree, "Inhomogeneous product: {entry}");
+                target[i1][i2].add_basis_element(i, c);
+            }
+        }
+
+        Self::new(p, name, names, products).with_context(|| "Invalid multiplication table")
+    }
+
+    fn from_presentation(p: ValidPrime, name: String, json: &Value) -> anyhow::Result<Self> {
+        let generators = json["generators"]
+            .as_object()
+            .ok_or_else(|| anyhow!("generators must be an object"))?;
+        let mut gen_names = Vec::new();
+        let mut gen_degrees = Vec::new();
+        for (gen_name, degree) in generators {
+            let degree = degree
+                .as_i64()
+                .ok_or_else(|| anyhow!("Invalid degree of {gen_name}"))?
+                as i32;
+            anyhow::ensure!(degree > 0, "Generator {gen_name} must have positive degree");
+            anyhow::ensure!(
+                !gen_name.contains([' ', '*', '^']) && gen_name != "1",
+                "Invalid generator name: {gen_name}"
+            );
+            gen_names.push(gen_name.clone());
+            gen_degrees.push(degree);
+        }
+        let max_degree = json["max_degree"]
+            .as_i64()
+            .map_or(DEFAULT_MAX_DEGREE, |x| x as i32);
+
+        // Relations are words in the generators, which we record as pairs (degree, index).
+        let lookup = |x: &str| {
+            gen_names
+                .iter()
+                .position(|g| g == x)
+                .map(|i| (gen_degrees[i], i))
+        };
+        let mut relations: Vec<(i32, Expression)> = Vec::new();
+        let entries = json["relations"].as_array().map_or(&[][..], Vec::as_slice);
+        for entry in entries {
+            let entry = entry
+                .as_str()
+                .ok_or_else(|| anyhow!("Relations must be strings"))?;
+            let mut relation = match entry.split_once('=') {
+                Some((lhs, rhs)) => {
+                    let mut lhs = parse_expression(p, lhs.trim(), &lookup)?;
+                    let rhs = parse_expression(p, rhs.trim(), &lookup)?;
+                    lhs.extend(
+                        rhs.into_iter()
+                            .map(|(c, w)| (p.as_u32() - c % p.as_u32(), w)),
+                    );
+                    lhs
+                }
+                None => parse_expression(p, entry, &lookup)?,
+            };
+            relation.retain(|(c, _)| c % p.as_u32() != 0);
+            let Some((_, word)) = relation.first() else {
+                continue;
+            };
+            let degree: i32 = word.iter().map(|(d, _)| d).sum();
+            anyhow::ensure!(
+                relation
+                    .iter()
+                    .all(|(_, w)| w.iter().map(|(d, _)| d).sum::<i32>() == degree),
+                "Inhomogeneous relation: {entry}"
+            );
+            anyhow::ensure!(degree > 0, "Invalid relation: {entry}");
+            relations.push((degree, relation));
+        }
+
+        let mut builder = PresentationBuilder {
+            p,
+            gen_degrees: &gen_degrees,
+            pairs: vec![vec![(usize::MAX, 0)]],
+            left: vec![Vec::new()],
+        };
+        let max_gen_degree = gen_degrees.iter().copied().max().unwrap_or(0);
+        let mut last_nonzero = 0;
+        let mut degree = 1;
+        while degree - last_nonzero <= max_gen_degree {
+            anyhow::ensure!(
+                degree <= max_degree,
+                "The algebra is not zero above degree {max_degree}"
+            );
+            if builder.add_degree(degree, &relations) > 0 {
+                last_nonzero = degree;
+            }
+            degree += 1;
+        }
+
+        let names: Vec<Vec<String>> = (0..=last_nonzero)
+            .map(|d| {
+                (0..builder.pairs[d as usize].len())
+                    .map(|i| builder.name(&gen_names, d, i))
+                    .collect()
+            })
+            .collect();
+        let mut products = zero_products(p, &names);
+        for d1 in 1..names.len() {
+            for d2 in 1..names.len() - d1 {
+                for i1 in 0..names[d1].len() {
+                    // The basis element is g * a, so its product with b is g * (a * b)
+                    let (g, j) = builder.pairs[d1][i1];
+                    let rest = d1 - gen_degrees[g] as usize;
+                    products[d1][d2][i1] = (0..names[d2].len())
+                        .map(|i2| {
+                            let ab = if rest == 0 {
+                                let mut v = FpVector::new(p, names[d2].len());
+                                v.set_entry(i2, 1);
+                                v
+                            } else {
+                                products[rest][d2][j][i2].clone()
+                            };
+                            builder.apply_left(g, (rest + d2) as i32, ab.as_slice())
+                        })
+                        .collect();
+                }
+            }
+        }
+        let mut result = Self::new(p, name, names, products)?;
+
+        // Generators that were renamed to monomials in the others can still be referred to by name
+        for (g, (gen_name, &degree)) in gen_names.iter().zip(&gen_degrees).enumerate() {
+            let Some(value) = builder.left.get(degree as usize) else {
+                continue;
+            };
+            let mut nonzero = value[g][0].iter_nonzero();
+            if let (Some((i, 1)), None) = (nonzero.next(), nonzero.next()) {
+                result
+                    .name_to_idx
+                    .entry(gen_name.clone())
+                    .or_insert((degree, i));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Construct the algebra from its multiplication table, computing the generators,
+    /// decompositions and relations. The products with the unit are filled in automatically.
+    fn new(
+        p: ValidPrime,
+        name: String,
+        names: Vec<Vec<String>>,
+        mut products: Vec<Vec<Vec<Vec<FpVector>>>>,
+    ) -> anyhow::Result<Self> {
+        let top = names.len() - 1;
+        for unit_times in &mut products[0] {
+            for (i, v) in unit_times[0].iter_mut().enumerate() {
+                v.set_entry(i, 1);
+            }
+        }
+        for row in &mut products {
+            for (i, times_unit) in row[0].iter_mut().enumerate() {
+                times_unit[0].set_entry(i, 1);
+            }
+        }
+
+        let mut result = Self {
+            p,
+            name,
+            name_to_idx: name_to_idx(&names),
+            names,
+            products,
+            generators: Vec::new(),
+            decompositions: Vec::new(),
+            relations: Vec::new(),
+            magic: 0,
+        };
+        result.check_associativity()?;
+
+        result.generators.push(Vec::new());
+        result.decompositions.push(vec![Vec::new()]);
+        result.relations.push(Vec::new());
+        for d in 1..=top {
+            result.compute_presentation(d as i32);
+        }
+        // Products that vanish because they land above the top degree give relations too
+        let max_gen_degree = (1..=top)
+            .rev()
+            .find(|&d| !result.generators[d].is_empty())
+            .unwrap_or(0);
+        for d in top + 1..=top + max_gen_degree {
+            result.compute_presentation(d as i32);
+        }
+        result.magic = result.compute_magic();
+        Ok(result)
+    }
+
+    fn check_associativity(&self) -> anyhow::Result<()> {
+        let top = self.names.len() - 1;
+        for d1 in 1..=top {
+            for d2 in 1..=top - d1 {
+                for d3 in 1..=top - d1 - d2 {
+                    for i1 in 0..self.names[d1].len() {
+                        for i2 in 0..self.names[d2].len() {
+                            for i3 in 0..self.names[d3].len() {
+                                let (a, b, c) = ((d1 as i32, i1), (d2 as i32, i2), (d3 as i32, i3));
+                                let left = self.multiply_triple(a, b, c, true);
+                                let right = self.multiply_triple(a, b, c, false);
+                                anyhow::ensure!(
+                                    left == right,
+                                    "Multiplication is not associative: ({} {}) {} != {} ({} {})",
+                                    self.names[d1][i1],
+                                    self.names[d2][i2],
+                                    self.names[d3][i3],
+                                    self.names[d1][i1],
+                                    self.names[d2][i2],
+                                    self.names[d3][i3],
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn multiply_triple(
+        &self,
+        (d1, i1): (i32, usize),
+        (d2, i2): (i32, usize),
+        (d3, i3): (i32, usize),
+        left_first: bool,
+    ) -> FpVector {
+        let mut result = FpVector::new(self.p, self.dimension(d1 + d2 + d3));
+        if left_first {
+            let ab = &self.products[d1 as usize][d2 as usize][i1][i2];
+            for (i, c) in ab.iter_nonzero() {
+                self.multiply_basis_elements(result.as_slice_mut(), c, d1 + d2, i, d3, i3);
+            }
+        } else {
+            let bc = &self.products[d2 as usize][d3 as usize][i2][i3];
+            for (i, c) in bc.iter_nonzero() {
+                self.multiply_basis_elements(result.as_slice_mut(), c, d1, i1, d2 + d3, i);
+            }
+        }
+        result
+    }
+
+    /// Compute the generators, decompositions and relations in degree `degree`, assuming they have
+    /// been computed in lower degrees.
+    fn compute_presentation(&mut self, degree: i32) {
+        let p = self.p;
+        let dim = self.dimension(degree);
+
+        // The products (g, a) of a generator with a basis element of positive degree
+        let mut pairs = Vec::new();
+        for d in 1..degree {
+            for &g in &self.generators[d as usize] {
+                for a in 0..self.dimension(degree - d) {
+                    pairs.push(((d, g), (degree - d, a)));
+                }
+            }
+        }
+        let image = |&((d1, i1), (d2, i2)): &((i32, usize), (i32, usize))| {
+            let mut result = FpVector::new(p, dim);
+            self.multiply_basis_elements(result.as_slice_mut(), 1, d1, i1, d2, i2);
+            result
+        };
+
+        let mut decomposables = Subspace::new(p, dim);
+        for pair in &pairs {
+            decomposables.add_vector(image(pair).as_slice());
+        }
+        let mut generators = Vec::new();
+        for i in 0..dim {
+            let mut v = FpVector::new(p, dim);
+            v.set_entry(i, 1);
+            if !decomposables.contains(v.as_slice()) {
+                decomposables.add_vector(v.as_slice());
+                generators.push(i);
+            }
+        }
+
+        // Express each basis element in terms of the generators and the products above
+        let rows: Vec<Vec<u32>> = generators
+            .iter()
+            .map(|&g| (0..dim).map(|i| u32::from(i == g)).collect())
+            .chain(pairs.iter().map(|pair| image(pair).iter().collect()))
+            .collect();
+        let quasi_inverse = (dim > 0).then(|| {
+            let mut matrix = Matrix::augmented_from_vec(p, &rows);
+            matrix.row_reduce();
+            matrix.compute_quasi_inverse()
+        });
+
+        let decompositions: Vec<Relation> = (0..dim)
+            .map(|i| {
+                if generators.contains(&i) {
+                    return vec![(1, (degree, i), (0, 0))];
+                }
+                let mut input = FpVector::new(p, dim);
+                input.set_entry(i, 1);
+                let mut preimage = FpVector::new(p, rows.len());
+                quasi_inverse
+                    .as_ref()
+                    .unwrap()
+                    .apply(preimage.as_slice_mut(), 1, input.as_slice());
+                preimage
+                    .iter_nonzero()
+                    .map(|(row, c)| match row.checked_sub(generators.len()) {
+                        None => (c, (degree, generators[row]), (0, 0)),
+                        Some(k) => (c, pairs[k].0, pairs[k].1),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Each product (g, a) must equal the decomposition of the result
+        let mut relations = Vec::new();
+        for pair in &pairs {
+            let mut terms: HashMap<((i32, usize), (i32, usize)), u32> = HashMap::default();
+            terms.insert(*pair, 1);
+            for (b, c) in image(pair).iter_nonzero() {
+                for &(d, x, y) in &decompositions[b] {
+                    let entry = terms.entry((x, y)).or_insert(0);
+                    *entry = (*entry + p.as_u32() - c * d % p.as_u32()) % p.as_u32();
+                }
+            }
+            let mut relation: Relation = terms
+                .into_iter()
+                .filter(|&(_, c)| c != 0)
+                .map(|((x, y), c)| (c, x, y))
+                .collect();
+            if !relation.is_empty() {
+                relation.sort_unstable();
+                relations.push(relation);
+            }
+        }
+
+        self.generators.push(generators);
+        self.decompositions.push(decompositions);
+        self.relations.push(relations);
+    }
+
+    fn compute_magic(&self) -> u32 {
+        // FNV-1a hash of the multiplication table
+        let mut hash: u32 = 0x811c9dc5;
+        let mut update = |x: u32| {
+            for byte in x.to_le_bytes() {
+                hash = (hash ^ byte as u32).wrapping_mul(0x01000193);
+            }
+        };
+        for (d1, row) in self.products.iter().enumerate() {
+            for (d2, entries) in row.iter().enumerate() {
+                update((d1 << 16 | d2) as u32);
+                for v in entries.iter().flatten() {
+                    v.iter().for_each(&mut update);
+                }
+            }
+        }
+        (self.p.as_u32() << 16) | 0x2000 | (hash & 0x1fff)
+    }
+
+    /// Evaluate a product of basis elements.
+    fn multiply_word(&self, word: &[(i32, usize)]) -> (i32, FpVector) {
+        let mut degree = 0;
+        let mut result = FpVector::new(self.p, 1);
+        result.set_entry(0, 1);
+        for &(d, i) in word {
+            let mut next = FpVector::new(self.p, self.dimension(degree + d));
+            for (j, c) in result.iter_nonzero() {
+                self.multiply_basis_elements(next.as_slice_mut(), c, degree, j, d, i);
+            }
+            degree += d;
+            result = next;
+        }
+        (degree, result)
+    }
+}
+
+/// The builder for an algebra specified by generators and relations. The basis in each degree
+/// consists of products $g \cdot a$ of a generator and a basis element of lower degree.
+struct PresentationBuilder<'a> {
+    p: ValidPrime,
+    gen_degrees: &'a [i32],
+    /// The basis element in each degree, as the generator `g` and the index of `a`. The unit is
+    /// represented by `usize::MAX`.
+    pairs: Vec<Vec<(usize, usize)>>,
+    /// `left[d][g][a]` is the product $g \cdot a$ in degree `d`, or empty if the degree of `g` is
+    /// larger than `d`.
+    left: Vec<Vec<Vec<FpVector>>>,
+}
+
+impl PresentationBuilder<'_> {
+    fn dimension(&self, degree: i32) -> usize {
+        self.pairs[degree as usize].len()
+    }
+
+    /// Left multiply an element of degree `degree - |g|` by the generator `g`.
+    fn apply_left(&self, g: usize, degree: i32, input: FpSlice) -> FpVector {
+        let degree = degree + self.gen_degrees[g];
+        let mut result = FpVector::new(self.p, self.pairs.get(degree as usize).map_or(0, Vec::len));
+        if result.is_empty() {
+            return result;
+        }
+        for (a, c) in input.iter_nonzero() {
+            result.add(&self.left[degree as usize][g][a], c);
+        }
+        result
+    }
+
+    /// Compute the algebra in degree `degree`, returning its dimension.
+    fn add_degree(&mut self, degree: i32, relations: &[(i32, Expression)]) -> usize {
+        let p = self.p;
+        // The products g * a, indexed by `offsets[g] + a`. The later generators come first, so
+        // that they are eliminated in favour of the earlier ones when choosing a basis.
+        let mut offsets = vec![0; self.gen_degrees.len()];
+        let mut num_pairs = 0;
+        for (g, &d) in self.gen_degrees.iter().enumerate().rev() {
+            offsets[g] = num_pairs;
+            if d <= degree {
+                num_pairs += self.dimension(degree - d);
+            }
+        }
+
+        // A relation r gives the relations r * a for every basis element a
+        let mut rows = Vec::new();
+        for (d, relation) in relations {
+            if *d > degree {
+                continue;
+            }
+            for a in 0..self.dimension(degree - d) {
+                let mut row = FpVector::new(p, num_pairs);
+                for (c, word) in relation {
+                    let (&(_, g), rest) = word.split_first().unwrap();
+                    let mut value = FpVector::new(p, self.dimension(degree - d));
+                    value.set_entry(a, 1);
+                    let mut value_degree = degree - d;
+                    for &(_, h) in rest.iter().rev() {
+                        value = self.apply_left(h, value_degree, value.as_slice());
+                        value_degree += self.gen_degrees[h];
+                    }
+                    for (i, v) in value.iter_nonzero() {
+                        row.add_basis_element(offsets[g] + i, c * v % p.as_u32());
+                    }
+                }
+                rows.push(row);
+            }
+        }
+        let mut matrix = Matrix::from_rows(p, rows, num_pairs);
+        matrix.row_reduce();
+
+        // The basis consists of the non-pivot columns
+        let pivots = matrix.pivots();
+        let mut new_index = vec![None; num_pairs];
+        let mut pairs = Vec::new();
+        for (g, &d) in self.gen_degrees.iter().enumerate() {
+            if d > degree {
+                continue;
+            }
+            for a in 0..self.dimension(degree - d) {
+                let col = offsets[g] + a;
+                if pivots.get(col).is_none_or(|&r| r < 0) {
+                    new_index[col] = Some(pairs.len());
+                    pairs.push((g, a));
+                }
+            }
+        }
+
+        let mut left = Vec::with_capacity(self.gen_degrees.len());
+        for (g, &d) in self.gen_degrees.iter().enumerate() {
+            if d > degree {
+                left.push(Vec::new());
+                continue;
+            }
+            let products = (0..self.dimension(degree - d))
+                .map(|a| {
+                    let col = offsets[g] + a;
+                    let mut v = FpVector::new(p, pairs.len());
+                    match new_index[col] {
+                        Some(i) => v.set_entry(i, 1),
+                        None => {
+                            let row = matrix.row(pivots[col] as usize);
+                            for (c, x) in row.iter_nonzero() {
+                                if let Some(i) = new_index[c] {
+                                    v.add_basis_element(i, p.as_u32() - x);
+                                }
+                            }
+                        }
+                    }
+                    v
+                })
+                .collect();
+            left.push(products);
+        }
+
+        let dim = pairs.len();
+        self.pairs.push(pairs);
+        self.left.push(left);
+        dim
+    }
+
+    /// The name of a basis element as a monomial in the generators.
+    fn name(&self, gen_names: &[String], degree: i32, idx: usize) -> String {
+        let mut word: Vec<usize> = Vec::new();
+        let (mut degree, mut idx) = (degree, idx);
+        while degree > 0 {
+            let (g, a) = self.pairs[degree as usize][idx];
+            word.push(g);
+            degree -= self.gen_degrees[g];
+            idx = a;
+        }
+        if word.is_empty() {
+            return "1".to_string();
+        }
+        let mut result = Vec::new();
+        for chunk in word.chunk_by(|a, b| a == b) {
+            match chunk.len() {
+                1 => result.push(gen_names[chunk[0]].clone()),
+                n => result.push(format!("{}^{n}", gen_names[chunk[0]])),
+            }
+        }
+        result.join(" ")
+    }
+}
+
+fn zero_products(p: ValidPrime, names: &[Vec<String>]) -> Vec<Vec<Vec<Vec<FpVector>>>> {
+    let top = names.len() - 1;
+    (0..=top)
+        .map(|d1| {
+            (0..=top - d1)
+                .map(|d2| {
+                    vec![
+                        vec![FpVector::new(p, names[d1 + d2].len()); names[d2].len()];
+                        names[d1].len()
+                    ]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn name_to_idx(names: &[Vec<String>]) -> HashMap<String, (i32, usize)> {
+    names
+        .iter()
+        .enumerate()
+        .flat_map(|(d, names)| {
+            names
+                .iter()
+                .enumerate()
+                .map(move |(i, name)| (name.clone(), (d as i32, i)))
+        })
+        .collect()
+}
+
+/// Parse a product of basis elements separated by spaces or `*`, where each factor may be raised to
+/// a power.
+fn parse_word(
+    s: &str,
+    lookup: &impl Fn(&str) -> Option<(i32, usize)>,
+) -> anyhow::Result<Vec<(i32, usize)>> {
+    let mut result = Vec::new();
+    for factor in s.split([' ', '*']).filter(|x| !x.is_empty()) {
+        let (name, power) = match factor.split_once('^') {
+            Some((name, power)) => (name, power.parse::<usize>()?),
+            None => (factor, 1),
+        };
+        if name == "1" {
+            continue;
+        }
+        let x = lookup(name).ok_or_else(|| anyhow!("Unknown basis element: {name}"))?;
+        result.extend(std::iter::repeat_n(x, power));
+    }
+    Ok(result)
+}
+
+/// Parse a sum of terms, each of which is an optional integer coefficient followed by a product
+/// of basis elements. The coefficients are reduced mod `p`.
+fn parse_expression(
+    p: ValidPrime,
+    s: &str,
+    lookup: &impl Fn(&str) -> Option<(i32, usize)>,
+) -> anyhow::Result<Expression> {
+    if s == "0" {
+        return Ok(Vec::new());
+    }
+    let mut result = Vec::new();
+    for term in s.split(" + ") {
+        let term = term.trim();
+        let (coef, word) = match term.split_once(' ') {
+            Some((coef, word)) if coef.parse::<i64>().is_ok() => (coef.parse::<i64>()?, word),
+            _ => (1, term),
+        };
+        let coef = coef.rem_euclid(p.as_i32() as i64) as u32;
+        if coef != 0 {
+            result.push((coef, parse_word(word, lookup)?));
+        }
+    }
+    Ok(result)
+}
+
+impl Algebra for FiniteDimensionalAlgebra {
+    fn prefix(&self) -> &str {
+        "fd"
+    }
+
+    fn magic(&self) -> u32 {
+        self.magic
+    }
+
+    fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
+    fn compute_basis(&self, _degree: i32) {}
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < 0 {
+            return 0;
+        }
+        self.names.get(degree as usize).map_or(0, Vec::len)
+    }
+
+    fn multiply_basis_elements(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r_idx: usize,
+        s_degree: i32,
+        s_idx: usize,
+    ) {
+        if let Some(products) = self
+            .products
+            .get(r_degree as usize)
+            .and_then(|x| x.get(s_degree as usize))
+        {
+            result.add(products[r_idx][s_idx].as_slice(), coeff);
+        }
+    }
+
+    fn default_filtration_one_products(&self) -> Vec<(String, i32, usize)> {
+        self.generators
+            .iter()
+            .enumerate()
+            .flat_map(|(d, gens)| {
+                gens.iter()
+                    .map(move |&i| (self.names[d][i].clone(), d as i32, i))
+            })
+            .collect()
+    }
+
+    fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        self.names[degree as usize][idx].clone()
+    }
+
+    fn basis_element_from_string(&self, elt: &str) -> Option<(i32, usize)> {
+        if let Some(&x) = self.name_to_idx.get(elt.trim()) {
+            return Some(x);
+        }
+        let word = parse_word(elt, &|x| self.name_to_idx.get(x).copied()).ok()?;
+        let (degree, value) = self.multiply_word(&word);
+        let mut nonzero = value.iter_nonzero();
+        match (nonzero.next(), nonzero.next()) {
+            (Some((i, 1)), None) => Some((degree, i)),
+            _ => None,
+        }
+    }
+}
+
+impl GeneratedAlgebra for FiniteDimensionalAlgebra {
+    fn generators(&self, degree: i32) -> Vec<usize> {
+        if degree < 0 {
+            return vec![];
+        }
+        self.generators
+            .get(degree as usize)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn decompose_basis_element(
+        &self,
+        degree: i32,
+        idx: usize,
+    ) -> Vec<(u32, (i32, usize), (i32, usize))> {
+        self.decompositions[degree as usize][idx].clone()
+    }
+
+    fn generating_relations(&self, degree: i32) -> Vec<Vec<(u32, (i32, usize), (i32, usize))>> {
+        if degree < 0 {
+            return vec![];
+        }
+        self.relations
+            .get(degree as usize)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use itertools::Itertools;
+    use serde_json::json;
+
+    use super::*;
+
+    fn summary(algebra: &FiniteDimensionalAlgebra) -> String {
+        let mut lines = Vec::new();
+        for d in 0..algebra.names.len() as i32 {
+            let basis = (0..algebra.dimension(d))
+                .map(|i| algebra.basis_element_to_string(d, i))
+                .join(", ");
+            let gens = algebra
+                .generators(d)
+                .into_iter()
+                .map(|i| algebra.basis_element_to_string(d, i))
+                .join(", ");
+            lines.push(format!("{d}: [{basis}] generators [{gens}]"));
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_presentation() {
+        let exterior = json!({
+            "generators": {"Q0": 1, "Q1": 3},
+            "relations": ["Q0^2", "Q1^2", "Q0 Q1 + Q1 Q0"]
+        });
+        let algebra = FiniteDimensionalAlgebra::from_json(ValidPrime::new(3), &exterior).unwrap();
+        expect![[r#"
+            0: [1] generators []
+            1: [Q0] generators [Q0]
+            2: [] generators []
+            3: [Q1] generators [Q1]
+            4: [Q0 Q1] generators []"#]]
+        .assert_eq(&summary(&algebra));
+
+        let truncated = json!({
+            "generators": {"x": 1, "y": 2},
+            "relations": ["x^2 = y", "y^2"]
+        });
+        let algebra = FiniteDimensionalAlgebra::from_json(ValidPrime::new(2), &truncated).unwrap();
+        expect![[r#"
+            0: [1] generators []
+            1: [x] generators [x]
+            2: [x^2] generators []
+            3: [x^3] generators []"#]]
+        .assert_eq(&summary(&algebra));
+        assert_eq!(algebra.basis_element_from_string("y"), Some((2, 0)));
+        assert_eq!(algebra.basis_element_from_string("x y"), Some((3, 0)));
+    }
+
+    #[test]
+    fn test_table() {
+        let json = json!({
+            "basis": {"x": 1, "x2": 2, "x3": 3},
+            "products": ["x * x = x2", "x * x2 = x3", "x2 * x = x3"]
+        });
+        let algebra = FiniteDimensionalAlgebra::from_json(ValidPrime::new(2), &json).unwrap();
+        expect![[r#"
+            0: [1] generators []
+            1: [x] generators [x]
+            2: [x2] generators []
+            3: [x3] generators []"#]]
+        .assert_eq(&summary(&algebra));
+        assert_eq!(algebra.basis_element_from_string("x x x"), Some((3, 0)));
+        assert!(algebra.generating_relations(2).is_empty());
+        assert_eq!(
+            algebra.generating_relations(4),
+            vec![vec![(1, (1, 0), (3, 0))]]
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        let non_associative = json!({
+            "basis": {"x": 1, "y": 2, "z": 3},
+            "products": ["x * x = y", "y * x = z"]
+        });
+        let infinite = json!({
+            "generators": {"x": 2},
+            "relations": [],
+            "max_degree": 20,
+        });
+        let inhomogeneous = json!({
+            "generators": {"x": 1, "y": 2},
+            "relations": ["x + y"],
+        });
+        for json in [non_associative, infinite, inhomogeneous] {
+            assert!(FiniteDimensionalAlgebra::from_json(ValidPrime::new(2), &json).is_err());
+        }
+    }
+}
@@ -78,7 +78,7 @@ impl MilnorProfile {
         if !self.truncated {
             return false;
         }
-        if self.p_part.len() != self.p_part[0] as usize {
+        if self.p_part.len() != self.p_part.first().map_or(0, |&x| x as usize) {
             return false;
         }
         if generic && self.q_part != (1 << (self.p_part.len() + 1)) - 1 {
@@ -98,6 +98,106 @@ impl Default for MilnorProfile {
     }
 }
 
+/// A named sub-Hopf algebra of the Steenrod algebra, which can be used in place of an explicit
+/// [`MilnorProfile`].
+///
+/// - `A(n)` is generated by $\mathrm{Sq}^1, \ldots, \mathrm{Sq}^{2^n}$ at the prime $2$, and by
+///   $\beta, P^1, \ldots, P^{p^{n - 1}}$ at odd primes.
+/// - `E(n)` is the exterior algebra on the Milnor primitives $Q_0, \ldots, Q_n$.
+/// - `P(n)` is generated by $P^1, \ldots, P^{p^{n - 1}}$. This only exists at odd primes, since
+///   the even primary analogue is a quotient rather than a subalgebra of the Steenrod algebra.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SubHopfAlgebra {
+    A(u32),
+    E(u32),
+    P(u32),
+}
+
+impl SubHopfAlgebra {
+    /// The largest `n` we accept. The generators of larger subalgebras do not have degrees that
+    /// fit in an `i32`, and their profiles overflow the `u32` bitmask of Milnor primitives.
+    const MAX_N: u32 = 30;
+
+    fn n(self) -> u32 {
+        match self {
+            Self::A(n) | Self::E(n) | Self::P(n) => n,
+        }
+    }
+
+    /// The profile function of the subalgebra at the prime `p`.
+    pub fn profile(self, p: ValidPrime) -> anyhow::Result<MilnorProfile> {
+        anyhow::ensure!(
+            self.n() <= Self::MAX_N,
+            "{self} is too large; n must be at most {}",
+            Self::MAX_N
+        );
+        let generic = p != 2;
+        // The q_part including Q_0, ..., Q_n
+        let q_part = |n: u32| (1 << (n + 1)) - 1;
+        Ok(match self {
+            Self::A(n) if generic => MilnorProfile {
+                truncated: true,
+                q_part: q_part(n),
+                p_part: (1..=n).rev().collect(),
+            },
+            Self::A(n) => MilnorProfile {
+                truncated: true,
+                q_part: !0,
+                p_part: (1..=n + 1).rev().collect(),
+            },
+            Self::E(n) if generic => MilnorProfile {
+                truncated: true,
+                q_part: q_part(n),
+                p_part: vec![],
+            },
+            Self::E(n) => MilnorProfile {
+                truncated: true,
+                q_part: !0,
+                p_part: vec![1; n as usize + 1],
+            },
+            Self::P(n) if generic => MilnorProfile {
+                truncated: true,
+                q_part: 0,
+                p_part: (1..=n).rev().collect(),
+            },
+            Self::P(_) => {
+                return Err(anyhow::anyhow!(
+                    "{self} is only a subalgebra of the Steenrod algebra at odd primes"
+                ));
+            }
+        })
+    }
+}
+
+impl std::fmt::Display for SubHopfAlgebra {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::A(n) => write!(f, "A({n})"),
+            Self::E(n) => write!(f, "E({n})"),
+            Self::P(n) => write!(f, "P({n})"),
+        }
+    }
+}
+
+impl std::str::FromStr for SubHopfAlgebra {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow::anyhow!("Invalid subalgebra name: {s}");
+        let (name, n) = s
+            .strip_suffix(')')
+            .and_then(|s| s.split_once('('))
+            .ok_or_else(invalid)?;
+        let n = n.trim().parse().map_err(|_| invalid())?;
+        match name.trim() {
+            "A" => Ok(Self::A(n)),
+            "E" => Ok(Self::E(n)),
+            "P" => Ok(Self::P(n)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
 pub type PPartEntry = u32;
 pub type PPart = Vec<PPartEntry>;
 
@@ -358,6 +458,10 @@ impl Algebra for MilnorAlgebra {
             }
     }
 
+    fn profile_description(&self) -> Option<String> {
+        (!self.profile.is_trivial()).then(|| serde_json::to_string(&self.profile).unwrap())
+    }
+
     fn prime(&self) -> ValidPrime {
         self.p
     }
@@ -697,7 +801,12 @@ impl GeneratedAlgebra for MilnorAlgebra {
         if degree <= 0 {
             return vec![];
         } else if degree == 1 {
-            return vec![0]; // Q_0
+            // Q_0, unless the profile excludes it
+            return if self.dimension(1) > 0 {
+                vec![0]
+            } else {
+                vec![]
+            };
         }
 
         let p = self.prime();
@@ -1723,14 +1832,20 @@ impl MilnorAlgebra {
 
 impl Bialgebra for MilnorAlgebra {
     fn coproduct(&self, op_deg: i32, op_idx: usize) -> Vec<(i32, usize, i32, usize)> {
-        assert_eq!(self.prime(), 2, "Coproduct at odd primes not supported");
         if op_deg == 0 {
             return vec![(0, 0, 0, 0)];
         }
         let xi_degrees = combinatorics::xi_degrees(self.prime());
+        let q = self.q();
+
+        let elt = self.basis_element_from_index(op_deg, op_idx);
+        if elt.q_part != 0 {
+            // This is a Milnor primitive Q_i by the choice of decomposition
+            return vec![(op_deg, op_idx, 0, 0), (0, 0, op_deg, op_idx)];
+        }
 
         let mut len = 1;
-        let p_part = &self.basis_element_from_index(op_deg, op_idx).p_part;
+        let p_part = &elt.p_part;
 
         for i in p_part.iter() {
             len *= i + 1;
@@ -1742,7 +1857,7 @@ impl Bialgebra for MilnorAlgebra {
         loop {
             let mut left_degree: i32 = 0;
             for i in 0..cur_ppart.len() {
-                left_degree += cur_ppart[i] as i32 * xi_degrees[i];
+                left_degree += cur_ppart[i] as i32 * xi_degrees[i] * q;
             }
             let right_degree: i32 = op_deg - left_degree;
 
@@ -1779,8 +1894,31 @@ impl Bialgebra for MilnorAlgebra {
         result
     }
 
+    /// An element $Q(E) P(R)$ is decomposed as $Q_{e_1} \cdots Q_{e_k} P(R)$ with $e_1 < \cdots <
+    /// e_k$, since the Milnor primitives $Q_i$ are primitive.
     fn decompose(&self, op_deg: i32, op_idx: usize) -> Vec<(i32, usize)> {
-        vec![(op_deg, op_idx)]
+        let elt = self.basis_element_from_index(op_deg, op_idx);
+        if elt.q_part == 0 || elt.q_part.is_power_of_two() && elt.p_part.is_empty() {
+            return vec![(op_deg, op_idx)];
+        }
+
+        let p = self.prime();
+        let mut result = Vec::with_capacity(elt.q_part.count_ones() as usize + 1);
+        if !elt.p_part.is_empty() {
+            let mut p_elt = MilnorBasisElement::from_p(elt.p_part.clone(), 0);
+            p_elt.compute_degree(p);
+            result.push((p_elt.degree, self.basis_element_to_index(&p_elt)));
+        }
+        let q_parts: Vec<usize> = BitflagIterator::set_bit_iterator(elt.q_part as u64).collect();
+        for &i in q_parts.iter().rev() {
+            let q_elt = MilnorBasisElement {
+                q_part: 1 << i,
+                p_part: vec![],
+                degree: combinatorics::tau_degrees(p)[i],
+            };
+            result.push((q_elt.degree, self.basis_element_to_index(&q_elt)));
+        }
+        result
     }
 }
 
@@ -2161,4 +2299,36 @@ mod tests {
             .is_valid()
         );
     }
+
+    #[rstest]
+    #[case(2, "A(1)", 8)]
+    #[case(2, "A(2)", 64)]
+    #[case(2, "E(2)", 8)]
+    #[case(3, "A(0)", 2)]
+    #[case(3, "A(1)", 12)]
+    #[case(3, "E(1)", 4)]
+    #[case(3, "P(2)", 27)]
+    fn test_sub_hopf_algebra(#[case] p: u32, #[case] name: &str, #[case] dimension: usize) {
+        let p = ValidPrime::new(p);
+        let subalgebra: SubHopfAlgebra = name.parse().unwrap();
+        assert_eq!(subalgebra.to_string(), name);
+
+        let profile = subalgebra.profile(p).unwrap();
+        assert!(profile.is_valid());
+
+        let algebra = MilnorAlgebra::new_with_profile(p, profile, false);
+        algebra.compute_basis(100);
+        assert_eq!(
+            (0..=100).map(|i| algebra.dimension(i)).sum::<usize>(),
+            dimension
+        );
+    }
+
+    #[test]
+    fn test_sub_hopf_algebra_invalid() {
+        assert!("A(1".parse::<SubHopfAlgebra>().is_err());
+        assert!("B(1)".parse::<SubHopfAlgebra>().is_err());
+        assert!("A(-1)".parse::<SubHopfAlgebra>().is_err());
+        assert!(SubHopfAlgebra::P(1).profile(ValidPrime::new(2)).is_err());
+    }
 }
@@ -15,8 +15,14 @@ pub mod combinatorics;
 pub mod field;
 pub use field::Field;
 
+pub mod finite_dimensional_algebra;
+pub use finite_dimensional_algebra::FiniteDimensionalAlgebra;
+
 pub mod milnor_algebra;
-pub use milnor_algebra::MilnorAlgebra;
+pub use milnor_algebra::{MilnorAlgebra, SubHopfAlgebra};
+
+pub mod motivic_milnor_algebra;
+pub use motivic_milnor_algebra::{MotivicBase, MotivicMilnorAlgebra};
 
 mod steenrod_algebra;
 pub use steenrod_algebra::{AlgebraType, SteenrodAlgebra};
@@ -0,0 +1,1176 @@
+//! The motivic Steenrod algebras over $\mathbb{C}$ and $\mathbb{R}$ at the prime 2.
+//!
+//! The dual motivic Steenrod algebra over a base $k \in \\{\mathbb{C}, \mathbb{R}\\}$ is
+//! $$ \mathcal{A}_\* = \mathbb{M}_2[\tau_0, \tau_1, \ldots, \xi_1, \xi_2, \ldots] / (\tau_i^2 =
+//! \tau \xi_{i + 1} + \rho \tau_{i + 1} + \rho \tau_0 \xi_{i + 1}), $$
+//! where $\mathbb{M}_2 = \mathbb{F}_2[\tau]$ over $\mathbb{C}$ and $\mathbb{F}_2[\tau, \rho]$ over
+//! $\mathbb{R}$ (so the $\rho$ terms vanish over $\mathbb{C}$). The algebra itself is the
+//! $\mathbb{M}_2$-linear dual, and we use the dual basis $Q(E) P(R)$ of the monomials
+//! $\tau(E) \xi(R)$. As an $\mathbb{F}_2$-algebra, it has basis $\tau^a \rho^b Q(E) P(R)$.
+//!
+//! The algebra is bigraded by the topological degree $t$ and the weight $w$, where
+//!
+//! | element | $(t, w)$ |
+//! | --- | --- |
+//! | $\tau$ | $(0, 1)$ |
+//! | $\rho$ | $(1, 1)$ |
+//! | $Q_i$ | $(2^{i + 1} - 1, 2^i - 1)$ |
+//! | $P(R)$ | $\sum_i R_i (2^{i + 1} - 2, 2^i - 1)$ |
+//!
+//! In particular, $\mathrm{Sq}^{2i} = P(i)$ and $\mathrm{Sq}^{2i + 1} = Q_0 P(i)$ have weight $i$.
+//! Since $\tau$ has topological degree 0, the algebra is not of finite type in the $t$ grading
+//! alone. Instead, the [`Algebra`] degree of an element is the *motivic degree* $t + w$, in which
+//! the algebra is connected and finite dimensional in each degree. The weight of a basis element
+//! is given by [`Algebra::basis_element_weight`].
+//!
+//! Over $\mathbb{C}$, the algebra is $\tau$-torsion free and setting $\tau = 1$ recovers the
+//! classical Steenrod algebra, with $Q(E) P(R) \mapsto \mathrm{Sq}(R')$ where $R'\_j = 2 R\_j +
+//! E\_{j - 1}$. Since this is injective in each bidegree, we compute products by multiplying in the
+//! classical Milnor basis and recovering the power of $\tau$ from the weights. Over $\mathbb{R}$
+//! there is no such shortcut, and we instead compute products by dualizing the coproduct of
+//! $\mathcal{A}\_\*$, taking into account the right unit $\eta_R(\tau) = \tau + \rho \tau_0$.
+
+use std::sync::{Arc, Mutex};
+
+use fp::{
+    prime::{TWO, ValidPrime, iter::BitflagIterator},
+    vector::{FpSliceMut, FpVector},
+};
+use itertools::Itertools;
+use once::OnceVec;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::algebra::{
+    Algebra, GeneratedAlgebra, MilnorAlgebra,
+    milnor_algebra::{MilnorBasisElement, PPart, PPartEntry},
+};
+
+/// The base scheme of the motivic Steenrod algebra.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MotivicBase {
+    Complex,
+    Real,
+}
+
+impl std::fmt::Display for MotivicBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Complex => write!(f, "C"),
+            Self::Real => write!(f, "R"),
+        }
+    }
+}
+
+/// A basis element $\tau^a \rho^b Q(E) P(R)$ of the motivic Steenrod algebra.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MotivicBasisElement {
+    pub tau: u32,
+    pub rho: u32,
+    /// A bitmask recording which of the $Q_i$ appear.
+    pub q_part: u32,
+    pub p_part: PPart,
+    /// The motivic degree $t + w$.
+    pub degree: i32,
+}
+
+impl MotivicBasisElement {
+    /// The topological degree $t$.
+    pub fn topological_degree(&self) -> i32 {
+        self.rho as i32 + monomial_t(self.q_part, &self.p_part)
+    }
+
+    /// The weight $w$.
+    pub fn weight(&self) -> i32 {
+        (self.tau + self.rho) as i32 + monomial_weight(self.q_part, &self.p_part)
+    }
+
+    /// Update the degree component to the correct degree
+    pub fn compute_degree(&mut self) {
+        self.degree = self.topological_degree() + self.weight();
+    }
+}
+
+impl std::fmt::Display for MotivicBasisElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        for (name, exp) in [("tau", self.tau), ("rho", self.rho)] {
+            match exp {
+                0 => (),
+                1 => parts.push(name.to_string()),
+                _ => parts.push(format!("{name}^{exp}")),
+            }
+        }
+        parts.extend(
+            BitflagIterator::set_bit_iterator(self.q_part as u64).map(|idx| format!("Q_{idx}")),
+        );
+        if !self.p_part.is_empty() {
+            parts.push(format!("P({})", self.p_part.iter().format(", ")));
+        }
+        if parts.is_empty() {
+            write!(f, "1")
+        } else {
+            write!(f, "{}", parts.iter().format(" "))
+        }
+    }
+}
+
+/// The topological degree of $Q(E) P(R)$, which is also the degree of $\tau(E) \xi(R)$.
+fn monomial_t(q_part: u32, p_part: &[PPartEntry]) -> i32 {
+    BitflagIterator::set_bit_iterator(q_part as u64)
+        .map(|i| (1 << (i + 1)) - 1)
+        .sum::<i32>()
+        + p_part
+            .iter()
+            .enumerate()
+            .map(|(i, &r)| r as i32 * ((1 << (i + 2)) - 2))
+            .sum::<i32>()
+}
+
+/// The weight of $Q(E) P(R)$, which is also the weight of $\tau(E) \xi(R)$.
+fn monomial_weight(q_part: u32, p_part: &[PPartEntry]) -> i32 {
+    BitflagIterator::set_bit_iterator(q_part as u64)
+        .map(|i| (1 << i) - 1)
+        .sum::<i32>()
+        + p_part
+            .iter()
+            .enumerate()
+            .map(|(i, &r)| r as i32 * ((1 << (i + 1)) - 1))
+            .sum::<i32>()
+}
+
+/// Converts the P part of a classical Milnor basis element $\mathrm{Sq}(R')$ into the $Q(E) P(R)$
+/// it realizes from.
+fn from_classical(p_part: &[PPartEntry]) -> (u32, PPart) {
+    let q_part = p_part.iter().enumerate().map(|(i, &r)| (r & 1) << i).sum();
+    let mut p_part: PPart = p_part.iter().map(|&r| r >> 1).collect();
+    while p_part.last() == Some(&0) {
+        p_part.pop();
+    }
+    (q_part, p_part)
+}
+
+/// The inverse of [`from_classical`].
+fn to_classical(q_part: u32, p_part: &[PPartEntry]) -> PPart {
+    let len = std::cmp::max(p_part.len(), (32 - q_part.leading_zeros()) as usize);
+    (0..len)
+        .map(|i| 2 * p_part.get(i).copied().unwrap_or(0) + ((q_part >> i) & 1))
+        .collect()
+}
+
+/// A monomial $\tau(E) \xi(R)$ in the dual Steenrod algebra.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct DualMonomial {
+    tau_part: u32,
+    xi_part: PPart,
+}
+
+impl DualMonomial {
+    fn xi(i: usize, n: PPartEntry) -> Self {
+        let mut result = Self::default();
+        result.mul_xi(i, n);
+        result
+    }
+
+    fn tau(i: usize) -> Self {
+        Self {
+            tau_part: 1 << i,
+            xi_part: vec![],
+        }
+    }
+
+    /// Multiply by $\xi_i^n$, where $\xi_0 = 1$.
+    fn mul_xi(&mut self, i: usize, n: PPartEntry) {
+        if i == 0 || n == 0 {
+            return;
+        }
+        if self.xi_part.len() < i {
+            self.xi_part.resize(i, 0);
+        }
+        self.xi_part[i - 1] += n;
+    }
+
+    fn t(&self) -> i32 {
+        monomial_t(self.tau_part, &self.xi_part)
+    }
+
+    fn weight(&self) -> i32 {
+        monomial_weight(self.tau_part, &self.xi_part)
+    }
+}
+
+/// A term $\tau^a \rho^b m$ with $m$ a monomial. Collections of terms are stored in a `HashSet`,
+/// and adding a term is implemented by toggling its membership.
+type Term = (u32, u32, DualMonomial);
+/// A term $\tau^a \rho^b l \otimes r$ in $\mathcal{A}_\* \otimes_{\mathbb{M}_2} \mathcal{A}_\*$,
+/// with all coefficients moved to the left.
+type TensorTerm = (u32, u32, DualMonomial, DualMonomial);
+/// A product of a generator with a basis element, as `((gen_degree, gen_idx), (degree, idx))`.
+type Product = ((i32, usize), (i32, usize));
+/// The value of a linear combination of products, as `(pivot, value, combination)`.
+type ReducedProduct = (usize, FpVector, FpVector);
+
+fn toggle<T: std::hash::Hash + Eq>(set: &mut HashSet<T>, x: T) {
+    if !set.remove(&x) {
+        set.insert(x);
+    }
+}
+
+/// The motivic Steenrod algebra over $\mathbb{C}$ or $\mathbb{R}$ at the prime 2, in the Milnor
+/// basis. See the [module level documentation](self) for the conventions used.
+pub struct MotivicMilnorAlgebra {
+    base: MotivicBase,
+
+    /// The classical Milnor algebra. We use this to enumerate monomials and to compute products
+    /// over $\mathbb{C}$.
+    classical: MilnorAlgebra,
+
+    basis_table: OnceVec<Vec<MotivicBasisElement>>,
+    basis_element_to_index_map: OnceVec<HashMap<MotivicBasisElement, usize>>,
+
+    /// Coproducts of monomials in the dual algebra.
+    coproduct_cache: Mutex<HashMap<DualMonomial, Arc<Vec<TensorTerm>>>>,
+    /// (r_degree, r_idx, s_degree, s_idx) -> indices of the terms of the product.
+    product_cache: Mutex<HashMap<(i32, usize, i32, usize), Arc<Vec<usize>>>>,
+}
+
+impl std::fmt::Display for MotivicMilnorAlgebra {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "MotivicMilnorAlgebra({})", self.base)
+    }
+}
+
+impl MotivicMilnorAlgebra {
+    pub fn new(base: MotivicBase) -> Self {
+        Self {
+            base,
+            classical: MilnorAlgebra::new(TWO, false),
+            basis_table: OnceVec::new(),
+            basis_element_to_index_map: OnceVec::new(),
+            coproduct_cache: Mutex::new(HashMap::default()),
+            product_cache: Mutex::new(HashMap::default()),
+        }
+    }
+
+    /// The motivic Steenrod algebra over `base` for the module specified by `json`. This checks
+    /// that the module is defined at the prime 2 and does not ask for a profile, which is not
+    /// supported.
+    pub fn from_json(json: &Value, base: MotivicBase) -> anyhow::Result<Self> {
+        #[derive(Deserialize)]
+        struct MotivicSpec {
+            p: ValidPrime,
+            profile: Option<Value>,
+        }
+
+        let spec = MotivicSpec::deserialize(json)?;
+        anyhow::ensure!(spec.p == 2, "Motivic Steenrod algebras require p = 2");
+        anyhow::ensure!(
+            spec.profile.is_none(),
+            "Profiles are not supported for motivic Steenrod algebras"
+        );
+        Ok(Self::new(base))
+    }
+
+    pub fn base(&self) -> MotivicBase {
+        self.base
+    }
+
+    fn real(&self) -> bool {
+        self.base == MotivicBase::Real
+    }
+
+    pub fn basis_element_from_index(&self, degree: i32, idx: usize) -> &MotivicBasisElement {
+        &self.basis_table[degree as usize][idx]
+    }
+
+    pub fn try_basis_element_to_index(&self, elt: &MotivicBasisElement) -> Option<usize> {
+        self.basis_element_to_index_map
+            .get(elt.degree as usize)?
+            .get(elt)
+            .copied()
+    }
+
+    pub fn basis_element_to_index(&self, elt: &MotivicBasisElement) -> usize {
+        self.try_basis_element_to_index(elt)
+            .unwrap_or_else(|| panic!("Didn't find element: {elt:?}"))
+    }
+
+    /// The index of $\tau^a \rho^b$ in degree $a + 2b$.
+    pub fn coefficient_index(&self, tau: u32, rho: u32) -> usize {
+        let degree = (tau + 2 * rho) as i32;
+        self.compute_basis(degree);
+        self.basis_element_to_index(&MotivicBasisElement {
+            tau,
+            rho,
+            degree,
+            ..Default::default()
+        })
+    }
+
+    fn element_from_term(&self, (tau, rho, m): Term) -> MotivicBasisElement {
+        let mut elt = MotivicBasisElement {
+            tau,
+            rho,
+            q_part: m.tau_part,
+            p_part: m.xi_part,
+            degree: 0,
+        };
+        elt.compute_degree();
+        elt
+    }
+
+    /// All monomials of the dual algebra of topological degree `t`. These are in bijection with the
+    /// classical Milnor basis in degree `t`.
+    fn monomials(&self, t: i32) -> impl Iterator<Item = DualMonomial> + '_ {
+        self.classical.compute_basis(t);
+        (0..self.classical.dimension(t)).map(move |i| {
+            let (tau_part, xi_part) =
+                from_classical(&self.classical.basis_element_from_index(t, i).p_part);
+            DualMonomial { tau_part, xi_part }
+        })
+    }
+
+    fn multiply_complex(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        r: &MotivicBasisElement,
+        s: &MotivicBasisElement,
+    ) {
+        let classical = |x: &MotivicBasisElement| MilnorBasisElement {
+            q_part: 0,
+            p_part: to_classical(x.q_part, &x.p_part),
+            degree: monomial_t(x.q_part, &x.p_part),
+        };
+        let (r_cl, s_cl) = (classical(r), classical(s));
+        let t = r_cl.degree + s_cl.degree;
+        let weight = r.weight() + s.weight();
+
+        self.classical.compute_basis(t);
+        let mut product = FpVector::new(TWO, self.classical.dimension(t));
+        self.classical
+            .multiply(product.as_slice_mut(), 1, &r_cl, &s_cl);
+
+        for (i, _) in product.iter_nonzero() {
+            let (q_part, p_part) =
+                from_classical(&self.classical.basis_element_from_index(t, i).p_part);
+            let tau = weight - monomial_weight(q_part, &p_part);
+            assert!(tau >= 0, "Negative power of tau in {r} * {s}");
+            let elt = MotivicBasisElement {
+                tau: tau as u32,
+                rho: 0,
+                q_part,
+                p_part,
+                degree: r.degree + s.degree,
+            };
+            result.add_basis_element(self.basis_element_to_index(&elt), coeff);
+        }
+    }
+
+    /// Multiply the term $\tau^a \rho^b m$ by $\tau_i$ and add the result to `out`.
+    fn mul_tau(&self, out: &mut HashSet<Term>, a: u32, b: u32, mut m: DualMonomial, i: usize) {
+        if m.tau_part & (1 << i) == 0 {
+            m.tau_part |= 1 << i;
+            toggle(out, (a, b, m));
+            return;
+        }
+        // tau_i^2 = tau xi_{i + 1} + rho tau_{i + 1} + rho tau_0 xi_{i + 1}
+        m.tau_part ^= 1 << i;
+        let mut m_xi = m.clone();
+        m_xi.mul_xi(i + 1, 1);
+        toggle(out, (a + 1, b, m_xi.clone()));
+        if self.real() {
+            self.mul_tau(out, a, b + 1, m, i + 1);
+            self.mul_tau(out, a, b + 1, m_xi, 0);
+        }
+    }
+
+    /// Add $\tau^a \rho^b l r$ to `out`.
+    fn mul_monomials(
+        &self,
+        out: &mut HashSet<Term>,
+        a: u32,
+        b: u32,
+        l: &DualMonomial,
+        r: &DualMonomial,
+    ) {
+        let mut m = l.clone();
+        for (i, &n) in r.xi_part.iter().enumerate() {
+            m.mul_xi(i + 1, n);
+        }
+        let mut current = HashSet::default();
+        current.insert((a, b, m));
+        for i in BitflagIterator::set_bit_iterator(r.tau_part as u64) {
+            let mut next = HashSet::default();
+            for (a, b, m) in current {
+                self.mul_tau(&mut next, a, b, m, i);
+            }
+            current = next;
+        }
+        for term in current {
+            toggle(out, term);
+        }
+    }
+
+    /// The coproduct of a monomial, with all coefficients moved to the left factor.
+    fn coproduct_monomial(&self, m: &DualMonomial) -> Arc<Vec<TensorTerm>> {
+        if let Some(v) = self.coproduct_cache.lock().unwrap().get(m) {
+            return Arc::clone(v);
+        }
+
+        // The coproducts of the generators making up m, each as a list of summands l ⊗ r.
+        let mut factors: Vec<Vec<(DualMonomial, DualMonomial)>> = Vec::new();
+        for k in BitflagIterator::set_bit_iterator(m.tau_part as u64) {
+            // Δτ_k = τ_k ⊗ 1 + Σ ξ_{k - i}^{2^i} ⊗ τ_i
+            let mut summands = vec![(DualMonomial::tau(k), DualMonomial::default())];
+            summands
+                .extend((0..=k).map(|i| (DualMonomial::xi(k - i, 1 << i), DualMonomial::tau(i))));
+            factors.push(summands);
+        }
+        for (k, &n) in m.xi_part.iter().enumerate() {
+            let k = k + 1;
+            // Δξ_k = Σ ξ_{k - i}^{2^i} ⊗ ξ_i
+            let summands: Vec<_> = (0..=k)
+                .map(|i| (DualMonomial::xi(k - i, 1 << i), DualMonomial::xi(i, 1)))
+                .collect();
+            factors.extend(std::iter::repeat_n(summands, n as usize));
+        }
+
+        let mut current: HashSet<TensorTerm> = HashSet::default();
+        current.insert((0, 0, DualMonomial::default(), DualMonomial::default()));
+        for summands in &factors {
+            let mut next = HashSet::default();
+            for (a, b, l, r) in &current {
+                for (gl, gr) in summands {
+                    let mut left = HashSet::default();
+                    self.mul_monomials(&mut left, *a, *b, l, gl);
+                    let mut right = HashSet::default();
+                    self.mul_monomials(&mut right, 0, 0, r, gr);
+
+                    for (x, y, r) in right {
+                        for (a, b, l) in &left {
+                            if !self.real() {
+                                toggle(&mut next, (a + x, b + y, l.clone(), r.clone()));
+                                continue;
+                            }
+                            // Move τ^x ρ^y to the left using η_R(τ) = τ + ρτ_0.
+                            for k in (0..=x).filter(|&k| x & k == k) {
+                                let mut terms = HashSet::default();
+                                terms.insert((a + x - k, b + y + k, l.clone()));
+                                for _ in 0..k {
+                                    let mut next_terms = HashSet::default();
+                                    for (a, b, l) in terms {
+                                        self.mul_tau(&mut next_terms, a, b, l, 0);
+                                    }
+                                    terms = next_terms;
+                                }
+                                for (a, b, l) in terms {
+                                    toggle(&mut next, (a, b, l, r.clone()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            current = next;
+        }
+
+        let result = Arc::new(current.into_iter().collect::<Vec<_>>());
+        self.coproduct_cache
+            .lock()
+            .unwrap()
+            .insert(m.clone(), Arc::clone(&result));
+        result
+    }
+
+    /// Computes $\mu^\* \nu^\*$, where $\mu^\*$ is the dual of the monomial $\mu$.
+    fn multiply_dual_monomials(&self, mu: &DualMonomial, nu: &DualMonomial) -> Vec<Term> {
+        let t = mu.t() + nu.t();
+        let weight = mu.weight() + nu.weight();
+        let min_t = if self.real() { 0 } else { t };
+
+        let mut result = Vec::new();
+        for t2 in min_t..=t {
+            for m in self.monomials(t2) {
+                // The coefficient is τ^a ρ^(t - t2), which has weight at least t - t2.
+                if m.weight() + (t - t2) > weight {
+                    continue;
+                }
+                let mut coefs = HashSet::default();
+                for (a, b, l, r) in self.coproduct_monomial(&m).iter() {
+                    if r == nu && l == mu {
+                        toggle(&mut coefs, (*a, *b));
+                    }
+                }
+                result.extend(coefs.into_iter().map(|(a, b)| (a, b, m.clone())));
+            }
+        }
+        result
+    }
+
+    /// Computes $\mu^\* \tau$. This is $\tau \mu^\*$ over $\mathbb{C}$ but not over
+    /// $\mathbb{R}$.
+    fn multiply_dual_monomial_by_tau(&self, mu: &DualMonomial) -> Vec<Term> {
+        let mut result = HashSet::default();
+        result.insert((1, 0, mu.clone()));
+        if self.real() {
+            // μ^* τ = τ μ^* + ρ Σ_m (coefficient of μ in m τ_0) m^*
+            for t in 0..mu.t() {
+                for m in self.monomials(t) {
+                    if m.weight() > mu.weight() {
+                        continue;
+                    }
+                    let mut product = HashSet::default();
+                    self.mul_tau(&mut product, 0, 0, m.clone(), 0);
+                    for (a, b, x) in product {
+                        if &x == mu {
+                            toggle(&mut result, (a, b + 1, m.clone()));
+                        }
+                    }
+                }
+            }
+        }
+        result.into_iter().collect()
+    }
+
+    /// Multiplies two basis elements by dualizing the coproduct.
+    fn multiply_dual(&self, r: &MotivicBasisElement, s: &MotivicBasisElement) -> HashSet<Term> {
+        let mu = DualMonomial {
+            tau_part: r.q_part,
+            xi_part: r.p_part.clone(),
+        };
+        let nu = DualMonomial {
+            tau_part: s.q_part,
+            xi_part: s.p_part.clone(),
+        };
+
+        // First compute μ^* τ^a, where s = τ^a ρ^b ν^*. Since ρ is central, we can ignore it.
+        let mut left = HashSet::default();
+        left.insert((0, 0, mu));
+        for _ in 0..s.tau {
+            let mut next = HashSet::default();
+            for (a, b, m) in left {
+                for (a2, b2, m2) in self.multiply_dual_monomial_by_tau(&m) {
+                    toggle(&mut next, (a + a2, b + b2, m2));
+                }
+            }
+            left = next;
+        }
+
+        let mut result = HashSet::default();
+        for (a, b, m) in left {
+            for (a2, b2, m2) in self.multiply_dual_monomials(&m, &nu) {
+                toggle(&mut result, (r.tau + a + a2, r.rho + s.rho + b + b2, m2));
+            }
+        }
+        result
+    }
+
+    fn multiply_real(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r_idx: usize,
+        s_degree: i32,
+        s_idx: usize,
+    ) {
+        let key = (r_degree, r_idx, s_degree, s_idx);
+        let cached = self.product_cache.lock().unwrap().get(&key).cloned();
+        let terms = cached.unwrap_or_else(|| {
+            let terms = self
+                .multiply_dual(
+                    self.basis_element_from_index(r_degree, r_idx),
+                    self.basis_element_from_index(s_degree, s_idx),
+                )
+                .into_iter()
+                .map(|term| self.basis_element_to_index(&self.element_from_term(term)))
+                .collect::<Vec<_>>();
+            let terms = Arc::new(terms);
+            self.product_cache
+                .lock()
+                .unwrap()
+                .insert(key, Arc::clone(&terms));
+            terms
+        });
+        for &idx in terms.iter() {
+            result.add_basis_element(idx, coeff);
+        }
+    }
+
+    /// Returns the generator $\mathrm{Sq}^{2^k}$, i.e. $Q_0$ if $k = 0$ and $P(2^{k - 1})$ otherwise.
+    fn sq_2k(&self, k: u32) -> (i32, usize) {
+        let elt = if k == 0 {
+            MotivicBasisElement {
+                q_part: 1,
+                degree: 1,
+                ..Default::default()
+            }
+        } else {
+            let mut elt = MotivicBasisElement {
+                p_part: vec![1 << (k - 1)],
+                ..Default::default()
+            };
+            elt.compute_degree();
+            elt
+        };
+        self.compute_basis(elt.degree);
+        (elt.degree, self.basis_element_to_index(&elt))
+    }
+}
+
+impl Algebra for MotivicMilnorAlgebra {
+    fn prefix(&self) -> &str {
+        match self.base {
+            MotivicBase::Complex => "c-motivic",
+            MotivicBase::Real => "r-motivic",
+        }
+    }
+
+    fn magic(&self) -> u32 {
+        (2 << 16)
+            + match self.base {
+                MotivicBase::Complex => 0x4000,
+                MotivicBase::Real => 0x4001,
+            }
+    }
+
+    fn prime(&self) -> ValidPrime {
+        TWO
+    }
+
+    fn compute_basis(&self, max_degree: i32) {
+        if max_degree < 0 {
+            return;
+        }
+        self.classical.compute_basis(max_degree);
+        let max_rho = |degree: i32| if self.real() { degree / 2 } else { 0 };
+
+        self.basis_table.extend(max_degree as usize, |degree| {
+            let degree = degree as i32;
+            let mut basis = Vec::new();
+            for rho in 0..=max_rho(degree) {
+                for t in 0..=degree - 2 * rho {
+                    for m in self.monomials(t) {
+                        let tau = degree - 2 * rho - t - m.weight();
+                        if tau >= 0 {
+                            basis.push(MotivicBasisElement {
+                                tau: tau as u32,
+                                rho: rho as u32,
+                                q_part: m.tau_part,
+                                p_part: m.xi_part,
+                                degree,
+                            });
+                        }
+                    }
+                }
+            }
+            basis
+        });
+
+        self.basis_element_to_index_map
+            .extend(max_degree as usize, |d| {
+                self.basis_table[d]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| (b.clone(), i))
+                    .collect()
+            });
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < 0 {
+            return 0;
+        }
+        self.basis_table[degree as usize].len()
+    }
+
+    fn basis_element_weight(&self, degree: i32, idx: usize) -> i32 {
+        self.basis_element_from_index(degree, idx).weight()
+    }
+
+    fn multiply_basis_elements(
+        &self,
+        result: FpSliceMut,
+        coeff: u32,
+        r_degree: i32,
+        r_idx: usize,
+        s_degree: i32,
+        s_idx: usize,
+    ) {
+        if coeff == 0 {
+            return;
+        }
+        match self.base {
+            MotivicBase::Complex => self.multiply_complex(
+                result,
+                coeff,
+                self.basis_element_from_index(r_degree, r_idx),
+                self.basis_element_from_index(s_degree, s_idx),
+            ),
+            MotivicBase::Real => {
+                self.multiply_real(result, coeff, r_degree, r_idx, s_degree, s_idx)
+            }
+        }
+    }
+
+    fn default_filtration_one_products(&self) -> Vec<(String, i32, usize)> {
+        (0..4)
+            .map(|k| {
+                let (degree, idx) = self.sq_2k(k);
+                (format!("h_{k}"), degree, idx)
+            })
+            .collect()
+    }
+
+    fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        format!("{}", self.basis_element_from_index(degree, idx))
+    }
+
+    fn basis_element_from_string(&self, elt: &str) -> Option<(i32, usize)> {
+        let mut result = MotivicBasisElement::default();
+
+        if let Some(n) = elt.strip_prefix("Sq").and_then(|x| x.parse::<u32>().ok()) {
+            result.q_part = n & 1;
+            if n > 1 {
+                result.p_part = vec![n >> 1];
+            }
+        } else {
+            let elt = elt.replace(", ", ",");
+            for token in elt.split_whitespace() {
+                let power = |name: &str| -> Option<u32> {
+                    let rest = token.strip_prefix(name)?;
+                    if rest.is_empty() {
+                        Some(1)
+                    } else {
+                        rest.strip_prefix('^')?.parse().ok()
+                    }
+                };
+                if token == "1" {
+                } else if let Some(n) = power("tau") {
+                    result.tau += n;
+                } else if let Some(n) = power("rho") {
+                    result.rho += n;
+                } else if let Some(i) = token.strip_prefix("Q_") {
+                    result.q_part |= 1 << i.parse::<u32>().ok()?;
+                } else if let Some(p) = token.strip_prefix("P(") {
+                    result.p_part = p
+                        .strip_suffix(')')?
+                        .split(',')
+                        .map(|x| x.parse().ok())
+                        .collect::<Option<_>>()?;
+                } else {
+                    return None;
+                }
+            }
+        }
+
+        if result.rho > 0 && !self.real() {
+            return None;
+        }
+        result.compute_degree();
+        self.compute_basis(result.degree);
+        self.try_basis_element_to_index(&result)
+            .map(|idx| (result.degree, idx))
+    }
+}
+
+impl GeneratedAlgebra for MotivicMilnorAlgebra {
+    fn generators(&self, degree: i32) -> Vec<usize> {
+        if degree <= 0 {
+            return vec![];
+        }
+        self.compute_basis(degree);
+        let mut result = Vec::new();
+        if degree == 1 {
+            result.push(self.coefficient_index(1, 0));
+        }
+        if degree == 2 && self.real() {
+            result.push(self.coefficient_index(0, 1));
+        }
+        for k in 0.. {
+            let (d, idx) = self.sq_2k(k);
+            if d == degree {
+                result.push(idx);
+            }
+            if d >= degree {
+                break;
+            }
+        }
+        result
+    }
+
+    fn generator_to_string(&self, degree: i32, idx: usize) -> String {
+        let elt = self.basis_element_from_index(degree, idx);
+        if elt.tau > 0 || elt.rho > 0 {
+            elt.to_string()
+        } else {
+            format!("Sq{}", elt.topological_degree())
+        }
+    }
+
+    fn decompose_basis_element(
+        &self,
+        degree: i32,
+        idx: usize,
+    ) -> Vec<(u32, (i32, usize), (i32, usize))> {
+        let elt = self.basis_element_from_index(degree, idx);
+        // Multiplying by τ^a ρ^b on the left is just multiplication of the coefficient.
+        if elt.tau > 0 || elt.rho > 0 {
+            let (tau, rho) = if elt.tau > 0 { (1, 0) } else { (0, 1) };
+            let mut rest = elt.clone();
+            rest.tau -= tau;
+            rest.rho -= rho;
+            rest.degree -= (tau + 2 * rho) as i32;
+            return vec![(
+                1,
+                ((tau + 2 * rho) as i32, self.coefficient_index(tau, rho)),
+                (rest.degree, self.basis_element_to_index(&rest)),
+            )];
+        }
+
+        // Otherwise, we find a linear combination of products of generators with other elements
+        // that gives our element.
+        let (products, reduced, _) = self.reduce_products(degree);
+        let mut value = FpVector::new(TWO, self.dimension(degree));
+        value.set_entry(idx, 1);
+        let mut combination = FpVector::new(TWO, products.len());
+        for (pivot, v, c) in &reduced {
+            if value.entry(*pivot) != 0 {
+                value.add(v, 1);
+                combination.add(c, 1);
+            }
+        }
+        assert!(value.is_zero(), "{elt} is not decomposable");
+
+        combination
+            .iter_nonzero()
+            .map(|(n, c)| (c, products[n].0, products[n].1))
+            .collect()
+    }
+
+    fn generating_relations(&self, degree: i32) -> Vec<Vec<(u32, (i32, usize), (i32, usize))>> {
+        if degree <= 0 {
+            return vec![];
+        }
+        // Non-generators act through their decompositions, which are combinations of products of
+        // generators with other elements. So the relations are the combinations of these products
+        // that vanish, which include the motivic Adem relations and the commutation relations of
+        // tau and rho.
+        let (products, _, kernel) = self.reduce_products(degree);
+        kernel
+            .into_iter()
+            .map(|c| {
+                c.iter_nonzero()
+                    .map(|(n, c)| (c, products[n].0, products[n].1))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl MotivicMilnorAlgebra {
+    /// Row reduce the products of generators with basis elements in degree `degree`. This returns
+    /// the list of products, the reduced values, where the combination records which products sum
+    /// to the value, and the combinations of products that vanish.
+    fn reduce_products(&self, degree: i32) -> (Vec<Product>, Vec<ReducedProduct>, Vec<FpVector>) {
+        let dim = self.dimension(degree);
+        let mut products = Vec::new();
+        let mut reduced: Vec<ReducedProduct> = Vec::new();
+        let mut kernel = Vec::new();
+        for gen_degree in 1..degree {
+            for gen_idx in self.generators(gen_degree) {
+                for i in 0..self.dimension(degree - gen_degree) {
+                    products.push(((gen_degree, gen_idx), (degree - gen_degree, i)));
+                }
+            }
+        }
+        for (n, &((d1, i1), (d2, i2))) in products.iter().enumerate() {
+            let mut value = FpVector::new(TWO, dim);
+            self.multiply_basis_elements(value.as_slice_mut(), 1, d1, i1, d2, i2);
+            let mut combination = FpVector::new(TWO, products.len());
+            combination.set_entry(n, 1);
+            for (pivot, v, c) in &reduced {
+                if value.entry(*pivot) != 0 {
+                    value.add(v, 1);
+                    combination.add(c, 1);
+                }
+            }
+            match value.first_nonzero() {
+                Some((pivot, _)) => reduced.push((pivot, value, combination)),
+                None => kernel.push(combination),
+            }
+        }
+        (products, reduced, kernel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn product(algebra: &MotivicMilnorAlgebra, r: &str, s: &str) -> String {
+        let (r_degree, r_idx) = algebra.basis_element_from_string(r).unwrap();
+        let (s_degree, s_idx) = algebra.basis_element_from_string(s).unwrap();
+        let degree = r_degree + s_degree;
+        algebra.compute_basis(degree);
+        let mut result = FpVector::new(TWO, algebra.dimension(degree));
+        algebra.multiply_basis_elements(result.as_slice_mut(), 1, r_degree, r_idx, s_degree, s_idx);
+        algebra.element_to_string(degree, result.as_slice())
+    }
+
+    #[test]
+    fn test_basis() {
+        let algebra = MotivicMilnorAlgebra::new(MotivicBase::Real);
+        algebra.compute_basis(4);
+        let basis = (0..=4)
+            .map(|d| {
+                let elements = (0..algebra.dimension(d))
+                    .map(|i| algebra.basis_element_to_string(d, i))
+                    .join(", ");
+                format!("{d}: {elements}")
+            })
+            .join("\n");
+        expect![[r#"
+            0: 1
+            1: tau, Q_0
+            2: tau^2, tau Q_0, rho
+            3: tau^3, tau^2 Q_0, P(1), tau rho, rho Q_0
+            4: tau^4, tau^3 Q_0, tau P(1), Q_0 P(1), Q_1, tau^2 rho, tau rho Q_0, rho^2"#]]
+        .assert_eq(&basis);
+    }
+
+    #[test]
+    fn test_products() {
+        let c = MotivicMilnorAlgebra::new(MotivicBase::Complex);
+        let r = MotivicMilnorAlgebra::new(MotivicBase::Real);
+
+        assert_eq!(product(&c, "P(1)", "P(1)"), "tau Q_0 Q_1");
+        assert_eq!(product(&c, "Q_0", "tau"), "tau Q_0");
+        assert_eq!(product(&r, "Q_0", "tau"), "tau Q_0 + rho");
+        assert_eq!(product(&r, "tau", "Q_0"), "tau Q_0");
+        assert_eq!(product(&r, "Q_0", "Q_0"), "0");
+        // The Adem relations Sq^2 Sq^2 = τ Sq^3 Sq^1 and Sq^2 Sq^3 = Sq^5 + Sq^4 Sq^1 + ρ Sq^3 Sq^1
+        assert_eq!(product(&r, "P(1)", "P(1)"), "tau Q_0 Q_1");
+        expect!["Q_1 P(1) + rho Q_0 Q_1"].assert_eq(&product(&r, "Sq2", "Sq3"));
+    }
+
+    /// Over $\mathbb{C}$, the classical shortcut must agree with dualizing the coproduct.
+    #[test]
+    fn test_complex_dual() {
+        let algebra = MotivicMilnorAlgebra::new(MotivicBase::Complex);
+        let max_degree = 12;
+        algebra.compute_basis(max_degree);
+        for r_degree in 0..=max_degree {
+            for s_degree in 0..=max_degree - r_degree {
+                let degree = r_degree + s_degree;
+                for r_idx in 0..algebra.dimension(r_degree) {
+                    for s_idx in 0..algebra.dimension(s_degree) {
+                        let mut expected = FpVector::new(TWO, algebra.dimension(degree));
+                        algebra.multiply_basis_elements(
+                            expected.as_slice_mut(),
+                            1,
+                            r_degree,
+                            r_idx,
+                            s_degree,
+                            s_idx,
+                        );
+                        let mut result = FpVector::new(TWO, algebra.dimension(degree));
+                        for term in algebra.multiply_dual(
+                            algebra.basis_element_from_index(r_degree, r_idx),
+                            algebra.basis_element_from_index(s_degree, s_idx),
+                        ) {
+                            result.add_basis_element(
+                                algebra.basis_element_to_index(&algebra.element_from_term(term)),
+                                1,
+                            );
+                        }
+                        assert_eq!(
+                            result,
+                            expected,
+                            "{} * {}",
+                            algebra.basis_element_to_string(r_degree, r_idx),
+                            algebra.basis_element_to_string(s_degree, s_idx)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Setting $\rho = 0$ in the real algebra recovers the complex one.
+    #[test]
+    fn test_real_mod_rho() {
+        let real = MotivicMilnorAlgebra::new(MotivicBase::Real);
+        let complex = MotivicMilnorAlgebra::new(MotivicBase::Complex);
+        let max_degree = 10;
+        real.compute_basis(max_degree);
+        complex.compute_basis(max_degree);
+        for r_degree in 0..=max_degree {
+            for s_degree in 0..=max_degree - r_degree {
+                let degree = r_degree + s_degree;
+                for r_idx in 0..complex.dimension(r_degree) {
+                    for s_idx in 0..complex.dimension(s_degree) {
+                        let lift = |d, i| {
+                            real.basis_element_to_index(complex.basis_element_from_index(d, i))
+                        };
+                        let mut real_result = FpVector::new(TWO, real.dimension(degree));
+                        real.multiply_basis_elements(
+                            real_result.as_slice_mut(),
+                            1,
+                            r_degree,
+                            lift(r_degree, r_idx),
+                            s_degree,
+                            lift(s_degree, s_idx),
+                        );
+                        let mut complex_result = FpVector::new(TWO, complex.dimension(degree));
+                        complex.multiply_basis_elements(
+                            complex_result.as_slice_mut(),
+                            1,
+                            r_degree,
+                            r_idx,
+                            s_degree,
+                            s_idx,
+                        );
+                        let mut reduced = FpVector::new(TWO, complex.dimension(degree));
+                        for (i, _) in real_result.iter_nonzero() {
+                            let elt = real.basis_element_from_index(degree, i);
+                            if elt.rho == 0 {
+                                reduced.add_basis_element(complex.basis_element_to_index(elt), 1);
+                            }
+                        }
+                        assert_eq!(reduced, complex_result);
+                    }
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(MotivicBase::Complex, 12)]
+    #[case(MotivicBase::Real, 8)]
+    fn test_associativity(#[case] base: MotivicBase, #[case] max_degree: i32) {
+        let algebra = MotivicMilnorAlgebra::new(base);
+        algebra.compute_basis(max_degree);
+
+        for a_degree in 0..=max_degree {
+            for b_degree in 0..=max_degree - a_degree {
+                for c_degree in 0..=max_degree - a_degree - b_degree {
+                    let ab_degree = a_degree + b_degree;
+                    let bc_degree = b_degree + c_degree;
+                    let degree = ab_degree + c_degree;
+                    for a in 0..algebra.dimension(a_degree) {
+                        for b in 0..algebra.dimension(b_degree) {
+                            let mut ab = FpVector::new(TWO, algebra.dimension(ab_degree));
+                            algebra.multiply_basis_elements(
+                                ab.as_slice_mut(),
+                                1,
+                                a_degree,
+                                a,
+                                b_degree,
+                                b,
+                            );
+                            for c in 0..algebra.dimension(c_degree) {
+                                let mut bc = FpVector::new(TWO, algebra.dimension(bc_degree));
+                                algebra.multiply_basis_elements(
+                                    bc.as_slice_mut(),
+                                    1,
+                                    b_degree,
+                                    b,
+                                    c_degree,
+                                    c,
+                                );
+                                let mut left = FpVector::new(TWO, algebra.dimension(degree));
+                                let mut right = FpVector::new(TWO, algebra.dimension(degree));
+                                algebra.multiply_element_by_basis_element(
+                                    left.as_slice_mut(),
+                                    1,
+                                    ab_degree,
+                                    ab.as_slice(),
+                                    c_degree,
+                                    c,
+                                );
+                                algebra.multiply_basis_element_by_element(
+                                    right.as_slice_mut(),
+                                    1,
+                                    a_degree,
+                                    a,
+                                    bc_degree,
+                                    bc.as_slice(),
+                                );
+                                assert_eq!(left, right);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(MotivicBase::Complex)]
+    #[case(MotivicBase::Real)]
+    fn test_decompose(#[case] base: MotivicBase) {
+        let algebra = MotivicMilnorAlgebra::new(base);
+        let max_degree = 10;
+        algebra.compute_basis(max_degree);
+        for degree in 1..=max_degree {
+            let generators = algebra.generators(degree);
+            for idx in 0..algebra.dimension(degree) {
+                if generators.contains(&idx) {
+                    continue;
+                }
+                let mut result = FpVector::new(TWO, algebra.dimension(degree));
+                for (c, (d1, i1), (d2, i2)) in algebra.decompose_basis_element(degree, idx) {
+                    algebra.multiply_basis_elements(result.as_slice_mut(), c, d1, i1, d2, i2);
+                }
+                let mut expected = FpVector::new(TWO, algebra.dimension(degree));
+                expected.set_entry(idx, 1);
+                assert_eq!(result, expected);
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(MotivicBase::Complex)]
+    #[case(MotivicBase::Real)]
+    fn test_generating_relations(#[case] base: MotivicBase) {
+        let algebra = MotivicMilnorAlgebra::new(base);
+        let max_degree = 8;
+        algebra.compute_basis(max_degree);
+        for degree in 1..=max_degree {
+            for relation in algebra.generating_relations(degree) {
+                let mut result = FpVector::new(TWO, algebra.dimension(degree));
+                for (c, (d1, i1), (d2, i2)) in relation {
+                    algebra.multiply_basis_elements(result.as_slice_mut(), c, d1, i1, d2, i2);
+                }
+                assert!(result.is_zero());
+            }
+        }
+        assert!(!algebra.generating_relations(2).is_empty());
+    }
+
+    #[test]
+    fn test_string_roundtrip() {
+        let algebra = MotivicMilnorAlgebra::new(MotivicBase::Real);
+        algebra.compute_basis(15);
+        for degree in 0..=15 {
+            for idx in 0..algebra.dimension(degree) {
+                let s = algebra.basis_element_to_string(degree, idx);
+                assert_eq!(
+                    algebra.basis_element_from_string(&s),
+                    Some((degree, idx)),
+                    "{s}"
+                );
+            }
+        }
+        assert_eq!(
+            algebra.basis_element_from_string("Sq5"),
+            algebra.basis_element_from_string("Q_0 P(2)")
+        );
+    }
+}
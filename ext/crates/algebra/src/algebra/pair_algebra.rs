@@ -89,6 +89,14 @@ pub trait PairAlgebra: Algebra {
         degree: i32,
         buffer: &mut impl io::Read,
     ) -> io::Result<Self::Element>;
+
+    /// Check that the algebra actually admits a lift to a split pair algebra. Some
+    /// implementations, such as the one for the Adem basis, are only placeholders that let
+    /// [`SteenrodAlgebra`](crate::SteenrodAlgebra) implement this trait, and their other methods
+    /// panic. This should be called before starting any computations with the pair algebra.
+    fn check_pair_algebra(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 use std::cell::RefCell;
@@ -171,6 +179,14 @@ impl PairAlgebra for MilnorAlgebra {
         0
     }
 
+    fn check_pair_algebra(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.prime() == 2,
+            "The Milnor algebra is only a pair algebra at p = 2"
+        );
+        Ok(())
+    }
+
     /// Assert that `elt` is in the image of the differential. Drop the data recording the
     /// complement of the image of the differential.
     fn finalize_element(elt: &mut Self::Element) {
@@ -9,7 +9,10 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::{
-    algebra::{AdemAlgebra, Algebra, Bialgebra, GeneratedAlgebra, MilnorAlgebra, UnstableAlgebra},
+    algebra::{
+        AdemAlgebra, Algebra, Bialgebra, GeneratedAlgebra, MilnorAlgebra, MotivicBase,
+        SubHopfAlgebra, UnstableAlgebra, milnor_algebra::MilnorProfile,
+    },
     pair_algebra::PairAlgebra,
 };
 
@@ -17,6 +20,10 @@ use crate::{
 pub enum AlgebraType {
     Adem,
     Milnor,
+    /// The motivic Steenrod algebra over $\mathbb{C}$ in the Milnor basis.
+    ComplexMotivic,
+    /// The motivic Steenrod algebra over $\mathbb{R}$ in the Milnor basis.
+    RealMotivic,
 }
 
 impl std::fmt::Display for AlgebraType {
@@ -27,6 +34,8 @@ impl std::fmt::Display for AlgebraType {
             match self {
                 Self::Adem => "adem",
                 Self::Milnor => "milnor",
+                Self::ComplexMotivic => "c-motivic",
+                Self::RealMotivic => "r-motivic",
             }
         )
     }
@@ -39,6 +48,8 @@ impl std::convert::TryFrom<&str> for AlgebraType {
         match s {
             "adem" => Ok(Self::Adem),
             "milnor" => Ok(Self::Milnor),
+            "c-motivic" => Ok(Self::ComplexMotivic),
+            "r-motivic" => Ok(Self::RealMotivic),
             _ => Err(anyhow!("Invalid algebra name: {}", s)),
         }
     }
@@ -94,37 +105,95 @@ impl<'a> TryInto<&'a MilnorAlgebra> for &'a SteenrodAlgebra {
     }
 }
 
+/// A profile function, given either explicitly or as the name of a sub-Hopf algebra.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ProfileSpec {
+    Named(String),
+    Explicit(MilnorProfile),
+}
+
+/// Either the list of Steenrod algebras a module supports, or the definition of a finite
+/// dimensional algebra, which is parsed by [`FiniteDimensionalAlgebra::from_json`] instead.
+///
+/// [`FiniteDimensionalAlgebra::from_json`]: crate::FiniteDimensionalAlgebra::from_json
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum AlgebraList {
+    Supported(Vec<String>),
+    Definition(serde::de::IgnoredAny),
+}
+
 #[derive(Deserialize, Debug)]
 struct AlgebraSpec {
     p: ValidPrime,
-    algebra: Option<Vec<String>>,
-    profile: Option<crate::algebra::milnor_algebra::MilnorProfile>,
+    algebra: Option<AlgebraList>,
+    profile: Option<ProfileSpec>,
+}
+
+impl AlgebraType {
+    /// The base of the motivic Steenrod algebra, if this is one of the motivic algebras. These are
+    /// not Steenrod algebras over $\mathbb{F}_p$, and are resolved separately.
+    pub fn motivic_base(self) -> Option<MotivicBase> {
+        match self {
+            Self::Adem | Self::Milnor => None,
+            Self::ComplexMotivic => Some(MotivicBase::Complex),
+            Self::RealMotivic => Some(MotivicBase::Real),
+        }
+    }
+
+    /// The algebra to use for the module specified by `json`. If the module lists the algebras it
+    /// supports and this is not one of them, the first supported algebra is used instead.
+    pub fn for_module(self, json: &Value) -> anyhow::Result<Self> {
+        let spec: AlgebraSpec = AlgebraSpec::deserialize(json)?;
+        if let Some(AlgebraList::Supported(list)) = spec.algebra {
+            let algebra_name = &self.to_string();
+            if !list.iter().any(|x| x == algebra_name) {
+                println!("Module does not support algebra {algebra_name}");
+                println!("Using {} instead", list[0]);
+                return list[0].parse();
+            }
+        }
+        Ok(self)
+    }
 }
 
 impl SteenrodAlgebra {
     pub fn from_json(
         json: &Value,
-        mut algebra_type: AlgebraType,
+        algebra_type: AlgebraType,
         unstable: bool,
     ) -> anyhow::Result<Self> {
         let spec: AlgebraSpec = AlgebraSpec::deserialize(json)?;
 
-        if let Some(list) = spec.algebra {
-            let algebra_name = &algebra_type.to_string();
-            if !list.iter().any(|x| x == algebra_name) {
-                println!("Module does not support algebra {algebra_name}");
-                println!("Using {} instead", list[0]);
-                algebra_type = list[0].parse()?;
-            }
-        }
+        anyhow::ensure!(
+            !matches!(spec.algebra, Some(AlgebraList::Definition(_))),
+            "The module is defined over its own finite dimensional algebra, not the Steenrod \
+             algebra"
+        );
+
+        let algebra_type = algebra_type.for_module(json)?;
+
+        let profile = match spec.profile {
+            None => MilnorProfile::default(),
+            Some(ProfileSpec::Explicit(profile)) => profile,
+            Some(ProfileSpec::Named(name)) => name.parse::<SubHopfAlgebra>()?.profile(spec.p)?,
+        };
+        anyhow::ensure!(
+            algebra_type != AlgebraType::Adem || profile.is_trivial(),
+            "Only the Milnor basis supports a non-trivial profile"
+        );
 
         Ok(match algebra_type {
             AlgebraType::Adem => Self::AdemAlgebra(AdemAlgebra::new(spec.p, unstable)),
-            AlgebraType::Milnor => Self::MilnorAlgebra(MilnorAlgebra::new_with_profile(
-                spec.p,
-                spec.profile.unwrap_or_default(),
-                unstable,
-            )),
+            AlgebraType::Milnor => {
+                Self::MilnorAlgebra(MilnorAlgebra::new_with_profile(spec.p, profile, unstable))
+            }
+            AlgebraType::ComplexMotivic | AlgebraType::RealMotivic => {
+                return Err(anyhow!(
+                    "The motivic Steenrod algebras are not Steenrod algebras over F_p"
+                ));
+            }
         })
     }
 }
@@ -145,6 +214,12 @@ macro_rules! dispatch_steenrod {
 impl PairAlgebra for AdemAlgebra {
     type Element = crate::pair_algebra::MilnorPairElement;
 
+    fn check_pair_algebra(&self) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "The Adem basis does not support secondary computations; use the Milnor basis instead"
+        ))
+    }
+
     fn element_is_zero(_elt: &Self::Element) -> bool {
         unimplemented!()
     }
@@ -206,6 +281,7 @@ impl PairAlgebra for SteenrodAlgebra {
     type Element = crate::pair_algebra::MilnorPairElement;
 
     dispatch_steenrod! {
+        fn check_pair_algebra(&self) -> anyhow::Result<()>;
         fn p_tilde(&self) -> usize;
         fn new_pair_element(&self, degree: i32) -> Self::Element;
         fn sigma_multiply_basis(&self, result: &mut Self::Element, coeff: u32, r_degree: i32, r_idx: usize, s_degree: i32, s_idx: usize);
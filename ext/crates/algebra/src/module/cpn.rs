@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use fp::{
+    prime::{Binomial, Prime, ValidPrime},
+    vector::FpSliceMut,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    algebra::{
+        AdemAlgebra, Algebra, MilnorAlgebra, SteenrodAlgebra,
+        adem_algebra::AdemBasisElement,
+        milnor_algebra::{MilnorBasisElement, PPartEntry},
+    },
+    module::Module,
+};
+
+/// This is $\mathbb{CP}_{\mathrm{min}/2}^{\mathrm{max}/2}$. The cohomology is the subquotient of
+/// $\mathbb{F}_p[y^\pm]$ given by elements of degree between min and max (inclusive), where $y$
+/// has degree 2. In particular, `min` and `max` are the degrees of the bottom and top cells, as in
+/// [`RealProjectiveSpace`](crate::module::RealProjectiveSpace).
+pub struct ComplexProjectiveSpace<A: Algebra> {
+    algebra: Arc<A>,
+    pub min: i32,
+    pub max: Option<i32>, // If None, then CP^oo
+}
+
+impl<A: Algebra> std::fmt::Display for ComplexProjectiveSpace<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(max) = self.max {
+            write!(f, "CP^{}_{}", max / 2, self.min / 2)
+        } else {
+            write!(f, "CP_{}", self.min / 2)
+        }
+    }
+}
+
+impl<A: Algebra> PartialEq for ComplexProjectiveSpace<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.min == other.min && self.max == other.max
+    }
+}
+
+impl<A: Algebra> Eq for ComplexProjectiveSpace<A> {}
+
+impl<A: Algebra> Module for ComplexProjectiveSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    type Algebra = A;
+
+    fn algebra(&self) -> Arc<A> {
+        Arc::clone(&self.algebra)
+    }
+
+    fn min_degree(&self) -> i32 {
+        self.min
+    }
+
+    fn max_computed_degree(&self) -> i32 {
+        i32::MAX
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < self.min || degree % 2 != 0 || self.max.is_some_and(|m| degree > m) {
+            return 0;
+        }
+        1
+    }
+
+    fn basis_element_to_string(&self, degree: i32, _idx: usize) -> String {
+        format!("y^{{{}}}", degree / 2)
+    }
+
+    fn act_on_basis(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        assert!(op_index < self.algebra().dimension(op_degree));
+        assert!(mod_index < self.dimension(mod_degree));
+
+        let output_degree = mod_degree + op_degree;
+
+        if coeff == 0 || self.dimension(output_degree) == 0 {
+            return;
+        }
+
+        let k = mod_degree / 2;
+        let c = match &(&*self.algebra).try_into() {
+            Ok(SteenrodAlgebra::AdemAlgebra(a)) => coef_adem(a, op_degree, op_index, k),
+            Ok(SteenrodAlgebra::MilnorAlgebra(a)) => coef_milnor(a, op_degree, op_index, k),
+            // Other algebras are rejected by `check_steenrod` on construction
+            Err(_) => unreachable!(),
+        };
+        result.add_basis_element(0, c * coeff % self.algebra.prime());
+    }
+
+    fn max_degree(&self) -> Option<i32> {
+        self.max
+    }
+}
+
+/// Check that `algebra` is a Steenrod algebra. The projective spaces and Thom spectra are only
+/// modules over the Steenrod algebra, so this is checked when they are constructed instead of when
+/// an operation first acts on them.
+pub(super) fn check_steenrod<A>(algebra: &A, name: &str) -> anyhow::Result<()>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    match algebra.try_into() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(anyhow!("{name} only supports the Steenrod algebra")),
+    }
+}
+
+/// Compute the coefficient of $P(R)$ on $y^k$, where $P(R) y = y^{p^i}$ if $R$ is the $i$th unit
+/// vector and zero otherwise. This is the multinomial coefficient $\binom{k}{k - |R|, r_1, r_2,
+/// \ldots}$, where $k$ is allowed to be negative.
+pub(super) fn power_coef(p: ValidPrime, k: i32, r: &[PPartEntry]) -> u32 {
+    let sum: PPartEntry = r.iter().sum();
+    if sum == 0 {
+        return 1;
+    }
+    // The coefficient only depends on k modulo any power of p greater than |R|.
+    let mut period = 1;
+    while period <= sum {
+        period *= p.as_u32();
+    }
+    let k = k.rem_euclid(period as i32) as PPartEntry;
+    if k < sum {
+        return 0;
+    }
+
+    let mut list = Vec::with_capacity(r.len() + 1);
+    list.push(k - sum);
+    list.extend_from_slice(r);
+    PPartEntry::multinomial(p, &mut list)
+}
+
+/// The $P$ part of a Milnor basis element acting on the cohomology of $\mathbb{CP}^\infty$, where
+/// the generator has degree 2. At the prime 2, this is half of the $P$ part, or `None` if the
+/// element acts trivially.
+pub(super) fn complex_p_part(p: ValidPrime, elt: &MilnorBasisElement) -> Option<Vec<PPartEntry>> {
+    if p != 2 {
+        return Some(elt.p_part.clone());
+    }
+    if elt.p_part.iter().any(|r| r % 2 != 0) {
+        return None;
+    }
+    Some(elt.p_part.iter().map(|r| r / 2).collect())
+}
+
+// Compute the coefficient of the operation on y^k.
+fn coef_adem(algebra: &AdemAlgebra, op_deg: i32, op_idx: usize, mut k: i32) -> u32 {
+    let p = algebra.prime();
+    let elt: &AdemBasisElement = algebra.basis_element_from_index(op_deg, op_idx);
+    if elt.bocksteins != 0 {
+        return 0;
+    }
+    let mut c = 1;
+    for &i in elt.ps.iter().rev() {
+        let i = if p == 2 {
+            if i % 2 != 0 {
+                return 0;
+            }
+            i / 2
+        } else {
+            i
+        };
+        c = c * power_coef(p, k, &[i]) % p;
+        if c == 0 {
+            return 0;
+        }
+        k += i as i32 * (p.as_i32() - 1);
+    }
+    c
+}
+
+fn coef_milnor(algebra: &MilnorAlgebra, op_deg: i32, op_idx: usize, k: i32) -> u32 {
+    let p = algebra.prime();
+    let elt: &MilnorBasisElement = algebra.basis_element_from_index(op_deg, op_idx);
+    if elt.q_part != 0 {
+        return 0;
+    }
+    complex_p_part(p, elt).map_or(0, |r| power_coef(p, k, &r))
+}
+
+impl<A: Algebra> ComplexProjectiveSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    pub fn new(algebra: Arc<A>, min: i32, max: Option<i32>) -> Self {
+        check_steenrod(&*algebra, "Complex projective space").unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(min % 2, 0);
+        if let Some(max) = max {
+            assert!(max >= min);
+            assert_eq!(max % 2, 0);
+        }
+        Self { algebra, min, max }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct CPSpec {
+    min: i32,
+    max: Option<i32>,
+}
+
+impl<A: Algebra> ComplexProjectiveSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    pub fn from_json(algebra: Arc<A>, json: &Value) -> anyhow::Result<Self> {
+        check_steenrod(&*algebra, "Complex projective space")?;
+        let spec: CPSpec = CPSpec::deserialize(json)?;
+        anyhow::ensure!(
+            spec.min % 2 == 0 && spec.max.is_none_or(|m| m % 2 == 0),
+            "The cells of complex projective space are in even degrees"
+        );
+        if let Some(max) = spec.max {
+            anyhow::ensure!(max >= spec.min, "max must be at least min");
+        }
+
+        Ok(Self {
+            algebra,
+            min: spec.min,
+            max: spec.max,
+        })
+    }
+
+    pub fn to_json(&self, json: &mut Value) {
+        json["name"] = Value::String(self.to_string());
+        json["type"] = Value::from("complex projective space");
+        json["min"] = Value::from(self.min);
+        if let Some(max) = self.max {
+            json["max"] = Value::from(max);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fp::prime::ValidPrime;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{AdemAlgebra, MilnorAlgebra, module::FDModule};
+
+    #[rstest]
+    #[case(2, -10, 24)]
+    #[case(3, -20, 40)]
+    #[case(5, -16, 60)]
+    fn test_cpn_valid(#[case] p: u32, #[case] min: i32, #[case] max: i32) {
+        let p = ValidPrime::new(p);
+        for algebra in [
+            SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(p, false)),
+            SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(p, false)),
+        ] {
+            let algebra = Arc::new(algebra);
+            algebra.compute_basis(max - min);
+            let module = FDModule::from(&ComplexProjectiveSpace::new(algebra, min, Some(max)));
+            for input_deg in min..=max {
+                for output_deg in input_deg + 1..=max {
+                    module.check_validity(input_deg, output_deg).unwrap();
+                }
+            }
+        }
+    }
+}
@@ -28,6 +28,9 @@ pub struct MuFreeModule<const U: bool, A: MuAlgebra<U>> {
     name: String,
     min_degree: i32,
     gen_names: OnceBiVec<Vec<String>>,
+    /// degree -> generator index -> weight, for algebras with a weight grading. See
+    /// [`Module::basis_element_weight`].
+    gen_weights: OnceBiVec<Vec<i32>>,
     /// degree -> internal index of first generator in degree
     gen_deg_idx_to_internal_idx: OnceBiVec<usize>,
     num_gens: OnceBiVec<usize>,
@@ -51,6 +54,7 @@ impl<const U: bool, A: MuAlgebra<U>> MuFreeModule<U, A> {
             name,
             min_degree,
             gen_names: OnceBiVec::new(min_degree),
+            gen_weights: OnceBiVec::new(min_degree),
             gen_deg_idx_to_internal_idx,
             num_gens: OnceBiVec::new(min_degree),
             basis_element_to_opgen: OnceBiVec::new(min_degree),
@@ -137,6 +141,13 @@ impl<const U: bool, A: MuAlgebra<U>> Module for MuFreeModule<U, A> {
         )
     }
 
+    fn basis_element_weight(&self, degree: i32, idx: usize) -> i32 {
+        let opgen = self.index_to_op_gen(degree, idx);
+        self.algebra
+            .basis_element_weight(opgen.operation_degree, opgen.operation_index)
+            + self.generator_weight(opgen.generator_degree, opgen.generator_index)
+    }
+
     fn act_on_basis(
         &self,
         mut result: FpSliceMut,
@@ -243,7 +254,26 @@ impl<const U: bool, A: MuAlgebra<U>> MuFreeModule<U, A> {
         self.num_gens[degree]
     }
 
+    /// The weight of a generator, for algebras with a weight grading. This is zero unless it was
+    /// specified in [`add_generators_with_weights`](Self::add_generators_with_weights).
+    pub fn generator_weight(&self, degree: i32, idx: usize) -> i32 {
+        self.gen_weights[degree][idx]
+    }
+
     pub fn add_generators(&self, degree: i32, num_gens: usize, names: Option<Vec<String>>) {
+        self.add_generators_with_weights(degree, num_gens, names, vec![0; num_gens]);
+    }
+
+    /// Same as [`add_generators`](Self::add_generators), but also specifies the weights of the
+    /// generators. This is only meaningful for algebras with a weight grading.
+    pub fn add_generators_with_weights(
+        &self,
+        degree: i32,
+        num_gens: usize,
+        names: Option<Vec<String>>,
+        weights: Vec<i32>,
+    ) {
+        assert_eq!(weights.len(), num_gens);
         // We need to acquire the lock because changing num_gens modifies the behaviour of
         // extend_table_entries, and the two cannot happen concurrently.
         let _lock = self.basis_element_to_opgen.lock();
@@ -258,6 +288,7 @@ impl<const U: bool, A: MuAlgebra<U>> MuFreeModule<U, A> {
         });
 
         self.gen_names.push_checked(gen_names, degree);
+        self.gen_weights.push_checked(weights, degree);
         self.num_gens.push_checked(num_gens, degree);
 
         let internal_gen_idx = self.gen_deg_idx_to_internal_idx[degree];
@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use fp::{
+    prime::{Prime, ValidPrime},
+    vector::FpSliceMut,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::cpn::{check_steenrod, power_coef};
+use crate::{
+    algebra::{
+        AdemAlgebra, Algebra, MilnorAlgebra, SteenrodAlgebra,
+        adem_algebra::AdemBasisElement,
+        milnor_algebra::{MilnorBasisElement, PPartEntry},
+    },
+    module::Module,
+};
+
+/// This is the stunted lens space $L_{\mathrm{min}}^{\mathrm{max}}$ at an odd prime $p$, i.e. the
+/// $(\mathrm{min} - 1)$-skeleton of $B\mathbb{Z}/p$ collapsed from its $\mathrm{max}$-skeleton. The
+/// cohomology is the subquotient of $\Lambda(x) \otimes \mathbb{F}_p[y^\pm]$ given by elements of
+/// degree between min and max (inclusive), where $|x| = 1$, $|y| = 2$ and $\beta x = y$.
+pub struct LensSpace<A: Algebra> {
+    algebra: Arc<A>,
+    pub min: i32,
+    pub max: Option<i32>, // If None, then L^oo
+}
+
+impl<A: Algebra> std::fmt::Display for LensSpace<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if let Some(max) = self.max {
+            write!(f, "L^{max}_{}", self.min)
+        } else {
+            write!(f, "L_{}", self.min)
+        }
+    }
+}
+
+impl<A: Algebra> PartialEq for LensSpace<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.min == other.min && self.max == other.max
+    }
+}
+
+impl<A: Algebra> Eq for LensSpace<A> {}
+
+/// The basis element $x^\varepsilon y^k$ in degree $2k + \varepsilon$.
+#[derive(Clone, Copy)]
+struct Monomial {
+    x: bool,
+    k: i32,
+}
+
+impl Monomial {
+    fn from_degree(degree: i32) -> Self {
+        Self {
+            x: degree.rem_euclid(2) == 1,
+            k: degree.div_euclid(2),
+        }
+    }
+
+    /// Apply $P(R)$, returning the coefficient.
+    fn apply_p(&mut self, p: ValidPrime, r: &[PPartEntry]) -> u32 {
+        let c = power_coef(p, self.k, r);
+        self.k += r
+            .iter()
+            .enumerate()
+            .map(|(i, &r)| r as i32 * (p.pow(i as u32 + 1) as i32 - 1))
+            .sum::<i32>();
+        c
+    }
+
+    /// Apply $Q_i$, returning whether the result is non-zero.
+    fn apply_q(&mut self, p: ValidPrime, i: u32) -> bool {
+        if !self.x {
+            return false;
+        }
+        self.x = false;
+        self.k += p.pow(i) as i32;
+        true
+    }
+}
+
+impl<A: Algebra> Module for LensSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    type Algebra = A;
+
+    fn algebra(&self) -> Arc<A> {
+        Arc::clone(&self.algebra)
+    }
+
+    fn min_degree(&self) -> i32 {
+        self.min
+    }
+
+    fn max_computed_degree(&self) -> i32 {
+        i32::MAX
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < self.min || self.max.is_some_and(|m| degree > m) {
+            return 0;
+        }
+        1
+    }
+
+    fn basis_element_to_string(&self, degree: i32, _idx: usize) -> String {
+        let m = Monomial::from_degree(degree);
+        if m.x {
+            format!("x y^{{{}}}", m.k)
+        } else {
+            format!("y^{{{}}}", m.k)
+        }
+    }
+
+    fn act_on_basis(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        assert!(op_index < self.algebra().dimension(op_degree));
+        assert!(mod_index < self.dimension(mod_degree));
+
+        let output_degree = mod_degree + op_degree;
+
+        if coeff == 0 || self.dimension(output_degree) == 0 {
+            return;
+        }
+
+        let m = Monomial::from_degree(mod_degree);
+        let c = match &(&*self.algebra).try_into() {
+            Ok(SteenrodAlgebra::AdemAlgebra(a)) => coef_adem(a, op_degree, op_index, m),
+            Ok(SteenrodAlgebra::MilnorAlgebra(a)) => coef_milnor(a, op_degree, op_index, m),
+            // Other algebras are rejected by `check_steenrod` on construction
+            Err(_) => unreachable!(),
+        };
+        result.add_basis_element(0, c * coeff % self.algebra.prime());
+    }
+
+    fn max_degree(&self) -> Option<i32> {
+        self.max
+    }
+}
+
+// Compute the coefficient of the operation on m.
+fn coef_adem(algebra: &AdemAlgebra, op_deg: i32, op_idx: usize, mut m: Monomial) -> u32 {
+    let p = algebra.prime();
+    let elt: &AdemBasisElement = algebra.basis_element_from_index(op_deg, op_idx);
+    let mut c = 1;
+    // The element is b^{e_0} P^{i_1} b^{e_1} ... P^{i_n} b^{e_n}, which we apply from the right.
+    for j in (0..=elt.ps.len()).rev() {
+        if elt.bocksteins >> j & 1 == 1 && !m.apply_q(p, 0) {
+            return 0;
+        }
+        if j > 0 {
+            c = c * m.apply_p(p, &[elt.ps[j - 1]]) % p;
+            if c == 0 {
+                return 0;
+            }
+        }
+    }
+    c
+}
+
+fn coef_milnor(algebra: &MilnorAlgebra, op_deg: i32, op_idx: usize, mut m: Monomial) -> u32 {
+    let p = algebra.prime();
+    let elt: &MilnorBasisElement = algebra.basis_element_from_index(op_deg, op_idx);
+    let c = m.apply_p(p, &elt.p_part);
+    match elt.q_part.count_ones() {
+        0 => c,
+        1 if m.apply_q(p, elt.q_part.trailing_zeros()) => c,
+        _ => 0,
+    }
+}
+
+impl<A: Algebra> LensSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    pub fn new(algebra: Arc<A>, min: i32, max: Option<i32>) -> Self {
+        assert_ne!(algebra.prime(), 2);
+        check_steenrod(&*algebra, "Lens space").unwrap_or_else(|e| panic!("{e}"));
+        if let Some(max) = max {
+            assert!(max >= min);
+        }
+        Self { algebra, min, max }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct LensSpaceSpec {
+    min: i32,
+    max: Option<i32>,
+}
+
+impl<A: Algebra> LensSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    pub fn from_json(algebra: Arc<A>, json: &Value) -> anyhow::Result<Self> {
+        check_steenrod(&*algebra, "Lens space")?;
+        let spec: LensSpaceSpec = LensSpaceSpec::deserialize(json)?;
+        anyhow::ensure!(
+            algebra.prime() != 2,
+            "Lens spaces are only supported at odd primes"
+        );
+        if let Some(max) = spec.max {
+            anyhow::ensure!(max >= spec.min, "max must be at least min");
+        }
+
+        Ok(Self {
+            algebra,
+            min: spec.min,
+            max: spec.max,
+        })
+    }
+
+    pub fn to_json(&self, json: &mut Value) {
+        json["name"] = Value::String(self.to_string());
+        json["type"] = Value::from("lens space");
+        json["min"] = Value::from(self.min);
+        if let Some(max) = self.max {
+            json["max"] = Value::from(max);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fp::prime::ValidPrime;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{AdemAlgebra, MilnorAlgebra, module::FDModule};
+
+    #[rstest]
+    #[case(3, -13, 30)]
+    #[case(5, -8, 50)]
+    fn test_lens_space_valid(#[case] p: u32, #[case] min: i32, #[case] max: i32) {
+        let p = ValidPrime::new(p);
+        for algebra in [
+            SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(p, false)),
+            SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(p, false)),
+        ] {
+            let algebra = Arc::new(algebra);
+            algebra.compute_basis(max - min);
+            let module = FDModule::from(&LensSpace::new(algebra, min, Some(max)));
+            for input_deg in min..=max {
+                for output_deg in input_deg + 1..=max {
+                    module.check_validity(input_deg, output_deg).unwrap();
+                }
+            }
+        }
+    }
+}
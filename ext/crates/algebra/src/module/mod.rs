@@ -1,9 +1,14 @@
+mod cpn;
 mod finite_dimensional_module;
 mod finitely_presented_module;
 mod free_module;
+mod lens_space;
 mod module_trait;
+mod motivic_coefficients;
+mod polynomial_algebra_module;
 mod rpn;
 pub mod steenrod_module;
+mod thom_spectrum;
 mod zero_module;
 
 mod hom_module;
@@ -15,16 +20,21 @@ pub mod block_structure;
 
 pub mod homomorphism;
 
+pub use cpn::ComplexProjectiveSpace;
 pub use finite_dimensional_module::FiniteDimensionalModule as FDModule;
 pub use finitely_presented_module::FinitelyPresentedModule as FPModule;
 pub use free_module::{
     FreeModule, GeneratorData, MuFreeModule, OperationGeneratorPair, UnstableFreeModule,
 };
 pub use hom_module::HomModule;
+pub use lens_space::LensSpace;
 pub use module_trait::{ActError, Module, ModuleFailedRelationError};
+pub use motivic_coefficients::MotivicCoefficients;
+pub use polynomial_algebra_module::PolynomialAlgebraModule;
 pub use quotient_module::QuotientModule;
 pub use rpn::RealProjectiveSpace;
-pub use steenrod_module::SteenrodModule;
+pub use steenrod_module::{MotivicModule, SteenrodModule};
 pub use suspension_module::SuspensionModule;
 pub use tensor_module::TensorModule;
+pub use thom_spectrum::ThomSpectrum;
 pub use zero_module::ZeroModule;
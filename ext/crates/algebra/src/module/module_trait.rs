@@ -149,6 +149,13 @@ pub trait Module: std::fmt::Display + std::any::Any + Send + Sync {
     /// The name of a basis element. This is useful for debugging and printing results.
     fn basis_element_to_string(&self, degree: i32, idx: usize) -> String;
 
+    /// The weight of a basis element, for modules over algebras with a weight grading such as the
+    /// motivic Steenrod algebras. This defaults to 0.
+    #[allow(unused_variables)]
+    fn basis_element_weight(&self, degree: i32, idx: usize) -> i32 {
+        0
+    }
+
     /// Whether this is the unit module.
     fn is_unit(&self) -> bool {
         self.min_degree() == 0 && self.max_degree() == Some(0) && self.dimension(0) == 1
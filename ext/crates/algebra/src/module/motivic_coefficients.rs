@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use fp::{
+    prime::TWO,
+    vector::{FpSliceMut, FpVector},
+};
+use serde_json::Value;
+
+use crate::{
+    algebra::{
+        Algebra, MotivicBase, MotivicMilnorAlgebra, motivic_milnor_algebra::MotivicBasisElement,
+    },
+    module::Module,
+};
+
+/// The motivic cohomology of a point $\mathbb{M}_2$, as a module over the motivic Steenrod algebra.
+///
+/// This is $\mathbb{F}_2[\tau]$ over $\mathbb{C}$ and $\mathbb{F}_2[\tau, \rho]$ over
+/// $\mathbb{R}$, and is the cohomology of the motivic sphere. As for the algebra, the degree of
+/// $\tau^a \rho^b$ is its motivic degree $a + 2b$, and the basis in each degree is ordered by the
+/// power of $\rho$.
+///
+/// The action of $\theta$ on $x$ is the $\mathbb{M}_2$ component of the product $\theta x$ in the
+/// algebra. Over $\mathbb{C}$ the positive degree Milnor basis elements act trivially, but over
+/// $\mathbb{R}$ we have e.g. $\mathrm{Sq}^1 \tau = \rho$.
+pub struct MotivicCoefficients {
+    algebra: Arc<MotivicMilnorAlgebra>,
+    base: MotivicBase,
+}
+
+impl std::fmt::Display for MotivicCoefficients {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "M_2({})", self.base)
+    }
+}
+
+impl MotivicCoefficients {
+    pub fn new(algebra: Arc<MotivicMilnorAlgebra>) -> Self {
+        let base = algebra.base();
+        Self { algebra, base }
+    }
+
+    pub fn from_json(algebra: Arc<MotivicMilnorAlgebra>, _json: &Value) -> anyhow::Result<Self> {
+        Ok(Self::new(algebra))
+    }
+
+    pub fn to_json(&self, json: &mut Value) {
+        json["name"] = Value::String(self.to_string());
+        json["type"] = Value::from("motivic coefficients");
+    }
+
+    /// The exponents $(a, b)$ of the basis element $\tau^a \rho^b$.
+    pub fn basis_element(&self, degree: i32, idx: usize) -> (u32, u32) {
+        (degree as u32 - 2 * idx as u32, idx as u32)
+    }
+}
+
+impl Module for MotivicCoefficients {
+    type Algebra = MotivicMilnorAlgebra;
+
+    fn algebra(&self) -> Arc<MotivicMilnorAlgebra> {
+        Arc::clone(&self.algebra)
+    }
+
+    fn min_degree(&self) -> i32 {
+        0
+    }
+
+    fn compute_basis(&self, degree: i32) {
+        self.algebra.compute_basis(degree);
+    }
+
+    fn max_computed_degree(&self) -> i32 {
+        i32::MAX
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < 0 {
+            0
+        } else {
+            match self.base {
+                MotivicBase::Complex => 1,
+                MotivicBase::Real => degree as usize / 2 + 1,
+            }
+        }
+    }
+
+    fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        let (tau, rho) = self.basis_element(degree, idx);
+        MotivicBasisElement {
+            tau,
+            rho,
+            degree,
+            ..Default::default()
+        }
+        .to_string()
+    }
+
+    fn basis_element_weight(&self, degree: i32, idx: usize) -> i32 {
+        let (a, b) = self.basis_element(degree, idx);
+        (a + b) as i32
+    }
+
+    fn act_on_basis(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        let algebra = &*self.algebra;
+        let output_degree = op_degree + mod_degree;
+        let (a, b) = self.basis_element(mod_degree, mod_index);
+        algebra.compute_basis(output_degree);
+
+        let mut product = FpVector::new(TWO, algebra.dimension(output_degree));
+        algebra.multiply_basis_elements(
+            product.as_slice_mut(),
+            1,
+            op_degree,
+            op_index,
+            mod_degree,
+            algebra.coefficient_index(a, b),
+        );
+        for (i, _) in product.iter_nonzero() {
+            let elt = algebra.basis_element_from_index(output_degree, i);
+            if elt.q_part == 0 && elt.p_part.is_empty() {
+                result.add_basis_element(elt.rho as usize, coeff);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    #[test]
+    fn test_action() {
+        let algebra = Arc::new(MotivicMilnorAlgebra::new(MotivicBase::Real));
+        let module = MotivicCoefficients::new(Arc::clone(&algebra));
+
+        let mut output = String::new();
+        for op in ["Sq1", "tau", "rho", "Sq2"] {
+            let (op_degree, op_index) = algebra.basis_element_from_string(op).unwrap();
+            for mod_degree in 1..=2 {
+                let degree = op_degree + mod_degree;
+                module.compute_basis(degree);
+                for idx in 0..module.dimension(mod_degree) {
+                    let mut result = FpVector::new(TWO, module.dimension(degree));
+                    module.act_on_basis(
+                        result.as_slice_mut(),
+                        1,
+                        op_degree,
+                        op_index,
+                        mod_degree,
+                        idx,
+                    );
+                    output += &format!(
+                        "{op} * {} = {}\n",
+                        module.basis_element_to_string(mod_degree, idx),
+                        module.element_to_string(degree, result.as_slice())
+                    );
+                }
+            }
+        }
+        expect![[r#"
+            Sq1 * tau = rho
+            Sq1 * tau^2 = 0
+            Sq1 * rho = 0
+            tau * tau = tau^2
+            tau * tau^2 = tau^3
+            tau * rho = tau rho
+            rho * tau = tau rho
+            rho * tau^2 = tau^2 rho
+            rho * rho = rho^2
+            Sq2 * tau = 0
+            Sq2 * tau^2 = tau rho^2
+            Sq2 * rho = 0
+        "#]]
+        .assert_eq(&output);
+    }
+}
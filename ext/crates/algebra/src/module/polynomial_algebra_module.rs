@@ -0,0 +1,1068 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, anyhow};
+use fp::{
+    prime::{Prime, minus_one_to_the_n},
+    vector::{FpSlice, FpSliceMut, FpVector},
+};
+use itertools::Itertools;
+use once::OnceBiVec;
+use rustc_hash::FxHashMap as HashMap;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::{
+    algebra::{
+        AdemAlgebra, Algebra, Bialgebra, GeneratedAlgebra, SteenrodAlgebra, UnstableAlgebra,
+        adem_algebra::AdemBasisElement,
+    },
+    module::Module,
+    steenrod_evaluator::SteenrodEvaluator,
+};
+
+/// A monomial, recorded as the exponents of the generators.
+type Monomial = Vec<u32>;
+
+struct Generator {
+    name: String,
+    degree: i32,
+    exterior: bool,
+}
+
+/// The monomials in a fixed degree.
+struct Basis {
+    monomials: Vec<Monomial>,
+    index: HashMap<Monomial, usize>,
+}
+
+/// A graded commutative algebra $P[x_1, \ldots] \otimes E[y_1, \ldots]$ with an action of a
+/// bialgebra, determined by the action on the generators via the Cartan formula.
+///
+/// This models the cohomology of spaces such as $B\mathbb{Z}/p$, $\mathbb{CP}^\infty$ and
+/// $BU(n)$. The module is unbounded, but can be truncated at a maximum degree. The basis in each
+/// degree consists of the monomials in the generators, where the generators are ordered by degree.
+///
+/// The action of the generators of the algebra on the generators of the module is specified
+/// explicitly, and the action of any other element is computed by decomposing it into products
+/// of generators. For the Cartan formula, we use [`Bialgebra::decompose`] and
+/// [`Bialgebra::coproduct`].
+pub struct PolynomialAlgebraModule<A: Algebra> {
+    algebra: Arc<A>,
+    name: String,
+    generators: Vec<Generator>,
+    /// `actions[k]` maps an algebra generator to its action on the `k`th generator. Missing
+    /// entries act by zero.
+    actions: Vec<HashMap<(i32, usize), FpVector>>,
+    reduced: bool,
+    max: Option<i32>,
+    basis: OnceBiVec<Basis>,
+    /// The action of arbitrary algebra elements on the generators.
+    generator_cache: Mutex<HashMap<(i32, usize, usize), FpVector>>,
+}
+
+impl<A: Algebra> std::fmt::Display for PolynomialAlgebraModule<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if !self.name.is_empty() {
+            return write!(f, "{}", self.name);
+        }
+        let polynomial = self.generators.iter().filter(|g| !g.exterior);
+        let exterior = self.generators.iter().filter(|g| g.exterior);
+        write!(f, "P[{}]", polynomial.map(|g| &g.name).join(", "))?;
+        if self.generators.iter().any(|g| g.exterior) {
+            write!(f, " ⊗ E[{}]", exterior.map(|g| &g.name).join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct PolynomialAlgebraSpec {
+    #[serde(default)]
+    polynomial: serde_json::Map<String, Value>,
+    #[serde(default)]
+    exterior: serde_json::Map<String, Value>,
+    #[serde(default)]
+    actions: Vec<String>,
+    #[serde(default)]
+    reduced: bool,
+    max: Option<i32>,
+}
+
+impl<A: Algebra + Bialgebra + GeneratedAlgebra> PolynomialAlgebraModule<A> {
+    /// Create a module with no actions. The generators are given as `(name, degree, exterior)`.
+    pub fn new(
+        algebra: Arc<A>,
+        name: String,
+        generators: impl IntoIterator<Item = (String, i32, bool)>,
+        reduced: bool,
+        max: Option<i32>,
+    ) -> anyhow::Result<Self> {
+        let p = algebra.prime();
+        let mut generators: Vec<Generator> = generators
+            .into_iter()
+            .map(|(name, degree, exterior)| Generator {
+                name,
+                degree,
+                exterior,
+            })
+            .collect();
+        generators.sort_by_key(|g| g.degree);
+        for g in &generators {
+            anyhow::ensure!(
+                g.degree > 0,
+                "Generator {} must have positive degree",
+                g.name
+            );
+            anyhow::ensure!(
+                p == 2 || g.exterior || g.degree % 2 == 0,
+                "Polynomial generator {} must have even degree",
+                g.name
+            );
+        }
+        anyhow::ensure!(
+            generators.iter().map(|g| &g.name).all_unique(),
+            "Generator names must be distinct"
+        );
+
+        Ok(Self {
+            actions: generators.iter().map(|_| HashMap::default()).collect(),
+            algebra,
+            name,
+            generators,
+            reduced,
+            max,
+            basis: OnceBiVec::new(0),
+            generator_cache: Mutex::new(HashMap::default()),
+        })
+    }
+
+    pub fn from_json(algebra: Arc<A>, json: &Value) -> anyhow::Result<Self> {
+        let spec = PolynomialAlgebraSpec::deserialize(json)?;
+        let name = json["name"].as_str().unwrap_or("").to_string();
+
+        let mut generators = Vec::new();
+        for (gens, exterior) in [(&spec.polynomial, false), (&spec.exterior, true)] {
+            for (gen_name, degree) in gens {
+                let degree = degree
+                    .as_i64()
+                    .ok_or_else(|| anyhow!("Invalid degree of {gen_name}"))?;
+                generators.push((gen_name.clone(), degree as i32, exterior));
+            }
+        }
+        let mut result = Self::new(algebra, name, generators, spec.reduced, spec.max)?;
+        result.algebra.compute_basis(result.max_relation_degree());
+
+        for action in &spec.actions {
+            result
+                .parse_action(action)
+                .with_context(|| format!("Failed to parse action: {action}"))?;
+        }
+        result.check_validity()?;
+        Ok(result)
+    }
+
+    pub fn to_json(&self, json: &mut Value) {
+        if !self.name.is_empty() {
+            json["name"] = Value::String(self.name.clone());
+        }
+        json["type"] = Value::from("polynomial algebra");
+        json["polynomial"] = json!({});
+        json["exterior"] = json!({});
+        for g in &self.generators {
+            let key = if g.exterior { "exterior" } else { "polynomial" };
+            json[key][&g.name] = Value::from(g.degree);
+        }
+        let mut actions = Vec::new();
+        for (k, g) in self.generators.iter().enumerate() {
+            for (&(op_deg, op_idx), value) in self.actions[k].iter().sorted_by_key(|x| x.0) {
+                actions.push(format!(
+                    "{} {} = {}",
+                    self.algebra.basis_element_to_string(op_deg, op_idx),
+                    g.name,
+                    self.element_to_string(g.degree + op_deg, value.as_slice())
+                ));
+            }
+        }
+        json["actions"] = Value::from(actions);
+        if self.reduced {
+            json["reduced"] = Value::Bool(true);
+        }
+        if let Some(max) = self.max {
+            json["max"] = Value::from(max);
+        }
+    }
+
+    /// Parse an action of the form `op x = poly`, where `op` is a generator of the algebra and `x`
+    /// is a generator of the module.
+    pub fn parse_action(&mut self, entry: &str) -> anyhow::Result<()> {
+        let (lhs, rhs) = entry
+            .split_once(" = ")
+            .ok_or_else(|| anyhow!("Invalid action: {entry}"))?;
+        let (op, g) = lhs
+            .rsplit_once(' ')
+            .ok_or_else(|| anyhow!("Invalid action: {entry}"))?;
+
+        let (op_deg, op_idx) = self
+            .algebra
+            .basis_element_from_string(op)
+            .ok_or_else(|| anyhow!("Invalid algebra element: {op}"))?;
+        self.algebra.compute_basis(op_deg);
+        anyhow::ensure!(
+            self.algebra.generators(op_deg).contains(&op_idx),
+            "{op} is not a generator of the algebra"
+        );
+        let k = self
+            .generator_index(g.trim())
+            .ok_or_else(|| anyhow!("Unknown generator: {g}"))?;
+
+        let value = self.parse_element(self.generators[k].degree + op_deg, rhs.trim())?;
+        self.actions[k].insert((op_deg, op_idx), value);
+        self.generator_cache.lock().unwrap().clear();
+        Ok(())
+    }
+
+    /// The largest degree of a relation checked by [`Self::check_validity`].
+    fn max_relation_degree(&self) -> i32 {
+        let p = self.algebra.prime();
+        let q = if p == 2 { 1 } else { 2 * (p.as_i32() - 1) };
+        q * self.generators.last().map_or(0, |g| g.degree) + 1
+    }
+
+    /// Check that the relations of the algebra hold on the generators, for relations in degrees
+    /// that are at most $q$ times the degree of the generator (plus one).
+    fn check_validity(&self) -> anyhow::Result<()> {
+        let p = self.algebra.prime();
+        let q = if p == 2 { 1 } else { 2 * (p.as_i32() - 1) };
+        for (k, g) in self.generators.iter().enumerate() {
+            let input_degree = g.degree;
+            let input_idx = self.basis(input_degree).index[&self.generator_monomial(k)];
+            for op_deg in 2..=q * g.degree + 1 {
+                for relation in self.algebra.generating_relations(op_deg) {
+                    let mut output =
+                        FpVector::new(p, self.basis(input_degree + op_deg).monomials.len());
+                    for &(c, (deg_1, idx_1), (deg_2, idx_2)) in &relation {
+                        let mut intermediate =
+                            FpVector::new(p, self.basis(input_degree + deg_2).monomials.len());
+                        self.act_monomial(
+                            intermediate.as_slice_mut(),
+                            1,
+                            deg_2,
+                            idx_2,
+                            input_degree,
+                            input_idx,
+                        );
+                        self.act_polynomial(
+                            output.as_slice_mut(),
+                            c,
+                            deg_1,
+                            idx_1,
+                            input_degree + deg_2,
+                            intermediate.as_slice(),
+                        );
+                    }
+                    anyhow::ensure!(
+                        output.is_zero(),
+                        "Relation {} fails on {}: got {}",
+                        relation
+                            .iter()
+                            .map(|&(c, (d1, i1), (d2, i2))| {
+                                format!(
+                                    "{c} {} {}",
+                                    self.algebra.basis_element_to_string(d1, i1),
+                                    self.algebra.basis_element_to_string(d2, i2)
+                                )
+                            })
+                            .join(" + "),
+                        g.name,
+                        self.element_to_string(input_degree + op_deg, output.as_slice())
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<A: Algebra + Bialgebra + GeneratedAlgebra> PolynomialAlgebraModule<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    /// The cohomology of the Eilenberg–MacLane space $K(\mathbb{Z}/p, n)$, truncated above degree
+    /// `max`.
+    ///
+    /// By a theorem of Serre and Cartan, this is the free graded commutative algebra on the
+    /// classes $P^I \iota_n$, where $I$ is admissible and either $e(I) < n$, or $e(I) = n$ and $I$
+    /// starts with a Bockstein. The generator $P^I \iota_n$ is named after $I$, e.g. `Sq2Sq1x`,
+    /// where `x` is the fundamental class.
+    ///
+    /// The action is computed in the unstable Adem algebra. We write $\theta P^I$ in the
+    /// admissible basis modulo elements of excess greater than $n$, and evaluate the terms of
+    /// excess $n$ that are not generators as $p$th powers.
+    pub fn eilenberg_maclane(
+        algebra: Arc<A>,
+        n: i32,
+        reduced: bool,
+        max: i32,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(n > 0, "Eilenberg–MacLane spaces must have positive degree");
+        let p = algebra.prime();
+        algebra.compute_basis(max - n);
+        let adem = AdemAlgebra::new(p, true);
+        adem.compute_basis(max - n);
+
+        let mut names = Vec::new();
+        for degree in 0..=max - n {
+            for idx in 0..adem.dimension(degree) {
+                let elt = adem.basis_element_from_index(degree, idx);
+                let excess = elt.excess(p) as i32;
+                if excess < n || (p != 2 && excess == n && elt.bocksteins & 1 == 1) {
+                    let name = format!("{}x", elt.to_string().replace(' ', ""));
+                    names.push(((degree, idx), name));
+                }
+            }
+        }
+        let generators = names.iter().map(|((degree, _), name)| {
+            let exterior = p != 2 && (n + degree) % 2 == 1;
+            (name.clone(), n + degree, exterior)
+        });
+        let module_name = format!("K(Z/{p}, {n})");
+        let mut result = Self::new(algebra, module_name, generators, reduced, Some(max))?;
+
+        let evaluator = SteenrodEvaluator::new(p);
+        evaluator.adem.compute_basis(max - n);
+        evaluator.milnor.compute_basis(max - n);
+        let em = EilenbergMacLane {
+            generators: names
+                .iter()
+                .map(|(key, name)| (*key, result.generator_index(name).unwrap()))
+                .collect(),
+            module: &result,
+            algebra: (&*result.algebra).try_into().ok().ok_or_else(|| {
+                anyhow!("Eilenberg–MacLane spaces are only supported over the Steenrod algebra")
+            })?,
+            adem,
+            evaluator,
+            n,
+        };
+        let mut actions = Vec::new();
+        for op_deg in 1..=max - n {
+            for op_idx in result.algebra.generators(op_deg) {
+                let op = em.to_adem(op_deg, op_idx);
+                for (&(degree, idx), &k) in &em.generators {
+                    if n + degree + op_deg > max {
+                        continue;
+                    }
+                    let value = em.act_on_generator(op_deg, &op, degree, idx);
+                    if !value.is_zero() {
+                        actions.push((k, (op_deg, op_idx), value));
+                    }
+                }
+            }
+        }
+        for (k, op, value) in actions {
+            result.actions[k].insert(op, value);
+        }
+        Ok(result)
+    }
+
+    pub fn eilenberg_maclane_from_json(algebra: Arc<A>, json: &Value) -> anyhow::Result<Self> {
+        let n = json["n"]
+            .as_i64()
+            .ok_or_else(|| anyhow!("Missing or invalid degree n"))?;
+        let max = json["max"]
+            .as_i64()
+            .ok_or_else(|| anyhow!("Missing or invalid maximum degree"))?;
+        let reduced = json["reduced"].as_bool().unwrap_or(false);
+        Self::eilenberg_maclane(algebra, n as i32, reduced, max as i32)
+    }
+}
+
+/// Helper for computing the action on the cohomology of $K(\mathbb{Z}/p, n)$. Admissible
+/// monomials are indexed by their position in the basis of `adem`, which has the unstable
+/// algebra enabled.
+struct EilenbergMacLane<'a, A: Algebra> {
+    module: &'a PolynomialAlgebraModule<A>,
+    /// The algebra of the module, which is a Steenrod algebra.
+    algebra: &'a SteenrodAlgebra,
+    adem: AdemAlgebra,
+    /// Used to convert from the Milnor basis.
+    evaluator: SteenrodEvaluator,
+    n: i32,
+    /// The generators of the module, indexed by the corresponding admissible monomial.
+    generators: HashMap<(i32, usize), usize>,
+}
+
+impl<A: Algebra> EilenbergMacLane<'_, A> {
+    /// Express a generator of the algebra in the admissible basis.
+    fn to_adem(&self, degree: i32, idx: usize) -> FpVector {
+        let p = self.adem.prime();
+        let mut result = FpVector::new(p, self.adem.dimension(degree));
+        match self.algebra {
+            SteenrodAlgebra::AdemAlgebra(algebra) => {
+                let elt = algebra.basis_element_from_index(degree, idx);
+                result.set_entry(self.adem.basis_element_to_index(elt), 1);
+            }
+            SteenrodAlgebra::MilnorAlgebra(algebra) => {
+                let elt = algebra.basis_element_from_index(degree, idx);
+                let mut milnor = FpVector::new(p, self.evaluator.milnor.dimension(degree));
+                milnor.set_entry(self.evaluator.milnor.basis_element_to_index(elt), 1);
+                let mut adem = FpVector::new(p, self.evaluator.adem.dimension(degree));
+                self.evaluator.milnor_to_adem(&mut adem, 1, degree, &milnor);
+
+                // The unstable algebra orders the admissible monomials by excess
+                for (i, c) in adem.iter_nonzero() {
+                    let elt = self.evaluator.adem.basis_element_from_index(degree, i);
+                    result.add_basis_element(self.adem.basis_element_to_index(elt), c);
+                }
+            }
+        }
+        result
+    }
+
+    /// The action of `op` on the generator $P^I \iota_n$, where $I$ is given by `degree` and `idx`.
+    fn act_on_generator(&self, op_deg: i32, op: &FpVector, degree: i32, idx: usize) -> FpVector {
+        let p = self.adem.prime();
+        let mut product = FpVector::new(p, self.adem.dimension(degree + op_deg));
+        for (i, c) in op.iter_nonzero() {
+            self.adem.multiply_basis_elements_unstable(
+                product.as_slice_mut(),
+                c,
+                op_deg,
+                i,
+                degree,
+                idx,
+                self.n,
+            );
+        }
+        let mut result = FpVector::new(
+            p,
+            self.module.basis(self.n + degree + op_deg).monomials.len(),
+        );
+        for (i, c) in product.iter_nonzero() {
+            result.add(&self.evaluate(degree + op_deg, i), c);
+        }
+        result
+    }
+
+    /// Evaluate $P^I \iota_n$, where $I$ is admissible of excess at most $n$.
+    fn evaluate(&self, degree: i32, idx: usize) -> FpVector {
+        let p = self.adem.prime();
+        let module_degree = self.n + degree;
+        let basis = self.module.basis(module_degree);
+        let mut result = FpVector::new(p, basis.monomials.len());
+        if let Some(&k) = self.generators.get(&(degree, idx)) {
+            result.set_entry(basis.index[&self.module.generator_monomial(k)], 1);
+            return result;
+        }
+
+        // Now I = P^s J, where P^s acts on P^J \iota_n as the pth power.
+        let elt = self.adem.basis_element_from_index(degree, idx);
+        debug_assert_eq!(elt.bocksteins & 1, 0);
+        let q = self.adem.q();
+        let tail = AdemBasisElement {
+            degree: degree - q * elt.ps[0] as i32,
+            bocksteins: elt.bocksteins >> 1,
+            ps: elt.ps[1..].to_vec(),
+            p_or_sq: elt.p_or_sq,
+        };
+        let tail_idx = self.adem.basis_element_to_index(&tail);
+        let tail_value = self.evaluate(tail.degree, tail_idx);
+
+        let tail_basis = self.module.basis(self.n + tail.degree);
+        for (i, c) in tail_value.iter_nonzero() {
+            let power: Monomial = tail_basis.monomials[i]
+                .iter()
+                .map(|&e| e * p.as_u32())
+                .collect();
+            // A power of an exterior generator vanishes
+            if let Some(&j) = basis.index.get(&power) {
+                result.add_basis_element(j, c);
+            }
+        }
+        result
+    }
+}
+
+impl<A: Algebra> PolynomialAlgebraModule<A> {
+    fn generator_index(&self, name: &str) -> Option<usize> {
+        self.generators.iter().position(|g| g.name == name)
+    }
+
+    fn generator_monomial(&self, k: usize) -> Monomial {
+        let mut monomial = vec![0; self.generators.len()];
+        monomial[k] = 1;
+        monomial
+    }
+
+    /// The monomials in degree `degree`, ignoring the truncation.
+    fn basis(&self, degree: i32) -> &Basis {
+        self.basis.extend(degree, |d| {
+            let mut monomials = Vec::new();
+            self.monomials(d, 0, &mut vec![0; self.generators.len()], &mut monomials);
+            let index = monomials
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (m.clone(), i))
+                .collect();
+            Basis { monomials, index }
+        });
+        &self.basis[degree]
+    }
+
+    /// Append the monomials of degree `degree` in the generators starting from `start` to
+    /// `result`, where `current` records the exponents of the earlier generators.
+    fn monomials(
+        &self,
+        degree: i32,
+        start: usize,
+        current: &mut Monomial,
+        result: &mut Vec<Monomial>,
+    ) {
+        if degree == 0 {
+            result.push(current.clone());
+            return;
+        }
+        let Some(g) = self.generators.get(start) else {
+            return;
+        };
+        let mut max_exponent = degree / g.degree;
+        if g.exterior {
+            max_exponent = max_exponent.min(1);
+        }
+        for e in (0..=max_exponent).rev() {
+            current[start] = e as u32;
+            self.monomials(degree - e * g.degree, start + 1, current, result);
+        }
+        current[start] = 0;
+    }
+
+    fn monomial_to_string(&self, monomial: &Monomial) -> String {
+        let factors: Vec<String> = std::iter::zip(&self.generators, monomial)
+            .filter(|(_, e)| **e > 0)
+            .map(|(g, &e)| {
+                if e == 1 {
+                    g.name.clone()
+                } else {
+                    format!("{}^{e}", g.name)
+                }
+            })
+            .collect();
+        if factors.is_empty() {
+            "1".to_string()
+        } else {
+            factors.join(" ")
+        }
+    }
+
+    fn element_to_string(&self, degree: i32, element: FpSlice) -> String {
+        let basis = self.basis(degree);
+        let terms = element
+            .iter_nonzero()
+            .map(|(i, c)| {
+                let monomial = self.monomial_to_string(&basis.monomials[i]);
+                if c == 1 {
+                    monomial
+                } else {
+                    format!("{c} {monomial}")
+                }
+            })
+            .join(" + ");
+        if terms.is_empty() {
+            "0".to_string()
+        } else {
+            terms
+        }
+    }
+
+    fn parse_element(&self, degree: i32, s: &str) -> anyhow::Result<FpVector> {
+        let p = self.algebra.prime();
+        let basis = self.basis(degree);
+        let mut result = FpVector::new(p, basis.monomials.len());
+        if s == "0" {
+            return Ok(result);
+        }
+        for term in s.split(" + ") {
+            let mut tokens = term.split_whitespace().peekable();
+            let coef = match tokens.peek().and_then(|x| x.parse::<i64>().ok()) {
+                Some(c) => {
+                    tokens.next();
+                    c.rem_euclid(p.as_i32() as i64) as u32
+                }
+                None => 1,
+            };
+            let mut monomial = vec![0; self.generators.len()];
+            for token in tokens {
+                let (name, exponent) = match token.split_once('^') {
+                    Some((name, e)) => (name, e.parse::<u32>()?),
+                    None => (token, 1),
+                };
+                let k = self
+                    .generator_index(name)
+                    .ok_or_else(|| anyhow!("Unknown generator: {name}"))?;
+                monomial[k] += exponent;
+            }
+            let term_degree: i32 = std::iter::zip(&self.generators, &monomial)
+                .map(|(g, &e)| g.degree * e as i32)
+                .sum();
+            anyhow::ensure!(term_degree == degree, "{term} has the wrong degree");
+            // Terms with repeated exterior generators vanish.
+            if let Some(&i) = basis.index.get(&monomial) {
+                result.add_basis_element(i, coef);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Add `coeff` times the product of two monomials to `result`.
+    fn multiply(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        (d1, i1): (i32, usize),
+        (d2, i2): (i32, usize),
+    ) {
+        let m1 = &self.basis(d1).monomials[i1];
+        let m2 = &self.basis(d2).monomials[i2];
+        let product: Monomial = std::iter::zip(m1, m2).map(|(a, b)| a + b).collect();
+        let Some(&idx) = self.basis(d1 + d2).index.get(&product) else {
+            // A repeated exterior generator
+            return;
+        };
+        // The sign of moving the odd generators of m2 past the later odd generators of m1
+        let mut swaps = 0;
+        let mut later_odd = 0;
+        for (k, g) in self.generators.iter().enumerate().rev() {
+            if g.degree % 2 == 1 {
+                swaps += m2[k] * later_odd;
+                later_odd += m1[k];
+            }
+        }
+        result.add_basis_element(
+            idx,
+            coeff * minus_one_to_the_n(self.algebra.prime(), swaps as i32)
+                % self.algebra.prime().as_u32(),
+        );
+    }
+}
+
+impl<A: Algebra + Bialgebra + GeneratedAlgebra> PolynomialAlgebraModule<A> {
+    /// The action on a monomial, ignoring the truncation.
+    fn act_monomial(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        if op_degree == 0 {
+            result.add_basis_element(mod_index, coeff);
+            return;
+        }
+        let p = self.algebra.prime();
+        let decomposition = self.algebra.decompose(op_degree, op_index);
+        let (&(last_deg, last_idx), rest) = decomposition.split_last().unwrap();
+        if rest.is_empty() {
+            self.act_piece(result, coeff, op_degree, op_index, mod_degree, mod_index);
+            return;
+        }
+
+        let mut working_degree = mod_degree;
+        let mut working_element = FpVector::new(p, self.basis(mod_degree).monomials.len());
+        working_element.set_entry(mod_index, 1);
+        for &(op_deg, op_idx) in rest {
+            let mut new_element =
+                FpVector::new(p, self.basis(working_degree + op_deg).monomials.len());
+            for (i, c) in working_element.iter_nonzero() {
+                self.act_piece(
+                    new_element.as_slice_mut(),
+                    c,
+                    op_deg,
+                    op_idx,
+                    working_degree,
+                    i,
+                );
+            }
+            working_element = new_element;
+            working_degree += op_deg;
+        }
+        for (i, c) in working_element.iter_nonzero() {
+            self.act_piece(
+                result.copy(),
+                (c * coeff) % p.as_u32(),
+                last_deg,
+                last_idx,
+                working_degree,
+                i,
+            );
+        }
+    }
+
+    fn act_polynomial(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        input: FpSlice,
+    ) {
+        let p = self.algebra.prime();
+        for (i, c) in input.iter_nonzero() {
+            self.act_monomial(
+                result.copy(),
+                (c * coeff) % p.as_u32(),
+                op_degree,
+                op_index,
+                mod_degree,
+                i,
+            );
+        }
+    }
+
+    /// The action of an element returned by [`Bialgebra::decompose`], using the Cartan formula.
+    fn act_piece(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        if op_degree == 0 {
+            result.add_basis_element(mod_index, coeff);
+            return;
+        }
+        if mod_degree == 0 {
+            return;
+        }
+        let p = self.algebra.prime();
+        let monomial = &self.basis(mod_degree).monomials[mod_index];
+        let k = monomial.iter().position(|&e| e > 0).unwrap();
+        let gen_degree = self.generators[k].degree;
+        if mod_degree == gen_degree {
+            self.act_on_generator(result, coeff, op_degree, op_index, k);
+            return;
+        }
+
+        // Write the monomial as x * rest, where x is its first generator
+        let mut rest = monomial.clone();
+        rest[k] -= 1;
+        let rest_degree = mod_degree - gen_degree;
+        let rest_index = self.basis(rest_degree).index[&rest];
+
+        for (deg_l, idx_l, deg_r, idx_r) in self.algebra.coproduct(op_degree, op_index) {
+            let mut left = FpVector::new(p, self.basis(gen_degree + deg_l).monomials.len());
+            self.act_on_generator(left.as_slice_mut(), 1, deg_l, idx_l, k);
+            if left.is_zero() {
+                continue;
+            }
+            let mut right = FpVector::new(p, self.basis(rest_degree + deg_r).monomials.len());
+            self.act_monomial(
+                right.as_slice_mut(),
+                1,
+                deg_r,
+                idx_r,
+                rest_degree,
+                rest_index,
+            );
+            let sign = minus_one_to_the_n(p, deg_r * gen_degree);
+            for (i, c) in left.iter_nonzero() {
+                for (j, d) in right.iter_nonzero() {
+                    self.multiply(
+                        result.copy(),
+                        coeff * c % p.as_u32() * d % p.as_u32() * sign % p.as_u32(),
+                        (gen_degree + deg_l, i),
+                        (rest_degree + deg_r, j),
+                    );
+                }
+            }
+        }
+    }
+
+    fn act_on_generator(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        k: usize,
+    ) {
+        let gen_degree = self.generators[k].degree;
+        if op_degree == 0 {
+            let idx = self.basis(gen_degree).index[&self.generator_monomial(k)];
+            result.add_basis_element(idx, coeff);
+            return;
+        }
+
+        let key = (op_degree, op_index, k);
+        let cached = self.generator_cache.lock().unwrap().get(&key).cloned();
+        let value = cached.unwrap_or_else(|| {
+            let value = self.compute_action_on_generator(op_degree, op_index, k);
+            self.generator_cache
+                .lock()
+                .unwrap()
+                .insert(key, value.clone());
+            value
+        });
+        result.add(value.as_slice(), coeff);
+    }
+
+    fn compute_action_on_generator(&self, op_degree: i32, op_index: usize, k: usize) -> FpVector {
+        let p = self.algebra.prime();
+        let gen_degree = self.generators[k].degree;
+        let output_degree = gen_degree + op_degree;
+        let mut result = FpVector::new(p, self.basis(output_degree).monomials.len());
+        if self.algebra.generators(op_degree).contains(&op_index) {
+            if let Some(value) = self.actions[k].get(&(op_degree, op_index)) {
+                result.add(value, 1);
+            }
+            return result;
+        }
+
+        for (c, (deg_1, idx_1), (deg_2, idx_2)) in
+            self.algebra.decompose_basis_element(op_degree, op_index)
+        {
+            let mut intermediate = FpVector::new(p, self.basis(gen_degree + deg_2).monomials.len());
+            self.act_on_generator(intermediate.as_slice_mut(), 1, deg_2, idx_2, k);
+            self.act_polynomial(
+                result.as_slice_mut(),
+                c,
+                deg_1,
+                idx_1,
+                gen_degree + deg_2,
+                intermediate.as_slice(),
+            );
+        }
+        result
+    }
+}
+
+impl<A: Algebra + Bialgebra + GeneratedAlgebra> Module for PolynomialAlgebraModule<A> {
+    type Algebra = A;
+
+    fn algebra(&self) -> Arc<A> {
+        Arc::clone(&self.algebra)
+    }
+
+    fn min_degree(&self) -> i32 {
+        if self.reduced {
+            self.generators.first().map_or(1, |g| g.degree)
+        } else {
+            0
+        }
+    }
+
+    fn compute_basis(&self, degree: i32) {
+        self.algebra.compute_basis(degree);
+        if degree >= 0 {
+            self.basis(degree);
+        }
+    }
+
+    fn max_computed_degree(&self) -> i32 {
+        i32::MAX
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < 0 || (degree == 0 && self.reduced) || self.max.is_some_and(|m| degree > m) {
+            return 0;
+        }
+        self.basis(degree).monomials.len()
+    }
+
+    fn act_on_basis(
+        &self,
+        result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        assert!(op_index < self.algebra.dimension(op_degree));
+        assert!(mod_index < self.dimension(mod_degree));
+
+        if coeff == 0 || self.dimension(mod_degree + op_degree) == 0 {
+            return;
+        }
+        self.act_monomial(result, coeff, op_degree, op_index, mod_degree, mod_index);
+    }
+
+    fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        self.monomial_to_string(&self.basis(degree).monomials[idx])
+    }
+
+    fn max_degree(&self) -> Option<i32> {
+        self.max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use fp::prime::{TWO, ValidPrime};
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        AdemAlgebra, MilnorAlgebra, SubHopfAlgebra,
+        module::{FDModule, RealProjectiveSpace},
+    };
+
+    fn adem(p: u32) -> SteenrodAlgebra {
+        SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(ValidPrime::new(p), false))
+    }
+
+    fn milnor(p: u32) -> SteenrodAlgebra {
+        SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(ValidPrime::new(p), false))
+    }
+
+    fn sub_hopf_algebra(p: u32, sub: SubHopfAlgebra) -> SteenrodAlgebra {
+        let p = ValidPrime::new(p);
+        let profile = sub.profile(p).unwrap();
+        SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new_with_profile(p, profile, false))
+    }
+
+    fn bz3(algebra: SteenrodAlgebra) -> PolynomialAlgebraModule<SteenrodAlgebra> {
+        let json = json!({
+            "polynomial": {"y": 2},
+            "exterior": {"x": 1},
+            "actions": ["b x = y", "P1 y = y^3"],
+            "max": 12,
+        });
+        PolynomialAlgebraModule::from_json(Arc::new(algebra), &json).unwrap()
+    }
+
+    /// Compare the action on $\mathbb{F}_2[x]$ with [`RealProjectiveSpace`].
+    #[rstest]
+    #[case(adem(2))]
+    #[case(milnor(2))]
+    fn test_rp_inf(#[case] algebra: SteenrodAlgebra) {
+        let algebra = Arc::new(algebra);
+        let max = 16;
+        algebra.compute_basis(max);
+        let json = json!({
+            "polynomial": {"x": 1},
+            "actions": ["Sq1 x = x^2"],
+            "reduced": true,
+        });
+        let module = PolynomialAlgebraModule::from_json(Arc::clone(&algebra), &json).unwrap();
+        let rp = RealProjectiveSpace::new(Arc::clone(&algebra), 1, None, false);
+
+        for op_deg in 0..max {
+            for op_idx in 0..algebra.dimension(op_deg) {
+                for mod_deg in 1..max - op_deg {
+                    let mut ours = FpVector::new(TWO, 1);
+                    let mut theirs = FpVector::new(TWO, 1);
+                    module.act_on_basis(ours.as_slice_mut(), 1, op_deg, op_idx, mod_deg, 0);
+                    rp.act_on_basis(theirs.as_slice_mut(), 1, op_deg, op_idx, mod_deg, 0);
+                    assert_eq!(
+                        ours,
+                        theirs,
+                        "{} x^{mod_deg}",
+                        algebra.basis_element_to_string(op_deg, op_idx)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Check that the truncation of $H^*(B\mathbb{Z}/3)$ satisfies all the relations of the
+    /// Steenrod algebra.
+    #[rstest]
+    #[case(adem(3))]
+    #[case(milnor(3))]
+    fn test_bz3_valid(#[case] algebra: SteenrodAlgebra) {
+        let module = FDModule::from(&bz3(algebra));
+        for input_deg in 0..=12 {
+            for output_deg in input_deg + 1..=12 {
+                module.check_validity(input_deg, output_deg).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_bz3() {
+        let module = bz3(milnor(3));
+        let mut output = json!({});
+        module.to_json(&mut output);
+        expect![[r#"["Q_0 x = y","P(1) y = y^3"]"#]].assert_eq(&output["actions"].to_string());
+
+        let mut x_y = FpVector::new(ValidPrime::new(3), module.dimension(7));
+        let (p1_deg, p1_idx) = module.algebra().basis_element_from_string("P1").unwrap();
+        module.act_on_basis(x_y.as_slice_mut(), 1, p1_deg, p1_idx, 3, 0);
+        expect!["x y^3"].assert_eq(&module.element_to_string(7, x_y.as_slice()));
+
+        let mut y_2 = FpVector::new(ValidPrime::new(3), module.dimension(8));
+        module.act_on_basis(y_2.as_slice_mut(), 1, p1_deg, p1_idx, 4, 0);
+        expect!["2 y^4"].assert_eq(&module.element_to_string(8, y_2.as_slice()));
+    }
+
+    /// Check that $H^*(K(\mathbb{Z}/p, n))$ satisfies all the relations of the Steenrod algebra.
+    #[rstest]
+    #[case(adem(2), 2, 20)]
+    #[case(milnor(2), 3, 20)]
+    #[case(adem(3), 1, 24)]
+    #[case(milnor(3), 2, 24)]
+    #[case(sub_hopf_algebra(2, SubHopfAlgebra::A(2)), 2, 20)]
+    #[case(sub_hopf_algebra(3, SubHopfAlgebra::A(1)), 1, 24)]
+    fn test_eilenberg_maclane_valid(
+        #[case] algebra: SteenrodAlgebra,
+        #[case] n: i32,
+        #[case] max: i32,
+    ) {
+        let module =
+            PolynomialAlgebraModule::eilenberg_maclane(Arc::new(algebra), n, true, max).unwrap();
+        let module = FDModule::from(&module);
+        for input_deg in n..=max {
+            for output_deg in input_deg + 1..=max {
+                module.check_validity(input_deg, output_deg).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_eilenberg_maclane() {
+        let module =
+            PolynomialAlgebraModule::eilenberg_maclane(Arc::new(adem(2)), 2, false, 20).unwrap();
+        let generators = module
+            .generators
+            .iter()
+            .map(|g| format!("{} {}", g.name, g.degree))
+            .join(", ");
+        expect!["x 2, Sq1x 3, Sq2Sq1x 5, Sq4Sq2Sq1x 9, Sq8Sq4Sq2Sq1x 17"].assert_eq(&generators);
+        let dimensions = (0..=10).map(|d| module.dimension(d)).join(" ");
+        expect!["1 0 1 1 1 2 2 2 3 4 4"].assert_eq(&dimensions);
+
+        let mut sq3_y = FpVector::new(TWO, module.dimension(6));
+        let (sq3_deg, sq3_idx) = module.algebra().basis_element_from_string("Sq3").unwrap();
+        module.act_on_basis(sq3_y.as_slice_mut(), 1, sq3_deg, sq3_idx, 3, 0);
+        expect!["Sq1x^2"].assert_eq(&module.element_to_string(6, sq3_y.as_slice()));
+
+        let module =
+            PolynomialAlgebraModule::eilenberg_maclane(Arc::new(milnor(3)), 1, true, 12).unwrap();
+        let mut output = json!({});
+        module.to_json(&mut output);
+        expect![[r#"["Q_0 x = bx","P(1) bx = bx^3"]"#]].assert_eq(&output["actions"].to_string());
+        assert_eq!(module.to_string(), "K(Z/3, 1)");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let algebra = Arc::new(adem(2));
+        let json = json!({
+            "polynomial": {"x": 2, "y": 3},
+            "actions": ["Sq1 x = y", "Sq1 y = x^2"],
+        });
+        assert!(PolynomialAlgebraModule::from_json(Arc::clone(&algebra), &json).is_err());
+
+        let json = json!({
+            "polynomial": {"x": 1},
+            "actions": ["Sq3 x = 0"],
+        });
+        assert!(PolynomialAlgebraModule::from_json(algebra, &json).is_err());
+    }
+}
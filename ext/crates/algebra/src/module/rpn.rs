@@ -7,6 +7,7 @@ use fp::{
 use serde::Deserialize;
 use serde_json::Value;
 
+use super::cpn::check_steenrod;
 use crate::{
     algebra::{
         AdemAlgebra, Algebra, MilnorAlgebra, SteenrodAlgebra,
@@ -113,7 +114,12 @@ where
 
         let output_degree = mod_degree + op_degree;
 
-        if op_degree == 0 || coeff == 0 || self.dimension(output_degree) == 0 {
+        if coeff == 0 || self.dimension(output_degree) == 0 {
+            return;
+        }
+
+        if op_degree == 0 {
+            result.add_basis_element(0, coeff);
             return;
         }
 
@@ -122,6 +128,7 @@ where
             Ok(SteenrodAlgebra::MilnorAlgebra(a)) => {
                 coef_milnor(a, op_degree, op_index, mod_degree)
             }
+            // Other algebras are rejected by `check_steenrod` on construction
             Err(_) => unreachable!(),
         } {
             result.add_basis_element(0, 1);
@@ -190,10 +197,7 @@ where
 {
     pub fn new(algebra: Arc<A>, min: i32, max: Option<i32>, clear_bottom: bool) -> Self {
         assert_eq!(algebra.prime(), 2);
-        assert!(
-            (&*algebra).try_into().is_ok(),
-            "Real Projective Space only supports Steenrod Algebra"
-        );
+        check_steenrod(&*algebra, "Real projective space").unwrap_or_else(|e| panic!("{e}"));
 
         if let Some(max) = max {
             assert!(max >= min);
@@ -214,8 +218,12 @@ struct RPSpec {
     max: Option<i32>,
 }
 
-impl<A: Algebra> RealProjectiveSpace<A> {
+impl<A: Algebra> RealProjectiveSpace<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
     pub fn from_json(algebra: Arc<A>, json: &Value) -> anyhow::Result<Self> {
+        check_steenrod(&*algebra, "Real projective space")?;
         let spec: RPSpec = RPSpec::deserialize(json)?;
         let clear_bottom = spec.clear_bottom.unwrap_or(false);
         let mut min = spec.min;
@@ -246,3 +254,27 @@ impl<A: Algebra> RealProjectiveSpace<A> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fp::vector::FpVector;
+
+    use super::*;
+
+    #[test]
+    fn test_unit_acts_as_identity() {
+        for algebra in [
+            SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(TWO, false)),
+            SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(TWO, false)),
+        ] {
+            let algebra = Arc::new(algebra);
+            algebra.compute_basis(0);
+            let module = RealProjectiveSpace::new(algebra, 1, Some(6), false);
+            for degree in 1..=6 {
+                let mut result = FpVector::new(TWO, 1);
+                module.act_on_basis(result.as_slice_mut(), 1, 0, 0, degree, 0);
+                assert_eq!(result.entry(0), 1, "1 * x^{degree} != x^{degree}");
+            }
+        }
+    }
+}
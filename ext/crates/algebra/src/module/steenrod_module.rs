@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
-use crate::algebra::SteenrodAlgebra;
+use crate::algebra::{MotivicMilnorAlgebra, SteenrodAlgebra};
 
 pub type SteenrodModule = Arc<dyn Module<Algebra = SteenrodAlgebra>>;
 
+/// A module over one of the motivic Steenrod algebras.
+pub type MotivicModule = Arc<dyn Module<Algebra = MotivicMilnorAlgebra>>;
+
 pub fn erase(module: impl Module<Algebra = SteenrodAlgebra>) -> SteenrodModule {
     Arc::new(module)
 }
@@ -14,23 +17,29 @@ mod json {
     use anyhow::anyhow;
 
     use super::*;
-    use crate::module::{FDModule, FPModule, RealProjectiveSpace, SuspensionModule};
+    use crate::{
+        algebra::{Algebra, GeneratedAlgebra},
+        module::{
+            ComplexProjectiveSpace, FDModule, FPModule, LensSpace, MotivicCoefficients,
+            PolynomialAlgebraModule, RealProjectiveSpace, SuspensionModule, ThomSpectrum,
+        },
+    };
+
+    fn box_new<A: Algebra>(
+        m: impl Module<Algebra = A>,
+        json: &serde_json::Value,
+    ) -> Arc<dyn Module<Algebra = A>> {
+        if let Some(shift) = json["shift"].as_i64() {
+            Arc::new(SuspensionModule::new(Arc::new(m), shift as i32))
+        } else {
+            Arc::new(m)
+        }
+    }
 
     pub fn from_json(
         algebra: Arc<SteenrodAlgebra>,
         json: &serde_json::Value,
     ) -> anyhow::Result<SteenrodModule> {
-        fn box_new(
-            m: impl Module<Algebra = SteenrodAlgebra>,
-            json: &serde_json::Value,
-        ) -> SteenrodModule {
-            if let Some(shift) = json["shift"].as_i64() {
-                Arc::new(SuspensionModule::new(Arc::new(m), shift as i32))
-            } else {
-                Arc::new(m)
-            }
-        }
-
         match json["type"].as_str() {
             Some("real projective space") => Ok(box_new(
                 RealProjectiveSpace::from_json(algebra, json)?,
@@ -42,10 +51,62 @@ mod json {
             Some("finitely presented module") => {
                 Ok(box_new(FPModule::from_json(algebra, json)?, json))
             }
+            Some("polynomial algebra") => Ok(box_new(
+                PolynomialAlgebraModule::from_json(algebra, json)?,
+                json,
+            )),
+            Some("eilenberg maclane") => Ok(box_new(
+                PolynomialAlgebraModule::eilenberg_maclane_from_json(algebra, json)?,
+                json,
+            )),
+            Some("lens space") => Ok(box_new(LensSpace::from_json(algebra, json)?, json)),
+            Some("complex projective space") => Ok(box_new(
+                ComplexProjectiveSpace::from_json(algebra, json)?,
+                json,
+            )),
+            Some("thom spectrum") => Ok(box_new(ThomSpectrum::from_json(algebra, json)?, json)),
+            Some("motivic coefficients") => Err(anyhow!(
+                "Motivic coefficients are only supported over the motivic Steenrod algebras"
+            )),
             Some(x) => Err(anyhow!("Unknown module type: {}", x)),
             None => Err(anyhow!("Missing module type")),
         }
     }
+
+    /// Construct a module over an algebra other than the Steenrod algebra, such as a
+    /// [finite dimensional algebra](crate::FiniteDimensionalAlgebra). Only the finite dimensional
+    /// and finitely presented modules make sense over any algebra, so the other module types are
+    /// not supported.
+    pub fn generic_from_json<A: GeneratedAlgebra>(
+        algebra: Arc<A>,
+        json: &serde_json::Value,
+    ) -> anyhow::Result<Arc<dyn Module<Algebra = A>>> {
+        match json["type"].as_str() {
+            Some("finite dimensional module") => {
+                Ok(box_new(FDModule::from_json(algebra, json)?, json))
+            }
+            Some("finitely presented module") => {
+                Ok(box_new(FPModule::from_json(algebra, json)?, json))
+            }
+            Some(x) => Err(anyhow!("Module type {x} is not supported over {algebra}")),
+            None => Err(anyhow!("Missing module type")),
+        }
+    }
+
+    /// Construct a module over a motivic Steenrod algebra. This supports the motivic coefficients
+    /// in addition to the module types of [`generic_from_json`].
+    pub fn motivic_from_json(
+        algebra: Arc<MotivicMilnorAlgebra>,
+        json: &serde_json::Value,
+    ) -> anyhow::Result<MotivicModule> {
+        match json["type"].as_str() {
+            Some("motivic coefficients") => Ok(box_new(
+                MotivicCoefficients::from_json(algebra, json)?,
+                json,
+            )),
+            _ => generic_from_json(algebra, json),
+        }
+    }
 }
 
 pub use json::*;
@@ -112,6 +112,10 @@ impl<M: Module> Module for SuspensionModule<M> {
         self.inner.element_to_string(degree - self.shift, element)
     }
 
+    fn basis_element_weight(&self, degree: i32, idx: usize) -> i32 {
+        self.inner.basis_element_weight(degree - self.shift, idx)
+    }
+
     fn algebra(&self) -> std::sync::Arc<Self::Algebra> {
         self.inner.algebra()
     }
@@ -0,0 +1,459 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use fp::{
+    prime::{Prime, ValidPrime},
+    vector::{FpSliceMut, FpVector},
+};
+use itertools::Itertools;
+use once::OnceBiVec;
+use rustc_hash::FxHashMap as HashMap;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::cpn::{check_steenrod, complex_p_part, power_coef};
+use crate::{
+    algebra::{Algebra, SteenrodAlgebra, milnor_algebra::PPartEntry},
+    module::Module,
+};
+
+/// A partition with exactly $n$ positive parts, in decreasing order.
+type Partition = Vec<u32>;
+
+/// The partitions in a fixed degree.
+struct Basis {
+    partitions: Vec<Partition>,
+    index: HashMap<Partition, usize>,
+}
+
+/// The Thom spectrum $MO(n)$ or $MU(n)$ of the universal bundle over $BO(n)$ or $BU(n)$.
+///
+/// By the splitting principle, the cohomology of $BO(n)$ is the ring of symmetric polynomials in
+/// variables $t_1, \ldots, t_n$ of degree 1 (or degree 2 for $BU(n)$), and the cohomology of the
+/// Thom spectrum is the free module on the Thom class $U = t_1 \cdots t_n$. Thus it has a basis
+/// given by the monomial symmetric polynomials $m_\mu$ for partitions $\mu$ with exactly $n$
+/// positive parts, which we write as $U m_\lambda$ with $\lambda = \mu - (1, \ldots, 1)$. The
+/// action is determined by the Cartan formula and $P(R) t = t^{p^k}$ if $R$ is the $k$th unit
+/// vector (and zero otherwise).
+///
+/// $MO(n)$ only exists at the prime 2.
+pub struct ThomSpectrum<A: Algebra> {
+    algebra: Arc<A>,
+    pub complex: bool,
+    pub n: u32,
+    pub max: Option<i32>,
+    basis: OnceBiVec<Basis>,
+    action_cache: Mutex<HashMap<(i32, usize, i32, usize), FpVector>>,
+}
+
+impl<A: Algebra> std::fmt::Display for ThomSpectrum<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let group = if self.complex { "U" } else { "O" };
+        write!(f, "M{group}({})", self.n)
+    }
+}
+
+impl<A: Algebra> PartialEq for ThomSpectrum<A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.complex == other.complex && self.n == other.n && self.max == other.max
+    }
+}
+
+impl<A: Algebra> Eq for ThomSpectrum<A> {}
+
+impl<A: Algebra> Module for ThomSpectrum<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    type Algebra = A;
+
+    fn algebra(&self) -> Arc<A> {
+        Arc::clone(&self.algebra)
+    }
+
+    fn min_degree(&self) -> i32 {
+        self.variable_degree() * self.n as i32
+    }
+
+    fn max_computed_degree(&self) -> i32 {
+        i32::MAX
+    }
+
+    fn dimension(&self, degree: i32) -> usize {
+        if degree < self.min_degree() || self.max.is_some_and(|m| degree > m) {
+            return 0;
+        }
+        self.basis(degree).partitions.len()
+    }
+
+    fn basis_element_to_string(&self, degree: i32, idx: usize) -> String {
+        let lambda = self.basis(degree).partitions[idx]
+            .iter()
+            .map(|&x| x - 1)
+            .filter(|&x| x > 0)
+            .collect::<Vec<_>>();
+        if lambda.is_empty() {
+            "U".to_string()
+        } else {
+            format!("U m_{{{}}}", lambda.iter().join(","))
+        }
+    }
+
+    fn act_on_basis(
+        &self,
+        mut result: FpSliceMut,
+        coeff: u32,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) {
+        assert!(op_index < self.algebra().dimension(op_degree));
+        assert!(mod_index < self.dimension(mod_degree));
+
+        let output_degree = mod_degree + op_degree;
+
+        if coeff == 0 || self.dimension(output_degree) == 0 {
+            return;
+        }
+
+        let key = (op_degree, op_index, mod_degree, mod_index);
+        let cached = self.action_cache.lock().unwrap().get(&key).cloned();
+        let value = cached.unwrap_or_else(|| {
+            let value = self.compute_action(op_degree, op_index, mod_degree, mod_index);
+            self.action_cache.lock().unwrap().insert(key, value.clone());
+            value
+        });
+        result.add(value.as_slice(), coeff);
+    }
+
+    fn max_degree(&self) -> Option<i32> {
+        self.max
+    }
+}
+
+impl<A: Algebra> ThomSpectrum<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    pub fn new(algebra: Arc<A>, complex: bool, n: u32, max: Option<i32>) -> Self {
+        check_steenrod(&*algebra, "Thom spectrum").unwrap_or_else(|e| panic!("{e}"));
+        assert!(complex || algebra.prime() == 2);
+        Self {
+            algebra,
+            complex,
+            n,
+            max,
+            basis: OnceBiVec::new(0),
+            action_cache: Mutex::new(HashMap::default()),
+        }
+    }
+
+    /// The operations $P(R)$ whose composite is the given element, in the order they are applied,
+    /// or `None` if the element acts trivially. At the prime 2, the $P(R)$ are halved in the complex
+    /// case.
+    fn pieces(&self, op_degree: i32, op_index: usize) -> Option<Vec<Vec<PPartEntry>>> {
+        let p = self.prime();
+        match (&*self.algebra).try_into() {
+            Ok(SteenrodAlgebra::AdemAlgebra(a)) => {
+                let elt = a.basis_element_from_index(op_degree, op_index);
+                if elt.bocksteins != 0 {
+                    return None;
+                }
+                elt.ps
+                    .iter()
+                    .rev()
+                    .map(|&i| match (self.complex && p == 2, i % 2) {
+                        (true, 0) => Some(vec![i / 2]),
+                        (true, _) => None,
+                        (false, _) => Some(vec![i]),
+                    })
+                    .collect()
+            }
+            Ok(SteenrodAlgebra::MilnorAlgebra(a)) => {
+                let elt = a.basis_element_from_index(op_degree, op_index);
+                if elt.q_part != 0 {
+                    return None;
+                }
+                if self.complex {
+                    Some(vec![complex_p_part(p, elt)?])
+                } else {
+                    Some(vec![elt.p_part.clone()])
+                }
+            }
+            // Other algebras are rejected by `check_steenrod` on construction
+            Err(_) => unreachable!(),
+        }
+    }
+
+    fn compute_action(
+        &self,
+        op_degree: i32,
+        op_index: usize,
+        mod_degree: i32,
+        mod_index: usize,
+    ) -> FpVector {
+        let p = self.prime();
+        let output_degree = op_degree + mod_degree;
+        let mut result = FpVector::new(p, self.basis(output_degree).partitions.len());
+        let Some(pieces) = self.pieces(op_degree, op_index) else {
+            return result;
+        };
+
+        let mut current: HashMap<Partition, u32> = HashMap::default();
+        current.insert(self.basis(mod_degree).partitions[mod_index].clone(), 1);
+        for r in &pieces {
+            let mut next: HashMap<Partition, u32> = HashMap::default();
+            for (mu, c) in current {
+                for alpha in distinct_permutations(&mu) {
+                    self.apply_to_monomial(&alpha, 0, r, &mut Vec::new(), c, &mut next);
+                }
+            }
+            next.retain(|_, c| *c != 0);
+            current = next;
+        }
+
+        let basis = self.basis(output_degree);
+        for (mu, c) in current {
+            result.add_basis_element(basis.index[&mu], c);
+        }
+        result
+    }
+
+    /// Apply $P(R)$ to the monomial $t^\alpha$ via the Cartan formula, where `r` is the part of $R$
+    /// not yet applied to the first `exponents.len()` variables, whose new exponents are
+    /// `exponents`. Only the terms whose exponents are decreasing are recorded, since these are the
+    /// coefficients of the monomial symmetric polynomials.
+    fn apply_to_monomial(
+        &self,
+        alpha: &[u32],
+        j: usize,
+        r: &[PPartEntry],
+        exponents: &mut Vec<u32>,
+        coeff: u32,
+        result: &mut HashMap<Partition, u32>,
+    ) {
+        let p = self.prime();
+        if j == alpha.len() {
+            if r.iter().all(|&x| x == 0) {
+                let entry = result.entry(exponents.clone()).or_insert(0);
+                *entry = (*entry + coeff) % p;
+            }
+            return;
+        }
+        let bound = exponents.last().copied().unwrap_or(u32::MAX);
+        let choices: Vec<Vec<PPartEntry>> = if j + 1 == alpha.len() || r.is_empty() {
+            vec![r.to_vec()]
+        } else {
+            r.iter().map(|&x| 0..=x).multi_cartesian_product().collect()
+        };
+        for s in choices {
+            let exponent = alpha[j]
+                + s.iter()
+                    .enumerate()
+                    .map(|(k, &x)| x * (p.pow(k as u32 + 1) - 1))
+                    .sum::<u32>();
+            if exponent > bound {
+                continue;
+            }
+            let c = power_coef(p, alpha[j] as i32, &s);
+            if c == 0 {
+                continue;
+            }
+            let rest: Vec<PPartEntry> = std::iter::zip(r, &s).map(|(a, b)| a - b).collect();
+            exponents.push(exponent);
+            self.apply_to_monomial(alpha, j + 1, &rest, exponents, coeff * c % p, result);
+            exponents.pop();
+        }
+    }
+}
+
+impl<A: Algebra> ThomSpectrum<A> {
+    fn prime(&self) -> ValidPrime {
+        self.algebra.prime()
+    }
+
+    /// The degree of the variables $t_i$.
+    fn variable_degree(&self) -> i32 {
+        if self.complex { 2 } else { 1 }
+    }
+
+    fn basis(&self, degree: i32) -> &Basis {
+        self.basis.extend(degree, |d| {
+            let mut partitions = Vec::new();
+            if d % self.variable_degree() == 0 {
+                let total = (d / self.variable_degree()) as u32;
+                partitions_into(total, self.n, total, &mut Vec::new(), &mut partitions);
+            }
+            let index = partitions
+                .iter()
+                .enumerate()
+                .map(|(i, m)| (m.clone(), i))
+                .collect();
+            Basis { partitions, index }
+        });
+        &self.basis[degree]
+    }
+}
+
+/// Append the partitions of `total` into exactly `parts` positive parts of size at most `bound`
+/// to `result`, each prefixed by `current`.
+fn partitions_into(
+    total: u32,
+    parts: u32,
+    bound: u32,
+    current: &mut Vec<u32>,
+    result: &mut Vec<Partition>,
+) {
+    if parts == 0 {
+        if total == 0 {
+            result.push(current.clone());
+        }
+        return;
+    }
+    if total < parts {
+        return;
+    }
+    for first in (1..=bound.min(total - parts + 1)).rev() {
+        current.push(first);
+        partitions_into(total - first, parts - 1, first, current, result);
+        current.pop();
+    }
+}
+
+/// The distinct permutations of a multiset.
+fn distinct_permutations(elements: &[u32]) -> Vec<Vec<u32>> {
+    let mut counts: Vec<(u32, usize)> = elements
+        .iter()
+        .dedup_with_count()
+        .map(|(c, &x)| (x, c))
+        .collect();
+    let mut result = Vec::new();
+    fn go(
+        counts: &mut [(u32, usize)],
+        current: &mut Vec<u32>,
+        n: usize,
+        result: &mut Vec<Vec<u32>>,
+    ) {
+        if current.len() == n {
+            result.push(current.clone());
+            return;
+        }
+        for i in 0..counts.len() {
+            if counts[i].1 > 0 {
+                counts[i].1 -= 1;
+                current.push(counts[i].0);
+                go(counts, current, n, result);
+                current.pop();
+                counts[i].1 += 1;
+            }
+        }
+    }
+    go(&mut counts, &mut Vec::new(), elements.len(), &mut result);
+    result
+}
+
+#[derive(Deserialize, Debug)]
+struct ThomSpectrumSpec {
+    group: String,
+    n: u32,
+    max: Option<i32>,
+}
+
+impl<A: Algebra> ThomSpectrum<A>
+where
+    for<'a> &'a A: TryInto<&'a SteenrodAlgebra>,
+{
+    pub fn from_json(algebra: Arc<A>, json: &Value) -> anyhow::Result<Self> {
+        check_steenrod(&*algebra, "Thom spectrum")?;
+        let spec: ThomSpectrumSpec = ThomSpectrumSpec::deserialize(json)?;
+        let complex = match spec.group.as_str() {
+            "O" => false,
+            "U" => true,
+            x => return Err(anyhow!("Unknown group {x}; expected O or U")),
+        };
+        anyhow::ensure!(
+            complex || algebra.prime() == 2,
+            "MO(n) is only supported at the prime 2"
+        );
+
+        Ok(Self {
+            algebra,
+            complex,
+            n: spec.n,
+            max: spec.max,
+            basis: OnceBiVec::new(0),
+            action_cache: Mutex::new(HashMap::default()),
+        })
+    }
+
+    pub fn to_json(&self, json: &mut Value) {
+        json["name"] = Value::String(self.to_string());
+        json["type"] = Value::from("thom spectrum");
+        json["group"] = Value::from(if self.complex { "U" } else { "O" });
+        json["n"] = Value::from(self.n);
+        if let Some(max) = self.max {
+            json["max"] = Value::from(max);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use fp::prime::ValidPrime;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{AdemAlgebra, MilnorAlgebra, module::FDModule};
+
+    #[rstest]
+    #[case(2, false, 3, 16)]
+    #[case(2, true, 2, 24)]
+    #[case(3, true, 2, 40)]
+    fn test_thom_spectrum_valid(
+        #[case] p: u32,
+        #[case] complex: bool,
+        #[case] n: u32,
+        #[case] max: i32,
+    ) {
+        let p = ValidPrime::new(p);
+        for algebra in [
+            SteenrodAlgebra::AdemAlgebra(AdemAlgebra::new(p, false)),
+            SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(p, false)),
+        ] {
+            let algebra = Arc::new(algebra);
+            algebra.compute_basis(max);
+            let module = FDModule::from(&ThomSpectrum::new(algebra, complex, n, Some(max)));
+            for input_deg in 0..=max {
+                for output_deg in input_deg + 1..=max {
+                    module.check_validity(input_deg, output_deg).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mo2() {
+        let algebra = Arc::new(SteenrodAlgebra::MilnorAlgebra(MilnorAlgebra::new(
+            ValidPrime::new(2),
+            false,
+        )));
+        algebra.compute_basis(4);
+        let module = ThomSpectrum::new(Arc::clone(&algebra), false, 2, None);
+        let basis = (2..=5)
+            .map(|d| {
+                (0..module.dimension(d))
+                    .map(|i| module.basis_element_to_string(d, i))
+                    .join(", ")
+            })
+            .join(" | ");
+        expect!["U | U m_{1} | U m_{2}, U m_{1,1} | U m_{3}, U m_{2,1}"].assert_eq(&basis);
+
+        // Sq(U) = w(U), so Sq^1 U = w_1 U and Sq^2 U = w_2 U.
+        let mut result = FpVector::new(ValidPrime::new(2), module.dimension(4));
+        let (sq2_deg, sq2_idx) = algebra.basis_element_from_string("Sq2").unwrap();
+        module.act_on_basis(result.as_slice_mut(), 1, sq2_deg, sq2_idx, 2, 0);
+        expect!["U m_{1,1}"].assert_eq(&module.element_to_string(4, result.as_slice()));
+    }
+}
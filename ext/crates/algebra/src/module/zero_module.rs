@@ -1,16 +1,16 @@
 use std::sync::Arc;
 
 use crate::{
-    SteenrodAlgebra,
-    module::{FDModule, Module, SteenrodModule},
+    algebra::Algebra,
+    module::{FDModule, Module},
 };
 
 pub trait ZeroModule: Module {
     fn zero_module(algebra: Arc<Self::Algebra>, min_degree: i32) -> Self;
 }
 
-impl ZeroModule for SteenrodModule {
-    fn zero_module(algebra: Arc<SteenrodAlgebra>, min_degree: i32) -> Self {
+impl<A: Algebra> ZeroModule for Arc<dyn Module<Algebra = A>> {
+    fn zero_module(algebra: Arc<A>, min_degree: i32) -> Self {
         Arc::new(FDModule::zero_module(algebra, min_degree))
     }
 }
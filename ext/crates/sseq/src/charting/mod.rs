@@ -62,28 +62,45 @@ pub trait Backend {
     ) -> Result<(), Self::Error>;
 
     fn init(&mut self, max: Bidegree) -> Result<(), Self::Error> {
+        self.init_with_labels(max, Bidegree::zero())
+    }
+
+    /// Draw the grid of a chart of size `max`, where the bottom left corner of the chart has
+    /// coordinates `origin`. The grid lines are labelled by their actual coordinates, which may be
+    /// negative, e.g. for the Tate spectral sequence.
+    fn init_with_labels(&mut self, max: Bidegree, origin: Bidegree) -> Result<(), Self::Error> {
         self.header(max)?;
 
         for x in 0..=max.x() {
             let on_x_axis = Bidegree::x_y(x, 0);
+            let label = x + origin.x();
             self.line(
                 on_x_axis,
                 Bidegree::x_y(x, max.y()),
-                if x % 4 == 0 { "major-grid" } else { "grid" },
+                if label.rem_euclid(4) == 0 {
+                    "major-grid"
+                } else {
+                    "grid"
+                },
             )?;
-            if x % 4 == 0 {
-                self.text(on_x_axis, x, Orientation::Below)?;
+            if label.rem_euclid(4) == 0 {
+                self.text(on_x_axis, label, Orientation::Below)?;
             }
         }
         for y in 0..=max.y() {
             let on_y_axis = Bidegree::x_y(0, y);
+            let label = y + origin.y();
             self.line(
                 on_y_axis,
                 Bidegree::x_y(max.x(), y),
-                if y % 4 == 0 { "major-grid" } else { "grid" },
+                if label.rem_euclid(4) == 0 {
+                    "major-grid"
+                } else {
+                    "grid"
+                },
             )?;
-            if y % 4 == 0 {
-                self.text(on_y_axis, y, Orientation::Left)?;
+            if label.rem_euclid(4) == 0 {
+                self.text(on_y_axis, label, Orientation::Left)?;
             }
         }
         Ok(())
@@ -45,6 +45,15 @@ impl<T: io::Write> SvgBackend<T> {
      text-anchor: end;
      dominant-baseline: middle;
     }
+    .top-label {
+     text-anchor: middle;
+     dominant-baseline: text-after-edge;
+     font-size: 8px;
+    }
+    .hidden {
+     stroke: red;
+     stroke-dasharray: 3 2;
+    }
     "#;
 
     /// Print the legend for node patterns
@@ -133,8 +142,8 @@ impl<T: io::Write> Backend for SvgBackend<T> {
         )
     }
 
-    // SvgBackend only supports printing text to the left of or below a certain bidegree. This is
-    // used for printing axis labels.
+    // SvgBackend only supports printing text to the left of, below or above a certain bidegree.
+    // This is used for printing axis labels and the labels on top of the chart.
     fn text(
         &mut self,
         b: Bidegree,
@@ -145,7 +154,7 @@ impl<T: io::Write> Backend for SvgBackend<T> {
             Orientation::Left => ((-5, 0), "y-label"),
             Orientation::Right => unimplemented!(),
             Orientation::Below => ((0, 3), "x-label"),
-            Orientation::Above => unimplemented!(),
+            Orientation::Above => ((0, -3), "top-label"),
         };
 
         writeln!(
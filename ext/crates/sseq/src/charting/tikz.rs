@@ -16,6 +16,7 @@ impl<T: io::Write> TikzBackend<T> {
   major-grid/.style={ opacity = 0.2 },
   grid/.style={ opacity = 0.1 },
   d2/.style={ blue },
+  hidden/.style={ red, dashed },
 ]";
 
     pub fn new(out: T) -> Self {
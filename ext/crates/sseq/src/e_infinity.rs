@@ -0,0 +1,712 @@
+//! The $E_\infty$ page of a bigraded spectral sequence and the groups it converges to.
+//!
+//! [`EInfinity`] takes the last page of an [`Sseq`] whose differentials are known and assembles
+//! the associated graded in each stem into an abelian group, using the product that detects
+//! multiplication by $p$ and the hidden extensions declared by the user. As in the charts, the
+//! degree `(x, y)` is in stem `x` and filtration `y`, so this applies to Adams-graded spectral
+//! sequences such as [`Adams`](crate::Adams) and [`HomotopyFixedPoint`](crate::HomotopyFixedPoint),
+//! but not to the Serre-graded ones.
+
+use std::fmt;
+
+use fp::{
+    matrix::{Matrix, Subquotient},
+    prime::{Prime, ValidPrime},
+    vector::FpVector,
+};
+use once::MultiIndexed;
+
+use crate::{
+    charting::{Backend, Orientation},
+    coordinates::{Bidegree, BidegreeElement, BidegreeGenerator},
+    sseq::{Product, Sseq, SseqProfile},
+};
+
+struct DegreeData {
+    /// $E_\infty$ as a subquotient of the first page.
+    page: Subquotient,
+    /// Whether $E_\infty$ is known at this degree. This is the case if every class on the last
+    /// page is permanent, and so is every class that can support a differential hitting this
+    /// degree.
+    known: bool,
+}
+
+/// A hidden extension: the product of `product` with a lift of `source` is detected by `target`,
+/// which lies in higher filtration than the product itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiddenExtension {
+    pub product: String,
+    pub source: BidegreeElement,
+    pub target: BidegreeElement,
+}
+
+impl fmt::Display for HiddenExtension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} · {} = {}", self.product, self.source, self.target)
+    }
+}
+
+/// A finitely generated abelian group whose torsion is $p$-primary, as a sum of cyclic groups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    p: ValidPrime,
+    /// `Some(k)` for a summand $\mathbb{Z}/p^k$ and `None` for a summand of infinite order. These
+    /// are sorted with the summands of infinite order first, and then by decreasing order.
+    summands: Vec<Option<u32>>,
+}
+
+impl Group {
+    pub fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
+    pub fn summands(&self) -> &[Option<u32>] {
+        &self.summands
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.summands.is_empty()
+    }
+}
+
+impl fmt::Display for Group {
+    /// Print the group as e.g. `Z ⊕ Z/8 ⊕ Z/2`. A summand of infinite order is printed as `Z`, even
+    /// though the spectral sequence usually only sees its $p$-completion.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.summands.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, summand) in self.summands.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ⊕ ")?;
+            }
+            match summand {
+                Some(k) => write!(f, "Z/{}", self.p.as_u32().pow(*k))?,
+                None => write!(f, "Z")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The $E_\infty$ page of a bigraded spectral sequence together with the products and hidden
+/// extensions needed to solve the extension problems.
+///
+/// The product registered with [`EInfinity::add_p_product`] is the one detecting multiplication
+/// by $p$, e.g. $h_0$ in the Adams spectral sequence. Multiplication by $p$ on a stem is then
+/// given by this product, except on classes where it vanishes on $E_\infty$, where it is given by
+/// the hidden extensions by this product. Degrees within the range of the spectral sequence that
+/// are not defined are taken to be zero. A class whose product leaves the range is assumed to
+/// generate a summand of infinite order, which is correct for the towers in the Adams spectral
+/// sequence.
+pub struct EInfinity {
+    p: ValidPrime,
+    min: Bidegree,
+    max: Bidegree,
+    /// The coordinates that label the bottom left corner of a chart, as in the chart of the
+    /// spectral sequence.
+    label_origin: Bidegree,
+    data: MultiIndexed<2, DegreeData>,
+    products: Vec<(String, Product<2>)>,
+    /// The index in `products` of the product detecting multiplication by $p$.
+    p_product: Option<usize>,
+    hidden: Vec<HiddenExtension>,
+}
+
+impl EInfinity {
+    /// Compute the $E_\infty$ page of `sseq`, which must be up to date (see [`Sseq::update`]).
+    pub fn new<P: SseqProfile<2>>(sseq: &Sseq<2, P>) -> Self {
+        let incomplete: Vec<Bidegree> =
+            sseq.iter_degrees().filter(|&b| !sseq.complete(b)).collect();
+
+        let data = MultiIndexed::new();
+        for b in sseq.iter_degrees() {
+            debug_assert!(!sseq.invalid(b), "sseq is not up to date at {b}");
+            let known = incomplete.iter().all(|&c| {
+                let r = P::differential_length(b - c);
+                c != b && (r < P::MIN_R || P::profile(r, c) != b)
+            });
+            data.insert(
+                b,
+                DegreeData {
+                    page: sseq.page_data(b).last().unwrap().clone(),
+                    known,
+                },
+            );
+        }
+
+        Self {
+            p: sseq.prime(),
+            min: sseq.min(),
+            max: sseq.max(),
+            label_origin: sseq.label_origin(),
+            data,
+            products: Vec::new(),
+            p_product: None,
+            hidden: Vec::new(),
+        }
+    }
+
+    pub fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
+    /// The $E_\infty$ page at `b` as a subquotient of the first page, or `None` if `b` is not
+    /// defined.
+    pub fn page(&self, b: Bidegree) -> Option<&Subquotient> {
+        Some(&self.data.get(b)?.page)
+    }
+
+    /// The dimension of the $E_\infty$ page at `b`, which is zero if `b` is not defined.
+    pub fn dimension(&self, b: Bidegree) -> usize {
+        self.page(b).map_or(0, Subquotient::dimension)
+    }
+
+    /// Whether the $E_\infty$ page is known at `b`. See [`EInfinity::group`].
+    pub fn known(&self, b: Bidegree) -> bool {
+        self.data.get(b).is_some_and(|d| d.known)
+    }
+
+    /// Add a product to draw and to declare hidden extensions by.
+    pub fn add_product(&mut self, name: impl Into<String>, product: Product<2>) {
+        self.products.push((name.into(), product));
+    }
+
+    /// Add the product that detects multiplication by $p$, e.g. $h_0$ in the Adams spectral
+    /// sequence. This must have degree `(0, k)` for some `k > 0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product does not have the right degree.
+    pub fn add_p_product(&mut self, name: impl Into<String>, product: Product<2>) {
+        assert!(
+            product.b.x() == 0 && product.b.y() > 0,
+            "multiplication by p must preserve the stem and raise the filtration"
+        );
+        self.p_product = Some(self.products.len());
+        self.add_product(name, product);
+    }
+
+    pub fn hidden_extensions(&self) -> &[HiddenExtension] {
+        &self.hidden
+    }
+
+    /// Whether `b` lies outside the range of the spectral sequence.
+    fn out_of_range(&self, b: Bidegree) -> bool {
+        b.x() < self.min.x() || b.x() > self.max.x() || b.y() < self.min.y() || b.y() > self.max.y()
+    }
+
+    /// The product of `product` and the class `elem`, reduced to the $E_\infty$ page. This is
+    /// `None` if the product is not known. Degrees that are not defined are taken to be zero.
+    fn multiply(&self, elem: &BidegreeElement, product: &Product<2>) -> Option<Vec<u32>> {
+        let target_b = elem.degree() + product.b;
+        let Some(target) = self.page(target_b) else {
+            return Some(Vec::new());
+        };
+        let Some(matrix) = product.matrices.get(elem.degree()) else {
+            return target.is_empty().then(Vec::new);
+        };
+        let mut result = FpVector::new(self.p, target.ambient_dimension());
+        matrix.apply(result.as_slice_mut(), 1, elem.vec());
+        Some(target.reduce(result.as_slice_mut()))
+    }
+
+    /// The coordinates of `elem` in the basis of the $E_\infty$ page.
+    fn reduce(&self, elem: &BidegreeElement) -> Result<Vec<u32>, String> {
+        let Some(page) = self.page(elem.degree()) else {
+            return Err(format!("degree {} is not defined", elem.degree()));
+        };
+        if elem.vec().len() != page.ambient_dimension() {
+            return Err(format!("{elem} has the wrong dimension"));
+        }
+        let mut v = elem.vec().to_owned();
+        let coords = page.reduce(v.as_slice_mut());
+        if !v.is_zero() {
+            return Err(format!("{elem} does not survive to E_∞"));
+        }
+        Ok(coords)
+    }
+
+    /// Declare that the product of `product` with a lift of `source` is detected by `target`.
+    /// The product must have been added with [`EInfinity::add_product`] or
+    /// [`EInfinity::add_p_product`], both classes must be non-zero on $E_\infty$, and `target`
+    /// must lie in higher filtration than the product, which must vanish on `source` in
+    /// $E_\infty$.
+    pub fn add_hidden_extension(
+        &mut self,
+        product: &str,
+        source: BidegreeElement,
+        target: BidegreeElement,
+    ) -> Result<(), String> {
+        let Some((_, prod)) = self.products.iter().find(|(name, _)| name == product) else {
+            return Err(format!("unknown product {product}"));
+        };
+        let offset = target.degree() - source.degree() - prod.b;
+        if offset.x() != 0 || offset.y() <= 0 {
+            return Err(format!(
+                "{target} is not in higher filtration than {product} times {source}"
+            ));
+        }
+        for elem in [&source, &target] {
+            if self.reduce(elem)?.iter().all(|&c| c == 0) {
+                return Err(format!("{elem} is zero on E_∞"));
+            }
+        }
+        if let Some(visible) = self.multiply(&source, prod)
+            && visible.iter().any(|&c| c != 0)
+        {
+            return Err(format!(
+                "{product} times {source} is non-zero on E_∞, so the extension is not hidden"
+            ));
+        }
+        self.hidden.push(HiddenExtension {
+            product: product.to_owned(),
+            source,
+            target,
+        });
+        Ok(())
+    }
+
+    /// The range of stems of the spectral sequence.
+    pub fn stems(&self) -> std::ops::RangeInclusive<i32> {
+        self.min.x()..=self.max.x()
+    }
+
+    /// The group in stem `n` whose associated graded is the $E_\infty$ page.
+    ///
+    /// This fails if the $E_\infty$ page is not known in the stem, or if multiplication by $p$ is
+    /// not known. The result is only correct if all hidden extensions by $p$ in the stem have been
+    /// declared.
+    pub fn group(&self, n: i32) -> Result<Group, String> {
+        let Some(p_product) = self.p_product else {
+            return Err("no product detecting multiplication by p".to_owned());
+        };
+        let (name, prod) = &self.products[p_product];
+
+        // The degrees in the stem and the offset of their basis in the basis of the stem.
+        let mut degrees = Vec::new();
+        let mut dim = 0;
+        for y in self.min.y()..=self.max.y() {
+            let b = Bidegree::x_y(n, y);
+            let Some(d) = self.data.get(b) else {
+                continue;
+            };
+            if !d.known {
+                return Err(format!("E_∞ is not known at {b}"));
+            }
+            degrees.push((b, dim));
+            dim += d.page.dimension();
+        }
+        // Only used for degrees that are defined, since the E_∞ page is zero elsewhere.
+        let offset = |b: Bidegree| {
+            degrees
+                .iter()
+                .find(|&&(c, _)| c == b)
+                .map_or(0, |&(_, start)| start)
+        };
+
+        // Multiplication by p on the basis of the stem, and the basis elements whose product
+        // leaves the range of the spectral sequence.
+        let mut rows = vec![FpVector::new(self.p, dim); dim];
+        let mut unknown = Vec::new();
+        for &(b, start) in &degrees {
+            let page = &self.data[b].page;
+            let d = page.dimension();
+            if d == 0 {
+                continue;
+            }
+            if self.out_of_range(b + prod.b) {
+                unknown.extend(start..start + d);
+                continue;
+            }
+
+            // Rows [p x | x] for x running over the basis of E_∞. After row reduction, the rows
+            // with non-zero image span a complement of the kernel of the visible product.
+            let target_start = offset(b + prod.b);
+            let mut visible = Matrix::new(self.p, d, dim + d);
+            for (i, (mut row, g)) in std::iter::zip(visible.iter_mut(), page.gens()).enumerate() {
+                let elem = BidegreeElement::new(b, g.to_owned());
+                let Some(image) = self.multiply(&elem, prod) else {
+                    return Err(format!("{name} is not known on {b}"));
+                };
+                for (j, c) in image.into_iter().enumerate() {
+                    row.set_entry(target_start + j, c);
+                }
+                row.set_entry(dim + i, 1);
+            }
+            visible.row_reduce();
+            let kernel_start = visible.find_first_row_in_block(dim);
+
+            // Rows [x | p x] for x in the complement, followed by the hidden extensions, whose
+            // sources lie in the kernel. If the row reduction produces a row with zero source, the
+            // hidden extensions contradict each other.
+            let mut matrix = Matrix::new(self.p, 0, d + dim);
+            for v in visible.iter().take(kernel_start) {
+                let mut row = matrix.add_row();
+                row.slice_mut(0, d).assign(v.restrict(dim, dim + d));
+                row.slice_mut(d, d + dim).assign(v.restrict(0, dim));
+            }
+            for ext in &self.hidden {
+                if ext.product != *name || ext.source.degree() != b {
+                    continue;
+                }
+                let source = self.reduce(&ext.source)?;
+                let target = self.reduce(&ext.target)?;
+                let target_start = d + offset(ext.target.degree());
+                let mut row = matrix.add_row();
+                for (i, c) in source.into_iter().enumerate() {
+                    row.set_entry(i, c);
+                }
+                for (i, c) in target.into_iter().enumerate() {
+                    row.set_entry(target_start + i, c);
+                }
+            }
+            matrix.row_reduce();
+            let mut basis = Matrix::new(self.p, 0, d + dim);
+            for row in matrix.iter().filter(|row| !row.is_zero()) {
+                if row.restrict(0, d).is_zero() {
+                    return Err(format!(
+                        "the hidden extensions by {name} on {b} contradict each other"
+                    ));
+                }
+                basis.add_row().assign(row);
+            }
+            // Extend to a basis on which the rest of the kernel has zero image.
+            for i in 0..d {
+                if matrix.pivots()[i] < 0 {
+                    basis.add_row().set_entry(i, 1);
+                }
+            }
+            basis.row_reduce();
+            for i in 0..d {
+                let row = basis.row(basis.pivots()[i] as usize);
+                rows[start + i] = row.restrict(d, d + dim).to_owned();
+            }
+        }
+
+        Ok(Group {
+            p: self.p,
+            summands: jordan_type(self.p, rows, &unknown),
+        })
+    }
+
+    /// Draw the $E_\infty$ page with the products and hidden extensions, and the groups above each
+    /// stem. Hidden extensions are drawn with the style `hidden`.
+    pub fn write_to_graph<T: Backend>(
+        &self,
+        mut g: T,
+        header: impl FnOnce(&mut T) -> Result<(), T::Error>,
+    ) -> Result<(), T::Error> {
+        let min = self.min;
+        g.init_with_labels(self.max - min, self.label_origin)?;
+        header(&mut g)?;
+
+        for (b, d) in self.data.iter() {
+            let b = Bidegree::from(b);
+            if d.page.is_empty() {
+                continue;
+            }
+            g.node(b - min, d.page.dimension())?;
+
+            for (name, prod) in &self.products {
+                let source_b = b - prod.b;
+                let Some(source) = self.page(source_b) else {
+                    continue;
+                };
+                if source.is_empty() {
+                    continue;
+                }
+                if let Some(matrix) = prod.matrices.get(source_b) {
+                    let matrix = Subquotient::reduce_matrix(matrix, source, &d.page);
+                    g.structline_matrix(source_b - min, b - min, matrix, Some(name))?;
+                }
+            }
+        }
+
+        for ext in &self.hidden {
+            // These were checked when adding the extension.
+            let source = self.reduce(&ext.source).unwrap();
+            let target = self.reduce(&ext.target).unwrap();
+            for (i, _) in source.iter().enumerate().filter(|(_, c)| **c != 0) {
+                for (j, _) in target.iter().enumerate().filter(|(_, c)| **c != 0) {
+                    g.structline(
+                        BidegreeGenerator::new(ext.source.degree() - min, i),
+                        BidegreeGenerator::new(ext.target.degree() - min, j),
+                        Some("hidden"),
+                    )?;
+                }
+            }
+        }
+
+        for n in self.stems() {
+            let label = match self.group(n) {
+                Ok(group) if group.is_zero() => continue,
+                Ok(group) => group.to_string(),
+                Err(_) => "?".to_owned(),
+            };
+            g.text(
+                Bidegree::x_y(n, self.max.y()) - min,
+                label,
+                Orientation::Above,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for EInfinity {
+    /// Print the group in each stem, followed by the hidden extensions.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for n in self.stems() {
+            match self.group(n) {
+                Ok(group) => writeln!(f, "Stem {n}: {group}")?,
+                Err(e) => writeln!(f, "Stem {n}: ? ({e})")?,
+            }
+        }
+        if !self.hidden.is_empty() {
+            writeln!(f, "Hidden extensions:")?;
+            for ext in &self.hidden {
+                writeln!(f, "  {ext}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The sizes of the Jordan blocks of the nilpotent operator whose matrix has rows `rows`, where
+/// the basis elements in `unknown` are taken to generate blocks of infinite size. The result is
+/// sorted as in [`Group::summands`].
+fn jordan_type(p: ValidPrime, mut rows: Vec<FpVector>, unknown: &[usize]) -> Vec<Option<u32>> {
+    let dim = rows.len();
+    // Attach a chain of length longer than any honest block to each unknown element. The blocks
+    // containing these are exactly those that are too long.
+    let chain = dim + 1;
+    let total = dim + unknown.len() * chain;
+    for row in &mut rows {
+        row.extend_len(total);
+    }
+    for (k, &u) in unknown.iter().enumerate() {
+        let start = dim + k * chain;
+        rows[u].set_entry(start, 1);
+        for i in start..start + chain {
+            let mut row = FpVector::new(p, total);
+            if i + 1 < start + chain {
+                row.set_entry(i + 1, 1);
+            }
+            rows.push(row);
+        }
+    }
+    let operator = Matrix::from_rows(p, rows, total);
+
+    // ranks[k] is the rank of the k-th power of the operator, and the number of blocks of size at
+    // least k is ranks[k - 1] - ranks[k].
+    let mut ranks = vec![total];
+    let mut powers: Vec<FpVector> = (0..total)
+        .map(|i| {
+            let mut v = FpVector::new(p, total);
+            v.set_entry(i, 1);
+            v
+        })
+        .collect();
+    while *ranks.last().unwrap() > 0 {
+        for v in &mut powers {
+            let mut w = FpVector::new(p, total);
+            operator.apply(w.as_slice_mut(), 1, v.as_slice());
+            *v = w;
+        }
+        let mut m = Matrix::from_rows(p, powers.clone(), total);
+        ranks.push(m.row_reduce());
+    }
+
+    let mut summands = Vec::new();
+    let at_least = |k: usize| {
+        ranks
+            .get(k - 1)
+            .map_or(0, |&r| r - ranks.get(k).copied().unwrap_or(0))
+    };
+    for k in (1..ranks.len()).rev() {
+        let exact = at_least(k) - at_least(k + 1);
+        let summand = (k <= dim).then_some(k as u32);
+        summands.extend(std::iter::repeat_n(summand, exact));
+    }
+    summands.sort_by_key(|s| s.map_or(0, |k| u32::MAX - k));
+    summands
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use fp::prime::TWO;
+
+    use super::*;
+    use crate::Adams;
+
+    fn class(x: i32, y: i32) -> BidegreeElement {
+        BidegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(TWO, &[1]))
+    }
+
+    /// A truncated $h_0$-tower in stem 0, $\mathbb{Z}/8$ in stem 3, two classes in stem 5 that
+    /// are not connected by $h_0$, and a class in stem 7 that may hit stem 6.
+    fn example() -> EInfinity {
+        let mut sseq = Sseq::<2, Adams>::new(TWO);
+        let h0 = Product {
+            b: Bidegree::x_y(0, 1),
+            left: true,
+            matrices: MultiIndexed::new(),
+        };
+        let classes = [
+            (0, 0),
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (0, 5),
+            (1, 1),
+            (3, 1),
+            (3, 2),
+            (3, 3),
+            (5, 1),
+            (5, 3),
+            (6, 3),
+        ];
+        for (x, y) in classes {
+            sseq.set_dimension(Bidegree::x_y(x, y), 1);
+            sseq.add_permanent_class(&class(x, y));
+        }
+        sseq.set_dimension(Bidegree::x_y(7, 1), 1);
+        for (x, y) in [(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (3, 1), (3, 2)] {
+            h0.matrices
+                .insert(Bidegree::x_y(x, y), Matrix::from_vec(TWO, &[vec![1]]));
+        }
+        sseq.update();
+
+        let mut e_infinity = EInfinity::new(&sseq);
+        e_infinity.add_p_product("h_0", h0);
+        e_infinity
+    }
+
+    #[test]
+    fn test_e_infinity_groups() {
+        let mut e_infinity = example();
+        assert!(e_infinity.known(Bidegree::x_y(5, 3)));
+        assert!(!e_infinity.known(Bidegree::x_y(6, 3)));
+        assert!(!e_infinity.known(Bidegree::x_y(7, 1)));
+        assert_eq!(e_infinity.group(5).unwrap().to_string(), "Z/2 ⊕ Z/2");
+
+        e_infinity
+            .add_hidden_extension("h_0", class(5, 1), class(5, 3))
+            .unwrap();
+        expect![[r#"
+            Stem 0: Z
+            Stem 1: Z/2
+            Stem 2: 0
+            Stem 3: Z/8
+            Stem 4: 0
+            Stem 5: Z/4
+            Stem 6: ? (E_∞ is not known at (6, 3))
+            Stem 7: ? (E_∞ is not known at (7, 1))
+            Hidden extensions:
+              h_0 · (5, 1, [1]) = (5, 3, [1])
+        "#]]
+        .assert_eq(&e_infinity.to_string());
+    }
+
+    #[test]
+    fn test_e_infinity_invalid_extension() {
+        let mut e_infinity = example();
+        let errors = [
+            ("h_1", class(5, 1), class(5, 3)),
+            ("h_0", class(3, 1), class(3, 3)),
+            ("h_0", class(0, 0), class(0, 1)),
+            ("h_0", class(1, 1), class(1, 3)),
+        ]
+        .map(|(product, source, target)| {
+            format!(
+                "{}\n",
+                e_infinity
+                    .add_hidden_extension(product, source, target)
+                    .unwrap_err()
+            )
+        });
+        expect![[r#"
+            unknown product h_1
+            h_0 times (3, 1, [1]) is non-zero on E_∞, so the extension is not hidden
+            (0, 1, [1]) is not in higher filtration than h_0 times (0, 0, [1])
+            degree (1, 3) is not defined
+        "#]]
+        .assert_eq(&errors.concat());
+        assert!(e_infinity.hidden_extensions().is_empty());
+    }
+
+    #[test]
+    fn test_e_infinity_svg() {
+        let mut e_infinity = example();
+        e_infinity
+            .add_hidden_extension("h_0", class(5, 1), class(5, 3))
+            .unwrap();
+        let mut out = Vec::new();
+        e_infinity
+            .write_to_graph(crate::charting::SvgBackend::new(&mut out), |_| Ok(()))
+            .unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.contains(r#"class="structline hidden""#));
+        assert!(svg.contains(">Z/8</text>"));
+    }
+
+    /// The $E_\infty$ page of a Tate chart is labelled by the actual stems, like the chart of the
+    /// spectral sequence itself.
+    #[test]
+    fn test_e_infinity_tate_chart() {
+        use crate::{Tate, charting::TikzBackend};
+
+        let mut sseq = Sseq::<2, Tate>::new(TWO);
+        for x in -5..=-3 {
+            for y in -2..=1 {
+                sseq.set_dimension(Bidegree::x_y(x, y), 1);
+            }
+        }
+        sseq.add_differential(
+            2,
+            &class(-4, -2),
+            FpVector::from_slice(TWO, &[1]).as_slice(),
+        );
+        sseq.update();
+
+        let mut out = Vec::new();
+        EInfinity::new(&sseq)
+            .write_to_graph(TikzBackend::new(&mut out), |_| Ok(()))
+            .unwrap();
+        expect![[r#"
+            \begin{tikzpicture}[
+              major-grid/.style={ opacity = 0.2 },
+              grid/.style={ opacity = 0.1 },
+              d2/.style={ blue },
+              hidden/.style={ red, dashed },
+            ]
+            \draw [grid] (0, 0) -- (0, 3);
+            \draw [major-grid] (1, 0) -- (1, 3);
+            \node [below] at (1, 0) {-4};
+            \draw [grid] (2, 0) -- (2, 3);
+            \draw [grid] (0, 0) -- (2, 0);
+            \draw [grid] (0, 1) -- (2, 1);
+            \draw [major-grid] (0, 2) -- (2, 2);
+            \node [left] at (0, 2) {0};
+            \draw [grid] (0, 3) -- (2, 3);
+            \draw [fill] (0, 0) circle (0.1);
+            \draw [fill] (0, 1) circle (0.1);
+            \draw [fill] (0, 3) circle (0.1);
+            \draw [fill] (1, 1) circle (0.1);
+            \draw [fill] (1, 2) circle (0.1);
+            \draw [fill] (1, 3) circle (0.1);
+            \draw [fill] (2, 0) circle (0.1);
+            \draw [fill] (2, 1) circle (0.1);
+            \draw [fill] (2, 2) circle (0.1);
+            \draw [fill] (2, 3) circle (0.1);
+            \node [above] at (0, 3) {?};
+            \node [above] at (1, 3) {?};
+            \node [above] at (2, 3) {?};
+            \end{tikzpicture}
+        "#]]
+        .assert_eq(std::str::from_utf8(&out).unwrap());
+    }
+}
@@ -0,0 +1,456 @@
+//! Propagation of differentials and permanent classes along products using the Leibniz rule.
+//!
+//! [`Sseq::leibniz`] propagates a single differential along a single product. [`Leibniz`] keeps a
+//! list of products and iterates the Leibniz rule over all of them until nothing new is learnt.
+//! Every fact learnt this way remembers the fact and product it was derived from, so that when the
+//! differentials turn out to be inconsistent, we can trace the contradiction back to the facts
+//! that were put in.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+};
+
+use fp::vector::FpVector;
+
+use crate::{
+    coordinates::{degree::MultiDegree, element::MultiDegreeElement},
+    differential::Differential,
+    provenance::Justification,
+    sseq::{Product, Sseq, SseqProfile},
+};
+
+/// A fact about a spectral sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fact<const N: usize> {
+    /// $d_r$ `source` = `target`.
+    Differential {
+        r: i32,
+        source: MultiDegreeElement<N>,
+        target: FpVector,
+    },
+    /// The class is a permanent cycle.
+    PermanentClass(MultiDegreeElement<N>),
+}
+
+impl<const N: usize> Fact<N> {
+    /// The degree of the source of the differential or the permanent class.
+    pub fn degree(&self) -> MultiDegree<N> {
+        match self {
+            Self::Differential { source, .. } => source.degree(),
+            Self::PermanentClass(class) => class.degree(),
+        }
+    }
+
+    /// Add the fact to `sseq` as user input. Returns whether the fact is new.
+    pub fn add_to<P: SseqProfile<N>>(&self, sseq: &mut Sseq<N, P>) -> bool {
+        self.add_to_justified(sseq, Justification::User)
+    }
+
+    /// Add the fact to `sseq` with the given justification. Returns whether the fact is new.
+    pub fn add_to_justified<P: SseqProfile<N>>(
+        &self,
+        sseq: &mut Sseq<N, P>,
+        justification: Justification<N>,
+    ) -> bool {
+        match self {
+            Self::Differential { r, source, target } => {
+                sseq.add_differential_justified(*r, source, target.as_slice(), justification)
+            }
+            Self::PermanentClass(class) => sseq.add_permanent_class_justified(class, justification),
+        }
+    }
+
+    /// The degrees whose differentials may become inconsistent when adding this fact.
+    fn affected_degrees<P: SseqProfile<N>>(&self) -> Vec<MultiDegree<N>> {
+        match self {
+            Self::Differential { r, source, target } if !target.is_zero() => {
+                vec![source.degree(), P::profile(*r, source.degree())]
+            }
+            _ => vec![self.degree()],
+        }
+    }
+}
+
+impl<const N: usize> fmt::Display for Fact<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Differential { r, source, target } => {
+                write!(f, "d_{r} {source} = {target}")
+            }
+            Self::PermanentClass(class) => write!(f, "{class} is permanent"),
+        }
+    }
+}
+
+/// Why a fact holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// The fact is the `index`th input of [`Leibniz::propagate`].
+    Input { index: usize },
+    /// The fact is obtained from the fact with index `fact` in [`LeibnizReport::facts`] by
+    /// multiplying with the product named `product`.
+    Leibniz { fact: usize, product: String },
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Input { index } => write!(f, "input {index}"),
+            Self::Leibniz { fact, product } => write!(f, "{product} times [{fact}]"),
+        }
+    }
+}
+
+/// A degree where the differentials became inconsistent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contradiction<const N: usize> {
+    pub degree: MultiDegree<N>,
+    /// The index of the fact whose addition made the degree inconsistent.
+    pub fact: usize,
+    /// The indices of the facts added earlier that involve the degree. The contradiction is
+    /// between `fact` and some of these.
+    pub involved: Vec<usize>,
+}
+
+/// The result of [`Leibniz::propagate`].
+#[derive(Debug, Clone, Default)]
+pub struct LeibnizReport<const N: usize> {
+    /// All facts added to the spectral sequence, together with the reason they hold. The inputs
+    /// come first, in order, including those that were already known.
+    pub facts: Vec<(Fact<N>, Reason)>,
+    pub contradictions: Vec<Contradiction<N>>,
+}
+
+impl<const N: usize> LeibnizReport<N> {
+    pub fn is_consistent(&self) -> bool {
+        self.contradictions.is_empty()
+    }
+
+    /// The chain of facts that `fact` is derived from, starting with an input and ending with
+    /// `fact` itself.
+    pub fn trail(&self, mut fact: usize) -> Vec<usize> {
+        let mut trail = vec![fact];
+        while let Reason::Leibniz { fact: prev, .. } = self.facts[fact].1 {
+            fact = prev;
+            trail.push(fact);
+        }
+        trail.reverse();
+        trail
+    }
+
+    fn write_trail(&self, f: &mut fmt::Formatter, fact: usize) -> fmt::Result {
+        for i in self.trail(fact) {
+            let (fact, reason) = &self.facts[i];
+            writeln!(f, "  [{i}] {fact} ({reason})")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> fmt::Display for LeibnizReport<N> {
+    /// Print the contradictions with the trails of the facts involved.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for c in &self.contradictions {
+            writeln!(f, "Contradiction at {}:", c.degree)?;
+            self.write_trail(f, c.fact)?;
+            for &i in &c.involved {
+                writeln!(f, "conflicts with")?;
+                self.write_trail(f, i)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct LeibnizProduct<const N: usize> {
+    name: String,
+    product: Product<N>,
+    /// If the product is not permanent, the index of the product that is its differential.
+    differential: Option<usize>,
+    /// Whether we propagate along this product. This is false for products that are only known
+    /// to be the target of a differential.
+    propagate: bool,
+}
+
+/// A collection of products to propagate differentials and permanent classes along.
+#[derive(Default)]
+pub struct Leibniz<const N: usize> {
+    products: Vec<LeibnizProduct<N>>,
+}
+
+impl<const N: usize> Leibniz<N> {
+    pub fn new() -> Self {
+        Self {
+            products: Vec::new(),
+        }
+    }
+
+    /// Add a product by a permanent class.
+    pub fn add_product(&mut self, name: impl Into<String>, product: Product<N>) {
+        self.products.push(LeibnizProduct {
+            name: name.into(),
+            product,
+            differential: None,
+            propagate: true,
+        });
+    }
+
+    /// Add a product by a class `source` that supports a differential hitting the class
+    /// `target`.
+    pub fn add_product_differential(
+        &mut self,
+        source_name: impl Into<String>,
+        source: Product<N>,
+        target_name: impl Into<String>,
+        target: Product<N>,
+    ) {
+        self.products.push(LeibnizProduct {
+            name: target_name.into(),
+            product: target,
+            differential: None,
+            propagate: false,
+        });
+        self.products.push(LeibnizProduct {
+            name: source_name.into(),
+            product: source,
+            differential: Some(self.products.len() - 1),
+            propagate: true,
+        });
+    }
+
+    /// Add the facts in `input` to `sseq` and propagate them along all products until nothing new
+    /// is learnt. Facts that are already in `sseq` are not propagated unless they are part of
+    /// `input`.
+    ///
+    /// This records every contradiction encountered instead of stopping at the first one, and
+    /// updates `sseq` at the end.
+    pub fn propagate<P: SseqProfile<N>>(
+        &self,
+        sseq: &mut Sseq<N, P>,
+        input: impl IntoIterator<Item = Fact<N>>,
+    ) -> LeibnizReport<N> {
+        let mut report = LeibnizReport::default();
+        let mut inconsistent = HashSet::new();
+        let mut queue = VecDeque::new();
+
+        // Record a fact that has been added to `sseq` and check for contradictions. Returns the
+        // index of the fact.
+        let mut record = |sseq: &mut Sseq<N, P>,
+                          report: &mut LeibnizReport<N>,
+                          fact: Fact<N>,
+                          reason: Reason| {
+            let index = report.facts.len();
+            for b in fact.affected_degrees::<P>() {
+                // This recomputes the targets of the differentials modulo the known boundaries,
+                // which detects inconsistencies.
+                sseq.update_degree(b);
+                if !inconsistent.contains(&b)
+                    && sseq.differentials(b).iter().any(Differential::inconsistent)
+                {
+                    inconsistent.insert(b);
+                    let involved = report
+                        .facts
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, (f, _))| f.affected_degrees::<P>().contains(&b))
+                        .map(|(i, _)| i)
+                        .collect();
+                    report.contradictions.push(Contradiction {
+                        degree: b,
+                        fact: index,
+                        involved,
+                    });
+                }
+            }
+            report.facts.push((fact, reason));
+            index
+        };
+
+        for (index, fact) in input.into_iter().enumerate() {
+            fact.add_to(sseq);
+            let index = record(sseq, &mut report, fact, Reason::Input { index });
+            queue.push_back(index);
+        }
+
+        while let Some(index) = queue.pop_front() {
+            let (r, elem) = match &report.facts[index].0 {
+                Fact::Differential { r, source, .. } => (*r, source.clone()),
+                Fact::PermanentClass(class) => (i32::MAX, class.clone()),
+            };
+            for prod in self.products.iter().filter(|prod| prod.propagate) {
+                let target = prod.differential.map(|i| &self.products[i].product);
+                let Some(fact) = sseq.leibniz_fact(r, &elem, &prod.product, target) else {
+                    continue;
+                };
+                let justification = Justification::Leibniz {
+                    r: (r != i32::MAX).then_some(r),
+                    class: elem.clone(),
+                    product: prod.product.b,
+                    name: Some(prod.name.clone()),
+                };
+                if !fact.add_to_justified(sseq, justification) {
+                    continue;
+                }
+                let reason = Reason::Leibniz {
+                    fact: index,
+                    product: prod.name.clone(),
+                };
+                queue.push_back(record(sseq, &mut report, fact, reason));
+            }
+        }
+
+        sseq.update();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use fp::prime::TWO;
+    use once::MultiIndexed;
+
+    use super::*;
+    use crate::{Adams, CohomologicalSerre, coordinates::Bidegree};
+
+    fn class(x: i32, y: i32) -> MultiDegreeElement<2> {
+        MultiDegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(TWO, &[1]))
+    }
+
+    /// Two $h_0$-towers in stems 0 and 1 of length 6 and 4 respectively.
+    fn towers() -> (Sseq<2, Adams>, Leibniz<2>) {
+        let mut sseq = Sseq::<2, Adams>::new(TWO);
+        let h0 = Product {
+            b: Bidegree::x_y(0, 1),
+            left: true,
+            matrices: MultiIndexed::new(),
+        };
+        for (x, height) in [(0, 6), (1, 4)] {
+            for y in 0..height {
+                sseq.set_dimension(Bidegree::x_y(x, y), 1);
+            }
+            for y in 0..height - 1 {
+                h0.matrices.insert(
+                    Bidegree::x_y(x, y),
+                    fp::matrix::Matrix::from_vec(TWO, &[vec![1]]),
+                );
+            }
+        }
+        let mut leibniz = Leibniz::new();
+        leibniz.add_product("h_0", h0);
+        (sseq, leibniz)
+    }
+
+    fn d2(x: i32, y: i32) -> Fact<2> {
+        Fact::Differential {
+            r: 2,
+            source: class(x, y),
+            target: FpVector::from_slice(TWO, &[1]),
+        }
+    }
+
+    #[test]
+    fn test_leibniz_propagate() {
+        let (mut sseq, leibniz) = towers();
+        let report = leibniz.propagate(&mut sseq, [d2(1, 0)]);
+        assert!(report.is_consistent());
+        for y in 0..4 {
+            assert_eq!(
+                sseq.differentials(Bidegree::x_y(1, y))[2]
+                    .get_source_target_pairs()
+                    .len(),
+                1
+            );
+            assert!(
+                sseq.permanent_classes(Bidegree::x_y(0, y + 2))
+                    .contains(class(0, y + 2).vec())
+            );
+        }
+        assert_eq!(report.trail(3), vec![0, 1, 2, 3]);
+        expect!["d_2 (1, 1, [1]) = [1] (Leibniz rule on d_2 (1, 0, [1]) and h_0)"]
+            .assert_eq(&sseq.provenance(Bidegree::x_y(1, 1))[0].to_string());
+        expect![[r#"
+            d_2 (1, 0, [1]) = [1] (input 0)
+            d_2 (1, 1, [1]) = [1] (h_0 times [0])
+            d_2 (1, 2, [1]) = [1] (h_0 times [1])
+            d_2 (1, 3, [1]) = [1] (h_0 times [2])
+        "#]]
+        .assert_eq(
+            &report
+                .facts
+                .iter()
+                .map(|(fact, reason)| format!("{fact} ({reason})\n"))
+                .collect::<String>(),
+        );
+    }
+
+    /// Inputs that are already known are still propagated.
+    #[test]
+    fn test_leibniz_known_input() {
+        let (mut sseq, leibniz) = towers();
+        d2(1, 0).add_to(&mut sseq);
+        let report = leibniz.propagate(&mut sseq, [d2(1, 0)]);
+        assert!(report.is_consistent());
+        assert_eq!(report.facts.len(), 4);
+        assert_eq!(
+            sseq.differentials(Bidegree::x_y(1, 3))[2]
+                .get_source_target_pairs()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_leibniz_contradiction() {
+        let (mut sseq, leibniz) = towers();
+        let report = leibniz.propagate(&mut sseq, [Fact::PermanentClass(class(1, 2)), d2(1, 0)]);
+        assert!(!report.is_consistent());
+        expect![[r#"
+            Contradiction at (1, 2):
+              [1] d_2 (1, 0, [1]) = [1] (input 1)
+              [3] d_2 (1, 1, [1]) = [1] (h_0 times [1])
+              [4] d_2 (1, 2, [1]) = [1] (h_0 times [3])
+            conflicts with
+              [0] (1, 2, [1]) is permanent (input 0)
+        "#]]
+        .assert_eq(&report.to_string());
+    }
+
+    /// The Serre spectral sequence of $S^1 \to S^\infty \to \mathbb{CP}^\infty$, where
+    /// $d_2 z = x$ and the Leibniz rule kills everything but the unit.
+    #[test]
+    fn test_leibniz_serre() {
+        let mut sseq = Sseq::<2, CohomologicalSerre>::new(TWO);
+        let x = Product {
+            b: Bidegree::x_y(2, 0),
+            left: true,
+            matrices: MultiIndexed::new(),
+        };
+        for p in 0..=8 {
+            for q in 0..=1 {
+                sseq.set_dimension(Bidegree::x_y(p, q), if p % 2 == 0 { 1 } else { 0 });
+                if p % 2 == 0 && p < 8 {
+                    x.matrices.insert(
+                        Bidegree::x_y(p, q),
+                        fp::matrix::Matrix::from_vec(TWO, &[vec![1]]),
+                    );
+                }
+            }
+        }
+        let mut leibniz = Leibniz::new();
+        leibniz.add_product("x", x);
+
+        let report = leibniz.propagate(&mut sseq, [d2(0, 1)]);
+        assert!(report.is_consistent());
+
+        let e3: Vec<_> = sseq
+            .iter_degrees()
+            .filter(|&b| !sseq.page_data(b).get_max(3).is_empty())
+            .map(|b| b.to_string())
+            .collect();
+        expect![[r#"["(0, 0)", "(8, 1)"]"#]].assert_eq(&format!("{e3:?}"));
+        expect!["d_2 (6, 1, [1]) = [1] (Leibniz rule on d_2 (4, 1, [1]) and x)"]
+            .assert_eq(&sseq.provenance(Bidegree::x_y(6, 1))[0].to_string());
+    }
+}
@@ -3,8 +3,15 @@
 pub mod charting;
 pub mod coordinates;
 mod differential;
+mod e_infinity;
+mod leibniz;
+mod provenance;
+mod serialize;
 mod sseq;
 
 pub use differential::*;
+pub use e_infinity::*;
+pub use leibniz::*;
+pub use provenance::*;
 
 pub use crate::sseq::*;
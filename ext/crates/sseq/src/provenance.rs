@@ -0,0 +1,81 @@
+//! Records of why the differentials and permanent classes of a spectral sequence hold.
+//!
+//! Every time a new differential or permanent class is added to an [`Sseq`](crate::Sseq), the
+//! spectral sequence records the fact together with a [`Justification`] at the degree of the
+//! source (or of the permanent class). These can be queried with
+//! [`Sseq::provenance`](crate::Sseq::provenance) and are included when the spectral sequence is
+//! serialized.
+
+use std::fmt;
+
+use crate::{
+    coordinates::{degree::MultiDegree, element::MultiDegreeElement},
+    leibniz::Fact,
+};
+
+/// The reason a differential or permanent class holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Justification<const N: usize> {
+    /// The fact was supplied by the user, e.g. through the GUI or a call to
+    /// [`Sseq::add_differential`](crate::Sseq::add_differential).
+    User,
+    /// The class is permanent because it is hit by the differential $d_r$ `source`.
+    Boundary {
+        r: i32,
+        source: MultiDegreeElement<N>,
+    },
+    /// The fact is obtained by multiplying `class` with a product using the Leibniz rule. `r` is
+    /// the length of the differential on `class`, or `None` if `class` is permanent.
+    Leibniz {
+        r: Option<i32>,
+        class: MultiDegreeElement<N>,
+        /// The degree of the product.
+        product: MultiDegree<N>,
+        /// The name of the product, if known.
+        name: Option<String>,
+    },
+    /// The differential was computed by the secondary Steenrod algebra.
+    Secondary,
+    /// The fact was computed by some other algorithm, e.g. the $d_1$ differentials of the May
+    /// spectral sequence.
+    Computed { method: String },
+}
+
+impl<const N: usize> fmt::Display for Justification<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::User => write!(f, "user input"),
+            Self::Boundary { r, source } => write!(f, "hit by d_{r} {source}"),
+            Self::Leibniz {
+                r,
+                class,
+                product,
+                name,
+            } => {
+                match r {
+                    Some(r) => write!(f, "Leibniz rule on d_{r} {class}")?,
+                    None => write!(f, "Leibniz rule on permanent class {class}")?,
+                }
+                match name {
+                    Some(name) => write!(f, " and {name}"),
+                    None => write!(f, " and the product in degree {product}"),
+                }
+            }
+            Self::Secondary => write!(f, "secondary Steenrod algebra"),
+            Self::Computed { method } => write!(f, "computed by {method}"),
+        }
+    }
+}
+
+/// A fact that was added to a spectral sequence, together with its justification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Provenance<const N: usize> {
+    pub fact: Fact<N>,
+    pub justification: Justification<N>,
+}
+
+impl<const N: usize> fmt::Display for Provenance<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.fact, self.justification)
+    }
+}
@@ -0,0 +1,710 @@
+//! Serialization of [`Sseq`] and [`Product`]. Vectors and matrices are serialized as lists of
+//! integers, so that the output can be read by other programs and diffed across runs.
+
+use fp::{
+    matrix::Matrix,
+    prime::{Prime, ValidPrime},
+    vector::FpVector,
+};
+use once::MultiIndexed;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+use crate::{
+    coordinates::{degree::MultiDegree, element::MultiDegreeElement},
+    leibniz::Fact,
+    provenance::{Justification, Provenance},
+    sseq::{Product, Sseq, SseqProfile},
+};
+
+#[derive(Serialize, Deserialize)]
+struct SseqData<const N: usize> {
+    prime: ValidPrime,
+    degrees: Vec<DegreeData<N>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DegreeData<const N: usize> {
+    degree: MultiDegree<N>,
+    dimension: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    permanent_classes: Vec<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    differentials: Vec<DifferentialData>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    provenance: Vec<ProvenanceData<N>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DifferentialData {
+    r: i32,
+    source: Vec<u32>,
+    target: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProvenanceData<const N: usize> {
+    fact: FactData,
+    justification: JustificationData<N>,
+}
+
+/// A [`Fact`] whose source (or permanent class) lies in the degree it is listed under.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FactData {
+    Differential(DifferentialData),
+    PermanentClass(Vec<u32>),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JustificationData<const N: usize> {
+    User,
+    Boundary {
+        r: i32,
+        source: ElementData<N>,
+    },
+    Leibniz {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        r: Option<i32>,
+        class: ElementData<N>,
+        product: MultiDegree<N>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+    Secondary,
+    Computed {
+        method: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct ElementData<const N: usize> {
+    degree: MultiDegree<N>,
+    vector: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProductData<const N: usize> {
+    degree: MultiDegree<N>,
+    left: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prime: Option<ValidPrime>,
+    matrices: Vec<MatrixData<N>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct MatrixData<const N: usize> {
+    degree: MultiDegree<N>,
+    columns: usize,
+    rows: Vec<Vec<u32>>,
+}
+
+/// Check that `v` is a vector of length `len` over $\mathbb{F}_p$.
+fn to_vector<E: Error>(p: ValidPrime, len: usize, v: &[u32]) -> Result<FpVector, E> {
+    if v.len() != len {
+        return Err(E::custom(format!(
+            "vector {v:?} has length {} instead of {len}",
+            v.len()
+        )));
+    }
+    if let Some(x) = v.iter().find(|&&x| x >= p.as_u32()) {
+        return Err(E::custom(format!(
+            "entry {x} of {v:?} is not reduced mod {p}"
+        )));
+    }
+    Ok(FpVector::from_slice(p, v))
+}
+
+impl<const N: usize> From<&MultiDegreeElement<N>> for ElementData<N> {
+    fn from(elem: &MultiDegreeElement<N>) -> Self {
+        Self {
+            degree: elem.degree(),
+            vector: elem.vec().iter().collect(),
+        }
+    }
+}
+
+impl<const N: usize> From<&Provenance<N>> for ProvenanceData<N> {
+    fn from(provenance: &Provenance<N>) -> Self {
+        let fact = match &provenance.fact {
+            Fact::Differential { r, source, target } => FactData::Differential(DifferentialData {
+                r: *r,
+                source: source.vec().iter().collect(),
+                target: target.iter().collect(),
+            }),
+            Fact::PermanentClass(class) => FactData::PermanentClass(class.vec().iter().collect()),
+        };
+        let justification = match &provenance.justification {
+            Justification::User => JustificationData::User,
+            Justification::Boundary { r, source } => JustificationData::Boundary {
+                r: *r,
+                source: source.into(),
+            },
+            Justification::Leibniz {
+                r,
+                class,
+                product,
+                name,
+            } => JustificationData::Leibniz {
+                r: *r,
+                class: class.into(),
+                product: *product,
+                name: name.clone(),
+            },
+            Justification::Secondary => JustificationData::Secondary,
+            Justification::Computed { method } => JustificationData::Computed {
+                method: method.clone(),
+            },
+        };
+        Self {
+            fact,
+            justification,
+        }
+    }
+}
+
+fn to_element<const N: usize, P: SseqProfile<N>, E: Error>(
+    sseq: &Sseq<N, P>,
+    elem: &ElementData<N>,
+) -> Result<MultiDegreeElement<N>, E> {
+    let Some(dim) = sseq.get_dimension(elem.degree) else {
+        return Err(E::custom(format!("degree {} is not defined", elem.degree)));
+    };
+    let v = to_vector(sseq.prime(), dim, &elem.vector)?;
+    Ok(MultiDegreeElement::new(elem.degree, v))
+}
+
+/// Check that `diff` is a differential with source in degree `b` and return its source and
+/// target.
+fn to_differential<const N: usize, P: SseqProfile<N>, E: Error>(
+    sseq: &Sseq<N, P>,
+    b: MultiDegree<N>,
+    diff: &DifferentialData,
+) -> Result<(MultiDegreeElement<N>, FpVector), E> {
+    if diff.r < P::MIN_R {
+        return Err(E::custom(format!(
+            "invalid differential d_{} at {b}",
+            diff.r
+        )));
+    }
+    let target_b = P::profile(diff.r, b);
+    let Some(target_dim) = sseq.get_dimension(target_b) else {
+        return Err(E::custom(format!(
+            "target {target_b} of d_{} at {b} is not defined",
+            diff.r
+        )));
+    };
+    let source = to_vector(sseq.prime(), sseq.dimension(b), &diff.source)?;
+    let target = to_vector(sseq.prime(), target_dim, &diff.target)?;
+    Ok((MultiDegreeElement::new(b, source), target))
+}
+
+fn to_provenance<const N: usize, P: SseqProfile<N>, E: Error>(
+    sseq: &Sseq<N, P>,
+    b: MultiDegree<N>,
+    data: &ProvenanceData<N>,
+) -> Result<Provenance<N>, E> {
+    let fact = match &data.fact {
+        FactData::Differential(diff) => {
+            let (source, target) = to_differential(sseq, b, diff)?;
+            Fact::Differential {
+                r: diff.r,
+                source,
+                target,
+            }
+        }
+        FactData::PermanentClass(v) => Fact::PermanentClass(MultiDegreeElement::new(
+            b,
+            to_vector(sseq.prime(), sseq.dimension(b), v)?,
+        )),
+    };
+    let justification = match &data.justification {
+        JustificationData::User => Justification::User,
+        JustificationData::Boundary { r, source } => Justification::Boundary {
+            r: *r,
+            source: to_element(sseq, source)?,
+        },
+        JustificationData::Leibniz {
+            r,
+            class,
+            product,
+            name,
+        } => Justification::Leibniz {
+            r: *r,
+            class: to_element(sseq, class)?,
+            product: *product,
+            name: name.clone(),
+        },
+        JustificationData::Secondary => Justification::Secondary,
+        JustificationData::Computed { method } => Justification::Computed {
+            method: method.clone(),
+        },
+    };
+    Ok(Provenance {
+        fact,
+        justification,
+    })
+}
+
+/// A spectral sequence is serialized as the data needed to reconstruct it: the dimension of each
+/// degree, the permanent classes and the differentials, together with the
+/// [provenance](Sseq::provenance) of the facts that were added. The page data is not serialized, but is
+/// recomputed when deserializing, so the deserialized spectral sequence is always up to date (see
+/// [`Sseq::update`]).
+///
+/// The format is
+/// ```json
+/// {
+///   "prime": 2,
+///   "degrees": [
+///     {
+///       "degree": { "coords": [1, 0] },
+///       "dimension": 1,
+///       "permanent_classes": [[1]],
+///       "differentials": [
+///         { "r": 2, "source": [1], "target": [1] }
+///       ],
+///       "provenance": [
+///         {
+///           "fact": { "differential": { "r": 2, "source": [1], "target": [1] } },
+///           "justification": "user"
+///         }
+///       ]
+///     }
+///   ]
+/// }
+/// ```
+/// where the degrees are listed in sorted order, and `permanent_classes`, `differentials` and
+/// `provenance` are omitted if empty. Each entry of `differentials` says that $d_r$ `source` =
+/// `target`. These are the rows of the [`Differential`](crate::Differential) matrices, so they need
+/// not be the differentials that were originally added, but they span the same space.
+///
+/// The entries of `provenance` are the facts that were actually added, in order. A `fact` is
+/// either a `differential` as above or a `permanent_class` given by a vector. The `justification`
+/// is one of `"user"`, `"secondary"`, or an object with a single key: `boundary` with fields `r`
+/// and `source`, `leibniz` with fields `r` (omitted if the class is permanent), `class`, `product`
+/// and `name` (optional), or `computed` with field `method`. Here `source` and `class` are of the
+/// form `{ "degree": ..., "vector": [...] }`. Spectral sequences saved without provenance load
+/// with no provenance recorded.
+impl<const N: usize, P: SseqProfile<N>> Serialize for Sseq<N, P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let degrees = self
+            .iter_degrees()
+            .map(|b| DegreeData {
+                degree: b,
+                dimension: self.dimension(b),
+                permanent_classes: self
+                    .permanent_classes(b)
+                    .basis()
+                    .map(|v| v.iter().collect())
+                    .collect(),
+                differentials: self
+                    .differentials(b)
+                    .iter_enum()
+                    .flat_map(|(r, d)| {
+                        d.get_source_target_pairs()
+                            .into_iter()
+                            .map(move |(source, target)| DifferentialData {
+                                r,
+                                source: source.iter().collect(),
+                                target: target.iter().collect(),
+                            })
+                    })
+                    .collect(),
+                provenance: self.provenance(b).iter().map(Into::into).collect(),
+            })
+            .collect();
+        SseqData {
+            prime: self.prime(),
+            degrees,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const N: usize, P: SseqProfile<N>> Deserialize<'de> for Sseq<N, P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SseqData::<N>::deserialize(deserializer)?;
+        let p = data.prime;
+
+        let mut sseq = Self::new(p);
+        for d in &data.degrees {
+            if sseq.defined(d.degree) {
+                return Err(D::Error::custom(format!(
+                    "degree {} listed twice",
+                    d.degree
+                )));
+            }
+            sseq.set_dimension(d.degree, d.dimension);
+        }
+
+        for d in &data.degrees {
+            for v in &d.permanent_classes {
+                let v = to_vector(p, d.dimension, v)?;
+                sseq.add_permanent_class(&MultiDegreeElement::new(d.degree, v));
+            }
+            for diff in &d.differentials {
+                let (source, target) = to_differential(&sseq, d.degree, diff)?;
+                sseq.add_differential(diff.r, &source, target.as_slice());
+            }
+        }
+        // Replaying the facts above records them as user input, so we restore the saved
+        // provenance afterwards.
+        for d in &data.degrees {
+            let provenance = d
+                .provenance
+                .iter()
+                .map(|x| to_provenance(&sseq, d.degree, x))
+                .collect::<Result<_, _>>()?;
+            sseq.set_provenance(d.degree, provenance);
+        }
+        sseq.update();
+        Ok(sseq)
+    }
+}
+
+/// The format is
+/// ```json
+/// {
+///   "degree": { "coords": [0, 1] },
+///   "left": true,
+///   "prime": 2,
+///   "matrices": [
+///     { "degree": { "coords": [0, 0] }, "columns": 1, "rows": [[1]] }
+///   ]
+/// }
+/// ```
+/// where `prime` is omitted if there are no matrices.
+impl<const N: usize> Serialize for Product<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut prime = None;
+        let matrices = self
+            .matrices
+            .iter()
+            .map(|(b, m)| {
+                prime = Some(m.prime());
+                MatrixData {
+                    degree: MultiDegree::new(b),
+                    columns: m.columns(),
+                    rows: (0..m.rows()).map(|i| m.row(i).iter().collect()).collect(),
+                }
+            })
+            .collect();
+        ProductData {
+            degree: self.b,
+            left: self.left,
+            prime,
+            matrices,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Product<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ProductData::<N>::deserialize(deserializer)?;
+
+        let matrices = MultiIndexed::new();
+        if !data.matrices.is_empty() {
+            let Some(p) = data.prime else {
+                return Err(D::Error::missing_field("prime"));
+            };
+            for m in data.matrices {
+                for row in &m.rows {
+                    to_vector::<D::Error>(p, m.columns, row)?;
+                }
+                let matrix = if m.rows.is_empty() {
+                    Matrix::new(p, 0, m.columns)
+                } else {
+                    Matrix::from_vec(p, &m.rows)
+                };
+                if matrices.try_insert(m.degree, matrix).is_err() {
+                    return Err(D::Error::custom(format!(
+                        "degree {} listed twice",
+                        m.degree
+                    )));
+                }
+            }
+        }
+
+        Ok(Self {
+            b: data.degree,
+            left: data.left,
+            matrices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use fp::prime::TWO;
+
+    use super::*;
+    use crate::{Adams, coordinates::Bidegree};
+
+    fn example() -> Sseq<2, Adams> {
+        let mut sseq = Sseq::<2, Adams>::new(TWO);
+        sseq.set_dimension(Bidegree::x_y(0, 0), 1);
+        sseq.set_dimension(Bidegree::x_y(1, 0), 2);
+        sseq.set_dimension(Bidegree::x_y(0, 2), 1);
+        sseq.set_dimension(Bidegree::x_y(0, 3), 1);
+        sseq.add_differential(
+            2,
+            &MultiDegreeElement::new(Bidegree::x_y(1, 0), FpVector::from_slice(TWO, &[1, 1])),
+            FpVector::from_slice(TWO, &[1]).as_slice(),
+        );
+        sseq.add_permanent_class(&MultiDegreeElement::new(
+            Bidegree::x_y(0, 0),
+            FpVector::from_slice(TWO, &[1]),
+        ));
+        sseq.update();
+        sseq
+    }
+
+    #[test]
+    fn test_sseq_roundtrip() {
+        let sseq = example();
+        let json = serde_json::to_string_pretty(&sseq).unwrap();
+        expect![[r#"
+            {
+              "prime": 2,
+              "degrees": [
+                {
+                  "degree": {
+                    "coords": [
+                      0,
+                      0
+                    ]
+                  },
+                  "dimension": 1,
+                  "permanent_classes": [
+                    [
+                      1
+                    ]
+                  ],
+                  "provenance": [
+                    {
+                      "fact": {
+                        "permanent_class": [
+                          1
+                        ]
+                      },
+                      "justification": "user"
+                    }
+                  ]
+                },
+                {
+                  "degree": {
+                    "coords": [
+                      0,
+                      2
+                    ]
+                  },
+                  "dimension": 1,
+                  "permanent_classes": [
+                    [
+                      1
+                    ]
+                  ],
+                  "provenance": [
+                    {
+                      "fact": {
+                        "permanent_class": [
+                          1
+                        ]
+                      },
+                      "justification": {
+                        "boundary": {
+                          "r": 2,
+                          "source": {
+                            "degree": {
+                              "coords": [
+                                1,
+                                0
+                              ]
+                            },
+                            "vector": [
+                              1,
+                              1
+                            ]
+                          }
+                        }
+                      }
+                    }
+                  ]
+                },
+                {
+                  "degree": {
+                    "coords": [
+                      0,
+                      3
+                    ]
+                  },
+                  "dimension": 1
+                },
+                {
+                  "degree": {
+                    "coords": [
+                      1,
+                      0
+                    ]
+                  },
+                  "dimension": 2,
+                  "differentials": [
+                    {
+                      "r": 2,
+                      "source": [
+                        1,
+                        1
+                      ],
+                      "target": [
+                        1
+                      ]
+                    }
+                  ],
+                  "provenance": [
+                    {
+                      "fact": {
+                        "differential": {
+                          "r": 2,
+                          "source": [
+                            1,
+                            1
+                          ],
+                          "target": [
+                            1
+                          ]
+                        }
+                      },
+                      "justification": "user"
+                    }
+                  ]
+                }
+              ]
+            }"#]]
+        .assert_eq(&json);
+
+        let loaded: Sseq<2, Adams> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&loaded).unwrap(), json);
+        for b in sseq.iter_degrees() {
+            let (x, y) = (sseq.page_data(b), loaded.page_data(b));
+            assert_eq!(x.range(), y.range(), "page data differs at {b}");
+            for r in x.range() {
+                assert_eq!(
+                    x[r].quotient_dimension(),
+                    y[r].quotient_dimension(),
+                    "E_{r} differs at {b}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sseq_invalid() {
+        let json = serde_json::to_string(&example()).unwrap();
+        let invalid = [
+            json.replace(r#""dimension":2"#, r#""dimension":3"#),
+            json.replace(r#""prime":2"#, r#""prime":4"#),
+            json.replace(r#""r":2"#, r#""r":1"#),
+            json.replace(r#""target":[1]"#, r#""target":[2]"#),
+            json.replace(r#""vector":[1,1]"#, r#""vector":[1]"#),
+        ];
+        for json in invalid {
+            assert!(
+                serde_json::from_str::<Sseq<2, Adams>>(&json).is_err(),
+                "{json}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_provenance_roundtrip() {
+        let mut sseq = Sseq::<2, Adams>::new(TWO);
+        for (x, y) in [(0, 0), (0, 1), (1, 0), (1, 1), (0, 2), (0, 3)] {
+            sseq.set_dimension(Bidegree::x_y(x, y), 1);
+        }
+        let class =
+            |x, y| MultiDegreeElement::new(Bidegree::x_y(x, y), FpVector::from_slice(TWO, &[1]));
+        let one = FpVector::from_slice(TWO, &[1]);
+
+        sseq.add_permanent_class_justified(
+            &class(0, 0),
+            Justification::Computed {
+                method: "May".to_string(),
+            },
+        );
+        sseq.add_differential_justified(2, &class(1, 0), one.as_slice(), Justification::Secondary);
+        sseq.add_differential_justified(
+            2,
+            &class(1, 1),
+            one.as_slice(),
+            Justification::Leibniz {
+                r: Some(2),
+                class: class(1, 0),
+                product: Bidegree::x_y(0, 1),
+                name: Some("h_0".to_string()),
+            },
+        );
+        sseq.add_permanent_class_justified(
+            &class(0, 1),
+            Justification::Leibniz {
+                r: None,
+                class: class(0, 0),
+                product: Bidegree::x_y(0, 1),
+                name: None,
+            },
+        );
+        sseq.update();
+
+        let json = serde_json::to_string(&sseq).unwrap();
+        let loaded: Sseq<2, Adams> = serde_json::from_str(&json).unwrap();
+        let provenance = loaded
+            .iter_degrees()
+            .flat_map(|b| loaded.provenance(b))
+            .map(|x| format!("{x}\n"))
+            .collect::<String>();
+        expect![[r#"
+            (0, 0, [1]) is permanent (computed by May)
+            (0, 1, [1]) is permanent (Leibniz rule on permanent class (0, 0, [1]) and the product in degree (0, 1))
+            (0, 2, [1]) is permanent (hit by d_2 (1, 0, [1]))
+            (0, 3, [1]) is permanent (hit by d_2 (1, 1, [1]))
+            d_2 (1, 0, [1]) = [1] (secondary Steenrod algebra)
+            d_2 (1, 1, [1]) = [1] (Leibniz rule on d_2 (1, 0, [1]) and h_0)
+        "#]].assert_eq(&provenance);
+        for b in sseq.iter_degrees() {
+            assert_eq!(sseq.provenance(b), loaded.provenance(b));
+        }
+    }
+
+    #[test]
+    fn test_product_roundtrip() {
+        let product = Product {
+            b: Bidegree::x_y(0, 1),
+            left: true,
+            matrices: MultiIndexed::new(),
+        };
+        product
+            .matrices
+            .insert(Bidegree::x_y(0, 0), Matrix::from_vec(TWO, &[vec![1, 0]]));
+        product
+            .matrices
+            .insert(Bidegree::x_y(1, 0), Matrix::new(TWO, 0, 3));
+
+        let json = serde_json::to_string(&product).unwrap();
+        expect![[r#"{"degree":{"coords":[0,1]},"left":true,"prime":2,"matrices":[{"degree":{"coords":[0,0]},"columns":2,"rows":[[1,0]]},{"degree":{"coords":[1,0]},"columns":3,"rows":[]}]}"#]]
+            .assert_eq(&json);
+
+        let loaded: Product<2> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+
+        let empty = Product::<2> {
+            b: Bidegree::x_y(0, 1),
+            left: false,
+            matrices: MultiIndexed::new(),
+        };
+        let json = serde_json::to_string(&empty).unwrap();
+        expect![[r#"{"degree":{"coords":[0,1]},"left":false,"matrices":[]}"#]].assert_eq(&json);
+        assert!(serde_json::from_str::<Product<2>>(&json).is_ok());
+    }
+}
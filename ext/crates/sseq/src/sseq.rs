@@ -11,14 +11,25 @@ use once::MultiIndexed;
 use crate::{
     coordinates::{Bidegree, BidegreeGenerator, degree::MultiDegree, element::MultiDegreeElement},
     differential::Differential,
+    leibniz::Fact,
+    provenance::{Justification, Provenance},
 };
 
 /// The direction of the differentials
 pub trait SseqProfile<const N: usize> {
     const MIN_R: i32;
+    /// Whether charts label the `x`-axis by the actual `x`-coordinates. Otherwise the minimum `x`
+    /// is labelled 0, so that e.g. an unstable Adams chart is labelled by `t - s - min_degree`.
+    const ABSOLUTE_X_LABELS: bool = false;
     fn profile(r: i32, b: MultiDegree<N>) -> MultiDegree<N>;
     fn profile_inverse(r: i32, b: MultiDegree<N>) -> MultiDegree<N>;
     fn differential_length(offset: MultiDegree<N>) -> i32;
+
+    /// The total degree of a class in degree `b`, which determines the signs in the Leibniz rule.
+    /// For Adams-graded spectral sequences this is the stem.
+    fn total_degree(b: MultiDegree<N>) -> i32 {
+        b.n()
+    }
 }
 
 pub struct Adams;
@@ -39,6 +50,150 @@ impl SseqProfile<2> for Adams {
     }
 }
 
+/// The Adams–Novikov spectral sequence, bigraded by `(n, s)`. The differential $d_r$ has degree
+/// $(-1, r)$ as in the Adams spectral sequence, but the $E_2$ page is concentrated in even total
+/// degree, so that only the odd differentials can be non-zero.
+pub struct AdamsNovikov;
+
+impl SseqProfile<2> for AdamsNovikov {
+    const MIN_R: i32 = 2;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(-1, r)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(1, -r)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        offset.y()
+    }
+}
+
+/// The cohomological Serre spectral sequence, bigraded by `(p, q)` where `p` is the base degree and
+/// `q` the fiber degree. The differential $d_r$ has degree $(r, 1 - r)$. This is also the grading
+/// of the cohomological Atiyah–Hirzebruch spectral sequence $H^p(X; E^q) \Rightarrow E^{p + q}(X)$.
+pub struct CohomologicalSerre;
+
+/// The cohomological Atiyah–Hirzebruch spectral sequence, graded like the cohomological Serre
+/// spectral sequence.
+pub type CohomologicalAhss = CohomologicalSerre;
+
+impl SseqProfile<2> for CohomologicalSerre {
+    const MIN_R: i32 = 2;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(r, 1 - r)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(-r, r - 1)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        offset.x()
+    }
+
+    fn total_degree(b: Bidegree) -> i32 {
+        b.x() + b.y()
+    }
+}
+
+/// The homological Serre spectral sequence, bigraded by `(p, q)` where `p` is the base degree and
+/// `q` the fiber degree. The differential $d_r$ has degree $(-r, r - 1)$. This is also the grading
+/// of the homological Atiyah–Hirzebruch spectral sequence $H_p(X; E_q) \Rightarrow E_{p + q}(X)$.
+pub struct HomologicalSerre;
+
+/// The homological Atiyah–Hirzebruch spectral sequence, graded like the homological Serre spectral
+/// sequence.
+pub type HomologicalAhss = HomologicalSerre;
+
+impl SseqProfile<2> for HomologicalSerre {
+    const MIN_R: i32 = 2;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(-r, r - 1)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(r, 1 - r)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        -offset.x()
+    }
+
+    fn total_degree(b: Bidegree) -> i32 {
+        b.x() + b.y()
+    }
+}
+
+/// The homotopy fixed point spectral sequence $H^s(G; \pi_t E) \Rightarrow \pi_{t - s} E^{hG}$,
+/// drawn in Adams grading `(t - s, s)`. The differential $d_r$ has degree $(-1, r)$. The Tate
+/// spectral sequence has the same grading, except that `s` can be negative.
+pub struct HomotopyFixedPoint;
+
+/// The Tate spectral sequence, graded like the homotopy fixed point spectral sequence.
+pub type Tate = HomotopyFixedPoint;
+
+impl SseqProfile<2> for HomotopyFixedPoint {
+    const MIN_R: i32 = 2;
+    const ABSOLUTE_X_LABELS: bool = true;
+
+    fn profile(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(-1, r)
+    }
+
+    fn profile_inverse(r: i32, b: Bidegree) -> Bidegree {
+        b + Bidegree::x_y(1, -r)
+    }
+
+    fn differential_length(offset: Bidegree) -> i32 {
+        offset.y()
+    }
+}
+
+/// The motivic Adams spectral sequence, trigraded by `(n, s, w)`. The differential $d_r$ has
+/// degree $(-1, r, 0)$.
+pub struct MotivicAdams;
+
+impl SseqProfile<3> for MotivicAdams {
+    const MIN_R: i32 = 2;
+
+    fn profile(r: i32, b: MultiDegree<3>) -> MultiDegree<3> {
+        b + MultiDegree::new([-1, r, 0])
+    }
+
+    fn profile_inverse(r: i32, b: MultiDegree<3>) -> MultiDegree<3> {
+        b + MultiDegree::new([1, -r, 0])
+    }
+
+    fn differential_length(offset: MultiDegree<3>) -> i32 {
+        offset.s()
+    }
+}
+
+/// The May spectral sequence, trigraded by `(n, s, u)` where `u` is the May filtration. The
+/// differential $d_r$ has degree $(-1, 1, 1 - r)$.
+pub struct May;
+
+impl SseqProfile<3> for May {
+    const MIN_R: i32 = 1;
+
+    fn profile(r: i32, b: MultiDegree<3>) -> MultiDegree<3> {
+        b + MultiDegree::new([-1, 1, 1 - r])
+    }
+
+    fn profile_inverse(r: i32, b: MultiDegree<3>) -> MultiDegree<3> {
+        b + MultiDegree::new([1, -1, r - 1])
+    }
+
+    fn differential_length(offset: MultiDegree<3>) -> i32 {
+        1 - offset.coords()[2]
+    }
+}
+
 pub struct Product<const N: usize> {
     pub b: MultiDegree<N>,
     /// Whether the product acts on the left or not. This affects the sign in the Leibniz rule.
@@ -46,7 +201,7 @@ pub struct Product<const N: usize> {
     pub matrices: MultiIndexed<N, Matrix>,
 }
 
-struct DegreeData {
+struct DegreeData<const N: usize> {
     /// The dimension of the module at this degree (i.e. the number of generators).
     dimension: usize,
 
@@ -66,6 +221,10 @@ struct DegreeData {
 
     permanent_classes: Subspace,
 
+    /// The differentials and permanent classes added at this degree, in order, with their
+    /// justifications.
+    provenance: Vec<Provenance<N>>,
+
     /// Whether the page_data is no longer accurate.
     invalid: bool,
 }
@@ -78,7 +237,7 @@ pub struct Sseq<const N: usize, P: SseqProfile<N>> {
     /// # Invariants:
     /// - if `data[b].differentials[r]` is defined, then `data[b].page_data[r + 1]` and
     ///   `data[target].page_data[r + 1]` are always defined,
-    data: MultiIndexed<N, DegreeData>,
+    data: MultiIndexed<N, DegreeData<N>>,
 
     // `P` is itself a marker, so it's safe to claim that we own one. As opposed to
     // `PhantomData<*const P>`, this lets us implement `Send` and `Sync`.
@@ -94,6 +253,10 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         }
     }
 
+    pub fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
     pub fn min(&self) -> MultiDegree<N> {
         self.data
             .min_coords()
@@ -127,6 +290,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
                 differentials: BiVec::new(P::MIN_R),
                 page_data,
                 permanent_classes: Subspace::new(self.p, dim),
+                provenance: Vec::new(),
                 invalid: false,
             },
         );
@@ -135,6 +299,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
     pub fn clear(&mut self) {
         for (_, bd) in self.data.iter_mut() {
             bd.permanent_classes.set_to_zero();
+            bd.provenance.clear();
             bd.differentials
                 .iter_mut()
                 .for_each(Differential::set_to_zero);
@@ -152,10 +317,25 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         Some(self.data.get(b)?.dimension)
     }
 
+    /// Add a permanent class given by the user.
+    ///
     /// # Returns
     ///
     /// Whether a new permanent class was added
     pub fn add_permanent_class(&mut self, elem: &MultiDegreeElement<N>) -> bool {
+        self.add_permanent_class_justified(elem, Justification::User)
+    }
+
+    /// Add a permanent class and record `justification` as the reason it is permanent.
+    ///
+    /// # Returns
+    ///
+    /// Whether a new permanent class was added
+    pub fn add_permanent_class_justified(
+        &mut self,
+        elem: &MultiDegreeElement<N>,
+        justification: Justification<N>,
+    ) -> bool {
         let bd = &mut self.data[elem.degree()];
         let old_dim = bd.permanent_classes.dimension();
         let new_dim = bd.permanent_classes.add_vector(elem.vec());
@@ -165,6 +345,10 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
                 d.add(elem.vec(), None);
             }
             bd.invalid = true;
+            bd.provenance.push(Provenance {
+                fact: Fact::PermanentClass(elem.clone()),
+                justification,
+            });
         }
         old_dim != new_dim
     }
@@ -193,7 +377,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         }
     }
 
-    /// Add a $d_r$ differential with the given `source` and `target` classes.
+    /// Add a $d_r$ differential given by the user with the given `source` and `target` classes.
     ///
     /// # Return
     ///
@@ -203,6 +387,22 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         r: i32,
         source: &MultiDegreeElement<N>,
         target: FpSlice,
+    ) -> bool {
+        self.add_differential_justified(r, source, target, Justification::User)
+    }
+
+    /// Add a $d_r$ differential and record `justification` as the reason it holds. If the target
+    /// is non-zero, it is recorded as a permanent class hit by this differential.
+    ///
+    /// # Return
+    ///
+    /// Whether the differential is new
+    pub fn add_differential_justified(
+        &mut self,
+        r: i32,
+        source: &MultiDegreeElement<N>,
+        target: FpSlice,
+        justification: Justification<N>,
     ) -> bool {
         let target_b = P::profile(r, source.degree());
 
@@ -217,9 +417,23 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         let is_new = self.data[source.degree()].differentials[r].add(source.vec(), Some(target));
         if is_new {
             self.data[source.degree()].invalid = true;
+            self.data[source.degree()].provenance.push(Provenance {
+                fact: Fact::Differential {
+                    r,
+                    source: source.clone(),
+                    target: target.to_owned(),
+                },
+                justification,
+            });
             if !target.is_zero() {
                 self.data[target_b].invalid = true;
-                self.add_permanent_class(&MultiDegreeElement::new(target_b, target.to_owned()));
+                self.add_permanent_class_justified(
+                    &MultiDegreeElement::new(target_b, target.to_owned()),
+                    Justification::Boundary {
+                        r,
+                        source: source.clone(),
+                    },
+                );
                 let target_page_len = self.data[target_b].page_data.len();
                 for r in r + 1..target_page_len {
                     self.data[target_b].page_data[r].quotient(target);
@@ -368,6 +582,18 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         &self.data[b].page_data
     }
 
+    /// The differentials with source in degree `b` and the permanent classes in degree `b`, in
+    /// the order they were added, together with the reasons they hold. Facts that were already
+    /// implied by earlier ones are not recorded.
+    pub fn provenance(&self, b: MultiDegree<N>) -> &[Provenance<N>] {
+        &self.data[b].provenance
+    }
+
+    /// Replace the provenance records at degree `b`. This is used when deserializing.
+    pub(crate) fn set_provenance(&mut self, b: MultiDegree<N>, provenance: Vec<Provenance<N>>) {
+        self.data[b].provenance = provenance;
+    }
+
     /// Compute the product between `product` and the class `class`. Returns `None` if
     /// the product is not yet computed.
     pub fn multiply(
@@ -404,15 +630,51 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
         source_product: &Product<N>,
         target_product: Option<&Product<N>>,
     ) -> Option<(i32, MultiDegreeElement<N>)> {
+        self.leibniz_named(r, elem, None, source_product, target_product)
+    }
+
+    /// The same as [`Sseq::leibniz`], but records `name` as the name of `source_product` in the
+    /// [`Justification`] of the new fact.
+    pub fn leibniz_named(
+        &mut self,
+        r: i32,
+        elem: &MultiDegreeElement<N>,
+        name: Option<&str>,
+        source_product: &Product<N>,
+        target_product: Option<&Product<N>>,
+    ) -> Option<(i32, MultiDegreeElement<N>)> {
+        let fact = self.leibniz_fact(r, elem, source_product, target_product)?;
+        let justification = Justification::Leibniz {
+            r: (r != i32::MAX).then_some(r),
+            class: elem.clone(),
+            product: source_product.b,
+            name: name.map(String::from),
+        };
+        match fact {
+            Fact::PermanentClass(source) => self
+                .add_permanent_class_justified(&source, justification)
+                .then_some((i32::MAX, source)),
+            Fact::Differential { r, source, target } => self
+                .add_differential_justified(r, &source, target.as_slice(), justification)
+                .then_some((r, source)),
+        }
+    }
+
+    /// Compute the fact given by the Leibniz rule without adding it. See [`Sseq::leibniz`] for
+    /// the arguments. This returns `None` if the data needed to compute the product is not yet
+    /// available.
+    pub fn leibniz_fact(
+        &self,
+        r: i32,
+        elem: &MultiDegreeElement<N>,
+        source_product: &Product<N>,
+        target_product: Option<&Product<N>>,
+    ) -> Option<Fact<N>> {
         let source = self.multiply(elem, source_product)?;
 
         // The class and the product are both permanent.
         if r == i32::MAX && target_product.is_none() {
-            if self.add_permanent_class(&source) {
-                return Some((i32::MAX, source));
-            } else {
-                return None;
-            }
+            return Some(Fact::PermanentClass(source));
         }
 
         let neg_1 = self.p - 1;
@@ -434,7 +696,7 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
             let d = MultiDegreeElement::new(d_b, dx);
             let target = self.multiply(&d, source_product)?;
 
-            if source_product.left && source_product.b.x() % 2 != 0 {
+            if source_product.left && P::total_degree(source_product.b) % 2 != 0 {
                 result.add(&target.into_vec(), neg_1);
             } else {
                 result.add(&target.into_vec(), 1);
@@ -445,30 +707,46 @@ impl<const N: usize, P: SseqProfile<N>> Sseq<N, P> {
             let target = self.multiply(elem, target_product.unwrap())?;
             // why is this x - 1 but not x? This is what the original code does and came from trial
             // and error(?)
-            if !source_product.left && (elem.x() - 1) % 2 != 0 {
+            if !source_product.left && (P::total_degree(elem.degree()) - 1) % 2 != 0 {
                 result.add(&target.into_vec(), neg_1);
             } else {
                 result.add(&target.into_vec(), 1);
             }
         }
 
-        if self.add_differential(result_r, &source, result.as_slice()) {
-            Some((result_r, source))
-        } else {
-            None
-        }
+        Some(Fact::Differential {
+            r: result_r,
+            source,
+            target: result,
+        })
     }
 }
 
 /// Bigraded-specific methods (charting support).
 impl<P: SseqProfile<2>> Sseq<2, P> {
-    /// This shifts the sseq horizontally so that the minimum x is 0.
+    /// The coordinates that label the bottom left corner of a chart. See
+    /// [`SseqProfile::ABSOLUTE_X_LABELS`].
+    pub(crate) fn label_origin(&self) -> Bidegree {
+        let min = self.min();
+        if P::ABSOLUTE_X_LABELS {
+            min
+        } else {
+            Bidegree::x_y(0, min.y())
+        }
+    }
+
     /// Fallible variant of [`Self::write_to_graph`]: this holds the actual drawing logic.
     ///
+    /// This shifts the sseq horizontally so that the minimum x is 0. The stems are labelled starting
+    /// from 0 unless [`SseqProfile::ABSOLUTE_X_LABELS`] is set, in which case they are labelled by
+    /// the actual x-coordinates.
+    ///
     /// [`Self::write_to_graph`] requires the spectral sequence to have already been shifted so that
-    /// its minimum `y`-coordinate is `0`. This variant checks that precondition up front and
-    /// returns `Err(String)` instead of panicking; when it holds, the inner `Result<(), T::Error>`
-    /// carries any backend error. [`Self::write_to_graph`] is `try_write_to_graph(..).unwrap()`.
+    /// the chart contains the `y = 0` line, i.e. its minimum `y`-coordinate is at most `0`. It may
+    /// be negative, e.g. for the Tate spectral sequence, in which case the grid is labelled by the
+    /// actual filtrations. This variant checks that precondition up front and returns
+    /// `Err(String)` instead of panicking; when it holds, the inner `Result<(), T::Error>` carries
+    /// any backend error. [`Self::write_to_graph`] is `try_write_to_graph(..).unwrap()`.
     #[allow(clippy::type_complexity)]
     pub fn try_write_to_graph<'a, T: crate::charting::Backend>(
         &self,
@@ -479,10 +757,10 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
         header: impl FnOnce(&mut T) -> Result<(), T::Error>,
     ) -> Result<Result<(), T::Error>, String> {
         let min = self.min();
-        if min.y() != 0 {
+        if min.y() > 0 {
             return Err(format!(
-                "write_to_graph requires the minimum y-coordinate to be 0, found {}; shift the \
-                 spectral sequence first",
+                "write_to_graph requires the minimum y-coordinate to be at most 0, found {}; \
+                 shift the spectral sequence first",
                 min.y()
             ));
         }
@@ -490,7 +768,7 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
         Ok((move || {
             let max = self.max();
 
-            g.init(max - min)?;
+            g.init_with_labels(max - min, self.label_origin())?;
             header(&mut g)?;
 
             for b in self.iter_degrees() {
@@ -529,7 +807,7 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
                     let target_b = P::profile(r, b);
                     let shifted_target = target_b - min;
 
-                    if target_b.x() < 0 {
+                    if !self.defined(target_b) {
                         continue;
                     }
                     let d = self.differentials(b);
@@ -582,7 +860,7 @@ impl<P: SseqProfile<2>> Sseq<2, P> {
         header: impl FnOnce(&mut T) -> Result<(), T::Error>,
     ) -> Result<(), T::Error> {
         self.try_write_to_graph(g, r, differentials, products, header)
-            .expect("write_to_graph requires the minimum y-coordinate to be 0")
+            .expect("write_to_graph requires the minimum y-coordinate to be at most 0")
     }
 }
 
@@ -961,7 +1239,7 @@ mod tests {
         // The inner backend result should also be Ok for this simple chart.
         assert!(result.unwrap().is_ok());
 
-        // INVALID case: a sseq whose minimum y-coordinate is nonzero violates the
+        // INVALID case: a sseq whose minimum y-coordinate is positive violates the
         // precondition. The original `write_to_graph` would panic via `assert_eq!`; the
         // fallible variant must instead return `Err(String)`.
         let mut bad = Sseq::<2, Adams>::new(p);
@@ -974,7 +1252,131 @@ mod tests {
             no_products,
             |_| Ok(()),
         );
-        assert!(err.is_err(), "nonzero min y should error, not panic");
+        assert!(err.is_err(), "positive min y should error, not panic");
         assert!(err.unwrap_err().contains("minimum y-coordinate"));
     }
+
+    #[test]
+    fn test_profiles() {
+        fn check<P: SseqProfile<2>>(b: Bidegree) {
+            for r in P::MIN_R..6 {
+                let target = P::profile(r, b);
+                assert_eq!(P::profile_inverse(r, target), b);
+                assert_eq!(P::differential_length(target - b), r);
+            }
+        }
+        for b in [
+            Bidegree::x_y(0, 0),
+            Bidegree::x_y(3, -2),
+            Bidegree::x_y(-1, 5),
+        ] {
+            check::<Adams>(b);
+            check::<AdamsNovikov>(b);
+            check::<CohomologicalSerre>(b);
+            check::<HomologicalSerre>(b);
+            check::<HomotopyFixedPoint>(b);
+        }
+
+        assert_eq!(
+            CohomologicalAhss::profile(2, Bidegree::x_y(0, 1)),
+            Bidegree::x_y(2, 0)
+        );
+        assert_eq!(
+            HomologicalAhss::profile(3, Bidegree::x_y(3, 0)),
+            Bidegree::x_y(0, 2)
+        );
+        assert_eq!(Tate::profile(3, Bidegree::x_y(1, -2)), Bidegree::x_y(0, 1));
+    }
+
+    /// In the Serre grading, the sign in the Leibniz rule is determined by the total degree, so
+    /// multiplying $d_2 z = x$ by a class $w$ of degree $(0, 1)$ gives $d_2(wz) = -wx$.
+    #[test]
+    fn test_serre_leibniz_sign() {
+        let p = ValidPrime::new(3);
+        let mut sseq = Sseq::<2, CohomologicalSerre>::new(p);
+        for (x, y) in [(0, 1), (2, 0), (0, 2), (2, 1)] {
+            sseq.set_dimension(Bidegree::x_y(x, y), 1);
+        }
+        let z = BidegreeElement::new(Bidegree::x_y(0, 1), FpVector::from_slice(p, &[1]));
+        sseq.add_differential(2, &z, FpVector::from_slice(p, &[1]).as_slice());
+
+        let w = Product {
+            b: Bidegree::x_y(0, 1),
+            left: true,
+            matrices: MultiIndexed::new(),
+        };
+        for b in [Bidegree::x_y(0, 1), Bidegree::x_y(2, 0)] {
+            w.matrices.insert(b, Matrix::from_vec(p, &[vec![1]]));
+        }
+
+        let (r, wz) = sseq.leibniz(2, &z, &w, None).unwrap();
+        assert_eq!(r, 2);
+        assert_eq!(wz.degree(), Bidegree::x_y(0, 2));
+        sseq.update();
+        expect!["d_2 (0, 2, [1]) = [2] (Leibniz rule on d_2 (0, 1, [1]) and the product in degree (0, 1))"]
+            .assert_eq(&sseq.provenance(Bidegree::x_y(0, 2))[0].to_string());
+    }
+
+    /// A Tate chart with negative stems and filtrations, whose grid is labelled by the actual
+    /// coordinates.
+    #[test]
+    fn test_tate_chart() {
+        use crate::charting::TikzBackend;
+
+        let p = ValidPrime::new(2);
+        let mut sseq = Sseq::<2, Tate>::new(p);
+        for x in -5..=-3 {
+            for y in -2..=1 {
+                sseq.set_dimension(Bidegree::x_y(x, y), 1);
+            }
+        }
+        sseq.add_differential(
+            2,
+            &BidegreeElement::new(Bidegree::x_y(-4, -2), FpVector::from_slice(p, &[1])),
+            FpVector::from_slice(p, &[1]).as_slice(),
+        );
+        sseq.update();
+
+        let mut out = Vec::new();
+        sseq.write_to_graph(
+            TikzBackend::new(&mut out),
+            2,
+            true,
+            std::iter::empty(),
+            |_| Ok(()),
+        )
+        .unwrap();
+        expect![[r#"
+            \begin{tikzpicture}[
+              major-grid/.style={ opacity = 0.2 },
+              grid/.style={ opacity = 0.1 },
+              d2/.style={ blue },
+              hidden/.style={ red, dashed },
+            ]
+            \draw [grid] (0, 0) -- (0, 3);
+            \draw [major-grid] (1, 0) -- (1, 3);
+            \node [below] at (1, 0) {-4};
+            \draw [grid] (2, 0) -- (2, 3);
+            \draw [grid] (0, 0) -- (2, 0);
+            \draw [grid] (0, 1) -- (2, 1);
+            \draw [major-grid] (0, 2) -- (2, 2);
+            \node [left] at (0, 2) {0};
+            \draw [grid] (0, 3) -- (2, 3);
+            \draw [fill] (0, 0) circle (0.1);
+            \draw [fill] (0, 1) circle (0.1);
+            \draw [fill] (0, 2) circle (0.1);
+            \draw [fill] (0, 3) circle (0.1);
+            \draw [fill] (1, 0) circle (0.1);
+            \draw [d2] (1, 0) -- (0, 2);
+            \draw [fill] (1, 1) circle (0.1);
+            \draw [fill] (1, 2) circle (0.1);
+            \draw [fill] (1, 3) circle (0.1);
+            \draw [fill] (2, 0) circle (0.1);
+            \draw [fill] (2, 1) circle (0.1);
+            \draw [fill] (2, 2) circle (0.1);
+            \draw [fill] (2, 3) circle (0.1);
+            \end{tikzpicture}
+        "#]]
+        .assert_eq(std::str::from_utf8(&out).unwrap());
+    }
 }
@@ -0,0 +1,77 @@
+//! Computes the $E_2$ page of the Adams–Novikov spectral sequence with coefficients in
+//! $BP_*/I_n$ and draws it as a chart, in the same format as the `chart` example. For $n = 0$, this
+//! is the $E_2$ page of the sphere, and for $n = 1$ that of the mod $p$ Moore spectrum. See
+//! [`ext::novikov`] for details.
+//!
+//! There is a class for each cyclic summand. Over $\mathbb{Z}_{(p)}$, the orders of the summands
+//! other than $\mathbb{Z}/p$ are written above them, with `∞` for $\mathbb{Z}_{(p)}$.
+
+use ext::novikov::{AdamsNovikovE2, CyclicSummand};
+use fp::prime::{Prime, ValidPrime};
+use itertools::Itertools;
+use sseq::{
+    charting::{Backend, Orientation, SeqSeeBackend, SvgBackend, TikzBackend},
+    coordinates::Bidegree,
+};
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let p: ValidPrime = query::with_default("p", "2", str::parse);
+    let ideal = query::with_default("n (for the coefficients BP_*/I_n)", "0", str::parse);
+    let max = Bidegree::n_s(
+        query::with_default("Max n", "20", str::parse),
+        query::with_default("Max s", "5", str::parse),
+    );
+
+    let format = query::with_default("Output format (svg/tikz/seqsee)", "svg", |x| match x {
+        "svg" | "tikz" | "seqsee" => Ok(x.to_string()),
+        _ => Err(format!(
+            "unknown format '{x}'; expected one of svg, tikz, seqsee"
+        )),
+    });
+
+    let e2 = AdamsNovikovE2::new(p, ideal, max)?;
+    let sseq = e2.to_sseq();
+    let products: Vec<(String, sseq::Product<2>)> = Vec::new();
+
+    let labels: Vec<(Bidegree, String)> = (0..=max.s())
+        .flat_map(|s| (0..=max.n()).map(move |n| Bidegree::n_s(n, s)))
+        .filter(|&b| {
+            e2.summands(b)
+                .iter()
+                .any(|&x| x != CyclicSummand::Torsion(1))
+        })
+        .map(|b| {
+            let mut orders = e2.summands(b).iter().map(|x| match x {
+                CyclicSummand::Free => "∞".to_string(),
+                CyclicSummand::Torsion(k) => p.as_u32().pow(*k).to_string(),
+            });
+            (b - sseq.min(), orders.join(","))
+        })
+        .collect();
+
+    let out = std::io::stdout();
+    match format.as_str() {
+        "svg" => sseq.write_to_graph(SvgBackend::new(out), 2, false, products.iter(), |g| {
+            write_labels(g, &labels)
+        })?,
+        "tikz" => sseq.write_to_graph(TikzBackend::new(out), 2, false, products.iter(), |g| {
+            write_labels(g, &labels)
+        })?,
+        "seqsee" => {
+            sseq.write_to_graph(SeqSeeBackend::new(out), 2, false, products.iter(), |g| {
+                write_labels(g, &labels)
+            })?
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn write_labels<T: Backend>(g: &mut T, labels: &[(Bidegree, String)]) -> Result<(), T::Error> {
+    for (b, label) in labels {
+        g.text(*b, label, Orientation::Above)?;
+    }
+    Ok(())
+}
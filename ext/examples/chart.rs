@@ -1,14 +1,27 @@
+use std::collections::HashMap;
+
 use algebra::Algebra;
 use ext::{
-    chain_complex::{ChainComplex, FreeChainComplex},
-    utils::query_module,
+    chain_complex::FreeChainComplex,
+    motivic::{self, GeneratorWeights},
+    utils::{AnyResolution, query_any_module_only},
+};
+use itertools::Itertools;
+use sseq::{
+    Adams, Product, Sseq,
+    charting::{Backend, Orientation, SeqSeeBackend, SvgBackend, TikzBackend},
+    coordinates::Bidegree,
 };
-use sseq::charting::{SeqSeeBackend, SvgBackend, TikzBackend};
 
 fn main() -> anyhow::Result<()> {
     ext::utils::init_logging()?;
 
-    let resolution = query_module(None, false)?;
+    let resolution = query_any_module_only("Module")?;
+
+    let max = Bidegree::n_s(
+        query::with_default("Max n", "30", str::parse),
+        query::with_default("Max s", "7", str::parse),
+    );
 
     let format = query::with_default("Output format (svg/tikz/seqsee)", "svg", |x| match x {
         "svg" | "tikz" | "seqsee" => Ok(x.to_string()),
@@ -17,28 +30,78 @@ fn main() -> anyhow::Result<()> {
         )),
     });
 
-    let sseq = resolution.to_sseq();
-    let products: Vec<_> = resolution
-        .algebra()
-        .default_filtration_one_products()
-        .into_iter()
-        .map(|(name, op_deg, op_idx)| (name, resolution.filtration_one_products(op_deg, op_idx)))
-        .collect();
+    // Motivic charts are drawn in topological degrees, with one class for each generator of the
+    // minimal resolution, i.e. the motivic Ext with coefficients in F_2 (which over C is the Ext of
+    // C tau). The classes in each bidegree are ordered by weight, and their weights are written
+    // above them. The products by h_i preserve the weight up to the weight of h_i.
+    let mut labels = Vec::new();
+    let (sseq, products) = match resolution {
+        AnyResolution::Motivic(resolution) => {
+            resolution.compute_through_stem(motivic::resolution_bound(max));
+            let weights = GeneratorWeights::new(&resolution);
+            let sseq = weights.to_sseq();
+            let (collapsed, products) =
+                motivic::forget_weights(&sseq, &weights.filtration_one_products(&resolution));
 
-    let out = std::io::stdout();
-    match format.as_str() {
-        "svg" => {
-            sseq.write_to_graph(SvgBackend::new(out), 2, false, products.iter(), |_| Ok(()))?
+            let mut degree_weights: HashMap<Bidegree, Vec<i32>> = HashMap::new();
+            for b in sseq.iter_degrees() {
+                let list = degree_weights
+                    .entry(Bidegree::n_s(b.n(), b.s()))
+                    .or_default();
+                list.extend(std::iter::repeat_n(b.coords()[2], sseq.dimension(b)));
+            }
+            let min = collapsed.min();
+            labels = degree_weights
+                .into_iter()
+                .map(|(b, mut list)| {
+                    list.sort_unstable();
+                    (b - min, list.iter().join(","))
+                })
+                .collect();
+            (collapsed, products)
+        }
+        AnyResolution::Steenrod(resolution) => {
+            resolution.compute_through_stem(max);
+            classical_chart(&resolution)
         }
-        "tikz" => {
-            sseq.write_to_graph(TikzBackend::new(out), 2, false, products.iter(), |_| Ok(()))?
+        AnyResolution::FiniteDimensional(resolution) => {
+            resolution.compute_through_stem(max);
+            classical_chart(&resolution)
         }
+    };
+    let out = std::io::stdout();
+    match format.as_str() {
+        "svg" => sseq.write_to_graph(SvgBackend::new(out), 2, false, products.iter(), |g| {
+            write_labels(g, &labels)
+        })?,
+        "tikz" => sseq.write_to_graph(TikzBackend::new(out), 2, false, products.iter(), |g| {
+            write_labels(g, &labels)
+        })?,
         "seqsee" => {
-            sseq.write_to_graph(SeqSeeBackend::new(out), 2, false, products.iter(), |_| {
-                Ok(())
+            sseq.write_to_graph(SeqSeeBackend::new(out), 2, false, products.iter(), |g| {
+                write_labels(g, &labels)
             })?
         }
         _ => unreachable!(),
     }
     Ok(())
 }
+
+fn classical_chart(
+    resolution: &impl FreeChainComplex,
+) -> (Sseq<2, Adams>, Vec<(String, Product<2>)>) {
+    let products = resolution
+        .algebra()
+        .default_filtration_one_products()
+        .into_iter()
+        .map(|(name, op_deg, op_idx)| (name, resolution.filtration_one_products(op_deg, op_idx)))
+        .collect();
+    (resolution.to_sseq(), products)
+}
+
+fn write_labels<T: Backend>(g: &mut T, labels: &[(Bidegree, String)]) -> Result<(), T::Error> {
+    for (b, label) in labels {
+        g.text(*b, label, Orientation::Above)?;
+    }
+    Ok(())
+}
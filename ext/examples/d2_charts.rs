@@ -15,7 +15,7 @@ fn main() -> anyhow::Result<()> {
         true,
     )?);
 
-    let lift = SecondaryResolution::new(Arc::clone(&resolution));
+    let lift = SecondaryResolution::try_new(Arc::clone(&resolution))?;
     lift.extend_all();
 
     let sseq = lift.e3_page();
@@ -46,5 +46,11 @@ fn main() -> anyhow::Result<()> {
     write("e2_d2_clean", 2, true, 2)?;
     write("e3_clean", 3, false, 2)?;
 
+    // Save the spectral sequence so that it can be charted again with `sseq_chart`.
+    serde_json::to_writer(
+        File::create(format!("d2_{}.json", resolution.name()))?,
+        &serde_json::json!({ "sseq": sseq, "products": products }),
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,110 @@
+//! Computes the $E_\infty$ page of a spectral sequence that was saved as JSON and assembles it into
+//! the groups in each stem, taking into account the hidden extensions listed in the file.
+//!
+//! # Usage
+//! The input file is a JSON object with the fields
+//!  - `sseq`: an Adams spectral sequence in the format documented in the `Serialize`
+//!    implementation of [`sseq::Sseq`]. All differentials should be known, and all permanent
+//!    classes marked as such.
+//!  - `products` (optional): a list of pairs of the name of a product and the product, as saved
+//!    by the `d2_charts` example.
+//!  - `hidden_extensions` (optional): a list of hidden extensions of the form
+//!    ```json
+//!    {
+//!      "product": "h_0",
+//!      "source": { "degree": { "coords": [3, 1] }, "vector": [1] },
+//!      "target": { "degree": { "coords": [3, 3] }, "vector": [1] }
+//!    }
+//!    ```
+//!
+//! The program asks for the name of the product detecting multiplication by $p$, which is `h_0`
+//! by default, and prints either the group in each stem followed by the hidden extensions, or a
+//! chart of the $E_\infty$ page with the groups on top.
+//!
+//! A stem is marked with `?` if the $E_\infty$ page is not known, i.e. some class in the stem or
+//! some class that can hit the stem is not known to be permanent.
+
+use std::{fs::File, io::BufReader};
+
+use fp::{prime::ValidPrime, vector::FpVector};
+use serde_json::Value;
+use sseq::{
+    Adams, EInfinity, Product, Sseq,
+    charting::{SvgBackend, TikzBackend},
+    coordinates::{Bidegree, BidegreeElement},
+};
+
+fn element(p: ValidPrime, mut value: Value) -> anyhow::Result<BidegreeElement> {
+    let degree: Bidegree = serde_json::from_value(value["degree"].take())?;
+    let vector: Vec<u32> = serde_json::from_value(value["vector"].take())?;
+    Ok(BidegreeElement::new(
+        degree,
+        FpVector::from_slice(p, &vector),
+    ))
+}
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let mut data: Value = query::raw("Spectral sequence file", |path| {
+        File::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(|f| serde_json::from_reader(BufReader::new(f)).map_err(|e| e.to_string()))
+    });
+    let sseq: Sseq<2, Adams> = serde_json::from_value(data["sseq"].take())?;
+    let products: Vec<(String, Product<2>)> = match data["products"].take() {
+        Value::Null => Vec::new(),
+        products => serde_json::from_value(products)?,
+    };
+    let hidden: Vec<Value> = match data["hidden_extensions"].take() {
+        Value::Null => Vec::new(),
+        hidden => serde_json::from_value(hidden)?,
+    };
+
+    let p_name = query::with_default("Product detecting multiplication by p", "h_0", |name| {
+        if products
+            .iter()
+            .any(|(n, prod)| n == name && prod.b == Bidegree::x_y(0, 1))
+        {
+            Ok(name.to_string())
+        } else {
+            Err(format!("no product {name} in degree (0, 1)"))
+        }
+    });
+
+    let mut e_infinity = EInfinity::new(&sseq);
+    for (name, product) in products {
+        if name == p_name {
+            e_infinity.add_p_product(name, product);
+        } else {
+            e_infinity.add_product(name, product);
+        }
+    }
+    let p = sseq.prime();
+    for mut ext in hidden {
+        let Some(product) = ext["product"].as_str().map(String::from) else {
+            anyhow::bail!("hidden extension {ext} has no product");
+        };
+        let source = element(p, ext["source"].take())?;
+        let target = element(p, ext["target"].take())?;
+        e_infinity
+            .add_hidden_extension(&product, source, target)
+            .map_err(anyhow::Error::msg)?;
+    }
+
+    let format = query::with_default("Output format (text/svg/tikz)", "text", |x| match x {
+        "text" | "svg" | "tikz" => Ok(x.to_string()),
+        _ => Err(format!(
+            "unknown format '{x}'; expected one of text, svg, tikz"
+        )),
+    });
+
+    let out = std::io::stdout();
+    match format.as_str() {
+        "text" => print!("{e_infinity}"),
+        "svg" => e_infinity.write_to_graph(SvgBackend::new(out), |_| Ok(()))?,
+        "tikz" => e_infinity.write_to_graph(TikzBackend::new(out), |_| Ok(()))?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+//! Exports a resolution as JSON to standard output, in the format described in `EXPORT-FORMAT.md`.
+//! This includes the degrees of the generators and the differentials, with elements of the
+//! Steenrod algebra written in the basis used for the computation.
+//!
+//! The output can be loaded by other programs, e.g. in Python via
+//! ```python
+//! import json
+//! res = json.load(open("S_2.json"))
+//! d2 = res["differentials"][1]
+//! print(d2["outputs"][0])
+//! ```
+//! which prints
+//! ```text
+//! {'gen': [2, 0], 'value': [{'op': 'P(1)', 'gen': [1, 0], 'coef': 1}]}
+//! ```
+//! The result can be read back with [`ext::export::import_resolution`].
+
+use ext::export::export_resolution;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let resolution = ext::utils::query_module(None, false)?;
+    println!("{}", export_resolution(&resolution));
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Inspects a save directory (or archive) and reports which save files are complete, corrupt or
+//! missing for each kind of save data. A file is corrupt if its header does not match its location
+//! or its checksum is invalid, which typically happens if the program was terminated while writing
+//! it.
+//!
+//! Corrupt files can optionally be deleted, so that resuming the computation will recompute them.
+//! Likewise, lock files left over by a distributed computation that crashed (see
+//! [`MuResolution::distributed`](ext::resolution::MuResolution::distributed)) can be deleted.
+//! This must only be done if no process is using the save directory.
+//!
+//! # Sample output
+//! ```text
+//! augmentation_qis: 78 complete, 0 corrupt
+//! differentials: 77 complete, 1 corrupt, 1 missing
+//! kernels: 13 complete, 0 corrupt
+//! res_qis: 78 complete, 0 corrupt
+//! Corrupt: "S_2/differentials/5_12_differential": Invalid file checksum
+//! Missing in differentials: (3, 7)
+//! ```
+
+use std::path::PathBuf;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let dir: PathBuf = query::raw("Save directory", |x| {
+        core::result::Result::<PathBuf, std::convert::Infallible>::Ok(PathBuf::from(x))
+    });
+
+    let report = ext::save::inspect(&dir, None)?;
+    print!("{report}");
+
+    if report.corrupt().next().is_some()
+        && query::with_default("Delete corrupt files", "n", |response| {
+            if response.starts_with('y') || response.starts_with('n') {
+                Ok(response.starts_with('y'))
+            } else {
+                Err(format!(
+                    "unrecognized response '{response}'. Should be '(y)es' or '(n)o'"
+                ))
+            }
+        })
+    {
+        let count = report.delete_corrupt()?;
+        println!("Deleted {count} corrupt files");
+    }
+
+    if !report.locks.is_empty()
+        && query::with_default("Delete lock files", "n", |response| {
+            if response.starts_with('y') || response.starts_with('n') {
+                Ok(response.starts_with('y'))
+            } else {
+                Err(format!(
+                    "unrecognized response '{response}'. Should be '(y)es' or '(n)o'"
+                ))
+            }
+        })
+    {
+        let count = report.delete_locks()?;
+        println!("Deleted {count} lock files");
+    }
+    Ok(())
+}
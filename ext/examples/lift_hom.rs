@@ -42,7 +42,7 @@
 use std::{path::PathBuf, sync::Arc};
 
 use algebra::module::Module;
-use anyhow::{Context, anyhow};
+use anyhow::Context;
 use ext::{
     chain_complex::{AugmentedChainComplex, ChainComplex, FreeChainComplex},
     resolution_homomorphism::ResolutionHomomorphism,
@@ -63,9 +63,7 @@ fn main() -> anyhow::Result<()> {
     let source_name = source.name();
     let target = query::with_default("Target module", source_name, |s| {
         if s == source_name {
-            Ok(Arc::clone(&source))
-        } else if cfg!(feature = "nassau") {
-            Err(anyhow!("Can only resolve S_2 with nassau"))
+            Ok::<_, anyhow::Error>(Arc::clone(&source))
         } else {
             let config: utils::Config = s.try_into()?;
             let save_dir = query::optional("Target save directory", |x| {
@@ -78,10 +76,6 @@ fn main() -> anyhow::Result<()> {
 
             target.set_name(s.to_owned());
 
-            #[cfg(feature = "nassau")]
-            unreachable!();
-
-            #[cfg(not(feature = "nassau"))]
             Ok(Arc::new(target))
         }
     });
@@ -100,7 +94,7 @@ fn main() -> anyhow::Result<()> {
     target.compute_through_stem(b - shift);
 
     let target_module = target.target().module(0);
-    let hom = ResolutionHomomorphism::new(name.clone(), source, target, shift);
+    let hom = ResolutionHomomorphism::try_new(name.clone(), source, target, shift)?;
 
     eprintln!("\nInput Ext class to lift:");
     for output_t in 0..=target_module
@@ -150,22 +150,22 @@ impl PKData {
         // k-stem and beyond or of any classes of filtration higher than k/2+1.
         resolution.compute_through_stem(Bidegree::n_s(k - 2, k / 2 + 1));
 
-        let bottom_cell = ResolutionHomomorphism::from_class(
+        let bottom_cell = ResolutionHomomorphism::try_from_class(
             String::from("bottom_cell"),
             resolution.clone(),
             s_2_resolution.clone(),
             Bidegree::s_t(0, -k),
             &[1],
-        );
+        )?;
         bottom_cell.extend_all();
 
-        let minus_one_cell = ResolutionHomomorphism::from_class(
+        let minus_one_cell = ResolutionHomomorphism::try_from_class(
             String::from("minus_one_cell"),
             resolution.clone(),
             s_2_resolution.clone(),
             Bidegree::s_t(0, -1),
             &[1],
-        );
+        )?;
         minus_one_cell.extend_all();
 
         Ok(PKData {
@@ -0,0 +1,42 @@
+//! Compare the May spectral sequence with the minimal resolution. This computes the $E_1$ page of
+//! the May spectral sequence (and at the prime 2 the $E_2$ page) for the algebra of the module,
+//! and prints the dimension of the chosen page and of Ext in each bidegree in the format
+//! `n,s,may,ext`. The module should be the sphere, e.g. `S_2` or `S_2@A(2)`.
+
+use algebra::{MilnorAlgebra, SteenrodAlgebra};
+use anyhow::anyhow;
+use ext::{
+    chain_complex::ChainComplex,
+    may::{MayE1, compare_with_resolution},
+    utils::query_module_only,
+};
+use sseq::coordinates::Bidegree;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let resolution = query_module_only("Module", None, false)?;
+    let max = Bidegree::n_s(
+        query::with_default("Max n", "30", str::parse),
+        query::with_default("Max s", "7", str::parse),
+    );
+    let page = query::with_default("Page", "2", str::parse);
+
+    let algebra = resolution.algebra();
+    let algebra: &MilnorAlgebra = match &*algebra {
+        SteenrodAlgebra::MilnorAlgebra(a) => a,
+        _ => {
+            return Err(anyhow!(
+                "The May spectral sequence requires the Milnor basis"
+            ));
+        }
+    };
+
+    resolution.compute_through_stem(max);
+    let sseq = MayE1::new(algebra, max).to_sseq();
+
+    for c in compare_with_resolution(&sseq, page, &resolution, max) {
+        println!("{},{},{},{}", c.degree.n(), c.degree.s(), c.may, c.ext);
+    }
+    Ok(())
+}
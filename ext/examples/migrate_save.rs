@@ -0,0 +1,49 @@
+//! Upgrades a save directory (or archive) to the current save format. In particular, this adds a
+//! manifest to save directories created by older versions, which are otherwise refused.
+//!
+//! The manifest records the module being resolved, and it is the responsibility of the user to
+//! ensure that the save directory actually contains data for the specified module. The manifests
+//! of products are written without the class defining the product, which is recorded the next time
+//! the product is used.
+//!
+//! # Sample output
+//! ```text
+//! Module (default: S_2): S_2
+//! Unstable (default: n): n
+//! Save directory: S_2_milnor
+//! Upgraded "S_2_milnor" from format version 0 to 1
+//! ```
+
+use std::path::PathBuf;
+
+use ext::utils::Config;
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let config: Config = query::with_default("Module", "S_2", |s| s.try_into());
+    let unstable = query::with_default("Unstable", "n", |response| {
+        if response.starts_with('y') || response.starts_with('n') {
+            Ok(response.starts_with('y'))
+        } else {
+            Err(format!(
+                "unrecognized response '{response}'. Should be '(y)es' or '(n)o'"
+            ))
+        }
+    });
+    let dir: PathBuf = query::raw("Save directory", |x| {
+        core::result::Result::<PathBuf, std::convert::Infallible>::Ok(PathBuf::from(x))
+    });
+
+    let manifest = config.save_manifest(unstable)?;
+    let version = ext::save::migrate(&dir, &manifest)?;
+    if version == ext::save::SAVE_FORMAT_VERSION {
+        println!("{dir:?} is already at format version {version}");
+    } else {
+        println!(
+            "Upgraded {dir:?} from format version {version} to {}",
+            ext::save::SAVE_FORMAT_VERSION
+        );
+    }
+    Ok(())
+}
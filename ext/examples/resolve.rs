@@ -13,8 +13,16 @@
 //! · ·   ·       ·               ·
 //! ·
 //! ```
+//!
+//! Set the `PROGRESS` environment variable to `chart` to draw the progress of the computation and
+//! an estimate of the remaining time on stderr as it runs, or to `json` (resp. `json:<path>`) to
+//! write a line of JSON to stderr (resp. the file at `<path>`) for every bidegree started and
+//! finished. See [`ext::progress`] for details.
 
-use ext::chain_complex::{ChainComplex, FreeChainComplex};
+use ext::{
+    chain_complex::FreeChainComplex,
+    progress::{ProgressReporter, ProgressTracker},
+};
 use sseq::coordinates::Bidegree;
 
 fn main() -> anyhow::Result<()> {
@@ -26,7 +34,9 @@ fn main() -> anyhow::Result<()> {
     let s = query::with_default("Max s", "15", str::parse);
 
     let max = Bidegree::s_t(s, t);
-    res.compute_through_bidegree(max);
+    let mut progress = ProgressReporter::from_env(ProgressTracker::through_bidegree(&res, max))?;
+    res.compute_through_bidegree_with_progress(max, |event| progress.update(&event))?;
+    progress.finish();
 
     println!("{}", res.graded_dimension_string());
     Ok(())
@@ -18,23 +18,86 @@
 //! · ·   ·       ·               ·
 //! ·
 //! ```
+//!
+//! Over the motivic Steenrod algebras, the range is in topological degrees and the Ext groups are
+//! instead listed by their motivic degree `(n, s, w)`. Over $\mathbb{C}$, these are the summands
+//! of Ext as an $\mathbb{F}_2[\tau]$-module. Over $\mathbb{R}$, these are the dimensions of Ext
+//! with coefficients in $\mathbb{M}_2$, which is nonzero in arbitrarily negative weights, so we
+//! also ask for the largest coweight $n - w$ to compute. See [`ext::motivic`] for details.
+//!
+//! Set the `PROGRESS` environment variable to `chart` to draw the progress of the computation and
+//! an estimate of the remaining time on stderr as it runs, or to `json` (resp. `json:<path>`) to
+//! write a line of JSON to stderr (resp. the file at `<path>`) for every bidegree started and
+//! finished. See [`ext::progress`] for details.
 
-use ext::chain_complex::FreeChainComplex;
+use algebra::MotivicBase;
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    motivic::{self, GeneratorWeights},
+    progress::{ProgressReporter, ProgressTracker},
+    utils::AnyResolution,
+};
+use itertools::Itertools;
 use sseq::coordinates::Bidegree;
 
 fn main() -> anyhow::Result<()> {
     ext::utils::init_logging()?;
 
-    let res = ext::utils::query_module_only("Module", None, false)?;
+    let res = ext::utils::query_any_module_only("Module")?;
 
     let max = Bidegree::n_s(
         query::with_default("Max n", "30", str::parse),
         query::with_default("Max s", "15", str::parse),
     );
 
-    res.compute_through_stem(max);
+    let res = match res {
+        AnyResolution::Steenrod(res) => {
+            let mut progress =
+                ProgressReporter::from_env(ProgressTracker::through_stem(&res, max))?;
+            res.compute_through_stem_with_progress(max, |event| progress.update(&event))?;
+            progress.finish();
 
-    println!("{}", res.graded_dimension_string());
+            println!("{}", res.graded_dimension_string());
+            return Ok(());
+        }
+        AnyResolution::FiniteDimensional(res) => {
+            let mut progress =
+                ProgressReporter::from_env(ProgressTracker::through_stem(&res, max))?;
+            res.compute_through_stem_with_progress(max, |event| progress.update(&event))?;
+            progress.finish();
+
+            println!("{}", res.graded_dimension_string());
+            return Ok(());
+        }
+        AnyResolution::Motivic(res) => res,
+    };
+
+    // Over R, the Ext groups are nonzero in arbitrarily negative weights
+    let max_coweight = (res.algebra().base() == MotivicBase::Real)
+        .then(|| query::with_default("Max coweight", "4", str::parse));
+    let bound = match max_coweight {
+        Some(max_coweight) => motivic::coefficient_resolution_bound(max, max_coweight),
+        None => motivic::resolution_bound(max),
+    };
+    let mut progress = ProgressReporter::from_env(ProgressTracker::through_stem(&res, bound))?;
+    res.compute_through_stem_with_progress(bound, |event| progress.update(&event))?;
+    progress.finish();
+
+    let weights = GeneratorWeights::new(&res);
+    match max_coweight {
+        Some(max_coweight) => {
+            let sseq = motivic::coefficient_ext(&res, &weights, max, max_coweight)?;
+            for b in sseq.iter_degrees() {
+                if sseq.dimension(b) > 0 {
+                    println!("{b}: {}", sseq.dimension(b));
+                }
+            }
+        }
+        None => {
+            let summands = motivic::tau_bockstein(&res, &weights, max)?;
+            println!("{}", summands.iter().join("\n"));
+        }
+    }
 
     Ok(())
 }
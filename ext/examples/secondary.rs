@@ -27,8 +27,17 @@
 //! homotopy (i.e. the difference in degrees between the input class and the λ part of the answer;
 //! 2 in the case of `secondary`), and the maximum value of `s` is the `max_s` of the resolution.
 //!
-//! After running this script for all `s` in the range, run it as usual to produce the final
-//! output. An example script is as follows:
+//! Alternatively, the work can be split into many smaller jobs of roughly equal size by setting
+//! `SECONDARY_SHARD=phase:index/count`, which runs the jobs in the `index`th of `count` shards of
+//! `phase`, where `0 <= index < count`. This is suited to cluster array jobs. The phases have to be
+//! run one after the other: first all shards of the phase `composites`, and then all shards of the
+//! phase `intermediates`, which use the saved composites. The
+//! [`secondary_jobs`](../secondary_jobs/index.html) script lists the jobs that are done, and
+//! merges the results of shards that were run with separate save directories. In this case, the
+//! composites have to be merged into the save directories of the `intermediates` shards as well.
+//!
+//! After running this script for all `s` (resp. all shards) in the range, run it as usual to
+//! produce the final output. An example script is as follows:
 //!
 //! ```shell
 //! #!/bin/sh
@@ -62,13 +71,17 @@ fn main() -> anyhow::Result<()> {
 
     // The d2 differential is intrinsic to the resolution and needs no unit, so we avoid the unit
     // setup with `without_unit`.
-    let sec_e2 = SecondaryExtAlgebra::new(Arc::new(ExtAlgebra::without_unit(resolution)));
+    let sec_e2 = SecondaryExtAlgebra::try_new(Arc::new(ExtAlgebra::without_unit(resolution)))?;
 
     if let Some(s) = ext::utils::secondary_job() {
         sec_e2.compute_partial(s);
         return Ok(());
     }
 
+    if let Some(shard) = ext::utils::secondary_shard() {
+        return sec_e2.run_shard(shard);
+    }
+
     sec_e2.extend_all();
 
     let e2 = sec_e2.ext_algebra();
@@ -0,0 +1,55 @@
+//! This lists the jobs of the [`secondary`](../secondary/index.html) script and whether they are
+//! done, i.e. whether their results are in the save directory. See the documentation of
+//! `secondary` for how to run the jobs in shards.
+//!
+//! If the shards were run with separate save directories, e.g. on machines that do not share a
+//! drive, then the results can be merged into the main save directory by specifying the save
+//! directories of the shards. The main save directory must contain the resolution, and the final
+//! output is then produced by running `secondary` with the main save directory.
+//!
+//! # Sample output
+//! ```text
+//! Module (default: S_2): S_2
+//! Module save directory (optional): /tmp/save
+//! Max n (default: 30): 20
+//! Max s (default: 7): 6
+//! Merge from (optional): /tmp/shard_0
+//! Merge from (optional): /tmp/shard_1
+//! Merge from (optional):
+//! 72 of 72 jobs done
+//! ```
+
+use std::{path::PathBuf, sync::Arc};
+
+use ext::{
+    ext_algebra::{ExtAlgebra, secondary::SecondaryExtAlgebra},
+    secondary::SecondaryLift,
+    utils::query_module,
+};
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let resolution = Arc::new(query_module(Some(algebra::AlgebraType::Milnor), true)?);
+    let sec_e2 = SecondaryExtAlgebra::try_new(Arc::new(ExtAlgebra::without_unit(resolution)))?;
+    let lift = sec_e2.resolution_lift();
+    let jobs = lift.jobs();
+
+    while let Some(dir) = query::optional("Merge from", |x| {
+        core::result::Result::<PathBuf, std::convert::Infallible>::Ok(PathBuf::from(x))
+    }) {
+        for &job in &jobs {
+            lift.merge_job(job, &dir)?;
+        }
+    }
+
+    let pending: Vec<_> = jobs.iter().filter(|&&job| !lift.is_job_done(job)).collect();
+    if !pending.is_empty() {
+        println!("Pending jobs:");
+        for job in &pending {
+            println!("  {job}");
+        }
+    }
+    println!("{} of {} jobs done", jobs.len() - pending.len(), jobs.len());
+    Ok(())
+}
@@ -43,7 +43,7 @@ fn get_hom(
     name: &str,
     source: Arc<SecondaryResolution<QueryModuleResolution>>,
     target: Arc<SecondaryResolution<QueryModuleResolution>>,
-) -> HomData {
+) -> anyhow::Result<HomData> {
     let p = source.prime();
 
     let shift = Bidegree::n_s(
@@ -57,12 +57,12 @@ fn get_hom(
         .underlying()
         .compute_through_stem(shift + LAMBDA_BIDEGREE);
 
-    let hom = Arc::new(ResolutionHomomorphism::new(
+    let hom = Arc::new(ResolutionHomomorphism::try_new(
         ext_name.clone(),
         source.underlying(),
         target.underlying(),
         shift,
-    ));
+    )?);
 
     let num_gens = source.underlying().number_of_gens_in_bidegree(shift);
     let num_lambda_gens = hom
@@ -98,13 +98,13 @@ fn get_hom(
             for (i, &x) in v.iter().enumerate() {
                 class.set_entry(num_gens + i, x);
             }
-            Some(Arc::new(ResolutionHomomorphism::from_class(
+            Some(Arc::new(ResolutionHomomorphism::try_from_class(
                 lambda_name,
                 hom_lift.source(),
                 hom_lift.target(),
                 shift + LAMBDA_BIDEGREE,
                 &v,
-            )))
+            )?))
         }
     } else {
         None
@@ -116,12 +116,12 @@ fn get_hom(
         (x, "") => format!("[{x}]"),
         (x, y) => format!("[{x}] + λ{y}"),
     };
-    HomData {
+    Ok(HomData {
         name,
         class,
         hom_lift,
         lambda_part,
-    }
+    })
 }
 
 fn main() -> anyhow::Result<()> {
@@ -138,11 +138,11 @@ fn main() -> anyhow::Result<()> {
 
     let p = resolution.prime();
 
-    let res_lift = Arc::new(SecondaryResolution::new(Arc::clone(&resolution)));
+    let res_lift = Arc::new(SecondaryResolution::try_new(Arc::clone(&resolution))?);
     let unit_lift = if is_unit {
         Arc::clone(&res_lift)
     } else {
-        let lift = SecondaryResolution::new(Arc::clone(&unit));
+        let lift = SecondaryResolution::try_new(Arc::clone(&unit))?;
         Arc::new(lift)
     };
 
@@ -151,13 +151,13 @@ fn main() -> anyhow::Result<()> {
         class: _,
         hom_lift: a,
         lambda_part: a_lambda,
-    } = get_hom("a", Arc::clone(&res_lift), Arc::clone(&unit_lift));
+    } = get_hom("a", Arc::clone(&res_lift), Arc::clone(&unit_lift))?;
     let HomData {
         name: b_name,
         class: b_class,
         hom_lift: b,
         lambda_part: b_lambda,
-    } = get_hom("b", Arc::clone(&unit_lift), Arc::clone(&unit_lift));
+    } = get_hom("b", Arc::clone(&unit_lift), Arc::clone(&unit_lift))?;
 
     let shift = Bidegree::s_t(
         (a.underlying().shift + b.underlying().shift).s(),
@@ -238,6 +238,10 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let Some(shard) = ext::utils::secondary_shard() {
+        return ch_lift.run_shard(shard);
+    }
+
     ch_lift.extend_all();
 
     fn get_page_data(sseq: &sseq::Sseq<2, sseq::Adams>, b: Bidegree) -> &fp::matrix::Subquotient {
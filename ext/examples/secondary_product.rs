@@ -59,7 +59,7 @@ fn main() -> anyhow::Result<()> {
         e2.unit().compute_through_stem(res_max - shift);
     }
 
-    let sec_e2 = Arc::new(SecondaryExtAlgebra::new(Arc::clone(&e2)));
+    let sec_e2 = Arc::new(SecondaryExtAlgebra::try_new(Arc::clone(&e2))?);
     sec_e2.extend_all();
 
     // Check that the class survives to E3 (supports no d2).
@@ -76,6 +76,11 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let Some(shard) = ext::utils::secondary_shard() {
+        lift.underlying().extend_all();
+        return lift.run_shard(shard);
+    }
+
     // `x` multiplies on the left; the printed name is bracketed as in the original output.
     let disp = format!("[{name}]");
 
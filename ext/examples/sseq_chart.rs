@@ -0,0 +1,67 @@
+//! Draws a chart of a spectral sequence that was saved as JSON, such as the $E_2$ page with the
+//! $d_2$ differentials saved by the `d2_charts` example.
+//!
+//! # Usage
+//! The input file is a JSON object with fields `sseq`, an Adams spectral sequence in the format
+//! documented in the `Serialize` implementation of [`sseq::Sseq`], and optionally `products`, a list of pairs of the name of a
+//! product and the product. The chart of the requested page is printed to stdout.
+
+use std::{fs::File, io::BufReader};
+
+use serde_json::Value;
+use sseq::{
+    Adams, Product, Sseq,
+    charting::{SeqSeeBackend, SvgBackend, TikzBackend},
+};
+
+fn main() -> anyhow::Result<()> {
+    ext::utils::init_logging()?;
+
+    let mut data: Value = query::raw("Spectral sequence file", |path| {
+        File::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(|f| serde_json::from_reader(BufReader::new(f)).map_err(|e| e.to_string()))
+    });
+    let sseq: Sseq<2, Adams> = serde_json::from_value(data["sseq"].take())?;
+    let products: Vec<(String, Product<2>)> = match data["products"].take() {
+        Value::Null => Vec::new(),
+        products => serde_json::from_value(products)?,
+    };
+
+    let page = query::with_default("Page", "2", str::parse);
+    let differentials = query::yes_no("Draw differentials");
+
+    let format = query::with_default("Output format (svg/tikz/seqsee)", "svg", |x| match x {
+        "svg" | "tikz" | "seqsee" => Ok(x.to_string()),
+        _ => Err(format!(
+            "unknown format '{x}'; expected one of svg, tikz, seqsee"
+        )),
+    });
+
+    let out = std::io::stdout();
+    match format.as_str() {
+        "svg" => sseq.write_to_graph(
+            SvgBackend::new(out),
+            page,
+            differentials,
+            products.iter(),
+            |_| Ok(()),
+        )?,
+        "tikz" => sseq.write_to_graph(
+            TikzBackend::new(out),
+            page,
+            differentials,
+            products.iter(),
+            |_| Ok(()),
+        )?,
+        "seqsee" => sseq.write_to_graph(
+            SeqSeeBackend::new(out),
+            page,
+            differentials,
+            products.iter(),
+            |_| Ok(()),
+        )?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
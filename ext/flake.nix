@@ -80,7 +80,6 @@
           just lint
           just test
           just benchmarks
-          just benchmarks-nassau
           just benchmarks-concurrent
           just miri
         '');
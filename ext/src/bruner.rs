@@ -0,0 +1,377 @@
+//! Import resolutions computed by Bruner's [ext](http://www.rrb.wayne.edu/papers/index.html)
+//! program.
+//!
+//! A resolution in Bruner's format is a directory containing a file `hDiff.{s}` for each
+//! homological degree `s`, which lists the generators of the `s`th free module and their
+//! differentials, with elements of the Steenrod algebra written in the Milnor basis. The format is
+//! described in <https://arxiv.org/abs/2109.13117>, and
+//! [`save_bruner`](../../save_bruner/index.html) writes resolutions in this format. Resolutions
+//! can be read over either basis of the Steenrod algebra; over the Adem basis, the operations are
+//! converted using [`SteenrodEvaluator`].
+//!
+//! The imported resolution is an augmented chain complex of free modules over the same algebra as
+//! the resolutions produced by [`construct`](crate::utils::construct), so it can be used wherever
+//! a resolution is expected, e.g. as the source or target of a [`ResolutionHomomorphism`] or in an
+//! [`ExtAlgebra`]. The quasi-inverses needed to lift maps into it are computed on demand.
+//!
+//! [`ResolutionHomomorphism`]: crate::resolution_homomorphism::ResolutionHomomorphism
+//! [`ExtAlgebra`]: crate::ext_algebra::ExtAlgebra
+
+use std::{
+    fs::File,
+    io::{self, BufRead},
+    path::Path,
+    sync::Arc,
+};
+
+use algebra::{
+    Algebra, MilnorAlgebra, SteenrodAlgebra,
+    milnor_algebra::MilnorBasisElement,
+    module::{FreeModule, Module, SteenrodModule, homomorphism::FreeModuleHomomorphism},
+    steenrod_evaluator::SteenrodEvaluator,
+};
+use anyhow::{Context, anyhow};
+use fp::{prime::TWO, vector::FpVector};
+
+use crate::{
+    CCC,
+    chain_complex::{
+        ChainComplex, FiniteAugmentedChainComplex, FiniteChainComplex, TruncatedChainComplex,
+    },
+};
+
+/// The chain complex produced by [`read_bruner_resolution`].
+pub type BrunerResolution = TruncatedChainComplex<
+    FiniteAugmentedChainComplex<
+        FreeModule<SteenrodAlgebra>,
+        FreeModuleHomomorphism<FreeModule<SteenrodAlgebra>>,
+        FreeModuleHomomorphism<SteenrodModule>,
+        CCC,
+    >,
+>;
+
+/// Read the next non-empty line of `data` into `buf`. Returns whether a line was read.
+fn read_line(data: &mut impl BufRead, buf: &mut String) -> io::Result<bool> {
+    loop {
+        buf.clear();
+        if data.read_line(buf)? == 0 {
+            return Ok(false);
+        }
+        if !buf.trim().is_empty() {
+            return Ok(true);
+        }
+    }
+}
+
+/// A term of a differential in Bruner's format, which is a line of the form
+/// ```text
+/// $gen $op_deg $algebra_dim i(r1,r2,...)(r1,r2,...).
+/// ```
+/// denoting the sum of the listed Milnor basis elements applied to the `$gen`th generator of the
+/// target.
+struct Term {
+    gen_idx: usize,
+    op_deg: i32,
+    ops: Vec<Vec<u32>>,
+}
+
+fn parse_term(line: &str) -> anyhow::Result<Term> {
+    let mut fields = line.split_whitespace();
+    let mut next_field = |name: &str| {
+        fields
+            .next()
+            .with_context(|| format!("Missing {name} in line {line:?}"))
+    };
+    let gen_idx = next_field("generator")?.parse()?;
+    let op_deg: i32 = next_field("operation degree")?.parse()?;
+    anyhow::ensure!(op_deg >= 0, "Negative operation degree in line {line:?}");
+    let _algebra_dim: usize = next_field("algebra dimension")?.parse()?;
+    let ops = next_field("operation")?;
+
+    let ops = ops
+        .strip_prefix('i')
+        .and_then(|x| x.strip_suffix('.'))
+        .with_context(|| format!("Invalid operation in line {line:?}"))?;
+    let ops = ops
+        .split_terminator(')')
+        .map(|op| {
+            let op = op
+                .strip_prefix('(')
+                .with_context(|| format!("Invalid operation in line {line:?}"))?;
+            op.split_terminator(',')
+                .map(|x| Ok(x.trim().parse()?))
+                .collect::<anyhow::Result<Vec<u32>>>()
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Term {
+        gen_idx,
+        op_deg,
+        ops,
+    })
+}
+
+/// Convert the Milnor basis element with p-part `p_part` to an element of `algebra`.
+fn operation(
+    algebra: &SteenrodAlgebra,
+    evaluator: &SteenrodEvaluator,
+    degree: i32,
+    p_part: &[u32],
+) -> anyhow::Result<FpVector> {
+    let mut p_part = p_part.to_vec();
+    // Bruner writes the identity as (0)
+    while p_part.last() == Some(&0) {
+        p_part.pop();
+    }
+    let elt = MilnorBasisElement {
+        q_part: 0,
+        p_part,
+        degree,
+    };
+    let basis_vector = |milnor: &MilnorAlgebra| {
+        milnor.compute_basis(degree);
+        let idx = milnor
+            .try_basis_element_to_index(&elt)
+            .with_context(|| format!("Invalid operation {elt} in degree {degree}"))?;
+        let mut result = FpVector::new(TWO, milnor.dimension(degree));
+        result.set_entry(idx, 1);
+        anyhow::Ok(result)
+    };
+
+    match algebra {
+        SteenrodAlgebra::MilnorAlgebra(milnor) => basis_vector(milnor),
+        SteenrodAlgebra::AdemAlgebra(_) => {
+            let milnor = basis_vector(&evaluator.milnor)?;
+            evaluator.adem.compute_basis(degree);
+            let mut adem = FpVector::new(TWO, evaluator.adem.dimension(degree));
+            evaluator.milnor_to_adem(&mut adem, 1, degree, &milnor);
+            Ok(adem)
+        }
+    }
+}
+
+/// The contents of a file `hDiff.{s}`: the maximum degree, and for each generator its degree and
+/// the terms of its differential.
+struct BrunerFile {
+    max_degree: i32,
+    gens: Vec<(i32, Vec<Term>)>,
+}
+
+fn read_file(path: &Path) -> anyhow::Result<BrunerFile> {
+    let mut f = io::BufReader::new(File::open(path)?);
+    let mut buf = String::new();
+
+    if !read_line(&mut f, &mut buf)? {
+        return Err(anyhow!("File is empty"));
+    }
+    let mut header = buf.split_whitespace();
+    let num_gens: usize = header
+        .next()
+        .context("Missing number of generators")?
+        .parse()?;
+    let max_degree: i32 = header.next().context("Missing maximum degree")?.parse()?;
+
+    let mut gens = Vec::with_capacity(num_gens);
+    while read_line(&mut f, &mut buf)? {
+        let degree: i32 = buf.trim().parse()?;
+        if !read_line(&mut f, &mut buf)? {
+            return Err(anyhow!("Missing differential of generator {}", gens.len()));
+        }
+        let num_terms: usize = buf.trim().parse()?;
+        let mut terms = Vec::with_capacity(num_terms);
+        for _ in 0..num_terms {
+            if !read_line(&mut f, &mut buf)? {
+                return Err(anyhow!("Missing term of generator {}", gens.len()));
+            }
+            terms.push(parse_term(&buf)?);
+        }
+        anyhow::ensure!(
+            degree <= max_degree,
+            "Generator in degree {degree} exceeds maximum degree {max_degree}"
+        );
+        if let Some(&(prev, _)) = gens.last() {
+            anyhow::ensure!(prev <= degree, "Generators are not sorted by degree");
+        }
+        gens.push((degree, terms));
+    }
+    anyhow::ensure!(
+        gens.len() == num_gens,
+        "Expected {num_gens} generators but found {}",
+        gens.len()
+    );
+    Ok(BrunerFile { max_degree, gens })
+}
+
+/// Add the generators listed in `file` to `module`, and extend it by zero to the maximum degree of
+/// the file.
+fn add_generators(module: &FreeModule<SteenrodAlgebra>, file: &BrunerFile) {
+    let min_degree = module.min_degree();
+    module.algebra().compute_basis(file.max_degree - min_degree);
+    for t in min_degree..=file.max_degree {
+        let num_gens = file.gens.iter().filter(|&&(d, _)| d == t).count();
+        module.add_generators(t, num_gens, None);
+    }
+    module.compute_basis(file.max_degree);
+}
+
+/// The element of `target` described by `terms` in degree `degree`, where `basis` sends the
+/// indices used by Bruner to the degree and index of the corresponding basis element of `target`.
+fn element<M: Module<Algebra = SteenrodAlgebra> + ?Sized>(
+    target: &M,
+    evaluator: &SteenrodEvaluator,
+    degree: i32,
+    terms: &[Term],
+    basis: impl Fn(usize) -> anyhow::Result<(i32, usize)>,
+) -> anyhow::Result<FpVector> {
+    let algebra = target.algebra();
+    let mut result = FpVector::new(TWO, target.dimension(degree));
+    for term in terms {
+        let (gen_deg, gen_idx) = basis(term.gen_idx)?;
+        anyhow::ensure!(
+            gen_deg + term.op_deg == degree,
+            "Term on generator {} has degree {} but expected {degree}",
+            term.gen_idx,
+            gen_deg + term.op_deg
+        );
+        for p_part in &term.ops {
+            let op = operation(&algebra, evaluator, term.op_deg, p_part)?;
+            for (op_idx, _) in op.iter_nonzero() {
+                target.act_on_basis(
+                    result.as_slice_mut(),
+                    1,
+                    term.op_deg,
+                    op_idx,
+                    gen_deg,
+                    gen_idx,
+                );
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Read a resolution of `target` in Bruner's format from the directory `dir`. The algebra of
+/// `target` must be the Milnor or Adem basis of the mod 2 Steenrod algebra, and `target` must be
+/// concentrated in homological degree 0. The augmentation in `hDiff.0` refers to the basis
+/// elements of `target.module(0)`, indexed in order of degree.
+pub fn read_bruner_resolution(dir: &Path, target: Arc<CCC>) -> anyhow::Result<BrunerResolution> {
+    let module = target.module(0);
+    let algebra = module.algebra();
+    anyhow::ensure!(
+        algebra.prime() == 2,
+        "Bruner's format is only supported at the prime 2"
+    );
+    let evaluator = SteenrodEvaluator::new(TWO);
+    let min_degree = module.min_degree();
+
+    let mut files = Vec::new();
+    loop {
+        let path = dir.join(format!("hDiff.{}", files.len()));
+        if !path.exists() {
+            break;
+        }
+        files.push(read_file(&path).with_context(|| format!("Failed to read {path:?}"))?);
+    }
+    anyhow::ensure!(!files.is_empty(), "No hDiff.0 found in {dir:?}");
+    for (s, file) in files.iter().enumerate() {
+        if let Some(&(t, _)) = file.gens.first() {
+            anyhow::ensure!(
+                t >= min_degree,
+                "hDiff.{s} has a generator in degree {t} below the minimum degree {min_degree}"
+            );
+        }
+    }
+
+    let modules: Vec<Arc<FreeModule<SteenrodAlgebra>>> = (0..files.len())
+        .map(|s| {
+            Arc::new(FreeModule::new(
+                Arc::clone(&algebra),
+                format!("F{s}"),
+                min_degree,
+            ))
+        })
+        .collect();
+    for (m, file) in std::iter::zip(&modules, &files) {
+        add_generators(m, file);
+    }
+
+    // The degree and basis index of each generator of each free module, in order of degree.
+    let gen_indices: Vec<Vec<(i32, usize)>> = modules
+        .iter()
+        .zip(&files)
+        .map(|(m, file)| {
+            m.iter_gens(file.max_degree)
+                .map(|(t, idx)| (t, m.operation_generator_to_index(0, 0, t, idx)))
+                .collect()
+        })
+        .collect();
+
+    let mut differentials = Vec::with_capacity(files.len() - 1);
+    for s in 1..files.len() {
+        let prev = &modules[s - 1];
+        anyhow::ensure!(
+            files[s].max_degree <= files[s - 1].max_degree,
+            "hDiff.{s} is computed up to degree {} but hDiff.{} only up to degree {}",
+            files[s].max_degree,
+            s - 1,
+            files[s - 1].max_degree
+        );
+        let d = FreeModuleHomomorphism::new(Arc::clone(&modules[s]), Arc::clone(prev), 0);
+        let basis = |i: usize| {
+            gen_indices[s - 1]
+                .get(i)
+                .copied()
+                .with_context(|| format!("hDiff.{s} refers to non-existent generator {i}"))
+        };
+        let mut gens = files[s].gens.iter().peekable();
+        for t in min_degree..=files[s].max_degree {
+            let mut rows = Vec::new();
+            while let Some((_, terms)) = gens.next_if(|&&(d, _)| d == t) {
+                rows.push(
+                    element(&**prev, &evaluator, t, terms, basis)
+                        .with_context(|| format!("Invalid differential in hDiff.{s}"))?,
+                );
+            }
+            d.add_generators_from_rows(t, rows);
+        }
+        differentials.push(Arc::new(d));
+    }
+
+    // The augmentation, where generators are indexed by the basis of `module`.
+    let max_degree = files[0].max_degree;
+    module.compute_basis(max_degree);
+    let module_basis: Vec<(i32, usize)> = (min_degree..=max_degree)
+        .flat_map(|t| (0..module.dimension(t)).map(move |i| (t, i)))
+        .collect();
+    let basis = |i: usize| {
+        module_basis
+            .get(i)
+            .copied()
+            .with_context(|| format!("hDiff.0 refers to non-existent basis element {i}"))
+    };
+    let augmentation = FreeModuleHomomorphism::new(Arc::clone(&modules[0]), Arc::clone(&module), 0);
+    let mut gens = files[0].gens.iter().peekable();
+    for t in min_degree..=max_degree {
+        let mut rows = Vec::new();
+        while let Some((_, terms)) = gens.next_if(|&&(d, _)| d == t) {
+            rows.push(
+                element(&*module, &evaluator, t, terms, basis)
+                    .context("Invalid augmentation in hDiff.0")?,
+            );
+        }
+        augmentation.add_generators_from_rows(t, rows);
+    }
+
+    let mut chain_maps = vec![Arc::new(augmentation)];
+    chain_maps.extend(modules[1..].iter().map(|m| {
+        Arc::new(FreeModuleHomomorphism::new(
+            Arc::clone(m),
+            target.zero_module(),
+            0,
+        ))
+    }));
+    let num_s = modules.len() as i32;
+    Ok(TruncatedChainComplex::new(
+        FiniteChainComplex::new(modules, differentials).augment(target, chain_maps),
+        num_s,
+    ))
+}
@@ -276,6 +276,12 @@ impl<
         self.homotopies[source.s()].add_generators_from_rows_ooo(source.t(), outputs)
     }
 
+    /// The homotopies are defined on the source modules up to but excluding this homological
+    /// degree.
+    pub fn next_homological_degree(&self) -> i32 {
+        self.homotopies.len()
+    }
+
     pub fn homotopy(&self, source_s: i32) -> Arc<FreeModuleHomomorphism<U::Module>> {
         Arc::clone(&self.homotopies[source_s])
     }
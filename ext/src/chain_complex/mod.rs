@@ -1,5 +1,6 @@
 pub(crate) mod chain_homotopy;
 mod finite_chain_complex;
+mod truncated_chain_complex;
 
 use std::sync::Arc;
 
@@ -20,6 +21,7 @@ use fp::{
 };
 use itertools::Itertools;
 use sseq::coordinates::{Bidegree, BidegreeGenerator};
+pub use truncated_chain_complex::TruncatedChainComplex;
 
 use crate::{save::SaveDirectory, utils::unicode_num};
 
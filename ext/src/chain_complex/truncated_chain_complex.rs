@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use algebra::module::Module;
+use sseq::coordinates::Bidegree;
+
+use crate::chain_complex::{AugmentedChainComplex, ChainComplex};
+
+/// A chain complex that is only known in homological degrees below a fixed bound, such as a
+/// resolution that was computed elsewhere and read from disk.
+///
+/// The underlying chain complex is usually a [`FiniteChainComplex`](super::FiniteChainComplex),
+/// which regards every module past its last one as zero. This wrapper instead reports the modules
+/// past the bound as not yet computed, so that e.g. products are not mistaken to be zero there.
+pub struct TruncatedChainComplex<CC> {
+    cc: CC,
+    next_homological_degree: i32,
+}
+
+impl<CC: ChainComplex> TruncatedChainComplex<CC> {
+    /// Only regard the homological degrees `s < next_homological_degree` of `cc` as known.
+    pub fn new(cc: CC, next_homological_degree: i32) -> Self {
+        Self {
+            cc,
+            next_homological_degree,
+        }
+    }
+
+    pub fn inner(&self) -> &CC {
+        &self.cc
+    }
+}
+
+impl<CC: ChainComplex> ChainComplex for TruncatedChainComplex<CC> {
+    type Algebra = CC::Algebra;
+    type Homomorphism = CC::Homomorphism;
+    type Module = CC::Module;
+
+    fn algebra(&self) -> Arc<Self::Algebra> {
+        self.cc.algebra()
+    }
+
+    fn min_degree(&self) -> i32 {
+        self.cc.min_degree()
+    }
+
+    fn zero_module(&self) -> Arc<Self::Module> {
+        self.cc.zero_module()
+    }
+
+    fn module(&self, s: i32) -> Arc<Self::Module> {
+        self.cc.module(s)
+    }
+
+    fn differential(&self, s: i32) -> Arc<Self::Homomorphism> {
+        self.cc.differential(s)
+    }
+
+    fn has_computed_bidegree(&self, b: Bidegree) -> bool {
+        b.s() < self.next_homological_degree && b.t() <= self.module(b.s()).max_computed_degree()
+    }
+
+    fn compute_through_bidegree(&self, b: Bidegree) {
+        self.cc.compute_through_bidegree(b)
+    }
+
+    fn next_homological_degree(&self) -> i32 {
+        self.next_homological_degree
+    }
+}
+
+impl<CC: AugmentedChainComplex> AugmentedChainComplex for TruncatedChainComplex<CC> {
+    type ChainMap = CC::ChainMap;
+    type TargetComplex = CC::TargetComplex;
+
+    fn target(&self) -> Arc<Self::TargetComplex> {
+        self.cc.target()
+    }
+
+    fn chain_map(&self, s: i32) -> Arc<Self::ChainMap> {
+        self.cc.chain_map(s)
+    }
+}
@@ -0,0 +1,427 @@
+//! Export resolutions, chain maps and chain homotopies to a documented JSON format, and import them
+//! back.
+//!
+//! Unlike the [save files](crate::save), which are optimized for resuming computations, the export
+//! format is meant to be read by other programs. Elements of the algebra are written as strings in
+//! the basis of the algebra (e.g. `P(2, 1)` in the Milnor basis), so the data can be interpreted
+//! without knowing how we order our bases. The format is described in `EXPORT-FORMAT.md`.
+//!
+//! An imported resolution is a [`FiniteChainComplex`] of free modules, truncated to the
+//! homological degrees that were exported, which can be used as the source of a
+//! [`ResolutionHomomorphism`]. For example, one can lift the identity map to compare an imported
+//! resolution with one we computed ourselves.
+
+use std::sync::Arc;
+
+use algebra::{
+    Algebra,
+    module::{
+        FreeModule, Module,
+        homomorphism::{FreeModuleHomomorphism, ModuleHomomorphism},
+    },
+};
+use anyhow::Context;
+use fp::{
+    prime::Prime,
+    vector::{FpSlice, FpVector},
+};
+use serde_json::{Value, json};
+use sseq::coordinates::Bidegree;
+
+use crate::{
+    chain_complex::{ChainHomotopy, FiniteChainComplex, FreeChainComplex, TruncatedChainComplex},
+    resolution_homomorphism::ResolutionHomomorphism,
+};
+
+/// The version of the export format. This should be incremented whenever the format changes.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// The chain complex produced by [`import_resolution`].
+pub type ImportedResolution<A> =
+    TruncatedChainComplex<FiniteChainComplex<FreeModule<A>, FreeModuleHomomorphism<FreeModule<A>>>>;
+
+fn header(kind: &str, algebra: &impl Algebra) -> Value {
+    json!({
+        "type": kind,
+        "format_version": EXPORT_FORMAT_VERSION,
+        "prime": algebra.prime().as_u32(),
+        "algebra": algebra.prefix(),
+        "algebra_magic": algebra.magic(),
+    })
+}
+
+fn check_header(json: &Value, kind: &str, algebra: &impl Algebra) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        json["type"] == kind,
+        "Expected an exported {kind} but got {}",
+        json["type"]
+    );
+    anyhow::ensure!(
+        json["format_version"] == EXPORT_FORMAT_VERSION,
+        "Unsupported export format version {}",
+        json["format_version"]
+    );
+    anyhow::ensure!(
+        json["prime"] == algebra.prime().as_u32(),
+        "Exported data is at the prime {} but the algebra is at the prime {}",
+        json["prime"],
+        algebra.prime()
+    );
+    anyhow::ensure!(
+        json["algebra_magic"] == algebra.magic(),
+        "Exported data is over the algebra {} (magic {}) but the algebra is {} (magic {})",
+        json["algebra"],
+        json["algebra_magic"],
+        algebra.prefix(),
+        algebra.magic()
+    );
+    Ok(())
+}
+
+fn get_i32(json: &Value, field: &str) -> anyhow::Result<i32> {
+    json[field]
+        .as_i64()
+        .and_then(|x| i32::try_from(x).ok())
+        .with_context(|| format!("Missing an integer `{field}` field"))
+}
+
+fn get_array<'a>(json: &'a Value, field: &str) -> anyhow::Result<&'a Vec<Value>> {
+    json[field]
+        .as_array()
+        .with_context(|| format!("Missing an array `{field}` field"))
+}
+
+fn bidegree_to_json(b: Bidegree) -> Value {
+    json!({ "s": b.s(), "t": b.t() })
+}
+
+fn bidegree_from_json(json: &Value) -> anyhow::Result<Bidegree> {
+    Ok(Bidegree::s_t(get_i32(json, "s")?, get_i32(json, "t")?))
+}
+
+/// Write an element of `module` in degree `degree` as a list of terms.
+fn element_to_json<A: Algebra>(module: &FreeModule<A>, degree: i32, v: FpSlice) -> Value {
+    let algebra = module.algebra();
+    v.iter_nonzero()
+        .map(|(i, c)| {
+            let opgen = module.index_to_op_gen(degree, i);
+            json!({
+                "op": algebra.basis_element_to_string(opgen.operation_degree, opgen.operation_index),
+                "gen": [opgen.generator_degree, opgen.generator_index],
+                "coef": c,
+            })
+        })
+        .collect()
+}
+
+fn element_from_json<A: Algebra>(
+    module: &FreeModule<A>,
+    degree: i32,
+    json: &Value,
+) -> anyhow::Result<FpVector> {
+    let p = module.prime();
+    let algebra = module.algebra();
+    let mut result = FpVector::new(p, module.dimension(degree));
+    for term in json.as_array().context("Element is not an array")? {
+        let op = term["op"]
+            .as_str()
+            .context("Term is missing a string `op` field")?;
+        let (op_deg, op_idx) = algebra
+            .basis_element_from_string(op)
+            .with_context(|| format!("Invalid algebra element {op}"))?;
+        let (gen_deg, gen_idx) = term["gen"]
+            .as_array()
+            .and_then(|g| Some((g.first()?.as_i64()? as i32, g.get(1)?.as_u64()? as usize)))
+            .context("Term is missing a `gen` field of the form [t, idx]")?;
+        let coef = term["coef"]
+            .as_u64()
+            .context("Term is missing an integer `coef` field")?;
+
+        anyhow::ensure!(
+            op_deg + gen_deg == degree,
+            "Term {term} has degree {} but expected {degree}",
+            op_deg + gen_deg
+        );
+        anyhow::ensure!(
+            gen_deg >= module.min_degree()
+                && gen_deg <= module.max_computed_degree()
+                && gen_idx < module.number_of_gens_in_degree(gen_deg),
+            "Term {term} refers to a non-existent generator"
+        );
+        let idx = module.operation_generator_to_index(op_deg, op_idx, gen_deg, gen_idx);
+        result.add_basis_element(idx, (coef % p.as_u32() as u64) as u32);
+    }
+    Ok(result)
+}
+
+/// Write the values of `f` on all generators on which it is defined. Generators below the minimum
+/// degree of `f` are omitted since `f` necessarily vanishes on them.
+fn map_to_json<A: Algebra>(f: &FreeModuleHomomorphism<FreeModule<A>>) -> Value {
+    let source = f.source();
+    let target = f.target();
+    let max_t = f.next_degree() - 1;
+    let outputs: Vec<Value> = source
+        .iter_gens(max_t)
+        .filter(|&(t, _)| t >= f.min_degree())
+        .map(|(t, idx)| {
+            json!({
+                "gen": [t, idx],
+                "value": element_to_json(&target, t - f.degree_shift(), f.output(t, idx).as_slice()),
+            })
+        })
+        .collect();
+    json!({
+        "max_t": max_t,
+        "outputs": outputs,
+    })
+}
+
+/// Define `f` on the generators listed in `json`. The generators must be listed in order, and the
+/// degrees on which `f` is already defined are skipped.
+fn map_from_json<A: Algebra>(
+    f: &FreeModuleHomomorphism<FreeModule<A>>,
+    json: &Value,
+) -> anyhow::Result<()> {
+    let source = f.source();
+    let target = f.target();
+    let shift = f.degree_shift();
+    let max_t = get_i32(json, "max_t")?;
+    anyhow::ensure!(
+        max_t <= source.max_computed_degree(),
+        "Map is defined up to degree {max_t} but the source is only computed up to degree {}",
+        source.max_computed_degree()
+    );
+    anyhow::ensure!(
+        max_t - shift <= target.max_computed_degree(),
+        "Map is defined up to degree {max_t} but the target is only computed up to degree {}",
+        target.max_computed_degree() + shift
+    );
+
+    let mut outputs = get_array(json, "outputs")?.iter();
+    for t in f.min_degree()..=max_t {
+        let num_gens = source.number_of_gens_in_degree(t);
+        let rows = (0..num_gens)
+            .map(|idx| {
+                let output = outputs
+                    .next()
+                    .with_context(|| format!("Missing value on generator [{t}, {idx}]"))?;
+                anyhow::ensure!(
+                    output["gen"] == json!([t, idx]),
+                    "Expected value on generator [{t}, {idx}] but got {}",
+                    output["gen"]
+                );
+                element_from_json(&target, t - shift, &output["value"])
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if t >= f.next_degree() {
+            f.add_generators_from_rows(t, rows);
+        }
+    }
+    anyhow::ensure!(outputs.next().is_none(), "Too many values");
+    Ok(())
+}
+
+/// Export the modules and differentials of a free chain complex, typically a resolution. This
+/// includes all the data that has been computed.
+pub fn export_resolution<CC: FreeChainComplex>(cc: &CC) -> Value {
+    let algebra = cc.algebra();
+    let min_degree = cc.min_degree();
+
+    let mut modules = Vec::new();
+    let mut differentials = Vec::new();
+    for s in 0..cc.next_homological_degree() {
+        let module = cc.module(s);
+        let max_t = module.max_computed_degree();
+        if max_t < min_degree {
+            break;
+        }
+        let num_gens: Vec<usize> = (min_degree..=max_t)
+            .map(|t| module.number_of_gens_in_degree(t))
+            .collect();
+        modules.push(json!({
+            "s": s,
+            "max_t": max_t,
+            "num_gens": num_gens,
+        }));
+        if s > 0 {
+            let mut d = map_to_json(&cc.differential(s));
+            d["s"] = Value::from(s);
+            differentials.push(d);
+        }
+    }
+
+    let mut json = header("resolution", &*algebra);
+    json["min_degree"] = Value::from(min_degree);
+    json["modules"] = Value::from(modules);
+    json["differentials"] = Value::from(differentials);
+    json
+}
+
+/// Import a chain complex exported by [`export_resolution`]. The algebra must be the algebra the
+/// data was exported with.
+pub fn import_resolution<A: Algebra>(
+    json: &Value,
+    algebra: Arc<A>,
+) -> anyhow::Result<ImportedResolution<A>> {
+    check_header(json, "resolution", &*algebra)?;
+    let min_degree = get_i32(json, "min_degree")?;
+
+    let mut modules = Vec::new();
+    for (s, module_json) in get_array(json, "modules")?.iter().enumerate() {
+        let module = FreeModule::new(Arc::clone(&algebra), format!("F{s}"), min_degree);
+        let max_t = get_i32(module_json, "max_t")?;
+        let num_gens = get_array(module_json, "num_gens")?;
+        anyhow::ensure!(
+            num_gens.len() as i32 == max_t - min_degree + 1,
+            "Module F{s} is computed up to degree {max_t} but has {} generator counts",
+            num_gens.len()
+        );
+        algebra.compute_basis(max_t - min_degree);
+        for (t, n) in (min_degree..).zip(num_gens) {
+            let n = n
+                .as_u64()
+                .with_context(|| format!("Invalid number of generators of F{s} in degree {t}"))?;
+            module.compute_basis(t);
+            module.add_generators(t, n as usize, None);
+        }
+        modules.push(Arc::new(module));
+    }
+    anyhow::ensure!(!modules.is_empty(), "Exported resolution has no modules");
+
+    let differentials = get_array(json, "differentials")?;
+    anyhow::ensure!(
+        differentials.len() == modules.len() - 1,
+        "Expected {} differentials but got {}",
+        modules.len() - 1,
+        differentials.len()
+    );
+    let differentials = differentials
+        .iter()
+        .enumerate()
+        .map(|(i, d_json)| {
+            let s = i + 1;
+            let d = FreeModuleHomomorphism::new(
+                Arc::clone(&modules[s]),
+                Arc::clone(&modules[s - 1]),
+                0,
+            );
+            map_from_json(&d, d_json).with_context(|| format!("Invalid differential d{s}"))?;
+            Ok(Arc::new(d))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let num_s = modules.len() as i32;
+    Ok(TruncatedChainComplex::new(
+        FiniteChainComplex::new(modules, differentials),
+        num_s,
+    ))
+}
+
+/// Export the maps of a chain map on all generators on which it has been computed.
+pub fn export_chain_map<CC1, CC2>(f: &ResolutionHomomorphism<CC1, CC2>) -> Value
+where
+    CC1: FreeChainComplex,
+    CC2: FreeChainComplex<Algebra = CC1::Algebra>,
+{
+    let maps: Vec<Value> = (f.shift.s()..f.next_homological_degree())
+        .map(|s| {
+            let mut map = map_to_json(&f.get_map(s));
+            map["s"] = Value::from(s);
+            map
+        })
+        .collect();
+
+    let mut json = header("chain_map", &*f.algebra());
+    json["name"] = Value::from(f.name());
+    json["shift"] = bidegree_to_json(f.shift);
+    json["maps"] = Value::from(maps);
+    json
+}
+
+/// Import a chain map exported by [`export_chain_map`]. The source and target must have been
+/// computed on all bidegrees on which the chain map is defined, and must be (isomorphic to) the
+/// source and target of the exported chain map, with the same choice of generators.
+pub fn import_chain_map<CC1, CC2>(
+    json: &Value,
+    source: Arc<CC1>,
+    target: Arc<CC2>,
+) -> anyhow::Result<ResolutionHomomorphism<CC1, CC2>>
+where
+    CC1: FreeChainComplex,
+    CC2: FreeChainComplex<Algebra = CC1::Algebra>,
+{
+    check_header(json, "chain_map", &*source.algebra())?;
+    let name = json["name"].as_str().unwrap_or_default().to_owned();
+    let shift = bidegree_from_json(&json["shift"]).context("Invalid shift")?;
+
+    let f = ResolutionHomomorphism::try_new(name, source, target, shift)?;
+    for (s, map) in (shift.s()..).zip(get_array(json, "maps")?) {
+        anyhow::ensure!(
+            map["s"] == s,
+            "Expected the map on F{s} but got {}",
+            map["s"]
+        );
+        map_from_json(f.get_map_ensure_length(s), map)
+            .with_context(|| format!("Invalid map on F{s}"))?;
+    }
+    Ok(f)
+}
+
+/// Export the chain homotopy on all generators on which it has been computed.
+pub fn export_chain_homotopy<S, T, U>(h: &ChainHomotopy<S, T, U>) -> Value
+where
+    S: FreeChainComplex,
+    T: FreeChainComplex<Algebra = S::Algebra> + Sync,
+    U: FreeChainComplex<Algebra = S::Algebra> + Sync,
+{
+    let shift = h.shift();
+    let homotopies: Vec<Value> = (shift.s() - 1..h.next_homological_degree())
+        .map(|s| {
+            let mut map = map_to_json(&h.homotopy(s));
+            map["s"] = Value::from(s);
+            map
+        })
+        .collect();
+
+    let mut json = header("chain_homotopy", &*h.left().algebra());
+    json["left"] = Value::from(h.left().name());
+    json["right"] = Value::from(h.right().name());
+    json["shift"] = bidegree_to_json(shift);
+    json["homotopies"] = Value::from(homotopies);
+    json
+}
+
+/// Import a chain homotopy exported by [`export_chain_homotopy`], where `left` and `right` are the
+/// chain maps whose composite is null-homotopic. The source of `left` and the target of `right`
+/// must have been computed on all bidegrees on which the chain homotopy is defined.
+pub fn import_chain_homotopy<S, T, U>(
+    json: &Value,
+    left: Arc<ResolutionHomomorphism<S, T>>,
+    right: Arc<ResolutionHomomorphism<T, U>>,
+) -> anyhow::Result<ChainHomotopy<S, T, U>>
+where
+    S: FreeChainComplex,
+    T: FreeChainComplex<Algebra = S::Algebra> + Sync,
+    U: FreeChainComplex<Algebra = S::Algebra> + Sync,
+{
+    check_header(json, "chain_homotopy", &*left.algebra())?;
+    let shift = bidegree_from_json(&json["shift"]).context("Invalid shift")?;
+    anyhow::ensure!(
+        shift == left.shift + right.shift,
+        "Chain homotopy has shift {shift} but the chain maps have total shift {}",
+        left.shift + right.shift
+    );
+
+    let homotopies = get_array(json, "homotopies")?;
+    let h = ChainHomotopy::new(left, right);
+    h.initialize_homotopies(shift.s() - 1 + homotopies.len() as i32);
+    for (s, map) in (shift.s() - 1..).zip(homotopies) {
+        anyhow::ensure!(
+            map["s"] == s,
+            "Expected the homotopy on F{s} but got {}",
+            map["s"]
+        );
+        map_from_json(&h.homotopy(s), map).with_context(|| format!("Invalid homotopy on F{s}"))?;
+    }
+    Ok(h)
+}
@@ -24,7 +24,7 @@ use crate::{
     chain_complex::FreeChainComplex,
     resolution_homomorphism::ResolutionHomomorphism,
     secondary::{
-        LAMBDA_BIDEGREE, SecondaryLift, SecondaryResolution, SecondaryResolutionHomomorphism,
+        LAMBDA_BIDEGREE, SecondaryLift, SecondaryResolution, SecondaryResolutionHomomorphism, Shard,
     },
 };
 
@@ -64,21 +64,30 @@ where
 {
     /// Build the secondary layer over `alg`. Construction is cheap; call [`extend_all`](Self::extend_all)
     /// to actually compute the secondary resolutions and $E_3$ pages.
+    ///
+    /// # Panics
+    /// Panics if the algebra is not a pair algebra. See [`try_new`](Self::try_new).
     pub fn new(alg: Arc<ExtAlgebra<CC>>) -> Self {
-        let res_lift = Arc::new(SecondaryResolution::new(Arc::clone(alg.resolution())));
+        Self::try_new(alg).unwrap_or_else(|e| panic!("{e:#}"))
+    }
+
+    /// Same as [`new`](Self::new), but returns an error if the algebra does not admit a lift to a
+    /// pair algebra, e.g. if it is not the Milnor basis of the Steenrod algebra at the prime 2.
+    pub fn try_new(alg: Arc<ExtAlgebra<CC>>) -> anyhow::Result<Self> {
+        let res_lift = Arc::new(SecondaryResolution::try_new(Arc::clone(alg.resolution()))?);
         let unit_lift = if alg.is_unit() {
             Arc::clone(&res_lift)
         } else {
-            Arc::new(SecondaryResolution::new(Arc::clone(alg.unit())))
+            Arc::new(SecondaryResolution::try_new(Arc::clone(alg.unit()))?)
         };
-        Self {
+        Ok(Self {
             alg,
             res_lift,
             unit_lift,
             res_sseq: Mutex::new(None),
             unit_sseq: Mutex::new(None),
             secondary_products: DashMap::new(),
-        }
+        })
     }
 
     /// Extend the secondary resolutions as far as the underlying resolutions allow, then compute
@@ -109,6 +118,21 @@ where
         }
     }
 
+    /// Sharding entry point: run the jobs of the secondary resolutions in `shard`, saving the
+    /// results to the save directory. See [`SecondaryLift::jobs`].
+    pub fn run_shard(&self, shard: Shard) -> anyhow::Result<()> {
+        self.res_lift.run_shard(shard)?;
+        if !self.alg.is_unit() {
+            self.unit_lift.run_shard(shard)?;
+        }
+        Ok(())
+    }
+
+    /// The secondary resolution of the module.
+    pub fn resolution_lift(&self) -> &Arc<SecondaryResolution<CC>> {
+        &self.res_lift
+    }
+
     /// The primary [`ExtAlgebra`] this is built on.
     pub fn ext_algebra(&self) -> &Arc<ExtAlgebra<CC>> {
         &self.alg
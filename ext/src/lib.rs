@@ -80,6 +80,26 @@
 //! by appending `@basis_name`. For example, if we want to resolve `Ceta[1]` with the Adem basis, we
 //! can specify it as `Ceta[1]@adem`.
 //!
+//! Similarly, we can pick the algorithm used to compute the minimal resolution by appending
+//! `@nassau` or `@standard`. The default is the standard algorithm. Nassau's algorithm (see
+//! [`nassau`]) is much faster for finite modules, but requires the Milnor basis and does not
+//! support cofibers. The two suffixes can be combined, e.g. `C2@milnor@nassau`.
+//!
+//! The suffixes `@c-motivic` and `@r-motivic` select the motivic Steenrod algebra over $\mathbb{C}$
+//! or $\mathbb{R}$ instead. The motivic cohomology of a point is the module `M2`, so that
+//! `M2@c-motivic` resolves the $\mathbb{C}$-motivic sphere. The resolution records the weight of
+//! each generator, and [`motivic`] assembles them into motivic Ext groups. The motivic Steenrod
+//! algebras are not Steenrod algebras over $\mathbb{F}_2$, so these modules are resolved by
+//! [`utils::construct_motivic`] instead of [`utils::construct`], and only the examples that go
+//! through [`utils::query_any_module_only`] accept them. In particular, secondary computations
+//! are not supported over the motivic Steenrod algebras.
+//!
+//! We can also resolve over a sub-Hopf algebra of the Steenrod algebra by appending its name, e.g.
+//! `S_2@A(2)` resolves $\mathbb{F}_2$ over $A(2)$, which computes the Adams $E_2$ page of
+//! $\mathrm{tmf}$. The supported subalgebras are `A(n)`, `E(n)` and (at odd primes) `P(n)`; see
+//! [`algebra::SubHopfAlgebra`] for their definitions. This requires the Milnor basis, and is
+//! equivalent to setting the `profile` field of the module to the name of the subalgebra.
+//!
 //! ### Ext elements
 //! Each Ext group comes with a basis. The ith basis element of $\Ext^{s, n + s}$ is denoted `x_(n,
 //! s, i)`. If we want to specify an element in a particular Ext group, we either write it as a
@@ -98,31 +118,72 @@
 //! writing (only the data for said bidegree would be corrupted).
 //!
 //! For products, the subdirectory will be named after the name of the product. One must not reuse a
-//! name for different products; the script may produce and write erroneous results silently in such
-//! cases (though it practice it is likely to hit some error sooner or later).
+//! name for different products. For products defined by a class, this is detected and refused, but
+//! otherwise the script may produce and write erroneous results silently in such cases (though it
+//! practice it is likely to hit some error sooner or later).
+//!
+//! Each save directory contains a `manifest.json` recording the save format version, the prime,
+//! the algebra and a fingerprint of the module specification (see [`save::SaveManifest`]).
+//! Opening a save directory that belongs to a different computation results in an error. Save
+//! directories created by older versions have no manifest and are refused as well; they can be
+//! upgraded with the `migrate_save` example.
 //!
 //! If the script is compiled with the `zstd` feature, then it supports reading from zstd compressed
 //! save files, where each save file is individually compressed. The script will first look for the
 //! uncompressed file. If it does not exist, it then looks for the file with the same name but with
 //! a `.zst` extension.
 //!
-//! Note that any new save file will still be written uncompressed. To compress the files, one must
-//! run the `zstd` program on each file in the save directory. It is safe to remove the original
-//! file after compression (i.e. run with the `--rm` option).
+//! New save files are written uncompressed by default. To write compressed files instead, set the
+//! `SAVE_COMPRESSION_LEVEL` environment variable to the desired zstd compression level (or call
+//! [`save::set_compression_level`]). Each file is still compressed individually, so terminating
+//! the program halfway corrupts at most the file being written. Existing uncompressed files can
+//! also be compressed by running the `zstd` program on each file in the save directory. It is safe
+//! to remove the original file after compression (i.e. run with the `--rm` option).
+//!
+//! If the save directory has the extension `.archive`, e.g. `S_2.archive`, then all the save data
+//! is instead stored in a single indexed, append-only file at that path, which is useful on file
+//! systems with inode quotas. Each record in the archive has the same contents and checksum as
+//! the corresponding save file, and an incomplete record at the end of the archive (say because
+//! the program was terminated halfway) is discarded when the archive is opened. Note that the
+//! archive is never shrunk: overwritten and deleted records still occupy space. An archive must
+//! not be written to by multiple processes at the same time.
+//!
+//! Setting the `MEMORY_BUDGET` environment variable to a number of MiB limits how many kernels and
+//! quasi-inverses a resolution caches in memory (see [`resolution::MuResolution::memory_budget`]).
+//! Whatever does not fit is dropped once it is written to disk and reloaded when it is needed, e.g.
+//! for computing products. This requires a save directory that is both read from and written to.
+//! It is not a bound on the total memory usage: the differentials are needed to extend the
+//! resolution to every new internal degree, so they always stay in memory. They are much smaller
+//! than the kernels and quasi-inverses, which have a row for each basis element instead of each
+//! generator.
+//!
+//! A resolution can be computed by several processes at once, possibly on different machines
+//! sharing a filesystem, by setting the `DISTRIBUTED_RESOLUTION` environment variable to `1` and
+//! running the same script with the same save directory in each process (see
+//! [`resolution::MuResolution::distributed`]). The processes claim bidegrees with lock files in
+//! the save directory and pick up the bidegrees computed by the others. If a process crashes, the
+//! other processes break its locks once they find that it is dead or that it has stopped
+//! refreshing them (see [`save::SaveFile::try_lock`]).
 //!
 //! # List of examples
 //! Click on the individual examples for further information.
 //!
 //! | Name | Description |
 //! | --- | --- |
+//! | [adams_novikov](../adams_novikov/index.html) | Draw the Adams–Novikov $E_2$ page of the sphere, or with coefficients in $BP_*/I_n$. |
 //! | [algebra_dim](../algebra_dim/index.html) | Print the dimension of the Steenrod algebra in each degree. |
 //! | [bruner](../bruner/index.html) | Compare our basis with Bruner's. |
 //! | [define_module](../define_module/index.html) | Interactively define a Steenrod module. |
 //! | [differentials](../differentials/index.html) | Print all differentials in the minimal resolution. |
+//! | [e_infinity](../e_infinity/index.html) | Assemble the groups in each stem from the $E_\infty$ page of a spectral sequence saved as JSON. |
+//! | [export_resolution](../export_resolution/index.html) | Export a resolution as JSON for use by other programs. |
 //! | [filtration_one](../filtration_one/index.html) | Print all filtration one products. |
+//! | [inspect_save](../inspect_save/index.html) | Report complete, corrupt and missing save files, and optionally delete the corrupt ones. |
 //! | [lift_hom](../lift_hom/index.html) | Compute the map $\Ext(N, k) \to \Ext(M, k)$ induced by an element in $\Ext(M, N)$. |
 //! | [mahowald_invariant](../mahowald_invariant/index.html) | Compute (algebraic) Mahowald invariants. |
 //! | [massey](../massey/index.html) | Compute Massey products. |
+//! | [may](../may/index.html) | Compare the May spectral sequence with Ext. |
+//! | [migrate_save](../migrate_save/index.html) | Upgrade a save directory to the current save format. |
 //! | [num_gens](../num_gens/index.html) | Compute the dimension of Ext in each bidegree. |
 //! | [resolution_size](../resolution_size/index.html) | Compute the size of the minimal resolution in each bidegree |
 //! | [resolve](../resolve/index.html) | Resolve a module to a fixed $(s, t)$ and potentially save the resolution. |
@@ -131,6 +192,8 @@
 //! | [secondary](../secondary/index.html) | Compute $d_2$ differentials using the secondary Steenrod algebra. |
 //! | [secondary_product](../secondary_product/index.html) | Compute products in $\Mod_{C\lambda^2}$ using the secondary Steenrod algebra. |
 //! | [secondary_massey](../secondary_massey/index.html) | Compute Massey products in $\Mod_{C\lambda^2}$ using the secondary Steenrod algebra. |
+//! | [secondary_jobs](../secondary_jobs/index.html) | List and merge the jobs of a sharded `secondary` computation. |
+//! | [sseq_chart](../sseq_chart/index.html) | Draw a chart of a spectral sequence saved as JSON. |
 //! | [steenrod](../steenrod/index.html) | Compute Steenrod operations in Ext. |
 //! | [tensor](../tensor/index.html) | Compute the tensor product of two modules. |
 //! | [yoneda](../yoneda/index.html) | Compute a Yoneda representative of an Ext class. |
@@ -153,32 +216,45 @@
 //!   feature offers significant improvements at the prime 2.
 //! - `concurrent`: Use multiple threads for computations. The number of threads used can be
 //!   configured via the `RAYON_NUM_THREADS` environment variable.
-//! - `zstd`: Support reading zstd-compressed save files. Note that the save files are still written
-//!   uncompressed. If compression is desired, output files need to be compressed manually.
+//! - `zstd`: Support reading and writing zstd-compressed save files. Save files are only written
+//!   compressed if the `SAVE_COMPRESSION_LEVEL` environment variable is set.
 //! - `cache-multiplication`: Precompute and cache the multiplication table under the Milnor basis.
 //!   This is only feasible when using a small, finite subalgebra, e.g. when working with
 //!   $\mathrm{tmf}$ modules.
 //! - `logging`: Print timing information of the computations to stderr. Note that this has no
 //!   effect unless the `RUST_LOG` environment variable is set appropriately.
-//! - `nassau`: Use Nassau's algorithm to compute the minimal resolution instead of the usual
-//!   minimal resolution algorithm. When this feature is enabled, only finite dimensional modules
-//!   at the prime 2 can be resolved.
 
 #![allow(clippy::upper_case_acronyms)]
 #![deny(clippy::use_self, unsafe_op_in_unsafe_fn)]
 
+pub mod bruner;
 pub mod chain_complex;
+pub mod export;
 pub mod ext_algebra;
+pub mod may;
+pub mod motivic;
+pub mod novikov;
+pub mod progress;
 pub mod resolution;
 pub mod resolution_homomorphism;
 pub mod save;
 
 pub mod yoneda;
 
-use algebra::module::SteenrodModule;
+use std::sync::Arc;
+
+use algebra::{
+    FiniteDimensionalAlgebra,
+    module::{Module, MotivicModule, SteenrodModule},
+};
 
 use crate::chain_complex::FiniteChainComplex;
 pub type CCC = FiniteChainComplex<SteenrodModule>;
+/// The chain complexes resolved over the motivic Steenrod algebras, see [`motivic`].
+pub type MotivicCCC = FiniteChainComplex<MotivicModule>;
+/// The chain complexes resolved over a [`FiniteDimensionalAlgebra`] defined by the module.
+pub type FiniteDimensionalCCC =
+    FiniteChainComplex<Arc<dyn Module<Algebra = FiniteDimensionalAlgebra>>>;
 
 pub mod nassau;
 pub mod secondary;
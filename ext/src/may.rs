@@ -0,0 +1,400 @@
+//! The $E_1$ page of the May spectral sequence.
+//!
+//! The May filtration on the Steenrod algebra is the filtration by powers of the augmentation
+//! ideal, where the dual of $\xi_i^{p^j}$ has filtration $i$. The associated graded algebra is
+//! primitively generated, and its cohomology is the $E_1$ page of the May spectral sequence
+//! $$ E_1 \Longrightarrow \Ext_A(\mathbb{F}_p, \mathbb{F}_p). $$
+//!
+//! Explicitly (see Ravenel's green book, Theorem 3.2.5), at the prime $2$ this is the polynomial
+//! algebra on classes $h_{i, j}$ in degree $(s, t, u) = (1, 2^j (2^i - 1), i)$, where $u$ is the
+//! May filtration. At an odd prime, it is the tensor product of an exterior algebra on classes
+//! $h_{i, j}$ in degree $(1, 2(p^i - 1)p^j, i)$ and polynomial algebras on classes $b_{i, j}$ in
+//! degree $(2, 2(p^i - 1)p^{j + 1}, pi)$ and $a_i$ in degree $(1, 2p^i - 1, i + 1)$. For a
+//! [`MilnorAlgebra`] with a non-trivial profile, we only use the generators coming from the
+//! elements of the dual that survive to the quotient.
+//!
+//! The $d_1$ differential at the prime $2$ is given by
+//! $$ d_1 h_{i, j} = \sum_{0 < k < i} h_{i - k, k + j} h_{k, j}, $$
+//! and [`MayE1::to_sseq`] loads it into the spectral sequence, so that the $E_2$ page is computed
+//! as well. At odd primes, only the $E_1$ page is loaded.
+//!
+//! Since each page is an upper bound for $\Ext$, this is useful for checking the output of a
+//! [`Resolution`](crate::resolution::Resolution) of the sphere, see [`compare_with_resolution`].
+
+use std::{collections::HashMap, fmt::Write as _};
+
+use algebra::{Algebra, MilnorAlgebra};
+use fp::{
+    prime::{Prime, ValidPrime},
+    vector::FpVector,
+};
+use sseq::{
+    Justification, May, Sseq,
+    coordinates::{Bidegree, MultiDegree, MultiDegreeElement},
+};
+
+use crate::chain_complex::FreeChainComplex;
+
+/// A multiplicative generator of the $E_1$ page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MayGenerator {
+    pub name: String,
+    /// The degree `(n, s, u)` of the generator, where `u` is the May filtration.
+    pub degree: MultiDegree<3>,
+    /// Whether the generator is exterior (as opposed to polynomial).
+    pub exterior: bool,
+}
+
+/// The $E_1$ page of the May spectral sequence computing $\Ext_A(\mathbb{F}_p, \mathbb{F}_p)$,
+/// where $A$ is a [`MilnorAlgebra`].
+///
+/// This is computed for stems up to `max.n()` and homological degrees up to `max.s() + 1`, so that
+/// the $d_1$ differentials out of homological degree `max.s()` are known.
+pub struct MayE1 {
+    p: ValidPrime,
+    max: Bidegree,
+    generators: Vec<MayGenerator>,
+    /// The index of the generator $h_{i, j}$.
+    h_index: HashMap<(u32, u32), usize>,
+    /// The monomials in each degree, as exponent vectors indexed by the generators.
+    basis: HashMap<MultiDegree<3>, Vec<Vec<u32>>>,
+}
+
+impl MayE1 {
+    pub fn new(algebra: &MilnorAlgebra, max: Bidegree) -> Self {
+        let p = algebra.prime();
+        let profile = algebra.profile();
+        let max_t = max.n() + max.s() + 1;
+        let generic = algebra.generic();
+
+        let mut generators = Vec::new();
+        let mut h_index = HashMap::new();
+        // Add a generator if it is in range, returning its index
+        let mut add = |name: String, s: i32, t: i32, u: i32, exterior: bool| {
+            (t - s <= max.n()).then(|| {
+                generators.push(MayGenerator {
+                    name,
+                    degree: MultiDegree::new([t - s, s, u]),
+                    exterior,
+                });
+                generators.len() - 1
+            })
+        };
+
+        let p_ = p.as_i32();
+        let mut p_i = p_;
+        let mut i = 1;
+        // xi_i has degree 2(p^i - 1) at odd primes and 2^i - 1 at the prime 2
+        while (if generic { 2 * (p_i - 1) } else { p_i - 1 }) <= max_t {
+            let xi_degree = if generic { 2 * (p_i - 1) } else { p_i - 1 };
+            let mut p_j = 1;
+            let mut j = 0;
+            while j < profile.get_p_part(i as usize - 1) && xi_degree * p_j <= max_t {
+                let t = xi_degree * p_j;
+                if let Some(idx) = add(format!("h_{{{i},{j}}}"), 1, t, i, generic) {
+                    h_index.insert((i as u32, j), idx);
+                }
+                if generic && t * p_ <= max_t {
+                    add(format!("b_{{{i},{j}}}"), 2, t * p_, p_ * i, false);
+                }
+                j += 1;
+                p_j *= p_;
+            }
+            i += 1;
+            p_i *= p_;
+        }
+
+        if generic {
+            // a_i is dual to tau_i, which has degree 2p^i - 1
+            let mut p_i = 1;
+            let mut i = 0;
+            while 2 * p_i - 1 <= max_t {
+                if profile.q_part & (1 << i) != 0 {
+                    add(format!("a_{i}"), 1, 2 * p_i - 1, i + 1, false);
+                }
+                i += 1;
+                p_i *= p_;
+            }
+        }
+
+        let mut result = Self {
+            p,
+            max,
+            generators,
+            h_index,
+            basis: HashMap::new(),
+        };
+        result.compute_basis();
+        result
+    }
+
+    fn compute_basis(&mut self) {
+        fn recurse(
+            generators: &[MayGenerator],
+            max: Bidegree,
+            idx: usize,
+            degree: MultiDegree<3>,
+            exponents: &mut Vec<u32>,
+            basis: &mut HashMap<MultiDegree<3>, Vec<Vec<u32>>>,
+        ) {
+            if idx == generators.len() {
+                basis.entry(degree).or_default().push(exponents.clone());
+                return;
+            }
+            let generator = &generators[idx];
+            let mut degree = degree;
+            let mut e = 0;
+            loop {
+                exponents[idx] = e;
+                recurse(generators, max, idx + 1, degree, exponents, basis);
+                degree = degree + generator.degree;
+                e += 1;
+                if degree.n() > max.n() || degree.s() > max.s() + 1 || (generator.exterior && e > 1)
+                {
+                    break;
+                }
+            }
+            exponents[idx] = 0;
+        }
+
+        let mut exponents = vec![0; self.generators.len()];
+        recurse(
+            &self.generators,
+            self.max,
+            0,
+            MultiDegree::zero(),
+            &mut exponents,
+            &mut self.basis,
+        );
+        for monomials in self.basis.values_mut() {
+            monomials.sort_unstable();
+        }
+    }
+
+    pub fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
+    pub fn generators(&self) -> &[MayGenerator] {
+        &self.generators
+    }
+
+    /// The monomials spanning the $E_1$ page in degree `b`, as exponent vectors indexed by
+    /// [`MayE1::generators`].
+    pub fn basis(&self, b: MultiDegree<3>) -> &[Vec<u32>] {
+        self.basis.get(&b).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn dimension(&self, b: MultiDegree<3>) -> usize {
+        self.basis(b).len()
+    }
+
+    pub fn monomial_to_string(&self, monomial: &[u32]) -> String {
+        let mut result = String::new();
+        for (generator, &e) in self.generators.iter().zip(monomial) {
+            if e == 0 {
+                continue;
+            }
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            result.push_str(&generator.name);
+            if e > 1 {
+                write!(result, "^{e}").unwrap();
+            }
+        }
+        if result.is_empty() {
+            result.push('1');
+        }
+        result
+    }
+
+    /// The $d_1$ differential of the basis element `idx` in degree `b`. This returns `None` at odd
+    /// primes, or if the target is out of range.
+    pub fn d1(&self, b: MultiDegree<3>, idx: usize) -> Option<FpVector> {
+        if self.p != 2 || b.s() > self.max.s() {
+            return None;
+        }
+        let target = b + MultiDegree::new([-1, 1, 0]);
+        let target_basis = self.basis(target);
+        let mut result = FpVector::new(self.p, target_basis.len());
+
+        let monomial = &self.basis(b)[idx];
+        for (&(i, j), &g) in &self.h_index {
+            if monomial[g].is_multiple_of(2) {
+                continue;
+            }
+            for k in 1..i {
+                let (Some(&x), Some(&y)) =
+                    (self.h_index.get(&(i - k, k + j)), self.h_index.get(&(k, j)))
+                else {
+                    continue;
+                };
+                let mut term = monomial.clone();
+                term[g] -= 1;
+                term[x] += 1;
+                term[y] += 1;
+                let idx = target_basis.binary_search(&term).unwrap();
+                result.add_basis_element(idx, 1);
+            }
+        }
+        Some(result)
+    }
+
+    /// The May spectral sequence with the $E_1$ page, and at the prime $2$ also the $d_1$
+    /// differentials.
+    pub fn to_sseq(&self) -> Sseq<3, May> {
+        let mut sseq = Sseq::new(self.p);
+        for (&b, monomials) in &self.basis {
+            sseq.set_dimension(b, monomials.len());
+        }
+        for &b in self.basis.keys() {
+            let target = b + MultiDegree::new([-1, 1, 0]);
+            if !sseq.defined(target) {
+                continue;
+            }
+            for idx in 0..self.dimension(b) {
+                let Some(d1) = self.d1(b, idx) else {
+                    continue;
+                };
+                let mut source = FpVector::new(self.p, self.dimension(b));
+                source.set_entry(idx, 1);
+                sseq.add_differential_justified(
+                    1,
+                    &MultiDegreeElement::new(b, source),
+                    d1.as_slice(),
+                    Justification::Computed {
+                        method: "the May spectral sequence".to_string(),
+                    },
+                );
+            }
+        }
+        sseq.update();
+        sseq
+    }
+}
+
+/// The dimension of the $E_r$ page in bidegree `b`, summed over all May filtrations. If `r` is
+/// larger than the last page computed in some degree, the last page is used instead.
+pub fn page_dimension(sseq: &Sseq<3, May>, r: i32, b: Bidegree) -> usize {
+    let (min, max) = (sseq.min().coords()[2], sseq.max().coords()[2]);
+    (min..=max)
+        .filter_map(|u| {
+            let degree = MultiDegree::new([b.n(), b.s(), u]);
+            sseq.defined(degree).then(|| {
+                let pages = sseq.page_data(degree);
+                pages
+                    .get(r)
+                    .unwrap_or_else(|| pages.last().unwrap())
+                    .dimension()
+            })
+        })
+        .sum()
+}
+
+/// A bidegree where the $E_r$ page of the May spectral sequence is compared with $\Ext$.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MayComparison {
+    pub degree: Bidegree,
+    /// The dimension of the $E_r$ page
+    pub may: usize,
+    /// The dimension of $\Ext$ computed by the resolution
+    pub ext: usize,
+}
+
+/// Compare the $E_r$ page of the May spectral sequence with $\Ext$ as computed by `res`, which
+/// should be a resolution of $\mathbb{F}_p$ over the algebra used to construct the $E_1$ page. This
+/// returns the comparison in every bidegree in the range `max` that has been computed by `res`, in
+/// order of increasing stem.
+pub fn compare_with_resolution<CC: FreeChainComplex>(
+    sseq: &Sseq<3, May>,
+    r: i32,
+    res: &CC,
+    max: Bidegree,
+) -> Vec<MayComparison> {
+    res.iter_stem()
+        .filter(|b| b.n() <= max.n() && b.s() <= max.s())
+        .map(|b| MayComparison {
+            degree: b,
+            may: page_dimension(sseq, r, b),
+            ext: res.number_of_gens_in_bidegree(b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{chain_complex::ChainComplex, utils::construct};
+
+    fn may_e1(spec: &str, max: Bidegree) -> (crate::utils::QueryModuleResolution, MayE1) {
+        let res = construct(spec, None).unwrap();
+        res.compute_through_stem(max);
+        let algebra = res.algebra();
+        let e1 = MayE1::new((&*algebra).try_into().unwrap(), max);
+        (res, e1)
+    }
+
+    #[test]
+    fn test_generators() {
+        let max = Bidegree::n_s(12, 2);
+        let mut output = String::new();
+        for spec in ["S_2", "S_3", "S_2@A(1)"] {
+            let (_, e1) = may_e1(spec, max);
+            let generators = e1
+                .generators()
+                .iter()
+                .map(|g| format!("{} {}", g.name, g.degree))
+                .join(", ");
+            writeln!(output, "{spec}: {generators}").unwrap();
+        }
+        expect![[r#"
+            S_2: h_{1,0} (0, 1, 1), h_{1,1} (1, 1, 1), h_{1,2} (3, 1, 1), h_{1,3} (7, 1, 1), h_{2,0} (2, 1, 2), h_{2,1} (5, 1, 2), h_{2,2} (11, 1, 2), h_{3,0} (6, 1, 3)
+            S_3: h_{1,0} (3, 1, 1), b_{1,0} (10, 2, 3), h_{1,1} (11, 1, 1), a_0 (0, 1, 1), a_1 (4, 1, 2)
+            S_2@A(1): h_{1,0} (0, 1, 1), h_{1,1} (1, 1, 1), h_{2,0} (2, 1, 2)
+        "#]]
+        .assert_eq(&output);
+    }
+
+    #[rstest]
+    #[case("S_2", 1)]
+    #[case("S_2", 2)]
+    #[case("S_3", 1)]
+    #[case("S_2@A(2)", 2)]
+    fn test_upper_bound(#[case] spec: &str, #[case] r: i32) {
+        let max = Bidegree::n_s(20, 6);
+        let (res, e1) = may_e1(spec, max);
+        let sseq = e1.to_sseq();
+        for c in compare_with_resolution(&sseq, r, &res, max) {
+            assert!(c.may >= c.ext, "{c:?}");
+        }
+    }
+
+    /// The differences between the $E_2$ page and Ext in this range come from $d_2$ differentials
+    /// such as $d_2(b_{2, 0}) = h_1^3 + h_0^2 h_2$ and $d_2(h_0(1)) = h_0 h_2^2$.
+    #[test]
+    fn test_e2() {
+        let max = Bidegree::n_s(5, 4);
+        let (res, e1) = may_e1("S_2", max);
+        let sseq = e1.to_sseq();
+        let output = compare_with_resolution(&sseq, 2, &res, max)
+            .into_iter()
+            .filter(|c| c.may != c.ext)
+            .map(|c| format!("{}: {} vs {}", c.degree, c.may, c.ext))
+            .join("\n");
+        expect![[r#"
+            (3, 3): 2 vs 1
+            (3, 4): 1 vs 0
+            (4, 2): 1 vs 0
+            (4, 3): 1 vs 0
+            (4, 4): 2 vs 0
+            (5, 2): 1 vs 0
+            (5, 3): 3 vs 0
+            (5, 4): 3 vs 0"#]]
+        .assert_eq(&output);
+    }
+}
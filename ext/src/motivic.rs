@@ -0,0 +1,580 @@
+//! Ext over the motivic Steenrod algebras.
+//!
+//! The motivic Steenrod algebras $\mathcal{A}^\mathbb{C}$ and $\mathcal{A}^\mathbb{R}$ (see
+//! [`MotivicMilnorAlgebra`]) are trigraded, but the resolution code only sees a single internal
+//! degree. We use the motivic degree $D = t + w$ as the internal degree, so that $\tau$ and
+//! $\mathrm{Sq}^1$ both lie in degree $1$ and every degree is finite dimensional. This module
+//! recovers the topological degree $t$ and the weight $w$ of the generators of a minimal
+//! resolution and assembles them into motivic Ext groups.
+//!
+//! Resolving the motivic cohomology of a point $\mathbb{M}_2$ (the module `M2`), the generators of
+//! the minimal resolution compute $\Ext_{\mathcal{A}^\mathbb{C}/\tau}(\mathbb{F}_2, \mathbb{F}_2)$
+//! by a change of rings, and they satisfy the Chow bound $2w \leq t$. Over $\mathbb{C}$, the
+//! $\tau$-Bockstein [`tau_bockstein`] then computes $\Ext_{\mathcal{A}^\mathbb{C}}(\mathbb{M}_2,
+//! \mathbb{M}_2)$ as a sum of free and $\tau$-torsion $\mathbb{F}_2[\tau]$-modules. Over both
+//! $\mathbb{C}$ and $\mathbb{R}$, [`coefficient_ext`] computes the dimensions of
+//! $\Ext_{\mathcal{A}}(M, \mathbb{M}_2)$ directly from the cochain complex, which over
+//! $\mathbb{R}$ takes the place of the $\rho$-Bockstein. The generators are also equipped with the
+//! products by the $h_i$ by [`GeneratorWeights::filtration_one_products`], which split by weight.
+//!
+//! Throughout, motivic degrees are written as `(n, s, w)` where $n = t - s$ is the stem. With this
+//! convention $\tau$ lies in degree $(0, 0, -1)$, and $h_1$ in degree $(1, 1, 1)$.
+
+use std::{collections::HashMap, fmt};
+
+use algebra::{
+    Algebra, MotivicBase, MotivicMilnorAlgebra,
+    module::{Module, MotivicCoefficients},
+};
+use bivec::BiVec;
+use fp::{matrix::Matrix, prime::TWO, vector::FpVector};
+use itertools::Itertools;
+use once::MultiIndexed;
+use sseq::{
+    Adams, MotivicAdams, Product, Sseq,
+    coordinates::{Bidegree, MultiDegree},
+};
+
+use crate::chain_complex::FreeChainComplex;
+
+/// The largest weight of a generator in topological degree `t`, given by the Chow bound. The bound
+/// is relative to the minimal degree of the module to allow for shifts.
+fn max_weight(min_degree: i32, t: i32) -> i32 {
+    (t - min_degree).max(0) / 2
+}
+
+/// The (library) stem and homological degree through which a resolution has to be computed so that
+/// [`tau_bockstein`] is complete in the topological range `max`.
+///
+/// This accounts for the weights of the generators, as well as the fact that the $\tau$-Bockstein
+/// at $s$ involves the generators in homological degree $s + 1$.
+pub fn resolution_bound(max: Bidegree) -> Bidegree {
+    Bidegree::n_s(max.n() + (max.n() + max.s() + 1) / 2, max.s() + 1)
+}
+
+/// The weights of the generators of a minimal resolution over a motivic Steenrod algebra.
+///
+/// The resolution records the weight of each generator when it is added (see
+/// [`MuFreeModule::generator_weight`](algebra::module::MuFreeModule::generator_weight)), namely the
+/// weight of its image in the module being resolved in homological degree $0$, and the weight of
+/// (any term of) its boundary otherwise. This relies on the module being bigraded, which is
+/// recorded by [`Module::basis_element_weight`]. This collects them by topological degree.
+pub struct GeneratorWeights {
+    base: MotivicBase,
+    min_degree: i32,
+    /// s -> internal degree -> generator index -> weight
+    weights: Vec<BiVec<Vec<i32>>>,
+}
+
+impl GeneratorWeights {
+    /// Collect the weights of all generators of `res` that have been computed so far.
+    pub fn new(res: &impl FreeChainComplex<Algebra = MotivicMilnorAlgebra>) -> Self {
+        let base = res.algebra().base();
+        let min_degree = res.min_degree();
+
+        let weights = (0..res.next_homological_degree())
+            .map(|s| {
+                let module = res.module(s);
+                let mut row = BiVec::new(min_degree);
+                for t in min_degree..=module.max_computed_degree() {
+                    row.push(
+                        (0..module.number_of_gens_in_degree(t))
+                            .map(|idx| module.generator_weight(t, idx))
+                            .collect(),
+                    );
+                }
+                row
+            })
+            .collect();
+
+        Self {
+            base,
+            min_degree,
+            weights,
+        }
+    }
+
+    pub fn base(&self) -> MotivicBase {
+        self.base
+    }
+
+    /// The weight of the generator `idx` in homological degree `s` and internal degree `degree`.
+    pub fn weight(&self, s: i32, degree: i32, idx: usize) -> i32 {
+        self.weights[s as usize][degree][idx]
+    }
+
+    /// The motivic degree `(n, s, w)` of the generator `idx` in homological degree `s` and internal
+    /// degree `degree`.
+    pub fn motivic_degree(&self, s: i32, degree: i32, idx: usize) -> MultiDegree<3> {
+        let w = self.weight(s, degree, idx);
+        MultiDegree::new([degree - w - s, s, w])
+    }
+
+    /// Whether all generators in homological degree `s` and topological degree `t` are known.
+    pub fn complete(&self, s: i32, t: i32) -> bool {
+        s >= 0
+            && self
+                .weights
+                .get(s as usize)
+                .is_some_and(|row| t + max_weight(self.min_degree, t) <= row.max_degree())
+    }
+
+    /// The generators in homological degree `s` and topological degree `t`, as pairs of internal
+    /// degree and index.
+    fn generators(&self, s: i32, t: i32) -> Vec<(i32, usize)> {
+        let row = &self.weights[s as usize];
+        (0..=max_weight(self.min_degree, t))
+            .flat_map(|w| {
+                let degree = t + w;
+                row.get(degree).into_iter().flat_map(move |gens| {
+                    gens.iter()
+                        .enumerate()
+                        .filter(move |&(_, &w_)| w_ == w)
+                        .map(move |(idx, _)| (degree, idx))
+                })
+            })
+            .collect()
+    }
+
+    /// The generators of the resolution as a trigraded spectral sequence. This only includes the
+    /// degrees whose generators are all known.
+    pub fn to_sseq(&self) -> Sseq<3, MotivicAdams> {
+        let mut sseq = Sseq::new(TWO);
+        for (s, row) in self.weights.iter().enumerate() {
+            let s = s as i32;
+            for (degree, gens) in row.iter_enum() {
+                for &w in gens {
+                    let b = MultiDegree::new([degree - w - s, s, w]);
+                    if self.complete(s, b.t()) {
+                        sseq.set_dimension(b, sseq.get_dimension(b).unwrap_or(0) + 1);
+                    }
+                }
+            }
+        }
+        sseq
+    }
+
+    /// The products by the default filtration one operations of the algebra, e.g. $h_0$, $h_1$ and
+    /// $h_2$, between the generators in [`to_sseq`](Self::to_sseq). These preserve the weight up
+    /// to the weight of the operation, so the products of the underlying resolution split by
+    /// weight.
+    pub fn filtration_one_products<CC: FreeChainComplex>(
+        &self,
+        res: &CC,
+    ) -> Vec<(String, Product<3>)> {
+        let algebra = res.algebra();
+        algebra
+            .default_filtration_one_products()
+            .into_iter()
+            .map(|(name, op_deg, op_idx)| {
+                let op_weight = algebra.basis_element_weight(op_deg, op_idx);
+                let shift = MultiDegree::new([op_deg - op_weight - 1, 1, op_weight]);
+                let matrices = MultiIndexed::new();
+                for (s, (row, target_row)) in self.weights.iter().tuple_windows().enumerate() {
+                    let s = s as i32;
+                    for (degree, gens) in row.iter_enum() {
+                        let Some(target_gens) = target_row.get(degree + op_deg) else {
+                            continue;
+                        };
+                        let Some(product) =
+                            res.filtration_one_product(op_deg, op_idx, Bidegree::s_t(s, degree))
+                        else {
+                            continue;
+                        };
+                        for w in gens.iter().copied().unique() {
+                            let source = MultiDegree::new([degree - w - s, s, w]);
+                            let target = source + shift;
+                            if !self.complete(s, source.t()) || !self.complete(s + 1, target.t()) {
+                                continue;
+                            }
+                            let rows: Vec<Vec<u32>> = gens
+                                .iter()
+                                .positions(|&w_| w_ == w)
+                                .map(|i| {
+                                    target_gens
+                                        .iter()
+                                        .positions(|&w_| w_ == target.coords()[2])
+                                        .map(|j| product[i][j])
+                                        .collect()
+                                })
+                                .collect();
+                            matrices.insert(source, Matrix::from_vec(TWO, &rows));
+                        }
+                    }
+                }
+                (
+                    name,
+                    Product {
+                        b: shift,
+                        left: true,
+                        matrices,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// A summand of $\Ext_{\mathcal{A}^\mathbb{C}}(\mathbb{M}_2, \mathbb{M}_2)$ as an
+/// $\mathbb{F}_2[\tau]$-module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MotivicSummand {
+    /// The degree of the generator of the summand.
+    pub degree: MultiDegree<3>,
+    /// The summand is $\mathbb{F}_2[\tau]/\tau^k$ if this is `Some(k)`, and free otherwise.
+    pub torsion: Option<u32>,
+}
+
+impl fmt::Display for MotivicSummand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [n, s, w] = self.degree.coords();
+        match self.torsion {
+            None => write!(f, "({n}, {s}, {w}): F_2[tau]"),
+            Some(1) => write!(f, "({n}, {s}, {w}): F_2"),
+            Some(k) => write!(f, "({n}, {s}, {w}): F_2[tau]/tau^{k}"),
+        }
+    }
+}
+
+/// Compute $\Ext_{\mathcal{A}^\mathbb{C}}(\mathbb{M}_2, \mathbb{M}_2)$ in the topological range
+/// `max` by the $\tau$-Bockstein, given a minimal resolution of $\mathbb{M}_2$.
+///
+/// The cochain complex $\Hom_{\mathcal{A}^\mathbb{C}}(F_s, \mathbb{M}_2)$ is a free
+/// $\mathbb{F}_2[\tau]$-module on the duals $\phi_g$ of the generators, and $\delta \phi_g$ is the
+/// sum of $\tau^k \phi_{g'}$ over the terms $\tau^k g$ of the boundaries $d g'$. Since $\delta$
+/// preserves the topological degree, we can compute the cohomology in each topological degree
+/// separately, which we do by column reduction with the generators ordered by weight. A summand
+/// is returned for every pair of topological degrees `(n, s)` within `max` whose generators are
+/// all known (see [`resolution_bound`]), in increasing order of stem.
+pub fn tau_bockstein<CC>(
+    res: &CC,
+    weights: &GeneratorWeights,
+    max: Bidegree,
+) -> anyhow::Result<Vec<MotivicSummand>>
+where
+    CC: FreeChainComplex<Algebra = MotivicMilnorAlgebra>,
+{
+    if weights.base() != MotivicBase::Complex {
+        return Err(anyhow::anyhow!(
+            "The tau-Bockstein is only implemented for the C-motivic Steenrod algebra"
+        ));
+    }
+    let algebra = res.algebra();
+
+    let mut summands = Vec::new();
+    for t in res.min_degree()..=max.n() + max.s() {
+        let s_range = 0.max(t - max.n())..=max.s().min(t - res.min_degree());
+        if s_range.is_empty() {
+            continue;
+        }
+        // The cohomology at s involves the generators in homological degrees s - 1 to s + 1
+        let min_s = 0.max(s_range.start() - 1);
+        let max_s = *s_range.end();
+        if !(min_s..=max_s + 1).all(|s| weights.complete(s, t)) {
+            continue;
+        }
+
+        // Generators ordered by decreasing weight
+        let gens: Vec<Vec<(i32, usize, i32)>> = (min_s..=max_s + 1)
+            .map(|s| {
+                let mut gens: Vec<_> = weights
+                    .generators(s, t)
+                    .into_iter()
+                    .map(|(degree, idx)| (degree, idx, weights.weight(s, degree, idx)))
+                    .collect();
+                gens.sort_by_key(|&(_, _, w)| -w);
+                gens
+            })
+            .collect();
+        let gens = BiVec::from_vec(min_s, gens);
+
+        // For each s, the pivot rows in s + 1 (or `None` if the column reduces to zero).
+        let mut pivots: BiVec<Vec<Option<usize>>> = BiVec::new(min_s);
+        for s in min_s..=max_s {
+            let columns = &gens[s];
+            let rows = &gens[s + 1];
+            let source = res.module(s);
+            let d = res.differential(s + 1);
+
+            let mut matrix: Vec<FpVector> = columns
+                .iter()
+                .map(|&(degree, idx, w)| {
+                    let mut column = FpVector::new(TWO, rows.len());
+                    for (i, &(row_degree, row_idx, row_w)) in rows.iter().enumerate() {
+                        let k = row_w - w;
+                        if k <= 0 || row_degree - degree != k {
+                            continue;
+                        }
+                        let op_idx = algebra.coefficient_index(k as u32, 0);
+                        let entry = source.operation_generator_to_index(k, op_idx, degree, idx);
+                        column.set_entry(i, d.output(row_degree, row_idx).entry(entry));
+                    }
+                    column
+                })
+                .collect();
+
+            let mut low_to_column: Vec<Option<usize>> = vec![None; rows.len()];
+            let mut column_pivots = Vec::with_capacity(columns.len());
+            for j in 0..matrix.len() {
+                let pivot = loop {
+                    let Some((low, _)) = matrix[j].iter_nonzero().last() else {
+                        break None;
+                    };
+                    match low_to_column[low] {
+                        Some(j_) => {
+                            let (earlier, current) = matrix.split_at_mut(j);
+                            current[0].add(&earlier[j_], 1);
+                        }
+                        None => {
+                            low_to_column[low] = Some(j);
+                            break Some(low);
+                        }
+                    }
+                };
+                column_pivots.push(pivot);
+            }
+            pivots.push(column_pivots);
+        }
+
+        for s in s_range {
+            let hit: Vec<bool> = if s == 0 {
+                vec![false; gens[0].len()]
+            } else {
+                let mut hit = vec![false; gens[s].len()];
+                for (j, &pivot) in pivots[s - 1].iter().enumerate() {
+                    if let Some(i) = pivot {
+                        hit[i] = true;
+                        let (_, _, w_source) = gens[s - 1][j];
+                        let (degree, idx, w) = gens[s][i];
+                        summands.push(MotivicSummand {
+                            degree: weights.motivic_degree(s, degree, idx),
+                            torsion: Some((w - w_source) as u32),
+                        });
+                    }
+                }
+                hit
+            };
+            for (i, &(degree, idx, _)) in gens[s].iter().enumerate() {
+                if !hit[i] && pivots[s][i].is_none() {
+                    summands.push(MotivicSummand {
+                        degree: weights.motivic_degree(s, degree, idx),
+                        torsion: None,
+                    });
+                }
+            }
+        }
+    }
+    summands.sort_by_key(|x| {
+        let [n, s, w] = x.degree.coords();
+        (n, s, w)
+    });
+    Ok(summands)
+}
+
+/// The (library) stem and homological degree through which a resolution has to be computed so that
+/// [`coefficient_ext`] is complete in the topological range `max`, down to the coweight
+/// `max_coweight`.
+///
+/// Over $\mathbb{R}$, a cochain in topological degree $t$ and weight $w$ involves the generators
+/// in topological degrees up to $2(t - w)$, which is where the coweight comes in.
+pub fn coefficient_resolution_bound(max: Bidegree, max_coweight: i32) -> Bidegree {
+    let t = (max.n() + max.s()).max(2 * (max.s() + 1 + max_coweight));
+    Bidegree::n_s(t + t / 2, max.s() + 1)
+}
+
+/// Compute $\Ext_{\mathcal{A}}(M, \mathbb{M}_2)$ in the topological range `max`, given a minimal
+/// resolution of $M$ over a motivic Steenrod algebra $\mathcal{A}$.
+///
+/// This works over both $\mathbb{C}$ and $\mathbb{R}$, by computing the cohomology of the cochain
+/// complex $\Hom_{\mathcal{A}}(F_s, \mathbb{M}_2)$ in each degree. A cochain of degree $(t, w)$
+/// sends a generator $g$ to a multiple of $\tau^a \rho^b$, where $b = t_g - t$ and $a = w_g - w -
+/// b$. Over $\mathbb{R}$ the groups are nonzero in arbitrarily negative weights (e.g.
+/// $\tau^{2k}$ in degree $(0, 0, -2k)$), so we only compute the weights $w$ with coweight $n - w$
+/// at most `max_coweight`, and for the same reason the stems down to `-max_coweight` (relative to
+/// the minimal degree of $M$). The result only includes the degrees whose cochains are all known (see
+/// [`coefficient_resolution_bound`]).
+pub fn coefficient_ext<CC>(
+    res: &CC,
+    weights: &GeneratorWeights,
+    max: Bidegree,
+    max_coweight: i32,
+) -> anyhow::Result<Sseq<3, MotivicAdams>>
+where
+    CC: FreeChainComplex<Algebra = MotivicMilnorAlgebra>,
+{
+    let coefficients = MotivicCoefficients::new(res.algebra());
+    let min_degree = res.min_degree();
+
+    // The largest topological degree of a generator that a cochain in degree (t, w) can be nonzero
+    // on.
+    let max_t = |t: i32, w: i32| match weights.base() {
+        MotivicBase::Complex => t,
+        MotivicBase::Real => 2 * (t - w) - min_degree,
+    };
+
+    // The basis of the cochains in degree (s, t, w), as pairs of internal degree and index of the
+    // generator. The corresponding power of rho is `t_g - t`.
+    let basis = |s: i32, t: i32, w: i32| -> Vec<(i32, usize)> {
+        (t.max(min_degree)..=max_t(t, w))
+            .flat_map(|t_g| {
+                weights
+                    .generators(s, t_g)
+                    .into_iter()
+                    .filter(move |&(degree, idx)| weights.weight(s, degree, idx) - w >= t_g - t)
+            })
+            .collect()
+    };
+
+    // The rank of the coboundary from degree (s, t, w) to (s + 1, t, w)
+    let rank = |s: i32, t: i32, w: i32| -> usize {
+        let rows = basis(s, t, w);
+        let columns = basis(s + 1, t, w);
+        let row_of: HashMap<(i32, usize), usize> =
+            rows.iter().enumerate().map(|(i, &g)| (g, i)).collect();
+
+        let source = res.module(s);
+        let d = res.differential(s + 1);
+        let internal_degree = t + w;
+
+        let mut matrix = Matrix::new(TWO, rows.len(), columns.len());
+        for (j, &(degree, idx)) in columns.iter().enumerate() {
+            let rho = (degree - weights.weight(s + 1, degree, idx) - t) as usize;
+            let mut result = FpVector::new(TWO, coefficients.dimension(degree - internal_degree));
+            for (k, c) in d.output(degree, idx).iter_nonzero() {
+                let opgen = source.index_to_op_gen(degree, k);
+                let Some(&i) = row_of.get(&(opgen.generator_degree, opgen.generator_index)) else {
+                    continue;
+                };
+                let (gen_degree, gen_idx) = rows[i];
+                let gen_rho = (gen_degree - weights.weight(s, gen_degree, gen_idx) - t) as usize;
+                result.set_to_zero();
+                coefficients.act_on_basis(
+                    result.as_slice_mut(),
+                    c,
+                    opgen.operation_degree,
+                    opgen.operation_index,
+                    gen_degree - internal_degree,
+                    gen_rho,
+                );
+                matrix.row_mut(i).add_basis_element(j, result.entry(rho));
+            }
+        }
+        matrix.row_reduce()
+    };
+
+    // Over R, the negative stems contain the powers of rho, e.g. rho^k in degree (-k, 0, -k).
+    let min_n = match weights.base() {
+        MotivicBase::Complex => min_degree,
+        MotivicBase::Real => min_degree - max_coweight,
+    };
+
+    let mut sseq = Sseq::new(TWO);
+    let mut ranks: HashMap<(i32, i32, i32), usize> = HashMap::new();
+    for n in min_n..=max.n() {
+        for s in 0..=max.s() {
+            let t = n + s;
+            for w in n - max_coweight..=max_weight(min_degree, t) {
+                let complete = (0.max(s - 1)..=s + 1)
+                    .all(|s_| (t..=max_t(t, w)).all(|t_| weights.complete(s_, t_)));
+                if !complete {
+                    continue;
+                }
+                let mut rank = |s| *ranks.entry((s, t, w)).or_insert_with(|| rank(s, t, w));
+                let boundaries = if s > 0 { rank(s - 1) } else { 0 };
+                let dimension = basis(s, t, w).len() - rank(s) - boundaries;
+                sseq.set_dimension(MultiDegree::new([n, s, w]), dimension);
+            }
+        }
+    }
+    Ok(sseq)
+}
+
+/// Collapse a trigraded spectral sequence to a bigraded one by summing over the weights, e.g. for
+/// charting. The classes in each bidegree are ordered by increasing weight, and the products are
+/// collapsed accordingly. The products in a bidegree are dropped unless they are known in every
+/// weight.
+pub fn forget_weights(
+    sseq: &Sseq<3, MotivicAdams>,
+    products: &[(String, Product<3>)],
+) -> (Sseq<2, Adams>, Vec<(String, Product<2>)>) {
+    // (n, s) -> the degrees in increasing order of weight
+    let mut degrees: HashMap<Bidegree, Vec<MultiDegree<3>>> = HashMap::new();
+    for b in sseq.iter_degrees() {
+        degrees
+            .entry(Bidegree::n_s(b.n(), b.s()))
+            .or_default()
+            .push(b);
+    }
+    for list in degrees.values_mut() {
+        list.sort_by_key(|b| b.coords()[2]);
+    }
+    // The offset of the classes of a degree in the collapsed bidegree
+    let offset = |b: MultiDegree<3>| -> usize {
+        degrees[&Bidegree::n_s(b.n(), b.s())]
+            .iter()
+            .take_while(|b_| b_.coords()[2] < b.coords()[2])
+            .map(|&b_| sseq.dimension(b_))
+            .sum()
+    };
+
+    let mut result = Sseq::new(TWO);
+    for (&b, list) in &degrees {
+        result.set_dimension(b, list.iter().map(|&b_| sseq.dimension(b_)).sum());
+    }
+
+    let products = products
+        .iter()
+        .map(|(name, product)| {
+            let shift = Bidegree::n_s(product.b.n(), product.b.s());
+            let matrices = MultiIndexed::new();
+            'bidegree: for (&b, list) in &degrees {
+                let Some(target_list) = degrees.get(&(b + shift)) else {
+                    continue;
+                };
+                let mut matrix = Matrix::new(TWO, result.dimension(b), result.dimension(b + shift));
+                for &source in list {
+                    if sseq.dimension(source) == 0 {
+                        continue;
+                    }
+                    let target = source + product.b;
+                    if !target_list.contains(&target) {
+                        continue;
+                    }
+                    let Some(block) = product.matrices.get(source) else {
+                        continue 'bidegree;
+                    };
+                    let (row_offset, column_offset) = (offset(source), offset(target));
+                    for (i, row) in block.iter().enumerate() {
+                        for (j, c) in row.iter_nonzero() {
+                            matrix
+                                .row_mut(row_offset + i)
+                                .set_entry(column_offset + j, c);
+                        }
+                    }
+                }
+                matrices.insert(b, matrix);
+            }
+            (
+                name.clone(),
+                Product {
+                    b: shift,
+                    left: product.left,
+                    matrices,
+                },
+            )
+        })
+        .collect();
+
+    (result, products)
+}
+
+/// The summands of $\Ext_{\mathcal{A}^\mathbb{C}}(\mathbb{M}_2, \mathbb{M}_2)$ as a trigraded
+/// spectral sequence, where each summand contributes a single class in the degree of its generator.
+pub fn summands_to_sseq(summands: &[MotivicSummand]) -> Sseq<3, MotivicAdams> {
+    let mut sseq = Sseq::new(TWO);
+    for summand in summands {
+        let b = summand.degree;
+        sseq.set_dimension(b, sseq.get_dimension(b).unwrap_or(0) + 1);
+    }
+    sseq
+}
@@ -1,26 +1,27 @@
 //! This module implements [Nassau's algorithm](https://arxiv.org/abs/1910.04063).
 //!
-//! The main export is the [`Resolution`] object, which is a resolution of the sphere at the prime 2
-//! using Nassau's algorithm. It aims to provide an API similar to
-//! [`resolution::Resolution`](crate::resolution::Resolution). From an API point of view, the main
-//! difference between the two is that our `Resolution` is a chain complex over [`MilnorAlgebra`]
-//! over [`SteenrodAlgebra`](algebra::SteenrodAlgebra).
+//! The main export is the [`Resolution`] object, which is a resolution of a Steenrod module using
+//! Nassau's algorithm. It aims to provide an API similar to
+//! [`resolution::Resolution`](crate::resolution::Resolution), and supports the same modules at all
+//! primes, as long as the algebra uses the Milnor basis. The algorithm only gives a speedup for
+//! bounded modules; unbounded modules are resolved with the trivial subalgebra, which amounts to
+//! the usual algorithm.
 //!
-//! To make use of this resolution in the example scripts, enable the `nassau` feature. This will
-//! cause [`utils::query_module`](crate::utils::query_module) to return the `Resolution` from this
-//! module instead of [`resolution`](crate::resolution). There is no formal polymorphism involved;
-//! the feature changes the return type of the function. While this is an incorrect use of features,
-//! we find that this the easiest way to make all scripts support both types of resolutions.
+//! To make use of this resolution in the example scripts, append `@nassau` to the module
+//! specification, e.g. `S_2@nassau`. This will cause [`utils::query_module`](crate::utils::query_module)
+//! to return the [`QueryModuleResolution::Nassau`](crate::utils::QueryModuleResolution::Nassau)
+//! variant, which wraps the `Resolution` from this module.
 
 use std::{
     fmt::Display,
     io,
     sync::{Arc, Mutex, mpsc},
+    time::Instant,
 };
 
 use algebra::{
     Algebra, combinatorics,
-    milnor_algebra::{MilnorAlgebra, PPartEntry},
+    milnor_algebra::{MilnorAlgebra, MilnorBasisElement, MilnorProfile, PPartEntry},
     module::{
         FreeModule, GeneratorData, Module, ZeroModule,
         homomorphism::{FreeModuleHomomorphism, FullModuleHomomorphism, ModuleHomomorphism},
@@ -30,7 +31,7 @@ use anyhow::anyhow;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use fp::{
     matrix::{AugmentedMatrix, Matrix},
-    prime::{TWO, ValidPrime},
+    prime::{Prime, ValidPrime},
     vector::{FpSlice, FpSliceMut, FpVector},
 };
 use itertools::Itertools;
@@ -39,23 +40,28 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator};
 
 use crate::{
     chain_complex::{AugmentedChainComplex, ChainComplex, FiniteChainComplex, FreeChainComplex},
-    save::{SaveDirectory, SaveKind},
+    progress::{ProgressEvent, StepStats},
+    save::{SaveDirectory, SaveKind, SaveManifest},
     utils::{LogWriter, parallel::ParallelGuard},
 };
 
-/// See [`resolution::SenderData`](../resolution/struct.SenderData.html). This differs by not having the `new` field.
+/// See [`resolution::SenderData`](../resolution/struct.SenderData.html).
 struct SenderData {
     b: Bidegree,
+    stats: Option<StepStats>,
     retry: bool,
+    started: bool,
     sender: mpsc::Sender<Self>,
 }
 
 impl SenderData {
-    pub(crate) fn send(b: Bidegree, sender: mpsc::Sender<Self>) {
+    pub(crate) fn send(b: Bidegree, stats: Option<StepStats>, sender: mpsc::Sender<Self>) {
         sender
             .send(Self {
                 b,
+                stats,
                 retry: false,
+                started: false,
                 sender: sender.clone(),
             })
             .unwrap()
@@ -66,7 +72,21 @@ impl SenderData {
         sender
             .send(Self {
                 b,
+                stats: None,
                 retry: true,
+                started: false,
+                sender: sender.clone(),
+            })
+            .unwrap()
+    }
+
+    pub(crate) fn send_started(b: Bidegree, sender: &mpsc::Sender<Self>) {
+        sender
+            .send(Self {
+                b,
+                stats: None,
+                retry: false,
+                started: true,
                 sender: sender.clone(),
             })
             .unwrap()
@@ -75,15 +95,35 @@ impl SenderData {
 
 const MAX_NEW_GENS: usize = 10;
 
+/// Get the underlying [`MilnorAlgebra`] of an algebra that is known to use the Milnor basis.
+fn milnor_algebra<A>(algebra: &A) -> &MilnorAlgebra
+where
+    for<'a> &'a A: TryInto<&'a MilnorAlgebra>,
+{
+    match algebra.try_into() {
+        Ok(algebra) => algebra,
+        Err(_) => unreachable!("Nassau's algorithm requires the Milnor basis"),
+    }
+}
+
 /// A Milnor subalgebra to be used in [Nassau's algorithm](https://arxiv.org/abs/1910.04063). This
 /// is equipped with an ordering of the signature as in Lemma 2.4 of the paper.
 ///
 /// To simplify implementation, we pick the ordering so that the (reverse) lexicographic ordering
 /// in Lemma 2.4 is just the (reverse) lexicographic ordering of the P parts. This corresponds to
 /// the ordering of $\mathcal{P}$ where $P^s_t < P^{s'}_t$ if $s < s'$).
+///
+/// At odd primes, the signature of an element additionally records which of the $Q_k$ in the
+/// subalgebra it involves. This is stored as a bitmask in an extra entry at the end of the
+/// signature, so that it is the most significant part of the ordering. For this to be compatible
+/// with the relation $P(R) Q_k = Q_k P(R) + \sum_i Q_{k + i} P(R - p^k e_i)$, we require that
+/// $Q_j$ is in the subalgebra whenever some $P^j_t$ is.
 #[derive(Clone)]
 struct MilnorSubalgebra {
+    p: ValidPrime,
     profile: Vec<u8>,
+    /// A bitmask of the $Q_k$ in the subalgebra. This is always zero at the prime 2.
+    q_part: u32,
 }
 
 impl MilnorSubalgebra {
@@ -91,37 +131,91 @@ impl MilnorSubalgebra {
     #[allow(dead_code)]
     const INFINITY: u8 = (std::mem::size_of::<PPartEntry>() * 4 - 1) as u8;
 
-    fn new(profile: Vec<u8>) -> Self {
-        Self { profile }
+    fn new(p: ValidPrime, profile: Vec<u8>, q_part: u32) -> Self {
+        Self { p, profile, q_part }
     }
 
     /// The algebra with trivial profile, corresponding to the trivial algebra.
-    fn zero_algebra() -> Self {
-        Self { profile: vec![] }
+    fn zero_algebra(p: ValidPrime) -> Self {
+        Self::new(p, vec![], 0)
+    }
+
+    fn generic(&self) -> bool {
+        self.p != 2
+    }
+
+    fn q(&self) -> i32 {
+        if self.generic() {
+            2 * self.p.as_i32() - 2
+        } else {
+            1
+        }
+    }
+
+    /// The number of entries in a signature. At odd primes, the last entry is the Q part.
+    fn signature_len(&self) -> usize {
+        self.profile.len() + usize::from(self.generic())
+    }
+
+    /// Intersect the subalgebra with the subalgebra of the Steenrod algebra given by `profile`.
+    /// At odd primes, this then shrinks the P part so that $Q_j$ is in the subalgebra whenever
+    /// some $P^j_t$ is.
+    fn restrict_to(&mut self, profile: &MilnorProfile) {
+        self.q_part &= profile.q_part;
+        let max_height = if self.generic() {
+            self.q_part.trailing_ones()
+        } else {
+            u32::MAX
+        };
+        for (i, entry) in self.profile.iter_mut().enumerate() {
+            *entry =
+                std::cmp::min(*entry as PPartEntry, profile.get_p_part(i)).min(max_height) as u8;
+        }
+        while self.profile.last() == Some(&0) {
+            self.profile.pop();
+        }
+    }
+
+    /// Whether this is a sub-Hopf algebra that contains $Q_j$ whenever it contains some $P^j_t$.
+    /// This is only meaningful at odd primes.
+    fn is_valid_generic(&self) -> bool {
+        let profile = MilnorProfile {
+            truncated: true,
+            q_part: self.q_part,
+            p_part: self.profile.iter().map(|&x| x as PPartEntry).collect(),
+        };
+        let max_height = self.q_part.trailing_ones();
+        profile.is_valid() && self.profile.iter().all(|&h| h as u32 <= max_height)
     }
 
     /// Computes the signature of an element
-    fn has_signature(&self, ppart: &[PPartEntry], signature: &[PPartEntry]) -> bool {
+    fn has_signature(&self, elt: &MilnorBasisElement, signature: &[PPartEntry]) -> bool {
         for (i, (&profile, &signature)) in self.profile.iter().zip(signature).enumerate() {
-            let ppart = ppart.get(i).copied().unwrap_or(0);
-            if ppart & ((1 << profile) - 1) != signature {
+            let ppart = elt.p_part.get(i).copied().unwrap_or(0);
+            let modulus = self.p.as_u32().pow(profile as u32);
+            let residue = if self.generic() {
+                ppart % modulus
+            } else {
+                ppart & (modulus - 1)
+            };
+            if residue != signature {
                 return false;
             }
         }
-        true
+        !self.generic() || elt.q_part & self.q_part == signature[self.profile.len()]
     }
 
     fn zero_signature(&self) -> Vec<PPartEntry> {
-        vec![0; self.profile.len()]
+        vec![0; self.signature_len()]
     }
 
     /// Give a list of basis elements in degree `degree` that has signature `signature`.
     ///
     /// This requires passing the algebra for borrow checker reasons.
-    fn signature_mask<'a>(
+    fn signature_mask<'a, A: Algebra>(
         &'a self,
         algebra: &'a MilnorAlgebra,
-        module: &'a FreeModule<MilnorAlgebra>,
+        module: &'a FreeModule<A>,
         degree: i32,
         signature: &'a [PPartEntry],
     ) -> impl Iterator<Item = usize> + 'a {
@@ -131,41 +225,38 @@ impl MilnorSubalgebra {
                       start: [offset],
                       end: _,
                   }| {
-                algebra
-                    .ppart_table(degree - gen_deg)
-                    .iter()
-                    .enumerate()
-                    .filter_map(move |(n, op)| {
-                        if self.has_signature(op, signature) {
-                            Some(offset + n)
-                        } else {
-                            None
-                        }
-                    })
+                let op_deg = degree - gen_deg;
+                (0..algebra.dimension(op_deg)).filter_map(move |n| {
+                    if self.has_signature(algebra.basis_element_from_index(op_deg, n), signature) {
+                        Some(offset + n)
+                    } else {
+                        None
+                    }
+                })
             },
         )
     }
 
     /// Get the matrix of a free module homomorphism when restricted to the subquotient given by
     /// the signature.
-    fn signature_matrix(
+    fn signature_matrix<A: Algebra>(
         &self,
-        hom: &FreeModuleHomomorphism<FreeModule<MilnorAlgebra>>,
+        algebra: &MilnorAlgebra,
+        hom: &FreeModuleHomomorphism<FreeModule<A>>,
         degree: i32,
         signature: &[PPartEntry],
     ) -> Matrix {
         let p = hom.prime();
         let source = hom.source();
         let target = hom.target();
-        let algebra = target.algebra();
         let target_degree = degree - hom.degree_shift();
 
         let target_mask: Vec<usize> = self
-            .signature_mask(&algebra, &target, degree - hom.degree_shift(), signature)
+            .signature_mask(algebra, &target, degree - hom.degree_shift(), signature)
             .collect();
 
         let source_mask: Vec<usize> = self
-            .signature_mask(&algebra, &source, degree, signature)
+            .signature_mask(algebra, &source, degree, signature)
             .collect();
 
         let mut scratch = FpVector::new(p, target.dimension(target_degree));
@@ -186,24 +277,83 @@ impl MilnorSubalgebra {
         SignatureIterator::new(self, degree)
     }
 
+    /// The degree of the smallest elements whose signature has entry `value` at `idx`.
+    fn signature_entry_degree(&self, idx: usize, value: PPartEntry) -> i32 {
+        if idx < self.profile.len() {
+            self.q() * combinatorics::xi_degrees(self.p)[idx] * value as i32
+        } else {
+            let tau_degrees = combinatorics::tau_degrees(self.p);
+            (0..PPartEntry::BITS)
+                .filter(|&k| (value >> k) & 1 == 1)
+                .map(|k| tau_degrees[k as usize])
+                .sum()
+        }
+    }
+
+    /// The value following `value` at entry `idx` of a signature, or `None` if `value` is the
+    /// largest possible value. The Q part runs through the subsets of `self.q_part` in increasing
+    /// order, which is also the order of increasing degree.
+    fn next_signature_entry(&self, idx: usize, value: PPartEntry) -> Option<PPartEntry> {
+        if idx < self.profile.len() {
+            let next = value + 1;
+            (next < self.p.as_u32().pow(self.profile[idx] as u32)).then_some(next)
+        } else {
+            let next = (value | !self.q_part).wrapping_add(1) & self.q_part;
+            (next != 0).then_some(next)
+        }
+    }
+
     fn top_degree(&self) -> i32 {
-        self.profile
+        let p_top: i32 = self
+            .profile
+            .iter()
+            .enumerate()
+            .map(|(idx, &entry)| {
+                self.signature_entry_degree(idx, self.p.as_u32().pow(entry as u32) - 1)
+            })
+            .sum();
+        p_top + self.signature_entry_degree(self.profile.len(), self.q_part)
+    }
+
+    /// The slope of the vanishing line of Ext over this subalgebra. This is the maximum slope of
+    /// the May generators, where $P^j_t$ contributes $p^{j + 1} |\xi_t| / 2$ and $Q_k$
+    /// contributes $|Q_k|$. At the prime 2 this is $2^j |\xi_t|$ since the associated graded
+    /// algebra is exterior instead of truncated polynomial.
+    fn vanishing_slope(&self) -> i32 {
+        let p = self.p.as_i32();
+        let xi_degrees = combinatorics::xi_degrees(self.p);
+        let tau_degrees = combinatorics::tau_degrees(self.p);
+
+        let p_slope = self
+            .profile
             .iter()
-            .map(|&entry| (1 << entry) - 1)
             .enumerate()
-            .map(|(idx, entry)| ((1 << (idx + 1)) - 1) * entry)
-            .sum()
+            .filter(|&(_, &entry)| entry > 0)
+            .map(|(idx, &entry)| p.pow(entry as u32) * self.q() * xi_degrees[idx] / 2);
+        let q_slope = (0..u32::BITS)
+            .filter(|&k| (self.q_part >> k) & 1 == 1)
+            .map(|k| tau_degrees[k as usize]);
+
+        p_slope.chain(q_slope).max().unwrap_or(0)
     }
 
-    fn optimal_for(b: Bidegree) -> Self {
+    /// Find the largest subalgebra in our sequence of subalgebras of `algebra` for which the
+    /// bidegree `b` lies in the vanishing region.
+    fn optimal_for(algebra: &MilnorAlgebra, b: Bidegree) -> Self {
         let b_is_in_vanishing_region = |subalgebra: &Self| {
-            let coeff = (1 << subalgebra.profile.len()) - 1;
-            b.t() >= coeff * (b.s() + 1) + subalgebra.top_degree()
+            b.t() >= subalgebra.vanishing_slope() * (b.s() + 1) + subalgebra.top_degree()
         };
-        SubalgebraIterator::new()
+        SubalgebraIterator::new(algebra.prime())
+            // If the algebra has a non-trivial profile, the restricted subalgebras eventually
+            // stabilize, so we have to stop once the subalgebras are too large to matter.
+            .take_while(|subalgebra| subalgebra.top_degree() <= b.t())
+            .map(|mut subalgebra| {
+                subalgebra.restrict_to(algebra.profile());
+                subalgebra
+            })
             .take_while(b_is_in_vanishing_region)
             .last()
-            .unwrap_or(Self::zero_algebra())
+            .unwrap_or_else(|| Self::zero_algebra(algebra.prime()))
     }
 
     fn to_bytes(&self, buffer: &mut impl io::Write) -> io::Result<()> {
@@ -213,10 +363,15 @@ impl MilnorSubalgebra {
         let len = self.profile.len();
         let zeros = [0; 8];
         let padding = len - ((len / 8) * 8);
-        buffer.write_all(&zeros[0..padding])
+        buffer.write_all(&zeros[0..padding])?;
+
+        if self.generic() {
+            buffer.write_u64::<LittleEndian>(self.q_part as u64)?;
+        }
+        Ok(())
     }
 
-    fn from_bytes(data: &mut impl io::Read) -> io::Result<Self> {
+    fn from_bytes(p: ValidPrime, data: &mut impl io::Read) -> io::Result<Self> {
         let len = data.read_u64::<LittleEndian>()? as usize;
         let mut profile = vec![0; len];
 
@@ -228,7 +383,13 @@ impl MilnorSubalgebra {
             data.read_exact(&mut buf[0..padding])?;
             assert_eq!(buf, [0; 8]);
         }
-        Ok(Self { profile })
+
+        let q_part = if p != 2 {
+            data.read_u64::<LittleEndian>()? as u32
+        } else {
+            0
+        };
+        Ok(Self::new(p, profile, q_part))
     }
 
     fn signature_to_bytes(signature: &[PPartEntry], buffer: &mut impl io::Write) -> io::Result<()> {
@@ -257,7 +418,7 @@ impl MilnorSubalgebra {
     }
 
     fn signature_from_bytes(&self, data: &mut impl io::Read) -> io::Result<Vec<PPartEntry>> {
-        let len = self.profile.len();
+        let len = self.signature_len();
         let mut signature: Vec<PPartEntry> = vec![0; len];
 
         if cfg!(target_endian = "little") && std::mem::size_of::<PPartEntry>() == 2 {
@@ -284,10 +445,22 @@ impl MilnorSubalgebra {
 
 impl Display for MilnorSubalgebra {
     fn fmt(&self, out: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
-        if self.profile.is_empty() {
-            write!(out, "F_2")
-        } else if self.profile.len() as u8 == self.profile[0] {
-            write!(out, "A({})", self.profile.len() - 1)
+        let len = self.profile.len();
+        if self.profile.is_empty() && self.q_part == 0 {
+            write!(out, "F_{}", self.p)
+        } else if !self.generic() && len as u8 == self.profile[0] {
+            write!(out, "A({})", len - 1)
+        } else if self.generic()
+            && self.profile.first().copied().unwrap_or(0) == len as u8
+            && self.q_part == (1 << (len + 1)) - 1
+        {
+            write!(out, "A({len})")
+        } else if self.generic() {
+            write!(
+                out,
+                "Algebra with profile {:?} and Q part {:#b}",
+                self.profile, self.q_part
+            )
         } else {
             write!(out, "Algebra with profile {:?}", self.profile)
         }
@@ -297,23 +470,63 @@ impl Display for MilnorSubalgebra {
 /// An iterator that iterates through a sequence of [`MilnorSubalgebra`] of increasing size. This
 /// is used by [`MilnorSubalgebra::optimal_for`] to find the largest subalgebra in this sequence
 /// that is applicable to a bidegree.
+///
+/// At the prime 2, this runs through the $A(n)$ and the subalgebras between them. At odd primes,
+/// we repeatedly add the May generator $P^j_t$ or $Q_k$ of smallest slope (see
+/// [`MilnorSubalgebra::vanishing_slope`]) such that the result is still a sub-Hopf algebra that
+/// contains $Q_j$ whenever it contains some $P^j_t$.
 struct SubalgebraIterator {
     current: MilnorSubalgebra,
 }
 
 impl SubalgebraIterator {
-    fn new() -> Self {
+    fn new(p: ValidPrime) -> Self {
         Self {
-            current: MilnorSubalgebra::new(vec![]),
+            current: MilnorSubalgebra::zero_algebra(p),
         }
     }
+
+    /// Add the May generator of smallest slope such that the result is still a valid
+    /// subalgebra.
+    fn next_generic(&mut self) {
+        let p = self.current.p.as_i32();
+        let xi_degrees = combinatorics::xi_degrees(self.current.p);
+        let tau_degrees = combinatorics::tau_degrees(self.current.p);
+
+        let next_q = self.current.q_part.trailing_ones();
+        let mut candidates = vec![{
+            let mut candidate = self.current.clone();
+            candidate.q_part |= 1 << next_q;
+            (tau_degrees[next_q as usize], candidate)
+        }];
+        for (idx, &xi_degree) in xi_degrees[..=self.current.profile.len()].iter().enumerate() {
+            let mut candidate = self.current.clone();
+            if idx == candidate.profile.len() {
+                candidate.profile.push(0);
+            }
+            let slope = p.pow(candidate.profile[idx] as u32 + 1) * (p - 1) * xi_degree;
+            candidate.profile[idx] += 1;
+            candidates.push((slope, candidate));
+        }
+        candidates.sort_unstable_by_key(|(slope, _)| *slope);
+
+        // Adding the next Q is always valid, so this always finds a candidate
+        self.current = candidates
+            .into_iter()
+            .map(|(_, candidate)| candidate)
+            .find(MilnorSubalgebra::is_valid_generic)
+            .unwrap();
+    }
 }
 
 impl Iterator for SubalgebraIterator {
     type Item = MilnorSubalgebra;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current.profile.is_empty()
+        if self.current.generic() {
+            self.next_generic();
+            Some(self.current.clone())
+        } else if self.current.profile.is_empty()
             || self.current.profile[0] == self.current.profile.len() as u8
         {
             // We are at F_2 or at A(n) where n = self.current.profile.len() - 1.
@@ -347,7 +560,7 @@ struct SignatureIterator<'a> {
 impl<'a> SignatureIterator<'a> {
     fn new(subalgebra: &'a MilnorSubalgebra, degree: i32) -> Self {
         Self {
-            current: vec![0; subalgebra.profile.len()],
+            current: subalgebra.zero_signature(),
             degree,
             subalgebra,
             signature_degree: 0,
@@ -359,24 +572,21 @@ impl Iterator for SignatureIterator<'_> {
     type Item = Vec<PPartEntry>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let xi_degrees = combinatorics::xi_degrees(TWO);
-        let len = self.current.len();
         for (i, current) in self.current.iter_mut().enumerate() {
-            *current += 1;
-            self.signature_degree += xi_degrees[i];
-
-            if self.signature_degree > self.degree || *current == 1 << self.subalgebra.profile[i] {
-                self.signature_degree -= xi_degrees[i] * *current as i32;
-                *current = 0;
-                if i + 1 == len {
-                    return None;
+            let old_degree = self.subalgebra.signature_entry_degree(i, *current);
+            self.signature_degree -= old_degree;
+
+            if let Some(next) = self.subalgebra.next_signature_entry(i, *current) {
+                let new_degree = self.subalgebra.signature_entry_degree(i, next);
+                if self.signature_degree + new_degree <= self.degree {
+                    *current = next;
+                    self.signature_degree += new_degree;
+                    return Some(self.current.clone());
                 }
-            } else {
-                return Some(self.current.clone());
             }
+            // Reset this entry and carry over to the next one
+            *current = 0;
         }
-        // This only happens when the profile is trivial
-        assert!(self.current.is_empty());
         None
     }
 }
@@ -388,25 +598,32 @@ enum Magic {
     Fix = -3,
 }
 
-/// A resolution of `S_2` using Nassau's algorithm.
+/// A resolution of a module using Nassau's algorithm.
 ///
 /// This aims to have an API similar to that of
-/// [`resolution::Resolution`](crate::resolution::Resolution). From an API point of view, the main
-/// difference between the two is that this is a chain complex over [`MilnorAlgebra`] over
-/// [`SteenrodAlgebra`](algebra::SteenrodAlgebra).
-pub struct Resolution<M: ZeroModule<Algebra = MilnorAlgebra>> {
+/// [`resolution::Resolution`](crate::resolution::Resolution). The algebra of the module must use
+/// the Milnor basis, i.e. it is either a [`MilnorAlgebra`] or a
+/// [`SteenrodAlgebra::MilnorAlgebra`](algebra::SteenrodAlgebra::MilnorAlgebra).
+///
+/// If the module is unbounded, there is no vanishing region to exploit, and every step is
+/// computed with the trivial subalgebra. This is then equivalent to the usual algorithm.
+pub struct Resolution<M: ZeroModule> {
     lock: Mutex<()>,
     name: String,
-    max_degree: i32,
-    modules: OnceBiVec<Arc<FreeModule<MilnorAlgebra>>>,
-    zero_module: Arc<FreeModule<MilnorAlgebra>>,
-    differentials: OnceBiVec<Arc<FreeModuleHomomorphism<FreeModule<MilnorAlgebra>>>>,
+    /// The top degree of the module, if it is bounded.
+    max_degree: Option<i32>,
+    modules: OnceBiVec<Arc<FreeModule<M::Algebra>>>,
+    zero_module: Arc<FreeModule<M::Algebra>>,
+    differentials: OnceBiVec<Arc<FreeModuleHomomorphism<FreeModule<M::Algebra>>>>,
     target: Arc<FiniteChainComplex<M>>,
     chain_maps: OnceBiVec<Arc<FreeModuleHomomorphism<M>>>,
     save_dir: SaveDirectory,
 }
 
-impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
+impl<M: ZeroModule> Resolution<M>
+where
+    for<'a> &'a M::Algebra: TryInto<&'a MilnorAlgebra>,
+{
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -424,11 +641,14 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
         save_dir: impl Into<SaveDirectory>,
     ) -> anyhow::Result<Self> {
         let save_dir = save_dir.into();
-        let max_degree = module
-            .max_degree()
-            .ok_or_else(|| anyhow!("Nassau's algorithm requires bounded module"))?;
+        if TryInto::<&MilnorAlgebra>::try_into(&*module.algebra()).is_err() {
+            return Err(anyhow!("Nassau's algorithm requires the Milnor basis"));
+        }
+        let max_degree = module.max_degree();
+        let min_degree = module.min_degree();
         let target = Arc::new(FiniteChainComplex::ccdz(module));
 
+        save_dir.check_manifest(&SaveManifest::new(&*target.algebra()))?;
         if let Some(p) = save_dir.write() {
             for subdir in SaveKind::nassau_data() {
                 subdir.create_dir(p)?;
@@ -437,7 +657,11 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
 
         Ok(Self {
             lock: Mutex::new(()),
-            zero_module: Arc::new(FreeModule::new(target.algebra(), "F_{-1}".to_string(), 0)),
+            zero_module: Arc::new(FreeModule::new(
+                target.algebra(),
+                "F_{-1}".to_string(),
+                min_degree,
+            )),
             name: String::new(),
             modules: OnceBiVec::new(0),
             differentials: OnceBiVec::new(0),
@@ -535,8 +759,8 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
             scratch.to_bytes(f)?;
 
             scratch.set_scratch_vector_size(full_matrix.columns());
-            for (i, _) in preimage.iter_nonzero() {
-                scratch.as_slice_mut().add(full_matrix.row(i), 1);
+            for (i, c) in preimage.iter_nonzero() {
+                scratch.as_slice_mut().add(full_matrix.row(i), c);
             }
             scratch.to_bytes(f)?;
         }
@@ -587,11 +811,12 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
 
         let target = &*self.modules[b.s() - 1];
         let algebra = target.algebra();
+        let algebra = milnor_algebra(&*algebra);
 
         let zero_sig = subalgebra.zero_signature();
         let target_dim = target.dimension(b.t());
         let target_mask: Vec<usize> = subalgebra
-            .signature_mask(&algebra, target, b.t(), &zero_sig)
+            .signature_mask(algebra, target, b.t(), &zero_sig)
             .collect();
         let target_masked_dim = target_mask.len();
 
@@ -612,7 +837,7 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
 
         let guard = tracing::info_span!("step", signature = ?zero_sig).entered();
         let next_mask: Vec<usize> = subalgebra
-            .signature_mask(&algebra, &self.modules[b.s() - 2], b.t(), &zero_sig)
+            .signature_mask(algebra, &self.modules[b.s() - 2], b.t(), &zero_sig)
             .collect();
         let next_masked_dim = next_mask.len();
 
@@ -646,13 +871,14 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
         }
 
         // Compute image
-        let mut n = subalgebra.signature_matrix(&self.differentials[b.s()], b.t(), &zero_sig);
+        let mut n =
+            subalgebra.signature_matrix(algebra, &self.differentials[b.s()], b.t(), &zero_sig);
         n.row_reduce();
         let next_row = n.rows();
 
         let num_new_gens = n.extend_image(0, n.columns(), &kernel, 0).len();
 
-        if b.t() < b.s() {
+        if b.t() - self.min_degree() < b.s() {
             assert_eq!(num_new_gens, 0, "Adding generators at {b}");
         }
 
@@ -667,8 +893,8 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
             .zip_eq(&mut dxs)
         {
             x.as_slice_mut().add_unmasked(x_masked, 1, &target_mask);
-            for (i, _) in x_masked.iter_nonzero() {
-                dx.as_slice_mut().add(full_matrix.row(i), 1);
+            for (i, c) in x_masked.iter_nonzero() {
+                dx.as_slice_mut().add(full_matrix.row(i), c);
             }
         }
 
@@ -682,8 +908,8 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
             let _guard = tracing::info_span!("step", ?signature).entered();
             target_mask.clear();
             next_mask.clear();
-            target_mask.extend(subalgebra.signature_mask(&algebra, target, b.t(), &signature));
-            next_mask.extend(subalgebra.signature_mask(&algebra, next, b.t(), &signature));
+            target_mask.extend(subalgebra.signature_mask(algebra, target, b.t(), &signature));
+            next_mask.extend(subalgebra.signature_mask(algebra, next, b.t(), &signature));
 
             let full_matrix = {
                 let _guard = ParallelGuard::new();
@@ -710,14 +936,15 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
                     if pivots[i] < 0 {
                         continue;
                     }
-                    if dx.entry(v) != 0 {
-                        scratch.as_slice_mut().add(preimage.row(row), 1);
+                    let c = dx.entry(v);
+                    if c != 0 {
+                        scratch.as_slice_mut().add(preimage.row(row), p - c);
                     }
                     row += 1;
                 }
-                for (i, _) in scratch.iter_nonzero() {
-                    x.add_basis_element(target_mask[i], 1);
-                    dx.as_slice_mut().add(full_matrix.row(i), 1);
+                for (i, c) in scratch.iter_nonzero() {
+                    x.add_basis_element(target_mask[i], c);
+                    dx.as_slice_mut().add(full_matrix.row(i), c);
                 }
             }
             Self::write_qi(
@@ -755,12 +982,12 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
         let chain_map = &self.chain_maps[0];
         let d = &self.differentials[0];
 
-        let source_dim = source_module.dimension(t);
-        let target_dim = target_module.dimension(t);
-
         source_module.compute_basis(t);
         target_module.compute_basis(t);
 
+        let source_dim = source_module.dimension(t);
+        let target_dim = target_module.dimension(t);
+
         if target_dim == 0 {
             source_module.extend_by_zero(t);
             chain_map.extend_by_zero(t);
@@ -850,7 +1077,8 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
         Ok(())
     }
 
-    fn step_resolution_with_result(&self, b: Bidegree) -> anyhow::Result<()> {
+    /// Compute the bidegree `b`. Returns whether it was loaded from the save directory.
+    fn step_resolution_with_result(&self, b: Bidegree) -> anyhow::Result<bool> {
         let p = self.prime();
         let set_data = || {
             let d = &self.differentials[b.s()];
@@ -871,7 +1099,7 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
 
         if b.s() == 0 {
             self.step0(b.t());
-            return Ok(());
+            return Ok(false);
         }
 
         if let Some(dir) = self.save_dir.read()
@@ -900,37 +1128,97 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
 
             set_data();
 
-            return Ok(());
+            return Ok(true);
         }
 
         if b.s() == 1 {
             self.step1(b.t())?;
             set_data();
-            return Ok(());
+            return Ok(false);
         }
 
-        self.step_resolution_with_subalgebra(
-            b,
-            MilnorSubalgebra::optimal_for(b - Bidegree::s_t(0, self.max_degree)),
-        )?;
+        let algebra = self.algebra();
+        let algebra = milnor_algebra(&*algebra);
+        let subalgebra = match self.max_degree {
+            Some(max_degree) => {
+                MilnorSubalgebra::optimal_for(algebra, b - Bidegree::s_t(0, max_degree))
+            }
+            None => MilnorSubalgebra::zero_algebra(self.prime()),
+        };
+        self.step_resolution_with_subalgebra(b, subalgebra)?;
         self.chain_maps[b.s()].extend_by_zero(b.t());
 
         set_data();
-        Ok(())
+        Ok(false)
     }
 
-    fn step_resolution(&self, b: Bidegree) {
-        self.step_resolution_with_result(b)
+    /// Compute the bidegree `b`. We do not record the matrix sizes and timings in the
+    /// [`StepStats`], since the matrices are reduced in pieces by
+    /// [`Resolution::step_resolution_with_subalgebra`].
+    fn step_resolution(&self, b: Bidegree) -> StepStats {
+        let start = Instant::now();
+        let loaded = self
+            .step_resolution_with_result(b)
             .unwrap_or_else(|e| panic!("Error computing bidegree {b}: {e}"));
+        StepStats {
+            loaded,
+            num_new_gens: self.number_of_gens_in_bidegree(b),
+            time: start.elapsed(),
+            ..Default::default()
+        }
     }
 
     /// This function resolves up till a fixed stem instead of a fixed t.
     #[tracing::instrument(skip(self), fields(self = self.name, %max))]
     pub fn compute_through_stem(&self, max: Bidegree) {
+        self.compute_through_stem_with_progress(max, |_| ())
+            .unwrap_or_else(|e| panic!("{e:#}"));
+    }
+
+    /// Compute through the bidegree `max`, reporting progress to `cb`. See
+    /// [`MuResolution::compute_through_bidegree_with_progress`] for the events that are reported.
+    /// Of the [`StepStats`], only `loaded`, `num_new_gens` and `time` are recorded. Unlike the
+    /// standard backend, this does not support distributed computations, so it never fails.
+    ///
+    /// [`MuResolution::compute_through_bidegree_with_progress`]:
+    ///     crate::resolution::MuResolution::compute_through_bidegree_with_progress
+    pub fn compute_through_bidegree_with_progress(
+        &self,
+        max: Bidegree,
+        mut cb: impl FnMut(ProgressEvent),
+    ) -> anyhow::Result<()> {
+        let min_degree = self.min_degree();
+        let _lock = self.lock.lock();
+
+        self.extend_through_degree(max.s());
+        self.algebra().compute_basis(max.t() - min_degree);
+
+        for t in min_degree..=max.t() {
+            for s in 0..=max.s() {
+                let b = Bidegree::s_t(s, t);
+                if self.has_computed_bidegree(b) {
+                    continue;
+                }
+                cb(ProgressEvent::Started(b));
+                let stats = self.step_resolution(b);
+                cb(ProgressEvent::Finished(b, stats));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute through the stem of `max`, reporting progress to `cb`. See
+    /// [`Resolution::compute_through_bidegree_with_progress`].
+    pub fn compute_through_stem_with_progress(
+        &self,
+        max: Bidegree,
+        mut cb: impl FnMut(ProgressEvent),
+    ) -> anyhow::Result<()> {
+        let min_degree = self.min_degree();
         let _lock = self.lock.lock();
 
         self.extend_through_degree(max.s());
-        self.algebra().compute_basis(max.t());
+        self.algebra().compute_basis(max.t() - min_degree);
 
         let tracing_span = tracing::Span::current();
         maybe_rayon::in_place_scope(|scope| {
@@ -942,17 +1230,17 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
             // annoying to do correctly. It seems more prudent to improve parallelism elsewhere.
 
             // Things that we have finished computing.
-            let mut progress: Vec<i32> = vec![-1; max.s() as usize + 1];
-            // We will kickstart the process by pretending we have computed (0, - 1). So
-            // we must pretend we have only computed up to (0, - 2);
-            progress[0] = -2;
+            let mut progress: Vec<i32> = vec![min_degree - 1; max.s() as usize + 1];
+            // We will kickstart the process by pretending we have computed (0, min_degree - 1). So
+            // we must pretend we have only computed up to (0, min_degree - 2);
+            progress[0] = min_degree - 2;
 
             let (sender, receiver) = mpsc::channel();
-            SenderData::send(Bidegree::s_t(0, -1), sender);
+            SenderData::send(Bidegree::s_t(0, min_degree - 1), None, sender);
 
             let f = |b, sender| {
                 if self.has_computed_bidegree(b) {
-                    SenderData::send(b, sender);
+                    SenderData::send(b, None, sender);
                 } else {
                     let tracing_span = tracing_span.clone();
                     scope.spawn(move |_| {
@@ -961,17 +1249,29 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
                             SenderData::send_retry(b, sender);
                             return;
                         }
-                        self.step_resolution(b);
-                        SenderData::send(b, sender);
+                        SenderData::send_started(b, &sender);
+                        let stats = self.step_resolution(b);
+                        SenderData::send(b, Some(stats), sender);
                     });
                 }
             };
 
-            while let Ok(SenderData { b, retry, sender }) = receiver.recv() {
+            while let Ok(SenderData {
+                b,
+                stats,
+                retry,
+                started,
+                sender,
+            }) = receiver.recv()
+            {
                 if retry {
                     f(b, sender);
                     continue;
                 }
+                if started {
+                    cb(ProgressEvent::Started(b));
+                    continue;
+                }
                 assert!(progress[b.s() as usize] == b.t() - 1);
                 progress[b.s() as usize] = b.t();
 
@@ -986,20 +1286,27 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> Resolution<M> {
                     // We are computing a normal step
                     f(b + Bidegree::s_t(0, 1), sender);
                 } else if distance == 1 && b.s() < max.s() {
-                    SenderData::send(b + Bidegree::s_t(0, 1), sender);
+                    SenderData::send(b + Bidegree::s_t(0, 1), None, sender);
+                }
+                if let Some(stats) = stats {
+                    cb(ProgressEvent::Finished(b, stats));
                 }
             }
         });
+        Ok(())
     }
 }
 
-impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
-    type Algebra = MilnorAlgebra;
+impl<M: ZeroModule> ChainComplex for Resolution<M>
+where
+    for<'a> &'a M::Algebra: TryInto<&'a MilnorAlgebra>,
+{
+    type Algebra = M::Algebra;
     type Homomorphism = FreeModuleHomomorphism<FreeModule<Self::Algebra>>;
     type Module = FreeModule<Self::Algebra>;
 
     fn prime(&self) -> ValidPrime {
-        TWO
+        self.target.prime()
     }
 
     fn algebra(&self) -> Arc<Self::Algebra> {
@@ -1015,7 +1322,7 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
     }
 
     fn min_degree(&self) -> i32 {
-        0
+        self.target.min_degree()
     }
 
     fn has_computed_bidegree(&self, b: Bidegree) -> bool {
@@ -1028,20 +1335,8 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
 
     #[tracing::instrument(skip(self), fields(self = self.name, %max))]
     fn compute_through_bidegree(&self, max: Bidegree) {
-        let _lock = self.lock.lock();
-
-        self.extend_through_degree(max.s());
-        self.algebra().compute_basis(max.t());
-
-        for t in 0..=max.t() {
-            for s in 0..=max.s() {
-                let b = Bidegree::s_t(s, t);
-                if self.has_computed_bidegree(b) {
-                    continue;
-                }
-                self.step_resolution(b);
-            }
-        }
+        self.compute_through_bidegree_with_progress(max, |_| ())
+            .unwrap_or_else(|e| panic!("{e:#}"));
     }
 
     fn next_homological_degree(&self) -> i32 {
@@ -1071,15 +1366,16 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
 
         let target_dim = f.read_u64::<LittleEndian>().unwrap() as usize;
         let zero_mask_dim = f.read_u64::<LittleEndian>().unwrap() as usize;
-        let subalgebra = MilnorSubalgebra::from_bytes(&mut f).unwrap();
+        let subalgebra = MilnorSubalgebra::from_bytes(p, &mut f).unwrap();
         let source = &self.modules[b.s()];
         let target = &self.modules[b.s() - 1];
         let algebra = target.algebra();
+        let algebra = milnor_algebra(&*algebra);
 
         let mut inputs: Vec<FpVector> = inputs.iter().map(|x| x.into().to_owned()).collect();
         let mut mask: Vec<usize> = Vec::with_capacity(zero_mask_dim + 8);
         mask.extend(subalgebra.signature_mask(
-            &algebra,
+            algebra,
             source,
             b.t(),
             &subalgebra.zero_signature(),
@@ -1111,7 +1407,7 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
             assert_eq!(mask.len(), zero_mask_dim + num_new_gens);
 
             let target_zero_mask: Vec<usize> = subalgebra
-                .signature_mask(&algebra, target, b.t(), &subalgebra.zero_signature())
+                .signature_mask(algebra, target, b.t(), &subalgebra.zero_signature())
                 .collect();
             let mut matrix = AugmentedMatrix::<3>::new(
                 p,
@@ -1143,7 +1439,7 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
                 let signature = subalgebra.signature_from_bytes(&mut f).unwrap();
 
                 mask.clear();
-                mask.extend(subalgebra.signature_mask(&algebra, source, b.t(), &signature));
+                mask.extend(subalgebra.signature_mask(algebra, source, b.t(), &signature));
                 scratch0.set_scratch_vector_size(mask.len());
             } else if col == Magic::Fix as usize {
                 // We need to fix the differential problem
@@ -1152,8 +1448,9 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
                 // row reduction. We do this manually for borrow checker reasons.
                 for (j, &k) in target_zero_mask.iter().enumerate() {
                     for i in 0..dx_matrix.rows() {
-                        if dx_matrix.row_segment(i, 1, 1).entry(k) != 0 {
-                            dx_matrix.row_segment_mut(i, 0, 0).add_basis_element(j, 1);
+                        let c = dx_matrix.row_segment(i, 1, 1).entry(k);
+                        if c != 0 {
+                            dx_matrix.row_segment_mut(i, 0, 0).add_basis_element(j, c);
                         }
                     }
                 }
@@ -1168,10 +1465,14 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
                     for (input, output) in inputs.iter_mut().zip(results.iter_mut()) {
                         let entry = input.entry(col);
                         if entry != 0 {
-                            output
-                                .into()
-                                .add_unmasked(dx_matrix.row_segment(i, 2, 2), 1, &mask);
-                            input.as_slice_mut().add(dx_matrix.row_segment(i, 1, 1), 1);
+                            output.into().add_unmasked(
+                                dx_matrix.row_segment(i, 2, 2),
+                                entry,
+                                &mask,
+                            );
+                            input
+                                .as_slice_mut()
+                                .add(dx_matrix.row_segment(i, 1, 1), p - entry);
                         }
                     }
                 }
@@ -1185,26 +1486,29 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
                 for (input, output) in inputs.iter_mut().zip(results.iter_mut()) {
                     let entry = input.entry(col);
                     if entry != 0 {
-                        output.into().add_unmasked(scratch0.as_slice(), 1, &mask);
+                        output
+                            .into()
+                            .add_unmasked(scratch0.as_slice(), entry, &mask);
                         // If we resume a resolve_through_stem, input may be longer than scratch1.
                         input
                             .slice_mut(0, scratch1.len())
-                            .add(scratch1.as_slice(), 1);
+                            .add(scratch1.as_slice(), p - entry);
                     }
                 }
 
                 // Row reduce the differentials
                 if !target_zero_mask.is_empty() {
                     for i in 0..dx_matrix.rows() {
-                        if dx_matrix.row_segment(i, 1, 1).entry(col) != 0 {
+                        let c = dx_matrix.row_segment(i, 1, 1).entry(col);
+                        if c != 0 {
                             dx_matrix
                                 .row_segment_mut(i, 2, 2)
                                 .slice_mut(0, zero_mask_dim)
-                                .add(scratch0.as_slice(), 1);
+                                .add(scratch0.as_slice(), p - c);
                             dx_matrix
                                 .row_segment_mut(i, 1, 1)
                                 .slice_mut(0, target_dim)
-                                .add(scratch1.as_slice(), 1);
+                                .add(scratch1.as_slice(), p - c);
                         }
                     }
                 }
@@ -1224,7 +1528,10 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> ChainComplex for Resolution<M> {
     }
 }
 
-impl<M: ZeroModule<Algebra = MilnorAlgebra>> AugmentedChainComplex for Resolution<M> {
+impl<M: ZeroModule> AugmentedChainComplex for Resolution<M>
+where
+    for<'a> &'a M::Algebra: TryInto<&'a MilnorAlgebra>,
+{
     type ChainMap = FreeModuleHomomorphism<M>;
     type TargetComplex = FiniteChainComplex<M, FullModuleHomomorphism<M, M>>;
 
@@ -1240,6 +1547,7 @@ impl<M: ZeroModule<Algebra = MilnorAlgebra>> AugmentedChainComplex for Resolutio
 #[cfg(test)]
 mod tests {
     use expect_test::expect;
+    use fp::prime::TWO;
 
     use super::*;
 
@@ -1265,7 +1573,7 @@ mod tests {
 
     #[test]
     fn test_signature_iterator() {
-        let subalgebra = MilnorSubalgebra::new(vec![2, 1]);
+        let subalgebra = MilnorSubalgebra::new(TWO, vec![2, 1], 0);
         assert_eq!(
             subalgebra.iter_signatures(6).collect::<Vec<_>>(),
             vec![
@@ -1312,14 +1620,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_subalgebra_iterator_generic() {
+        for p in [3, 5, 7] {
+            let p = ValidPrime::new(p);
+            for subalgebra in SubalgebraIterator::new(p).take(30) {
+                assert!(subalgebra.is_valid_generic(), "{subalgebra}");
+            }
+        }
+
+        let subalgebras = SubalgebraIterator::new(ValidPrime::new(3))
+            .take(7)
+            .map(|subalgebra| subalgebra.to_string())
+            .collect::<Vec<_>>();
+        expect![[r#"
+            [
+                "A(0)",
+                "Algebra with profile [] and Q part 0b11",
+                "A(1)",
+                "Algebra with profile [1] and Q part 0b111",
+                "Algebra with profile [1, 1] and Q part 0b111",
+                "A(2)",
+                "Algebra with profile [2, 1] and Q part 0b1111",
+            ]
+        "#]]
+        .assert_debug_eq(&subalgebras);
+    }
+
+    #[test]
+    fn test_signature_iterator_generic() {
+        // The Q part is the most significant entry and runs through subsets of {Q_0, Q_1}.
+        let subalgebra = MilnorSubalgebra::new(ValidPrime::new(3), vec![1], 0b11);
+        assert_eq!(
+            subalgebra.iter_signatures(6).collect::<Vec<_>>(),
+            vec![vec![1, 0], vec![0, 1], vec![1, 1], vec![0, 2], vec![0, 3],]
+        );
+    }
+
     #[test]
     fn test_signature_iterator_large() {
-        let subalgebra = MilnorSubalgebra::new(vec![
+        let subalgebra = MilnorSubalgebra::new(
+            TWO,
+            vec![
+                0,
+                MilnorSubalgebra::INFINITY,
+                MilnorSubalgebra::INFINITY,
+                MilnorSubalgebra::INFINITY,
+            ],
             0,
-            MilnorSubalgebra::INFINITY,
-            MilnorSubalgebra::INFINITY,
-            MilnorSubalgebra::INFINITY,
-        ]);
+        );
         assert_eq!(
             subalgebra.iter_signatures(7).collect::<Vec<_>>(),
             vec![vec![0, 1, 0, 0], vec![0, 2, 0, 0], vec![0, 0, 1, 0],]
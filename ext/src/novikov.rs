@@ -0,0 +1,1009 @@
+//! The $E_2$ page of the Adams–Novikov spectral sequence.
+//!
+//! The $E_2$ page of the Adams–Novikov spectral sequence of a spectrum $X$ is
+//! $\Ext_{BP_*BP}(BP_*, BP_*X)$, computed over the Hopf algebroid $(BP_*, BP_*BP)$ where
+//! $$ BP_* = \mathbb{Z}_{(p)}[v_1, v_2, \ldots],\quad BP_*BP = BP_*[t_1, t_2, \ldots] $$
+//! and $|v_i| = |t_i| = 2(p^i - 1)$. We compute this via the cobar complex, using the linear algebra
+//! of [`fp`]. More generally, we work with the comodules $BP_*/I_n$, where $I_n = (p, v_1, \ldots,
+//! v_{n - 1})$ is the invariant prime ideal. For $n = 0$, this is the $E_2$ page of the sphere, and
+//! for $n = 1$ that of the mod $p$ Moore spectrum.
+//!
+//! For $n \geq 1$ the coefficients are an $\mathbb{F}_p$-vector space. For $n = 0$, the cobar
+//! complex is a complex of free $\mathbb{Z}_{(p)}$-modules, and we compute it modulo $p^K$ for the
+//! largest $K$ that the integer arithmetic allows. The cohomology is then read off from the Smith
+//! normal forms of the differentials, which are exact as long as the torsion in range has exponent
+//! less than $K$. In practice the exponents are far smaller, e.g. $\Ext^1$ is cyclic of order
+//! $p^{1 + \nu_p(k)}$ in degree $2k(p - 1)$ at odd primes.
+//!
+//! The structure maps are computed from the formulas of Ravenel's green book, Theorem 4.3.13,
+//!  $$ \begin{aligned}
+//!     \eta_R(m_n) &= \sum_{i + j = n} m_i t_j^{p^i},\\
+//!     \sum_{i + j = n} m_i \Delta(t_j)^{p^i} &= \sum_{i + j + k = n} m_i t_j^{p^i} \otimes t_k^{p^{i
+//!     + j}},
+//! \end{aligned} $$
+//! where the $m_i$ are the coefficients of the logarithm of the formal group law, related to the
+//! Hazewinkel generators $v_i$ by $p m_n = \sum_{0 \leq i < n} m_i v_{n - i}^{p^i}$. These formulas
+//! have integral coefficients when written in terms of the $m_i$. Since $p^i m_i$ is integral in
+//! the $v_i$, we can compute the structure maps in terms of the $v_i$ modulo $p^k$ for a large
+//! enough $k$, and then divide out the powers of $p$.
+
+use std::fmt;
+
+use fp::{
+    matrix::Matrix,
+    prime::{Prime, ValidPrime},
+    vector::FpVector,
+};
+use itertools::Itertools;
+use rustc_hash::FxHashMap as HashMap;
+use sseq::{AdamsNovikov, Sseq, coordinates::Bidegree};
+
+/// A polynomial over $\mathbb{Z}/m$, as a map from exponent vectors to coefficients.
+type Polynomial = HashMap<Vec<u32>, u64>;
+
+/// A graded polynomial ring over $\mathbb{Z}/m$, truncated above `max_degree`.
+struct Ring {
+    modulus: u64,
+    degrees: Vec<i32>,
+    max_degree: i32,
+}
+
+impl Ring {
+    fn degree(&self, monomial: &[u32]) -> i32 {
+        self.degrees
+            .iter()
+            .zip(monomial)
+            .map(|(&d, &e)| d * e as i32)
+            .sum()
+    }
+
+    fn mul_mod(&self, a: u64, b: u64) -> u64 {
+        ((a as u128 * b as u128) % self.modulus as u128) as u64
+    }
+
+    fn constant(&self, c: u64) -> Polynomial {
+        let mut result = Polynomial::default();
+        if !c.is_multiple_of(self.modulus) {
+            result.insert(vec![0; self.degrees.len()], c % self.modulus);
+        }
+        result
+    }
+
+    fn variable(&self, i: usize) -> Polynomial {
+        let mut result = Polynomial::default();
+        if self.degrees[i] <= self.max_degree {
+            let mut monomial = vec![0; self.degrees.len()];
+            monomial[i] = 1;
+            result.insert(monomial, 1);
+        }
+        result
+    }
+
+    /// Add `c * b` to `a`.
+    fn add(&self, a: &mut Polynomial, b: &Polynomial, c: u64) {
+        for (monomial, &x) in b {
+            let entry = a.entry(monomial.clone()).or_insert(0);
+            *entry = (*entry + self.mul_mod(c, x)) % self.modulus;
+            if *entry == 0 {
+                a.remove(monomial);
+            }
+        }
+    }
+
+    fn sub(&self, a: &mut Polynomial, b: &Polynomial) {
+        self.add(a, b, self.modulus - 1);
+    }
+
+    fn mul(&self, a: &Polynomial, b: &Polynomial) -> Polynomial {
+        let b: Vec<_> = b.iter().map(|(m, &c)| (m, c, self.degree(m))).collect();
+        let mut result = Polynomial::default();
+        for (x, &c) in a {
+            let degree = self.degree(x);
+            for &(y, d, y_degree) in &b {
+                if degree + y_degree > self.max_degree {
+                    continue;
+                }
+                let monomial: Vec<u32> = x.iter().zip(y).map(|(i, j)| i + j).collect();
+                let entry = result.entry(monomial).or_insert(0);
+                *entry = (*entry + self.mul_mod(c, d)) % self.modulus;
+            }
+        }
+        result.retain(|_, c| *c != 0);
+        result
+    }
+
+    fn pow(&self, a: &Polynomial, mut e: u32) -> Polynomial {
+        let mut result = self.constant(1);
+        let mut a = a.clone();
+        while e > 0 {
+            if e & 1 == 1 {
+                result = self.mul(&result, &a);
+            }
+            e >>= 1;
+            if e > 0 {
+                a = self.mul(&a, &a);
+            }
+        }
+        result
+    }
+}
+
+/// The Hopf algebroid $(BP_*/I_n, BP_*BP/I_n)$, truncated to internal degrees at most
+/// `max_degree`. For $n = 0$, the coefficients are taken modulo $p^K$, where $K$ is the
+/// [`precision`](Self::precision).
+pub struct BPHopfAlgebroid {
+    p: ValidPrime,
+    ideal: u32,
+    precision: u32,
+    max_degree: i32,
+    /// The number of generators $v_i$ (and $t_i$) in range.
+    num_gens: usize,
+    ring: Ring,
+    /// The right unit $\eta_R(v_i)$, indexed by `i - 1`. Monomials are indexed by the variables
+    /// $v_1, \ldots, v_N$, then $t_1, \ldots, t_N$, and then another copy of the $t_i$ for the
+    /// second tensor factor. The right unit only involves the first two sets of variables.
+    eta_r: Vec<Polynomial>,
+    /// The coproduct $\Delta(t_i)$, indexed by `i - 1`, with $v_i$ in the left tensor factor.
+    delta: Vec<Polynomial>,
+}
+
+impl BPHopfAlgebroid {
+    /// Compute the structure maps of the Hopf algebroid. This fails if the range is too large for
+    /// the integer arithmetic used.
+    pub fn new(p: ValidPrime, ideal: u32, max_degree: i32) -> anyhow::Result<Self> {
+        let pu = p.as_u32() as u64;
+        let gen_degree = |i: usize| 2 * (p.as_i32().pow(i as u32) - 1);
+        let num_gens = (1..).take_while(|&i| gen_degree(i) <= max_degree).count();
+        let degrees: Vec<i32> = (0..3)
+            .flat_map(|_| (1..=num_gens).map(gen_degree))
+            .collect();
+
+        // A monomial in the m_i has a denominator of p^w, where w is its weight, and the weight is
+        // at most the degree divided by |m_1|.
+        let max_weight = (max_degree.max(0) / (2 * (p.as_i32() - 1))) as u32;
+        // The number of p-adic digits of the structure maps we need after dividing out p^w. Over
+        // Z_(p), we take as many as fit, and insist on comfortably more than the exponents of the
+        // torsion we expect in range.
+        let max_digits = (1..).take_while(|&k| pu.checked_pow(k).is_some_and(|m| m < 1 << 63));
+        let precision = match ideal {
+            0 => max_digits.last().unwrap().saturating_sub(max_weight),
+            _ => 1,
+        };
+        anyhow::ensure!(
+            precision > if ideal == 0 { max_weight } else { 0 },
+            "Degree {max_degree} is out of range at the prime {p}"
+        );
+        let modulus = pu.pow(max_weight + precision);
+
+        let ring = Ring {
+            modulus,
+            degrees: degrees.clone(),
+            max_degree,
+        };
+        let n = num_gens;
+        let one = ring.constant(1);
+        let coef = |i: usize| {
+            if i == 0 {
+                ring.constant(1)
+            } else {
+                ring.variable(i - 1)
+            }
+        };
+        let t = |slot: usize, j: usize| {
+            if j == 0 {
+                ring.constant(1)
+            } else {
+                ring.variable(slot * n + j - 1)
+            }
+        };
+        let pow_p = |i: usize| pu.pow(i as u32) as u32;
+
+        // Everything here is a polynomial in the m_i and the t_i.
+        let mut eta_m = vec![one.clone()];
+        let mut eta_v = vec![Polynomial::default()];
+        let mut delta = vec![one.clone()];
+        for k in 1..=n {
+            let mut x = Polynomial::default();
+            for i in 0..=k {
+                ring.add(
+                    &mut x,
+                    &ring.mul(&coef(i), &ring.pow(&t(1, k - i), pow_p(i))),
+                    1,
+                );
+            }
+            eta_m.push(x);
+
+            let mut x = Polynomial::default();
+            ring.add(&mut x, &eta_m[k], pu);
+            for i in 1..k {
+                ring.sub(
+                    &mut x,
+                    &ring.mul(&eta_m[i], &ring.pow(&eta_v[k - i], pow_p(i))),
+                );
+            }
+            eta_v.push(x);
+
+            let mut x = Polynomial::default();
+            for i in 0..=k {
+                for j in 0..=k - i {
+                    let left = ring.mul(&coef(i), &ring.pow(&t(1, j), pow_p(i)));
+                    let right = ring.pow(&t(2, k - i - j), pow_p(i + j));
+                    ring.add(&mut x, &ring.mul(&left, &right), 1);
+                }
+            }
+            for i in 1..=k {
+                ring.sub(
+                    &mut x,
+                    &ring.mul(&coef(i), &ring.pow(&delta[k - i], pow_p(i))),
+                );
+            }
+            delta.push(x);
+        }
+
+        // Now express p^i m_i in terms of the v_i
+        let mut p_m = vec![one.clone()];
+        for k in 1..=n {
+            let mut x = Polynomial::default();
+            ring.add(&mut x, &ring.variable(k - 1), pu.pow(k as u32 - 1));
+            for (i, p_m_i) in p_m.iter().enumerate().take(k).skip(1) {
+                let term = ring.mul(p_m_i, &ring.pow(&ring.variable(k - i - 1), pow_p(i)));
+                ring.add(&mut x, &term, pu.pow((k - i - 1) as u32));
+            }
+            p_m.push(x);
+        }
+
+        let mut result = Self {
+            p,
+            ideal,
+            precision,
+            max_degree,
+            num_gens,
+            ring: Ring {
+                modulus: pu.pow(precision),
+                degrees,
+                max_degree,
+            },
+            eta_r: Vec::new(),
+            delta: Vec::new(),
+        };
+
+        // The generators v_1, ..., v_{n - 1} are zero in BP_*/I_n
+        let killed = n.min(ideal.saturating_sub(1) as usize);
+        let precision_modulus = pu.pow(precision);
+        let mut powers = HashMap::default();
+        let mut to_v = |x: &Polynomial| {
+            let mut sum = Polynomial::default();
+            for (monomial, &c) in x {
+                let weight: u32 = (1..).zip(&monomial[..n]).map(|(i, e)| i * e).sum();
+                let m_part = powers.entry(monomial[..n].to_vec()).or_insert_with(|| {
+                    (0..n).fold(one.clone(), |acc, i| {
+                        ring.mul(&acc, &ring.pow(&p_m[i + 1], monomial[i]))
+                    })
+                });
+                let mut t_part = monomial.clone();
+                t_part[..n].fill(0);
+                let mut t_part_poly = Polynomial::default();
+                t_part_poly.insert(t_part, 1);
+                ring.add(
+                    &mut sum,
+                    &ring.mul(m_part, &t_part_poly),
+                    ring.mul_mod(c, pu.pow(max_weight - weight)),
+                );
+            }
+            let denominator = pu.pow(max_weight);
+            sum.into_iter()
+                .filter_map(|(monomial, c)| {
+                    assert_eq!(c % denominator, 0, "Non-integral structure map");
+                    let c = (c / denominator) % precision_modulus;
+                    let killed = monomial[..killed].iter().any(|&e| e > 0);
+                    (c != 0 && !killed).then_some((monomial, c))
+                })
+                .collect::<Polynomial>()
+        };
+        result.eta_r = eta_v[1..].iter().map(&mut to_v).collect();
+        result.delta = delta[1..].iter().map(&mut to_v).collect();
+        Ok(result)
+    }
+
+    pub fn prime(&self) -> ValidPrime {
+        self.p
+    }
+
+    /// The `n` such that the coefficients are $BP_*/I_n$.
+    pub fn ideal(&self) -> u32 {
+        self.ideal
+    }
+
+    /// The number $K$ of $p$-adic digits of the coefficients. This is $1$ unless the coefficients
+    /// are $BP_*$ itself.
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    pub fn max_degree(&self) -> i32 {
+        self.max_degree
+    }
+
+    /// The right unit $\eta_R(v_i)$ as a string. This panics if $v_i$ is out of range.
+    pub fn format_right_unit(&self, i: usize) -> String {
+        self.format(&self.eta_r[i - 1], false)
+    }
+
+    /// The coproduct $\Delta(t_i)$ as a string. This panics if $t_i$ is out of range.
+    pub fn format_coproduct(&self, i: usize) -> String {
+        self.format(&self.delta[i - 1], true)
+    }
+
+    /// Format an element of $\Gamma$, or of $\Gamma \otimes \Gamma$ if `tensor` is set.
+    fn format(&self, x: &Polynomial, tensor: bool) -> String {
+        let n = self.num_gens;
+        let mut terms: Vec<_> = x.iter().collect();
+        terms.sort_by_key(|(m, _)| {
+            (
+                self.ring.degree(&m[..n]),
+                m.iter().rev().collect::<Vec<_>>(),
+            )
+        });
+        let terms: Vec<(&str, String)> = terms
+            .into_iter()
+            .map(|(monomial, &c)| {
+                let mut factors: Vec<String> = monomial
+                    .chunks(n)
+                    .zip(["v", "t", "t"])
+                    .map(|(exponents, name)| {
+                        (1..)
+                            .zip(exponents)
+                            .filter(|(_, e)| **e > 0)
+                            .map(|(i, &e)| match e {
+                                1 => format!("{name}_{i}"),
+                                _ => format!("{name}_{i}^{e}"),
+                            })
+                            .join(" ")
+                    })
+                    .collect();
+                if tensor {
+                    for factor in &mut factors[1..] {
+                        if factor.is_empty() {
+                            factor.push('1');
+                        }
+                    }
+                    factors = vec![
+                        factors[0].clone(),
+                        format!("{} ⊗ {}", factors[1], factors[2]),
+                    ];
+                }
+                let mut term = factors.into_iter().filter(|f| !f.is_empty()).join(" ");
+                if term.is_empty() {
+                    term.push('1');
+                }
+                // Over Z_(p), write the coefficients as signed integers
+                let modulus = self.ring.modulus;
+                let (sign, c) = if self.precision > 1 && c > modulus / 2 {
+                    ("-", modulus - c)
+                } else {
+                    ("+", c)
+                };
+                match c {
+                    1 => (sign, term),
+                    _ => (sign, format!("{c} {term}")),
+                }
+            })
+            .collect();
+        let mut result = String::new();
+        for (i, (sign, term)) in terms.into_iter().enumerate() {
+            match (i, sign) {
+                (0, "+") => {}
+                (0, _) => result.push('-'),
+                _ => result.push_str(&format!(" {sign} ")),
+            }
+            result.push_str(&term);
+        }
+        result
+    }
+}
+
+/// The normalized cobar complex of $(BP_*/I_n, BP_*BP/I_n)$. A basis element of $C^s$ is a
+/// monomial $a [t^{\beta_1} | \cdots | t^{\beta_s}]$ with $a \in BP_*/I_n$ and each $\beta_i$
+/// non-zero. This is encoded as the concatenation of the exponent vectors of $a$ and the
+/// $\beta_i$. The coefficients are integers modulo the modulus of the algebroid.
+struct CobarComplex<'a> {
+    algebroid: &'a BPHopfAlgebroid,
+    /// The monomials of $BP_*/I_n$ in each degree, indexed by the degree divided by $2(p - 1)$.
+    coefficients: Vec<Vec<Vec<u32>>>,
+    /// The non-trivial monomials in the $t_i$ in each degree, indexed as above.
+    t_monomials: Vec<Vec<Vec<u32>>>,
+    eta_r: HashMap<Vec<u32>, Polynomial>,
+    delta: HashMap<Vec<u32>, Polynomial>,
+}
+
+impl<'a> CobarComplex<'a> {
+    fn new(algebroid: &'a BPHopfAlgebroid) -> Self {
+        let n = algebroid.num_gens;
+        let q = 2 * (algebroid.p.as_i32() - 1);
+        let degrees = &algebroid.ring.degrees[..n];
+        let monomials = |skip: usize| {
+            (0..=algebroid.max_degree / q)
+                .map(|d| monomials(degrees, skip, d * q))
+                .collect::<Vec<_>>()
+        };
+        let mut t_monomials = monomials(0);
+        t_monomials[0].clear();
+        Self {
+            algebroid,
+            coefficients: monomials(algebroid.ideal.saturating_sub(1) as usize),
+            t_monomials,
+            eta_r: HashMap::default(),
+            delta: HashMap::default(),
+        }
+    }
+
+    fn q(&self) -> i32 {
+        2 * (self.algebroid.p.as_i32() - 1)
+    }
+
+    /// The basis of $C^s$ in internal degree `t`, in a fixed order.
+    fn basis(&self, s: usize, t: i32) -> Vec<Vec<u32>> {
+        let q = self.q();
+        let mut result = Vec::new();
+        if t < 0 || t % q != 0 {
+            return result;
+        }
+        let t = (t / q) as usize;
+        for (d, coefficients) in self.coefficients.iter().enumerate().take(t + 1) {
+            let mut partial: Vec<Vec<u32>> = coefficients.clone();
+            let mut remaining = vec![t - d; partial.len()];
+            for slot in 0..s {
+                let last = slot + 1 == s;
+                let mut next = Vec::new();
+                let mut next_remaining = Vec::new();
+                for (prefix, &r) in partial.iter().zip(&remaining) {
+                    let range = if last { r..=r } else { 1..=r };
+                    for d in range {
+                        for monomial in &self.t_monomials[d] {
+                            let mut key = prefix.clone();
+                            key.extend_from_slice(monomial);
+                            next.push(key);
+                            next_remaining.push(r - d);
+                        }
+                    }
+                }
+                partial = next;
+                remaining = next_remaining;
+            }
+            result.extend(
+                partial
+                    .into_iter()
+                    .zip(remaining)
+                    .filter(|(_, r)| *r == 0)
+                    .map(|(key, _)| key),
+            );
+        }
+        result
+    }
+
+    fn eta_r(&mut self, a: &[u32]) -> Polynomial {
+        if let Some(x) = self.eta_r.get(a) {
+            return x.clone();
+        }
+        let algebroid = self.algebroid;
+        let ring = &algebroid.ring;
+        let x = a.iter().enumerate().fold(ring.constant(1), |acc, (i, &e)| {
+            ring.mul(&acc, &ring.pow(&algebroid.eta_r[i], e))
+        });
+        self.eta_r.insert(a.to_vec(), x.clone());
+        x
+    }
+
+    fn delta(&mut self, beta: &[u32]) -> Polynomial {
+        if let Some(x) = self.delta.get(beta) {
+            return x.clone();
+        }
+        let algebroid = self.algebroid;
+        let ring = &algebroid.ring;
+        let x = beta
+            .iter()
+            .enumerate()
+            .fold(ring.constant(1), |acc, (i, &e)| {
+                ring.mul(&acc, &ring.pow(&algebroid.delta[i], e))
+            });
+        self.delta.insert(beta.to_vec(), x.clone());
+        x
+    }
+
+    /// Move the coefficient `a` in front of `slots` to the far left, using the relation
+    /// $\gamma | a \gamma' = \gamma \eta_R(a) | \gamma'$. This returns the resulting terms as
+    /// coefficients and keys.
+    fn push_left(&mut self, a: &[u32], slots: &[u32]) -> Vec<(u64, Vec<u32>)> {
+        let n = self.algebroid.num_gens;
+        if slots.is_empty() || a.iter().all(|&e| e == 0) {
+            let mut key = a.to_vec();
+            key.extend_from_slice(slots);
+            return vec![(1, key)];
+        }
+        let ring = &self.algebroid.ring;
+        let (prefix, last) = slots.split_at(slots.len() - n);
+        let mut result = Vec::new();
+        for (monomial, c) in self.eta_r(a) {
+            for (d, mut key) in self.push_left(&monomial[..n], prefix) {
+                key.extend(last.iter().zip(&monomial[n..2 * n]).map(|(x, y)| x + y));
+                result.push((ring.mul_mod(c, d), key));
+            }
+        }
+        result
+    }
+
+    /// The differential of a basis element of $C^s$, given by
+    /// $$ d(a[\gamma_1 | \cdots | \gamma_s]) = (\eta_R(a) - a)[\gamma_1 | \cdots | \gamma_s]
+    ///     + \sum_{i = 1}^s (-1)^i a[\gamma_1 | \cdots | \bar\Delta(\gamma_i) | \cdots |
+    ///     \gamma_s]. $$
+    fn differential(&mut self, key: &[u32]) -> HashMap<Vec<u32>, u64> {
+        let n = self.algebroid.num_gens;
+        let ring = &self.algebroid.ring;
+        let modulus = ring.modulus;
+        let mut result = HashMap::default();
+        let mut add = |key: Vec<u32>, c: u64| {
+            let entry = result.entry(key).or_insert(0);
+            *entry = (*entry + c) % modulus;
+        };
+
+        let (a, slots) = key.split_at(n);
+        for (monomial, c) in self.eta_r(a) {
+            if monomial[n..2 * n].iter().all(|&e| e == 0) {
+                continue;
+            }
+            let mut key = monomial[..2 * n].to_vec();
+            key.extend_from_slice(slots);
+            add(key, c);
+        }
+
+        let s = slots.len() / n;
+        for i in 0..s {
+            let sign = if i % 2 == 0 { modulus - 1 } else { 1 };
+            let (prefix, rest) = slots.split_at(i * n);
+            let (beta, suffix) = rest.split_at(n);
+            for (monomial, c) in self.delta(beta) {
+                let (x, y) = (&monomial[n..2 * n], &monomial[2 * n..]);
+                if x.iter().all(|&e| e == 0) || y.iter().all(|&e| e == 0) {
+                    continue;
+                }
+                for (d, mut key) in self.push_left(&monomial[..n], prefix) {
+                    key[..n].iter_mut().zip(a).for_each(|(x, y)| *x += y);
+                    key.extend_from_slice(x);
+                    key.extend_from_slice(y);
+                    key.extend_from_slice(suffix);
+                    add(key, ring.mul_mod(ring.mul_mod(sign, c), d));
+                }
+            }
+        }
+        result.retain(|_, c| *c != 0);
+        result
+    }
+
+    /// The elementary divisors of the differential $C^s \to C^{s + 1}$ in internal degree `t`,
+    /// given by their $p$-adic valuations. Over $\mathbb{F}_p$, these are all zero and there are
+    /// as many as the rank.
+    fn elementary_divisors(&mut self, s: usize, t: i32) -> Vec<u32> {
+        let p = self.algebroid.p;
+        let source = self.basis(s, t);
+        let target = self.basis(s + 1, t);
+        if source.is_empty() || target.is_empty() {
+            return Vec::new();
+        }
+        let index: HashMap<&[u32], usize> = target
+            .iter()
+            .enumerate()
+            .map(|(i, k)| (k.as_slice(), i))
+            .collect();
+
+        if self.algebroid.ideal > 0 {
+            let rows = source
+                .iter()
+                .map(|key| {
+                    let mut row = FpVector::new(p, target.len());
+                    for (k, c) in self.differential(key) {
+                        row.add_basis_element(index[k.as_slice()], c as u32);
+                    }
+                    row
+                })
+                .collect();
+            let rank = Matrix::from_rows(p, rows, target.len()).row_reduce();
+            return vec![0; rank];
+        }
+
+        let rows = source
+            .iter()
+            .map(|key| {
+                let mut row = vec![0; target.len()];
+                for (k, c) in self.differential(key) {
+                    row[index[k.as_slice()]] = c;
+                }
+                row
+            })
+            .collect();
+        smith_normal_form(p.as_u32() as u64, self.algebroid.precision, rows)
+    }
+}
+
+/// The $p$-adic valuations of the nonzero elementary divisors of a matrix over
+/// $\mathbb{Z}/p^k$. Since $\mathbb{Z}/p^k$ is a local ring, we can always pivot on an entry of
+/// minimal valuation, which divides every other entry.
+fn smith_normal_form(p: u64, k: u32, mut matrix: Vec<Vec<u64>>) -> Vec<u32> {
+    let modulus = p.pow(k);
+    let mul = |a: u64, b: u64| ((a as u128 * b as u128) % modulus as u128) as u64;
+    let valuation = |mut x: u64| {
+        let mut v = 0;
+        while x.is_multiple_of(p) {
+            x /= p;
+            v += 1;
+        }
+        v
+    };
+    let columns = matrix.first().map_or(0, Vec::len);
+
+    let mut result = Vec::new();
+    for r in 0..matrix.len().min(columns) {
+        let pivot = (r..matrix.len())
+            .flat_map(|i| (r..columns).map(move |j| (i, j)))
+            .filter(|&(i, j)| matrix[i][j] != 0)
+            .min_by_key(|&(i, j)| valuation(matrix[i][j]));
+        let Some((i, j)) = pivot else {
+            break;
+        };
+        matrix.swap(r, i);
+        for row in &mut matrix {
+            row.swap(r, j);
+        }
+
+        // Scale the pivot to p^v
+        let v = valuation(matrix[r][r]);
+        let unit = matrix[r][r] / p.pow(v);
+        let inverse = mod_inverse(unit, modulus);
+        for x in &mut matrix[r] {
+            *x = mul(*x, inverse);
+        }
+
+        // The column operations only change the pivot row, which we can then discard
+        let (pivot_row, rest) = matrix.split_at_mut(r + 1);
+        let pivot_row = &pivot_row[r];
+        for row in rest {
+            if row[r] == 0 {
+                continue;
+            }
+            let c = row[r] / p.pow(v);
+            for (x, &y) in row.iter_mut().zip(pivot_row) {
+                *x = (*x + modulus - mul(c, y)) % modulus;
+            }
+        }
+        result.push(v);
+    }
+    result
+}
+
+/// The inverse of a unit modulo `modulus`.
+fn mod_inverse(a: u64, modulus: u64) -> u64 {
+    let (mut r0, mut r1) = (modulus as i128, a as i128);
+    let (mut s0, mut s1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (s0, s1) = (s1, s0 - q * s1);
+    }
+    debug_assert_eq!(r0, 1, "{a} is not a unit");
+    s0.rem_euclid(modulus as i128) as u64
+}
+
+/// The monomials of degree `degree` in variables of degree `degrees`, where the first `skip`
+/// variables are omitted.
+fn monomials(degrees: &[i32], skip: usize, degree: i32) -> Vec<Vec<u32>> {
+    fn go(
+        degrees: &[i32],
+        skip: usize,
+        i: usize,
+        degree: i32,
+        current: &mut Vec<u32>,
+        out: &mut Vec<Vec<u32>>,
+    ) {
+        if i == degrees.len() {
+            if degree == 0 {
+                out.push(current.clone());
+            }
+            return;
+        }
+        let max = if i < skip { 0 } else { degree / degrees[i] };
+        for e in 0..=max {
+            current[i] = e as u32;
+            go(degrees, skip, i + 1, degree - e * degrees[i], current, out);
+        }
+        current[i] = 0;
+    }
+    let mut out = Vec::new();
+    go(
+        degrees,
+        skip,
+        0,
+        degree,
+        &mut vec![0; degrees.len()],
+        &mut out,
+    );
+    out
+}
+
+/// A cyclic summand of the Adams–Novikov $E_2$ page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CyclicSummand {
+    /// $\mathbb{Z}_{(p)}$
+    Free,
+    /// $\mathbb{Z}/p^k$
+    Torsion(u32),
+}
+
+impl fmt::Display for CyclicSummand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Free => write!(f, "Z_(p)"),
+            Self::Torsion(1) => write!(f, "Z/p"),
+            Self::Torsion(k) => write!(f, "Z/p^{k}"),
+        }
+    }
+}
+
+/// The Adams–Novikov $E_2$ page $\Ext_{BP_*BP}^{s, t}(BP_*, BP_*/I_n)$ in a range of bidegrees.
+pub struct AdamsNovikovE2 {
+    algebroid: BPHopfAlgebroid,
+    max: Bidegree,
+    /// The cyclic summands, indexed by `s` and then `n`. The free summands come first, followed
+    /// by the torsion summands in increasing order.
+    summands: Vec<Vec<Vec<CyclicSummand>>>,
+}
+
+impl AdamsNovikovE2 {
+    /// Compute the $E_2$ page for the coefficients $BP_*/I_n$ where `n = ideal`, for stems up to
+    /// `max.n()` and filtrations up to `max.s()`. For `n = 0`, this is the $E_2$ page of the
+    /// sphere.
+    pub fn new(p: ValidPrime, ideal: u32, max: Bidegree) -> anyhow::Result<Self> {
+        let algebroid = BPHopfAlgebroid::new(p, ideal, max.n() + max.s())?;
+        let mut cobar = CobarComplex::new(&algebroid);
+        let max_s = max.s() as usize;
+
+        let mut summands = vec![vec![Vec::new(); max.n() as usize + 1]; max_s + 1];
+        for t in 0..=max.n() + max.s() {
+            let divisors: Vec<Vec<u32>> = (0..=max_s)
+                .map(|s| cobar.elementary_divisors(s, t))
+                .collect();
+            for s in 0..=max_s {
+                let n = t - s as i32;
+                if n < 0 || n > max.n() {
+                    continue;
+                }
+                let dim = cobar.basis(s, t).len();
+                let previous: &[u32] = if s > 0 { &divisors[s - 1] } else { &[] };
+                let free = dim - divisors[s].len() - previous.len();
+                let torsion = previous.iter().filter(|&&v| v > 0).sorted();
+                summands[s][n as usize] = std::iter::repeat_n(CyclicSummand::Free, free)
+                    .chain(torsion.map(|&v| CyclicSummand::Torsion(v)))
+                    .collect();
+            }
+        }
+        Ok(Self {
+            algebroid,
+            max,
+            summands,
+        })
+    }
+
+    pub fn algebroid(&self) -> &BPHopfAlgebroid {
+        &self.algebroid
+    }
+
+    pub fn max(&self) -> Bidegree {
+        self.max
+    }
+
+    /// The cyclic summands of $E_2$ in bidegree `b`. This is empty outside the computed range.
+    pub fn summands(&self, b: Bidegree) -> &[CyclicSummand] {
+        if b.n() < 0 || b.s() < 0 {
+            return &[];
+        }
+        self.summands
+            .get(b.s() as usize)
+            .and_then(|row| row.get(b.n() as usize))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The number of cyclic summands of $E_2$ in bidegree `b`. For $n \geq 1$, this is the
+    /// dimension as an $\mathbb{F}_p$-vector space. This is zero outside the computed range.
+    pub fn dimension(&self, b: Bidegree) -> usize {
+        self.summands(b).len()
+    }
+
+    /// The $E_2$ page as a spectral sequence with a class for each cyclic summand.
+    pub fn to_sseq(&self) -> Sseq<2, AdamsNovikov> {
+        let mut sseq = Sseq::new(self.algebroid.p);
+        for s in 0..=self.max.s() {
+            for n in 0..=self.max.n() {
+                let b = Bidegree::n_s(n, s);
+                sseq.set_dimension(b, self.dimension(b));
+            }
+        }
+        sseq
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write as _;
+
+    use expect_test::expect;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::{
+        chain_complex::{ChainComplex, FreeChainComplex},
+        utils::construct,
+    };
+
+    #[test]
+    fn test_structure_maps() {
+        let mut output = String::new();
+        for (p, ideal) in [(2, 1), (3, 1), (2, 0)] {
+            let algebroid =
+                BPHopfAlgebroid::new(ValidPrime::new(p), ideal, 2 * (p * p - 1) as i32).unwrap();
+            for i in 1..=2 {
+                let eta_r = algebroid.format_right_unit(i);
+                let delta = algebroid.format_coproduct(i);
+                writeln!(output, "p = {p}, n = {ideal}: eta_R(v_{i}) = {eta_r}").unwrap();
+                writeln!(output, "p = {p}, n = {ideal}: Delta(t_{i}) = {delta}").unwrap();
+            }
+        }
+        expect![[r#"
+            p = 2, n = 1: eta_R(v_1) = v_1
+            p = 2, n = 1: Delta(t_1) = t_1 ⊗ 1 + 1 ⊗ t_1
+            p = 2, n = 1: eta_R(v_2) = v_1 t_1^2 + v_1^2 t_1 + v_2
+            p = 2, n = 1: Delta(t_2) = t_2 ⊗ 1 + t_1 ⊗ t_1^2 + 1 ⊗ t_2 + v_1 t_1 ⊗ t_1
+            p = 3, n = 1: eta_R(v_1) = v_1
+            p = 3, n = 1: Delta(t_1) = t_1 ⊗ 1 + 1 ⊗ t_1
+            p = 3, n = 1: eta_R(v_2) = v_1 t_1^3 + 2 v_1^3 t_1 + v_2
+            p = 3, n = 1: Delta(t_2) = t_2 ⊗ 1 + t_1 ⊗ t_1^3 + 1 ⊗ t_2 + 2 v_1 t_1^2 ⊗ t_1 + 2 v_1 t_1 ⊗ t_1^2
+            p = 2, n = 0: eta_R(v_1) = 2 t_1 + v_1
+            p = 2, n = 0: Delta(t_1) = t_1 ⊗ 1 + 1 ⊗ t_1
+            p = 2, n = 0: eta_R(v_2) = -4 t_1^3 + 2 t_2 - 5 v_1 t_1^2 - 3 v_1^2 t_1 + v_2
+            p = 2, n = 0: Delta(t_2) = t_2 ⊗ 1 + t_1 ⊗ t_1^2 + 1 ⊗ t_2 - v_1 t_1 ⊗ t_1
+        "#]].assert_eq(&output);
+    }
+
+    #[rstest]
+    #[case(2, 0, 16)]
+    #[case(2, 1, 20)]
+    #[case(2, 2, 20)]
+    #[case(3, 0, 24)]
+    #[case(3, 1, 40)]
+    #[case(5, 1, 40)]
+    fn test_d_squared(#[case] p: u32, #[case] ideal: u32, #[case] max_degree: i32) {
+        let algebroid = BPHopfAlgebroid::new(ValidPrime::new(p), ideal, max_degree).unwrap();
+        let mut cobar = CobarComplex::new(&algebroid);
+        let ring = &algebroid.ring;
+        for t in 0..=max_degree {
+            for s in 0..3 {
+                for key in cobar.basis(s, t) {
+                    let mut result: HashMap<Vec<u32>, u64> = HashMap::default();
+                    for (k, c) in cobar.differential(&key) {
+                        for (k2, d) in cobar.differential(&k) {
+                            let entry = result.entry(k2).or_insert(0);
+                            *entry = (*entry + ring.mul_mod(c, d)) % ring.modulus;
+                        }
+                    }
+                    assert!(result.values().all(|&c| c == 0), "d^2 != 0 on {key:?}");
+                }
+            }
+        }
+    }
+
+    /// When the ideal is large, the coefficients are $\mathbb{F}_p$ in range and the Hopf
+    /// algebroid is the Hopf algebra $P_*$. At $p = 2$, this is the dual Steenrod algebra with
+    /// degrees doubled, and at odd primes it is the dual of the subalgebra of reduced powers.
+    #[rstest]
+    #[case(2, "S_2")]
+    #[case(3, "S_3@P(2)")]
+    fn test_compare_with_adams(#[case] p: u32, #[case] spec: &str) {
+        let max = Bidegree::n_s(20, 4);
+        let e2 = AdamsNovikovE2::new(ValidPrime::new(p), 10, max).unwrap();
+        let res = construct(spec, None).unwrap();
+        res.compute_through_stem(max);
+        for b in res.iter_stem() {
+            let b2 = if p == 2 {
+                Bidegree::n_s(2 * b.n() + b.s(), b.s())
+            } else {
+                b
+            };
+            if b2.n() <= max.n() && b.s() <= max.s() {
+                assert_eq!(e2.dimension(b2), res.number_of_gens_in_bidegree(b), "{b}");
+            }
+        }
+    }
+
+    /// The $E_2$ page of the mod $p$ Moore spectrum, printed as a chart with `s` increasing
+    /// upwards.
+    #[test]
+    fn test_moore_spectrum() {
+        let mut output = String::new();
+        for (p, max) in [(2, Bidegree::n_s(16, 4)), (3, Bidegree::n_s(30, 3))] {
+            let e2 = AdamsNovikovE2::new(ValidPrime::new(p), 1, max).unwrap();
+            writeln!(output, "p = {p}:").unwrap();
+            for s in (0..=max.s()).rev() {
+                let row = (0..=max.n())
+                    .map(|n| e2.dimension(Bidegree::n_s(n, s)))
+                    .join(" ");
+                writeln!(output, "{row}").unwrap();
+            }
+        }
+        expect![[r#"
+            p = 2:
+            0 0 0 0 1 0 1 0 1 0 2 0 2 0 2 0 2
+            0 0 0 1 0 1 0 1 0 3 0 2 0 2 0 3 0
+            0 0 1 0 1 0 2 0 3 0 3 0 2 0 4 0 4
+            0 1 0 2 0 1 0 3 0 3 0 2 0 2 0 4 0
+            1 0 1 0 1 0 1 0 1 0 1 0 1 0 1 0 1
+            p = 3:
+            0 0 0 0 0 0 0 0 0 0 0 0 0 1 0 0 0 0 0 0 0 1 0 0 0 0 0 0 0 1 0
+            0 0 0 0 0 0 0 0 0 0 1 0 0 0 1 0 0 0 0 0 0 0 0 0 0 0 1 0 0 0 1
+            0 0 0 1 0 0 0 1 0 0 0 2 0 0 0 1 0 0 0 1 0 0 0 1 0 0 0 2 0 0 0
+            1 0 0 0 1 0 0 0 1 0 0 0 1 0 0 0 1 0 0 0 1 0 0 0 1 0 0 0 1 0 0
+        "#]]
+        .assert_eq(&output);
+    }
+
+    /// The $E_2$ page of the sphere. In $\Ext^1$, this is $\mathbb{Z}/p^{1 + \nu_p(k)}$ in degree
+    /// $2k(p - 1)$ at odd primes, and at $p = 2$ it is $\mathbb{Z}/2$ for $k$ odd, $\mathbb{Z}/4$
+    /// for $k = 2$ and $\mathbb{Z}/2^{\nu_2(k) + 2}$ for $k \geq 4$ even, which is the image of
+    /// J.
+    #[test]
+    fn test_sphere() {
+        let mut output = String::new();
+        for (p, max) in [(2, Bidegree::n_s(12, 4)), (3, Bidegree::n_s(24, 3))] {
+            let e2 = AdamsNovikovE2::new(ValidPrime::new(p), 0, max).unwrap();
+            writeln!(output, "p = {p}:").unwrap();
+            for s in 0..=max.s() {
+                for n in 0..=max.n() {
+                    let b = Bidegree::n_s(n, s);
+                    if e2.dimension(b) > 0 {
+                        writeln!(output, "{b}: {}", e2.summands(b).iter().join(" + ")).unwrap();
+                    }
+                }
+            }
+        }
+        expect![[r#"
+            p = 2:
+            (0, 0): Z_(p)
+            (1, 1): Z/p
+            (3, 1): Z/p^2
+            (5, 1): Z/p
+            (7, 1): Z/p^4
+            (9, 1): Z/p
+            (11, 1): Z/p^3
+            (2, 2): Z/p
+            (6, 2): Z/p + Z/p
+            (8, 2): Z/p + Z/p
+            (10, 2): Z/p
+            (12, 2): Z/p
+            (3, 3): Z/p
+            (7, 3): Z/p
+            (9, 3): Z/p + Z/p
+            (11, 3): Z/p
+            (4, 4): Z/p
+            (8, 4): Z/p
+            (10, 4): Z/p
+            (12, 4): Z/p
+            p = 3:
+            (0, 0): Z_(p)
+            (3, 1): Z/p
+            (7, 1): Z/p
+            (11, 1): Z/p^2
+            (15, 1): Z/p
+            (19, 1): Z/p
+            (23, 1): Z/p^2
+            (10, 2): Z/p
+            (13, 3): Z/p
+        "#]]
+        .assert_eq(&output);
+    }
+}
@@ -0,0 +1,439 @@
+//! Structured progress reporting for long resolutions.
+//!
+//! [`MuResolution::compute_through_stem_with_progress`] and
+//! [`MuResolution::compute_through_bidegree_with_progress`] emit a [`ProgressEvent`] whenever a
+//! bidegree is started or finished. The events can be fed into a [`ProgressTracker`], which
+//! estimates the remaining time and draws a chart of the frontier of the computation, or into a
+//! [`ProgressReporter`], which does so live on `stderr` or writes the events as JSON lines for
+//! consumption by other programs.
+//!
+//! [`MuResolution::compute_through_stem_with_progress`]:
+//!     crate::resolution::MuResolution::compute_through_stem_with_progress
+//! [`MuResolution::compute_through_bidegree_with_progress`]:
+//!     crate::resolution::MuResolution::compute_through_bidegree_with_progress
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, IsTerminal, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use serde_json::{Value, json};
+use sseq::coordinates::Bidegree;
+
+use crate::chain_complex::ChainComplex;
+
+/// Statistics about the computation of a bidegree of a resolution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StepStats {
+    /// Whether the bidegree was loaded from the save directory instead of computed.
+    pub loaded: bool,
+    pub num_new_gens: usize,
+    /// The number of rows of the matrix that is row reduced, i.e. the dimension of the source.
+    pub matrix_rows: usize,
+    /// The number of columns of the matrix that is row reduced.
+    pub matrix_columns: usize,
+    /// The time spent computing the matrices of the differential and augmentation map, which is
+    /// dominated by the module action.
+    pub action_time: Duration,
+    /// The time spent row reducing the matrix and computing the new generators and
+    /// quasi-inverses.
+    pub reduction_time: Duration,
+    /// The total time spent on the bidegree, including reading and writing save files.
+    pub time: Duration,
+    /// The number of bytes written to the save directory, before compression.
+    pub bytes_written: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// We started computing (or loading) a bidegree.
+    Started(Bidegree),
+    /// We finished computing (or loading) a bidegree.
+    Finished(Bidegree, StepStats),
+}
+
+impl ProgressEvent {
+    pub fn degree(&self) -> Bidegree {
+        match self {
+            Self::Started(b) | Self::Finished(b, _) => *b,
+        }
+    }
+
+    /// The event as a JSON object. Durations are given in seconds.
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Started(b) => json!({
+                "event": "started",
+                "s": b.s(),
+                "t": b.t(),
+            }),
+            Self::Finished(b, stats) => json!({
+                "event": "finished",
+                "s": b.s(),
+                "t": b.t(),
+                "loaded": stats.loaded,
+                "num_new_gens": stats.num_new_gens,
+                "matrix_rows": stats.matrix_rows,
+                "matrix_columns": stats.matrix_columns,
+                "action_time": stats.action_time.as_secs_f64(),
+                "reduction_time": stats.reduction_time.as_secs_f64(),
+                "time": stats.time.as_secs_f64(),
+                "bytes_written": stats.bytes_written,
+            }),
+        }
+    }
+}
+
+/// The number of recently computed bidegrees whose timings are used to estimate the remaining
+/// time. The bidegrees get more expensive as the computation progresses, so older timings are
+/// not representative.
+const RECENT_STEPS: usize = 32;
+
+/// The width of the bars in [`ProgressTracker::chart`].
+const CHART_WIDTH: usize = 50;
+
+/// Keeps track of the progress of a resolution towards a fixed range.
+pub struct ProgressTracker {
+    start: Instant,
+    /// For each `s`, the number of bidegrees that are done and that are to be done in total.
+    rows: Vec<(usize, usize)>,
+    done: usize,
+    total: usize,
+    /// The total time spent on bidegrees that were computed, which is larger than the elapsed
+    /// time if we compute several bidegrees in parallel.
+    busy_time: Duration,
+    /// The time spent on the most recently computed bidegrees.
+    recent: VecDeque<Duration>,
+}
+
+impl ProgressTracker {
+    /// Track the progress of `res.compute_through_stem(max)`. Bidegrees that are already computed
+    /// are not counted.
+    pub fn through_stem(res: &impl ChainComplex, max: Bidegree) -> Self {
+        Self::new(res, max.s(), |s| max.n() + s)
+    }
+
+    /// Track the progress of `res.compute_through_bidegree(max)`. Bidegrees that are already
+    /// computed are not counted.
+    pub fn through_bidegree(res: &impl ChainComplex, max: Bidegree) -> Self {
+        Self::new(res, max.s(), |_| max.t())
+    }
+
+    fn new(res: &impl ChainComplex, max_s: i32, max_t: impl Fn(i32) -> i32) -> Self {
+        let rows: Vec<(usize, usize)> = (0..=max_s)
+            .map(|s| {
+                let total = (res.min_degree()..=max_t(s))
+                    .filter(|&t| !res.has_computed_bidegree(Bidegree::s_t(s, t)))
+                    .count();
+                (0, total)
+            })
+            .collect();
+        Self {
+            start: Instant::now(),
+            total: rows.iter().map(|&(_, total)| total).sum(),
+            rows,
+            done: 0,
+            busy_time: Duration::ZERO,
+            recent: VecDeque::with_capacity(RECENT_STEPS),
+        }
+    }
+
+    pub fn update(&mut self, event: &ProgressEvent) {
+        let ProgressEvent::Finished(b, stats) = event else {
+            return;
+        };
+        if let Some((done, _)) = self.rows.get_mut(b.s() as usize) {
+            *done += 1;
+        }
+        self.done += 1;
+        if !stats.loaded {
+            self.busy_time += stats.time;
+            if self.recent.len() == RECENT_STEPS {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(stats.time);
+        }
+    }
+
+    /// The number of bidegrees that are done.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// The number of bidegrees that are to be done in total.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// A rough estimate of the remaining time. This assumes that the remaining bidegrees take as
+    /// long as the recently computed ones, and that we keep computing as many bidegrees in
+    /// parallel as we have so far. This is `None` if nothing has been computed yet.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.recent.is_empty() || self.busy_time.is_zero() {
+            return None;
+        }
+        let recent = self.recent.iter().sum::<Duration>() / self.recent.len() as u32;
+        let remaining_work = recent.as_secs_f64() * self.total.saturating_sub(self.done) as f64;
+        let parallelism = self.busy_time.as_secs_f64() / self.elapsed().as_secs_f64();
+        Some(Duration::from_secs_f64(
+            remaining_work / parallelism.max(1.0),
+        ))
+    }
+
+    /// A chart of how far the computation has progressed in each homological degree, with the
+    /// largest `s` at the top, followed by a summary line.
+    pub fn chart(&self) -> String {
+        let mut result = String::new();
+        let width = self.rows.len().saturating_sub(1).to_string().len();
+        for (s, &(done, total)) in self.rows.iter().enumerate().rev() {
+            let filled = (done * CHART_WIDTH)
+                .checked_div(total)
+                .unwrap_or(CHART_WIDTH);
+            result.push_str(&format!(
+                "{s:>width$} {}{} {done}/{total}\n",
+                "#".repeat(filled),
+                ".".repeat(CHART_WIDTH - filled),
+            ));
+        }
+        result.push_str(&self.to_string());
+        result
+    }
+}
+
+impl fmt::Display for ProgressTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} bidegrees, elapsed {}",
+            self.done,
+            self.total,
+            FormatDuration(self.elapsed())
+        )?;
+        if let Some(eta) = self.eta() {
+            write!(f, ", ETA {}", FormatDuration(eta))?;
+        }
+        Ok(())
+    }
+}
+
+/// Format a duration as e.g. `1h 02m 03s`.
+struct FormatDuration(Duration);
+
+impl fmt::Display for FormatDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if h > 0 {
+            write!(f, "{h}h {m:02}m {s:02}s")
+        } else if m > 0 {
+            write!(f, "{m}m {s:02}s")
+        } else {
+            write!(f, "{s}s")
+        }
+    }
+}
+
+/// Where a [`ProgressReporter`] reports progress to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressOutput {
+    /// Draw the [`ProgressTracker::chart`] on `stderr`, redrawing it as the computation
+    /// progresses.
+    Chart,
+    /// Write each event as a line of JSON to the given file, or `stderr` if `None`. The
+    /// [`ProgressEvent::to_json`] of each finished event is augmented with the number of
+    /// bidegrees `done` and the `total` number of bidegrees, as well as the `elapsed` time and the
+    /// `eta` in seconds.
+    Json(Option<PathBuf>),
+}
+
+impl std::str::FromStr for ProgressOutput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "chart" => Ok(Self::Chart),
+            "json" => Ok(Self::Json(None)),
+            _ => match s.strip_prefix("json:") {
+                Some(path) => Ok(Self::Json(Some(PathBuf::from(path)))),
+                None => Err(anyhow::anyhow!(
+                    "Invalid progress output {s:?}. Expected chart, json or json:<path>"
+                )),
+            },
+        }
+    }
+}
+
+/// How often the live chart is redrawn.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Reports the progress of a resolution to a [`ProgressOutput`].
+pub struct ProgressReporter {
+    tracker: ProgressTracker,
+    output: Option<ProgressOutput>,
+    json: Option<Box<dyn Write>>,
+    /// The number of lines of the chart we last drew, and when we did so.
+    drawn: Option<(usize, Instant)>,
+}
+
+impl ProgressReporter {
+    pub fn new(tracker: ProgressTracker, output: Option<ProgressOutput>) -> anyhow::Result<Self> {
+        let json: Option<Box<dyn Write>> = match &output {
+            Some(ProgressOutput::Json(Some(path))) => {
+                Some(Box::new(io::BufWriter::new(std::fs::File::create(path)?)))
+            }
+            Some(ProgressOutput::Json(None)) => Some(Box::new(io::stderr())),
+            _ => None,
+        };
+        Ok(Self {
+            tracker,
+            output,
+            json,
+            drawn: None,
+        })
+    }
+
+    /// A reporter whose output is determined by the `PROGRESS` environment variable, which is
+    /// parsed as a [`ProgressOutput`]. If it is not set, progress is not reported. The chart is
+    /// only drawn if `stderr` is a terminal.
+    pub fn from_env(tracker: ProgressTracker) -> anyhow::Result<Self> {
+        let output = match std::env::var("PROGRESS") {
+            Ok(val) => Some(val.parse()?),
+            Err(_) => None,
+        }
+        .filter(|output| *output != ProgressOutput::Chart || io::stderr().is_terminal());
+        Self::new(tracker, output)
+    }
+
+    pub fn tracker(&self) -> &ProgressTracker {
+        &self.tracker
+    }
+
+    pub fn update(&mut self, event: &ProgressEvent) {
+        self.tracker.update(event);
+        if let Some(json) = &mut self.json {
+            let mut value = event.to_json();
+            if let ProgressEvent::Finished(..) = event {
+                let tracker = &self.tracker;
+                value["done"] = json!(tracker.done());
+                value["total"] = json!(tracker.total());
+                value["elapsed"] = json!(tracker.elapsed().as_secs_f64());
+                value["eta"] = json!(tracker.eta().map(|eta| eta.as_secs_f64()));
+            }
+            writeln!(json, "{value}").unwrap();
+        }
+        if self.output == Some(ProgressOutput::Chart)
+            && self
+                .drawn
+                .is_none_or(|(_, time)| time.elapsed() >= REDRAW_INTERVAL)
+        {
+            self.draw();
+        }
+    }
+
+    /// Report the final state. This should be called when the computation is done.
+    pub fn finish(mut self) {
+        match self.output {
+            Some(ProgressOutput::Chart) => self.draw(),
+            Some(ProgressOutput::Json(_)) => self.json.as_mut().unwrap().flush().unwrap(),
+            None => {}
+        }
+    }
+
+    fn draw(&mut self) {
+        let chart = self.tracker.chart();
+        let mut stderr = io::stderr().lock();
+        if let Some((lines, _)) = self.drawn {
+            // Move to the start of the previous chart. We clear each line as we redraw it.
+            write!(stderr, "\x1b[{lines}F").unwrap();
+        }
+        for line in chart.lines() {
+            writeln!(stderr, "\x1b[2K{line}").unwrap();
+        }
+        self.drawn = Some((chart.lines().count(), Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+    use crate::{chain_complex::FreeChainComplex, utils::construct_standard};
+
+    #[test]
+    fn test_progress_events() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let max = Bidegree::n_s(10, 4);
+
+        let res = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        let num_bidegrees = ProgressTracker::through_stem(&res, max).total();
+        // Track progress towards a larger range than we compute.
+        let mut tracker = ProgressTracker::through_stem(&res, Bidegree::n_s(20, 4));
+        let mut started = Vec::new();
+        res.compute_through_stem_with_progress(max, |event| {
+            tracker.update(&event);
+            match event {
+                ProgressEvent::Started(b) => started.push(b),
+                ProgressEvent::Finished(b, stats) => {
+                    assert!(started.contains(&b), "{b} finished before it started");
+                    assert!(!stats.loaded);
+                    assert!(stats.bytes_written > 0);
+                    assert_eq!(stats.num_new_gens, res.number_of_gens_in_bidegree(b));
+                    assert!(stats.action_time + stats.reduction_time <= stats.time);
+                }
+            }
+        })
+        .unwrap();
+        assert_eq!(started.len(), num_bidegrees);
+        assert_eq!(tracker.done(), started.len());
+        assert!(tracker.eta().is_some());
+
+        let chart = tracker.chart();
+        let (bars, summary) = chart.rsplit_once('\n').unwrap();
+        expect![[r#"
+            4 ##############################.................... 15/25
+            3 #############################..................... 14/24
+            2 ############################...................... 13/23
+            1 ###########################....................... 12/22
+            0 ##########################........................ 11/21"#]]
+        .assert_eq(bars);
+        assert!(summary.starts_with("65/115 bidegrees"), "{summary}");
+
+        // Now everything is loaded from the save directory.
+        let res = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        let mut finished = 0;
+        res.compute_through_stem_with_progress(max, |event| {
+            if let ProgressEvent::Finished(_, stats) = event {
+                assert!(stats.loaded);
+                assert_eq!(stats.bytes_written, 0);
+                finished += 1;
+            }
+        })
+        .unwrap();
+        assert_eq!(finished, num_bidegrees);
+    }
+
+    #[test]
+    fn test_progress_output_parse() {
+        assert_eq!(
+            "chart".parse::<ProgressOutput>().unwrap(),
+            ProgressOutput::Chart
+        );
+        assert_eq!(
+            "json".parse::<ProgressOutput>().unwrap(),
+            ProgressOutput::Json(None)
+        );
+        assert_eq!(
+            "json:progress.jsonl".parse::<ProgressOutput>().unwrap(),
+            ProgressOutput::Json(Some(PathBuf::from("progress.jsonl")))
+        );
+        assert!("csv".parse::<ProgressOutput>().is_err());
+    }
+}
@@ -1,6 +1,10 @@
 //! This module exports the [`Resolution`] object, which is a chain complex resolving a module. In
 //! particular, this contains the core logic that compute minimal resolutions.
-use std::sync::{Arc, Mutex, mpsc};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{Arc, Mutex, mpsc},
+    time::{Duration, Instant},
+};
 
 use algebra::{
     Algebra, MuAlgebra,
@@ -13,7 +17,8 @@ use anyhow::Context;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use dashmap::DashMap;
 use fp::{
-    matrix::{AugmentedMatrix, QuasiInverse, Subspace},
+    matrix::{AugmentedMatrix, Matrix, QuasiInverse, Subspace},
+    prime::Prime,
     vector::{FpSlice, FpSliceMut, FpVector},
 };
 use itertools::Itertools;
@@ -22,8 +27,9 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator};
 
 use crate::{
     chain_complex::{AugmentedChainComplex, ChainComplex},
-    save::{SaveDirectory, SaveKind},
-    utils::parallel::ParallelGuard,
+    progress::{ProgressEvent, StepStats},
+    save::{SaveDirectory, SaveKind, SaveLock, SaveManifest},
+    utils::{LogWriter, parallel::ParallelGuard},
 };
 
 /// In [`MuResolution::compute_through_stem`] and [`MuResolution::compute_through_bidegree`], we pass
@@ -31,10 +37,17 @@ use crate::{
 /// explicit struct instead of a tuple to avoid an infinite type problem.
 struct SenderData {
     b: Bidegree,
-    /// Whether this bidegree was newly calculated or have already been calculated.
-    new: bool,
-    /// Whether this job should be retried due to priority inversion avoidance.
-    retry: bool,
+    /// The statistics of the computation if this bidegree was newly calculated, and `None` if it
+    /// has already been calculated.
+    stats: Option<StepStats>,
+    /// If this job should be retried, e.g. due to priority inversion avoidance, the time at which
+    /// to do so.
+    retry: Option<Instant>,
+    /// Whether this only informs the supervisor that we started working on this bidegree.
+    started: bool,
+    /// If this bidegree could not be computed, the reason why. The supervisor then stops scheduling
+    /// new bidegrees and returns the error once the running ones have finished.
+    error: Option<anyhow::Error>,
     /// The sender object used to send the `SenderData`. We put this in the struct and pass it
     /// around the mpsc, so that when all senders are dropped, we know the computation has
     /// completed. Compared to keeping track of calculations manually, this has the advantage of
@@ -43,23 +56,53 @@ struct SenderData {
 }
 
 impl SenderData {
-    fn send(b: Bidegree, new: bool, sender: mpsc::Sender<Self>) {
+    fn send(b: Bidegree, stats: Option<StepStats>, sender: mpsc::Sender<Self>) {
+        sender
+            .send(Self {
+                b,
+                stats,
+                retry: None,
+                started: false,
+                error: None,
+                sender: sender.clone(),
+            })
+            .unwrap()
+    }
+
+    fn send_retry(b: Bidegree, at: Instant, sender: mpsc::Sender<Self>) {
         sender
             .send(Self {
                 b,
-                new,
-                retry: false,
+                stats: None,
+                retry: Some(at),
+                started: false,
+                error: None,
                 sender: sender.clone(),
             })
             .unwrap()
     }
 
-    fn send_retry(b: Bidegree, sender: mpsc::Sender<Self>) {
+    fn send_started(b: Bidegree, sender: &mpsc::Sender<Self>) {
         sender
             .send(Self {
                 b,
-                new: false,
-                retry: true,
+                stats: None,
+                retry: None,
+                started: true,
+                error: None,
+                sender: sender.clone(),
+            })
+            .unwrap()
+    }
+
+    fn send_error(b: Bidegree, error: anyhow::Error, sender: mpsc::Sender<Self>) {
+        sender
+            .send(Self {
+                b,
+                stats: None,
+                retry: None,
+                started: false,
+                error: Some(error),
                 sender: sender.clone(),
             })
             .unwrap()
@@ -72,6 +115,176 @@ impl SenderData {
 /// number if needs be, but up to the 140th stem we only see at most 8 new generators.
 const MAX_NEW_GENS: usize = 10;
 
+/// In a distributed computation, how long we wait before checking again whether a bidegree claimed
+/// by another process has been computed.
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How many times in a row we fail to claim a bidegree before warning that it is still locked.
+/// With [`LOCK_POLL_INTERVAL`], this is about a minute.
+const LOCK_WARNING_ATTEMPTS: u32 = 600;
+
+/// Handle the result of [`MuResolution::lock_bidegree`] when a worker tries to claim `b`. If
+/// another process holds the claim, we ask the supervisor to retry `b` after
+/// [`LOCK_POLL_INTERVAL`] and return `None`. If the lock file cannot be created, we pass the error
+/// on to the supervisor and return `None`.
+fn claim_or_retry(
+    b: Bidegree,
+    sender: &mpsc::Sender<SenderData>,
+    lock: anyhow::Result<Option<SaveLock>>,
+) -> Option<SaveLock> {
+    match lock {
+        Ok(None) => {
+            SenderData::send_retry(b, Instant::now() + LOCK_POLL_INTERVAL, sender.clone());
+            None
+        }
+        Ok(lock) => lock,
+        Err(e) => {
+            SenderData::send_error(b, e, sender.clone());
+            None
+        }
+    }
+}
+
+/// Receive the next message for the supervisor of a computation. Retries that are not due yet are
+/// kept in `delayed` until they are, so that no worker thread has to wait for them. Returns `None`
+/// once all senders are dropped.
+fn recv_supervisor(
+    receiver: &mpsc::Receiver<SenderData>,
+    delayed: &mut VecDeque<SenderData>,
+) -> Option<SenderData> {
+    loop {
+        // All retries are delayed by the same amount, so `delayed` is (essentially) sorted.
+        let data = match delayed.front().and_then(|data| data.retry) {
+            Some(at) if at <= Instant::now() => return delayed.pop_front(),
+            Some(at) => match receiver.recv_timeout(at.saturating_duration_since(Instant::now())) {
+                Ok(data) => data,
+                Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                // The senders in `delayed` keep the channel open.
+                Err(mpsc::RecvTimeoutError::Disconnected) => unreachable!(),
+            },
+            None => receiver.recv().ok()?,
+        };
+        match data.retry {
+            Some(at) if at > Instant::now() => delayed.push_back(data),
+            _ => return Some(data),
+        }
+    }
+}
+
+/// The kernels and quasi-inverses that are kept in memory when a resolution has a
+/// [`memory_budget`](MuResolution::memory_budget). All of them have also been written to the save
+/// directory, so they can be reloaded once they are evicted.
+///
+/// When the total size exceeds the budget, the least recently used quasi-inverses are dropped. A
+/// new kernel is only kept in memory if it fits in the budget after dropping all quasi-inverses,
+/// since the kernels are needed again much sooner.
+#[derive(Default)]
+struct ResidentData {
+    /// The quasi-inverses, their approximate size in bytes and when they were last used.
+    quasi_inverses: HashMap<Bidegree, (Arc<QuasiInverse>, usize, u64)>,
+    /// The keys of `quasi_inverses`, ordered by when they were last used.
+    by_last_used: BTreeMap<u64, Bidegree>,
+    /// The total size of the quasi-inverses.
+    quasi_inverse_size: usize,
+    /// The total size of the kernels in [`MuResolution::kernels`].
+    kernel_size: usize,
+    clock: u64,
+}
+
+impl ResidentData {
+    fn quasi_inverse(&mut self, b: Bidegree) -> Option<Arc<QuasiInverse>> {
+        let (qi, _, last_used) = self.quasi_inverses.get_mut(&b)?;
+        self.clock += 1;
+        self.by_last_used.remove(last_used);
+        self.by_last_used.insert(self.clock, b);
+        *last_used = self.clock;
+        Some(Arc::clone(qi))
+    }
+
+    /// Whether a quasi-inverse of `size` bytes can be kept in memory, possibly after dropping all
+    /// other quasi-inverses.
+    fn fits_quasi_inverse(&self, size: usize, budget: usize) -> bool {
+        self.kernel_size + size <= budget
+    }
+
+    fn insert_quasi_inverse(&mut self, b: Bidegree, qi: Arc<QuasiInverse>, budget: usize) {
+        self.clock += 1;
+        let size = matrix_size(qi.preimage()) + qi.pivots().map_or(0, std::mem::size_of_val);
+        if let Some((_, old_size, old_last_used)) =
+            self.quasi_inverses.insert(b, (qi, size, self.clock))
+        {
+            self.quasi_inverse_size -= old_size;
+            self.by_last_used.remove(&old_last_used);
+        }
+        self.by_last_used.insert(self.clock, b);
+        self.quasi_inverse_size += size;
+        self.evict(budget);
+    }
+
+    /// Account for a kernel of `size` bytes. Returns `false` if it does not fit in the budget, in
+    /// which case the kernel should not be kept in memory.
+    fn reserve_kernel(&mut self, size: usize, budget: usize) -> bool {
+        if self.kernel_size + size > budget {
+            return false;
+        }
+        self.kernel_size += size;
+        self.evict(budget);
+        true
+    }
+
+    fn release_kernel(&mut self, size: usize) {
+        self.kernel_size -= size;
+    }
+
+    /// Drop the least recently used quasi-inverses until we are within the budget.
+    fn evict(&mut self, budget: usize) {
+        while self.kernel_size + self.quasi_inverse_size > budget {
+            let Some((_, oldest)) = self.by_last_used.pop_first() else {
+                break;
+            };
+            let (_, size, _) = self.quasi_inverses.remove(&oldest).unwrap();
+            self.quasi_inverse_size -= size;
+        }
+    }
+}
+
+/// The approximate number of bytes used by a matrix over $\mathbb{F}_p$ with the given dimensions.
+fn packed_size(p: impl Prime, rows: usize, columns: usize) -> usize {
+    let p = p.as_u32();
+    let bits_per_entry = (u32::BITS - (p - 1).leading_zeros()) as usize;
+    rows * (columns * bits_per_entry).div_ceil(64) * 8
+}
+
+/// The approximate number of bytes used by `matrix`.
+fn matrix_size(matrix: &Matrix) -> usize {
+    packed_size(matrix.prime(), matrix.rows(), matrix.columns())
+}
+
+/// The approximate number of bytes used by `kernel`.
+fn kernel_size(kernel: &Subspace) -> usize {
+    matrix_size(kernel) + std::mem::size_of_val(kernel.pivots())
+}
+
+/// The weight of a new generator of a resolution, for algebras with a weight grading such as the
+/// motivic Steenrod algebras. This is the weight of the first term of its image `a_row` under the
+/// augmentation, or if that is zero, of its boundary `d_row`. These are homogeneous since they are
+/// rows of reduced matrices of weight-preserving maps. The weight is zero if there is no grading.
+fn new_generator_weight(
+    target_cc: &impl Module,
+    target_res: &impl Module,
+    t: i32,
+    a_row: FpSlice,
+    d_row: FpSlice,
+) -> i32 {
+    if let Some((i, _)) = a_row.iter_nonzero().next() {
+        return target_cc.basis_element_weight(t, i);
+    }
+    match d_row.iter_nonzero().next() {
+        Some((i, _)) if i < target_res.dimension(t) => target_res.basis_element_weight(t, i),
+        _ => 0,
+    }
+}
+
 pub type Resolution<CC> = MuResolution<false, CC>;
 pub type UnstableResolution<CC> = MuResolution<true, CC>;
 
@@ -93,6 +306,9 @@ where
     ///  returned by `generate_old_kernel_and_compute_new_kernel`, to be used if we run
     ///  compute_through_degree again.
     kernels: DashMap<Bidegree, Subspace>,
+    /// In a distributed computation, the number of consecutive failed attempts to claim each
+    /// bidegree, which we use to warn about bidegrees that stay locked.
+    lock_attempts: DashMap<Bidegree, u32>,
     save_dir: SaveDirectory,
 
     /// Whether we should save newly computed data to the disk. This has no effect if there is no
@@ -110,6 +326,35 @@ where
     /// augmentation map are useful when the target chain complex is not concentrated in one
     /// degree, and they tend to be quite small anyway.
     pub load_quasi_inverse: bool,
+
+    /// Whether other processes may be computing the same resolution in our save directory, e.g.
+    /// several nodes of a cluster with a shared filesystem. Defaults to false.
+    ///
+    /// If set, we claim each bidegree with a lock file before computing it, and bidegrees claimed
+    /// by other processes are loaded from the save directory once they are done. Each process
+    /// simply runs [`MuResolution::compute_through_stem`] as usual, and the processes split the
+    /// work on the frontier between them. This requires a save directory that is not an archive.
+    pub distributed: bool,
+
+    /// The maximum number of bytes of kernels and quasi-inverses to cache in memory, if any.
+    /// Defaults to `None`.
+    ///
+    /// If set and we save to a (combined) save directory, then the kernels and the quasi-inverses
+    /// of the differentials are kept in memory only as long as they fit in this many bytes. The
+    /// rest are dropped once they are written to disk and reloaded lazily when they are needed,
+    /// e.g. by [`ChainComplex::apply_quasi_inverse`] when computing products or chain homotopies.
+    /// A quasi-inverse that can never fit is streamed from disk instead of being loaded. This
+    /// takes precedence over [`MuResolution::load_quasi_inverse`], and is ignored without such a
+    /// save directory.
+    ///
+    /// This bounds the kernels and quasi-inverses only, and not the total memory usage. The free
+    /// modules, differentials and augmentation maps are never evicted, since extending the
+    /// resolution to a new internal degree evaluates every earlier differential in that degree, so
+    /// the differentials of low bidegrees are never out of the frontier. They record one vector for
+    /// each generator, while the kernels and quasi-inverses record one for each basis element of
+    /// the free modules, which are far more numerous.
+    pub memory_budget: Option<usize>,
+    resident: Mutex<ResidentData>,
 }
 
 impl<const U: bool, CC: ChainComplex> MuResolution<U, CC>
@@ -130,6 +375,7 @@ where
         let min_degree = complex.min_degree();
         let zero_module = Arc::new(MuFreeModule::new(algebra, "F_{-1}".to_string(), min_degree));
 
+        save_dir.check_manifest(&SaveManifest::new(&*complex.algebra()))?;
         if let Some(p) = save_dir.write() {
             for subdir in SaveKind::resolution_data() {
                 subdir.create_dir(p)?;
@@ -148,7 +394,11 @@ where
             modules: OnceBiVec::new(0),
             differentials: OnceVec::new(),
             kernels: DashMap::new(),
+            lock_attempts: DashMap::new(),
             load_quasi_inverse: true,
+            distributed: false,
+            memory_budget: None,
+            resident: Mutex::new(ResidentData::default()),
         })
     }
 
@@ -160,12 +410,17 @@ where
         &self.name
     }
 
-    fn add_generators(&self, b: Bidegree, num_new_gens: usize) {
-        let gen_names = (0..num_new_gens)
+    /// Add generators with the given weights in bidegree `b`. See [`new_generator_weight`].
+    fn add_generators(&self, b: Bidegree, weights: Vec<i32>) {
+        let gen_names = (0..weights.len())
             .map(|idx| format!("x_{:#}", BidegreeGenerator::new(b, idx)))
             .collect();
-        self.module(b.s())
-            .add_generators(b.t(), num_new_gens, Some(gen_names));
+        self.module(b.s()).add_generators_with_weights(
+            b.t(),
+            weights.len(),
+            Some(gen_names),
+            weights,
+        );
     }
 
     /// This function prepares the Resolution object to perform computations up to the
@@ -206,6 +461,123 @@ where
         }
     }
 
+    /// Whether quasi-inverses and kernels are dropped from memory once they are written to disk.
+    /// See [`MuResolution::memory_budget`].
+    fn evicts_to_disk(&self) -> bool {
+        self.memory_budget.is_some()
+            && self.should_save
+            && matches!(self.save_dir, SaveDirectory::Combined(_))
+    }
+
+    /// The quasi-inverse of the differential at `b`, from memory or the save directory. This is
+    /// `None` if we do not evict quasi-inverses to disk, the quasi-inverse was not saved, or it is
+    /// too large to ever fit in the memory budget. In the last case it should be streamed from the
+    /// save directory instead, so that we never hold all of it in memory.
+    fn resident_quasi_inverse(&self, b: Bidegree) -> anyhow::Result<Option<Arc<QuasiInverse>>> {
+        if !self.evicts_to_disk() {
+            return Ok(None);
+        }
+        let budget = self.memory_budget.unwrap();
+        if let Some(qi) = self.resident.lock().unwrap().quasi_inverse(b) {
+            return Ok(Some(qi));
+        }
+
+        // An upper bound for the size of the quasi-inverse, which has at most one row for each
+        // basis element of the target.
+        let d = self.differential(b.s());
+        let target_dim = d.target().dimension(b.t());
+        let max_size = packed_size(self.prime(), target_dim, d.source().dimension(b.t()))
+            + target_dim * std::mem::size_of::<isize>();
+        if !self
+            .resident
+            .lock()
+            .unwrap()
+            .fits_quasi_inverse(max_size, budget)
+        {
+            return Ok(None);
+        }
+
+        let Some(dir) = self.save_dir.read() else {
+            return Ok(None);
+        };
+        let Some(mut f) = self.save_file(SaveKind::ResQi, b).open_file(dir.clone()) else {
+            return Ok(None);
+        };
+        let qi = match QuasiInverse::from_bytes(self.prime(), &mut f) {
+            Ok(qi) => Arc::new(qi),
+            Err(e) => {
+                // We did not read all of the file, so we cannot verify its checksum.
+                f.abort();
+                return Err(e).with_context(|| format!("Failed to read quasi-inverse at {b}"));
+            }
+        };
+        drop(f);
+        self.resident
+            .lock()
+            .unwrap()
+            .insert_quasi_inverse(b, Arc::clone(&qi), budget);
+        Ok(Some(qi))
+    }
+
+    /// Keep the kernel at `b` in memory until [`MuResolution::get_kernel`] asks for it. If we evict
+    /// to disk and the kernel does not fit in the memory budget, it is read back from the save
+    /// directory instead.
+    fn keep_kernel(&self, b: Bidegree, kernel: Subspace) {
+        if self.evicts_to_disk()
+            && !self
+                .resident
+                .lock()
+                .unwrap()
+                .reserve_kernel(kernel_size(&kernel), self.memory_budget.unwrap())
+        {
+            return;
+        }
+        self.kernels.insert(b, kernel);
+    }
+
+    /// Claim the bidegree `b` in a distributed computation, so that we are the only process that
+    /// computes it or reads its save files. Returns `None` if another process has claimed it, in
+    /// which case we should try again later.
+    ///
+    /// The kernel at `b` is also covered by the claim on `b + (1, 0)`, since it is read and deleted
+    /// when computing the latter.
+    ///
+    /// If a process crashes, its claims are broken once it is known to be dead or its lock files
+    /// are no longer refreshed (see [`SaveFile::try_lock`](crate::save::SaveFile::try_lock)).
+    /// Until then, we warn after every [`LOCK_WARNING_ATTEMPTS`] failed attempts in a row.
+    ///
+    /// This fails if we have no save directory, or if the lock file cannot be created, e.g.
+    /// because the save directory is an archive.
+    fn lock_bidegree(&self, b: Bidegree) -> anyhow::Result<Option<SaveLock>> {
+        let dir = self
+            .save_dir
+            .write()
+            .context("A distributed resolution requires a save directory")?;
+        let save_file = self.save_file(SaveKind::Differential, b);
+        let lock = save_file
+            .try_lock(dir.clone())
+            .with_context(|| format!("Failed to lock bidegree {b}"))?;
+        if lock.is_some() {
+            self.lock_attempts.remove(&b);
+            return Ok(lock);
+        }
+
+        let mut attempts = self.lock_attempts.entry(b).or_insert(0);
+        *attempts += 1;
+        if attempts.is_multiple_of(LOCK_WARNING_ATTEMPTS) {
+            let (path, owner) = save_file.lock_owner(dir.clone());
+            tracing::warn!(
+                path = ?path,
+                owner = owner.map(|owner| owner.to_string()),
+                "bidegree {b} has been locked by another process for {} attempts. If that process \
+                 is no longer running, its lock is broken once it has not been refreshed for a \
+                 while.",
+                *attempts,
+            );
+        }
+        Ok(None)
+    }
+
     /// Gets the kernel of the differential starting at $(s, t)$. If this was previously computed,
     /// we simply retrieve the value (and remove it from the cache). Otherwise, we compute the
     /// kernel. This requires the differential to be computed at $(s, t - 1)$, but not $(s, t)$
@@ -213,6 +585,12 @@ where
     #[tracing::instrument(skip(self), fields(%b))]
     fn get_kernel(&self, b: Bidegree) -> Subspace {
         if let Some((_, v)) = self.kernels.remove(&b) {
+            if self.evicts_to_disk() {
+                self.resident
+                    .lock()
+                    .unwrap()
+                    .release_kernel(kernel_size(&v));
+            }
             return v;
         }
 
@@ -346,8 +724,11 @@ where
     /// To run `step_resolution(s, t)`, we must have already had run `step_resolution(s, t - 1)`
     /// and `step_resolution(s - 1, t - 1)`. It is more efficient if we have in fact run
     /// `step_resolution(s - 1, t)`, so try your best to arrange calls to be run in this order.
+    ///
+    /// Returns statistics about the computation for progress reporting.
     #[tracing::instrument(skip(self), fields(%b, num_new_gens, density))]
-    fn step_resolution(&self, b: Bidegree) {
+    fn step_resolution(&self, b: Bidegree) -> StepStats {
+        let start = Instant::now();
         if b.s() == 0 {
             self.zero_module.extend_by_zero(b.t());
         }
@@ -371,7 +752,7 @@ where
         match current_differential.next_degree().cmp(&b.t()) {
             std::cmp::Ordering::Greater => {
                 // Already computed this degree.
-                return;
+                return StepStats::default();
             }
             std::cmp::Ordering::Less => {
                 // Haven't computed far enough yet
@@ -413,8 +794,6 @@ where
                 "Malformed data: mismatched augmentation target dimension"
             );
 
-            self.add_generators(b, num_new_gens);
-
             let mut d_targets = Vec::with_capacity(num_new_gens);
             let mut a_targets = Vec::with_capacity(num_new_gens);
 
@@ -426,11 +805,26 @@ where
                 a_targets.push(FpVector::from_bytes(p, target_cc_dimension, &mut f).unwrap());
             }
             drop(f);
+
+            let weights = d_targets
+                .iter()
+                .zip(&a_targets)
+                .map(|(d, a)| {
+                    new_generator_weight(
+                        &*target_cc,
+                        &*target_res,
+                        b.t(),
+                        a.as_slice(),
+                        d.as_slice(),
+                    )
+                })
+                .collect();
+            self.add_generators(b, weights);
             current_differential.add_generators_from_rows(b.t(), d_targets);
             current_chain_map.add_generators_from_rows(b.t(), a_targets);
 
             // res qi
-            if self.load_quasi_inverse {
+            if self.load_quasi_inverse && !self.evicts_to_disk() {
                 if let Some(mut f) = self.save_file(SaveKind::ResQi, b).open_file(dir.clone()) {
                     let res_qi = QuasiInverse::from_bytes(p, &mut f).unwrap();
 
@@ -475,7 +869,12 @@ where
 
             current_chain_map.set_kernel(b.t(), None);
             current_chain_map.set_image(b.t(), None);
-            return;
+            return StepStats {
+                loaded: true,
+                num_new_gens,
+                time: start.elapsed(),
+                ..Default::default()
+            };
         }
 
         let mut matrix = AugmentedMatrix::<3>::new_with_capacity(
@@ -487,33 +886,43 @@ where
         );
         // Get the map (d, f) : X_{s, t} -> X_{s-1, t} (+) C_{s, t} into matrix
 
+        let timer = Instant::now();
         {
             let _guard = ParallelGuard::new();
             current_chain_map.get_matrix(matrix.segment(0, 0), b.t());
             current_differential.get_matrix(matrix.segment(1, 1), b.t());
         }
+        let action_time = timer.elapsed();
         matrix.segment(2, 2).add_identity();
 
+        let timer = Instant::now();
         matrix.row_reduce();
 
-        if !self.has_computed_bidegree(b + Bidegree::s_t(1, 0)) {
-            let kernel = matrix.compute_kernel();
+        let kernel =
+            (!self.has_computed_bidegree(b + Bidegree::s_t(1, 0))).then(|| matrix.compute_kernel());
+        let mut reduction_time = timer.elapsed();
+        let mut bytes_written = 0;
+
+        if let Some(kernel) = kernel {
             if self.should_save
                 && let Some(dir) = self.save_dir.write()
             {
-                let mut f = self
-                    .save_file(SaveKind::Kernel, b)
-                    .create_file(dir.clone(), true);
+                let mut f = LogWriter::new(
+                    self.save_file(SaveKind::Kernel, b)
+                        .create_file(dir.clone(), true),
+                );
 
                 kernel
                     .to_bytes(&mut f)
                     .with_context(|| format!("Failed to write kernel at {b}"))
                     .unwrap();
+                bytes_written += f.bytes_written();
             }
 
-            self.kernels.insert(b, kernel);
+            self.keep_kernel(b, kernel);
         }
 
+        let timer = Instant::now();
         // Now add generators to surject onto C_{s, t}.
         // (For now we are just adding the eventual images of the new generators into matrix, we will update
         // X_{s,t} and f later).
@@ -564,7 +973,18 @@ where
             );
         }
         let num_new_gens = cc_new_gens.len() + res_new_gens.len();
-        self.add_generators(b, num_new_gens);
+        let weights = (source_dimension..source_dimension + num_new_gens)
+            .map(|i| {
+                new_generator_weight(
+                    &*target_cc,
+                    &*target_res,
+                    b.t(),
+                    matrix.row_segment(i, 0, 0),
+                    matrix.row_segment(i, 1, 1),
+                )
+            })
+            .collect();
+        self.add_generators(b, weights);
 
         let new_rows = source_dimension + num_new_gens;
 
@@ -650,6 +1070,7 @@ where
             }
         }
         let (cm_qi, res_qi) = matrix.compute_quasi_inverses();
+        reduction_time += timer.elapsed();
 
         tracing::Span::current().record("num_new_gens", num_new_gens);
         tracing::Span::current().record(
@@ -665,27 +1086,28 @@ where
             // opening the differentials first to make sure we are not overwriting anything.
 
             // Open differentials file
-            let mut f = self
-                .save_file(SaveKind::Differential, b)
-                .create_file(dir.clone(), false);
+            let mut f = LogWriter::new(
+                self.save_file(SaveKind::Differential, b)
+                    .create_file(dir.clone(), false),
+            );
 
             // Write resolution qi
-            res_qi
-                .to_bytes(
-                    &mut self
-                        .save_file(SaveKind::ResQi, b)
-                        .create_file(dir.clone(), true),
-                )
-                .unwrap();
+            let mut g = LogWriter::new(
+                self.save_file(SaveKind::ResQi, b)
+                    .create_file(dir.clone(), true),
+            );
+            res_qi.to_bytes(&mut g).unwrap();
+            bytes_written += g.bytes_written();
+            drop(g);
 
             // Write augmentation qi
-            cm_qi
-                .to_bytes(
-                    &mut self
-                        .save_file(SaveKind::AugmentationQi, b)
-                        .create_file(dir.clone(), true),
-                )
-                .unwrap();
+            let mut g = LogWriter::new(
+                self.save_file(SaveKind::AugmentationQi, b)
+                    .create_file(dir.clone(), true),
+            );
+            cm_qi.to_bytes(&mut g).unwrap();
+            bytes_written += g.bytes_written();
+            drop(g);
 
             // Write differentials
             f.write_u64::<LittleEndian>(num_new_gens as u64).unwrap();
@@ -703,6 +1125,7 @@ where
             for n in 0..num_new_gens {
                 current_chain_map.output(b.t(), n).to_bytes(&mut f).unwrap();
             }
+            bytes_written += f.bytes_written();
             drop(f);
 
             // Delete kernel
@@ -713,7 +1136,14 @@ where
             }
         }
 
-        if self.load_quasi_inverse {
+        if self.evicts_to_disk() {
+            current_differential.set_quasi_inverse(b.t(), None);
+            self.resident.lock().unwrap().insert_quasi_inverse(
+                b,
+                Arc::new(res_qi),
+                self.memory_budget.unwrap(),
+            );
+        } else if self.load_quasi_inverse {
             current_differential.set_quasi_inverse(b.t(), Some(res_qi));
         } else {
             current_differential.set_quasi_inverse(b.t(), None);
@@ -727,13 +1157,80 @@ where
 
         current_differential.set_kernel(b.t(), None);
         current_differential.set_image(b.t(), None);
+
+        StepStats {
+            loaded: false,
+            num_new_gens,
+            matrix_rows: source_dimension,
+            matrix_columns: target_cc_dimension + target_res_dimension + source_dimension,
+            action_time,
+            reduction_time,
+            time: start.elapsed(),
+            bytes_written,
+        }
     }
 
+    /// See [`MuResolution::compute_through_bidegree_with_progress`]. This panics if the
+    /// computation fails.
     pub fn compute_through_bidegree_with_callback(
         &self,
         max: Bidegree,
         mut cb: impl FnMut(Bidegree),
     ) {
+        self.compute_through_bidegree_with_progress(max, |event| {
+            if let ProgressEvent::Finished(b, _) = event {
+                cb(b);
+            }
+        })
+        .unwrap_or_else(|e| panic!("{e:#}"));
+    }
+
+    /// Compute the bidegree `b` on a new thread in `scope` unless it has already been computed,
+    /// and report to the supervisor of [`MuResolution::compute_through_stem_with_progress`] or
+    /// [`MuResolution::compute_through_bidegree_with_progress`] through `sender`.
+    fn spawn_step<'a>(
+        &'a self,
+        scope: &maybe_rayon::Scope<'a>,
+        tracing_span: &tracing::Span,
+        b: Bidegree,
+        sender: mpsc::Sender<SenderData>,
+    ) {
+        if self.has_computed_bidegree(b) {
+            SenderData::send(b, None, sender);
+            return;
+        }
+        let tracing_span = tracing_span.clone();
+        scope.spawn(move |_| {
+            let _tracing_guard = tracing_span.enter();
+            if crate::utils::parallel::is_in_parallel() {
+                SenderData::send_retry(b, Instant::now(), sender);
+                return;
+            }
+            let _lock = if self.distributed {
+                let Some(lock) = claim_or_retry(b, &sender, self.lock_bidegree(b)) else {
+                    return;
+                };
+                Some(lock)
+            } else {
+                None
+            };
+            SenderData::send_started(b, &sender);
+            let stats = self.step_resolution(b);
+            SenderData::send(b, Some(stats), sender);
+        });
+    }
+
+    /// Compute through the bidegree `max`, reporting progress to `cb`. We report the bidegrees
+    /// that are computed or loaded from the save directory, but not those that have already been
+    /// computed. The callback is run on the current thread.
+    ///
+    /// This fails if a bidegree cannot be claimed in a distributed computation, in which case the
+    /// bidegrees that are already running are finished first.
+    pub fn compute_through_bidegree_with_progress(
+        &self,
+        max: Bidegree,
+        mut cb: impl FnMut(ProgressEvent),
+    ) -> anyhow::Result<()> {
         let min_degree = self.min_degree();
         let _lock = self.lock.lock();
 
@@ -752,36 +1249,37 @@ where
             progress[0] = min_degree - 2;
 
             let (sender, receiver) = mpsc::channel();
-            SenderData::send(Bidegree::s_t(0, min_degree - 1), false, sender);
+            let mut delayed = VecDeque::new();
+            SenderData::send(Bidegree::s_t(0, min_degree - 1), None, sender);
 
-            let f = |b, sender| {
-                if self.has_computed_bidegree(b) {
-                    SenderData::send(b, false, sender);
-                } else {
-                    let tracing_span = tracing_span.clone();
-                    scope.spawn(move |_| {
-                        let _tracing_guard = tracing_span.enter();
-                        if crate::utils::parallel::is_in_parallel() {
-                            SenderData::send_retry(b, sender);
-                            return;
-                        }
-                        self.step_resolution(b);
-                        SenderData::send(b, true, sender);
-                    });
-                }
-            };
+            let f = |b, sender| self.spawn_step(scope, &tracing_span, b, sender);
 
-            while let Ok(SenderData {
+            let mut result = Ok(());
+            while let Some(SenderData {
                 b,
-                new,
+                stats,
                 retry,
+                started,
+                error,
                 sender,
-            }) = receiver.recv()
+            }) = recv_supervisor(&receiver, &mut delayed)
             {
-                if retry {
+                if let Some(e) = error {
+                    result = result.and(Err(e));
+                    continue;
+                }
+                if result.is_err() {
+                    // Wait for the running bidegrees to finish without starting new ones
+                    continue;
+                }
+                if retry.is_some() {
                     f(b, sender);
                     continue;
                 }
+                if started {
+                    cb(ProgressEvent::Started(b));
+                    continue;
+                }
                 assert!(progress[b.s() as usize] == b.t() - 1);
                 progress[b.s() as usize] = b.t();
 
@@ -792,11 +1290,12 @@ where
                 if b.s() < max.s() && progress[b.s() as usize + 1] == b.t() - 1 {
                     f(b + Bidegree::s_t(1, 0), sender);
                 }
-                if new {
-                    cb(b);
+                if let Some(stats) = stats {
+                    cb(ProgressEvent::Finished(b, stats));
                 }
             }
-        });
+            result
+        })
     }
 
     /// This function resolves up till a fixed stem instead of a fixed t.
@@ -805,7 +1304,24 @@ where
         self.compute_through_stem_with_callback(max, |_| ());
     }
 
+    /// See [`MuResolution::compute_through_stem_with_progress`]. This panics if the computation
+    /// fails.
     pub fn compute_through_stem_with_callback(&self, max: Bidegree, mut cb: impl FnMut(Bidegree)) {
+        self.compute_through_stem_with_progress(max, |event| {
+            if let ProgressEvent::Finished(b, _) = event {
+                cb(b);
+            }
+        })
+        .unwrap_or_else(|e| panic!("{e:#}"));
+    }
+
+    /// Compute through the stem of `max`, reporting progress to `cb`. See
+    /// [`MuResolution::compute_through_bidegree_with_progress`] for the events that are reported.
+    pub fn compute_through_stem_with_progress(
+        &self,
+        max: Bidegree,
+        mut cb: impl FnMut(ProgressEvent),
+    ) -> anyhow::Result<()> {
         let min_degree = self.min_degree();
         let _lock = self.lock.lock();
 
@@ -824,36 +1340,37 @@ where
             progress[0] = min_degree - 2;
 
             let (sender, receiver) = mpsc::channel();
-            SenderData::send(Bidegree::s_t(0, min_degree - 1), false, sender);
+            let mut delayed = VecDeque::new();
+            SenderData::send(Bidegree::s_t(0, min_degree - 1), None, sender);
 
-            let f = |b, sender| {
-                if self.has_computed_bidegree(b) {
-                    SenderData::send(b, false, sender);
-                } else {
-                    let tracing_span = tracing_span.clone();
-                    scope.spawn(move |_| {
-                        let _tracing_guard = tracing_span.enter();
-                        if crate::utils::parallel::is_in_parallel() {
-                            SenderData::send_retry(b, sender);
-                            return;
-                        }
-                        self.step_resolution(b);
-                        SenderData::send(b, true, sender);
-                    });
-                }
-            };
+            let f = |b, sender| self.spawn_step(scope, &tracing_span, b, sender);
 
-            while let Ok(SenderData {
+            let mut result = Ok(());
+            while let Some(SenderData {
                 b,
-                new,
+                stats,
                 retry,
+                started,
+                error,
                 sender,
-            }) = receiver.recv()
+            }) = recv_supervisor(&receiver, &mut delayed)
             {
-                if retry {
+                if let Some(e) = error {
+                    result = result.and(Err(e));
+                    continue;
+                }
+                if result.is_err() {
+                    // Wait for the running bidegrees to finish without starting new ones
+                    continue;
+                }
+                if retry.is_some() {
                     f(b, sender);
                     continue;
                 }
+                if started {
+                    cb(ProgressEvent::Started(b));
+                    continue;
+                }
                 assert!(progress[b.s() as usize] == b.t() - 1);
                 progress[b.s() as usize] = b.t();
 
@@ -877,18 +1394,44 @@ where
                                 .exists(self.save_dir.read().cloned().unwrap()))
                     {
                         scope.spawn(move |_| {
-                            self.kernels.insert(next_b, self.get_kernel(next_b));
-                            SenderData::send(next_b, false, sender);
+                            // The kernel file may also be written by whoever computes `next_b` or
+                            // `b + (1, 1)`. If another process is doing so, we leave the kernel
+                            // to them.
+                            let _locks = if self.distributed {
+                                let locks = self.lock_bidegree(next_b).and_then(|lock| {
+                                    Ok(lock.zip(self.lock_bidegree(b + Bidegree::s_t(1, 1))?))
+                                });
+                                let locks = match locks {
+                                    Ok(locks) => locks,
+                                    Err(e) => {
+                                        SenderData::send_error(next_b, e, sender);
+                                        return;
+                                    }
+                                };
+                                let done = self
+                                    .save_file(SaveKind::Differential, b + Bidegree::s_t(1, 1))
+                                    .exists(self.save_dir.read().cloned().unwrap());
+                                if locks.is_none() || done {
+                                    SenderData::send(next_b, None, sender);
+                                    return;
+                                }
+                                locks
+                            } else {
+                                None
+                            };
+                            self.keep_kernel(next_b, self.get_kernel(next_b));
+                            SenderData::send(next_b, None, sender);
                         });
                     } else {
-                        SenderData::send(next_b, false, sender);
+                        SenderData::send(next_b, None, sender);
                     }
                 }
-                if new {
-                    cb(b);
+                if let Some(stats) = stats {
+                    cb(ProgressEvent::Finished(b, stats));
                 }
             }
-        });
+            result
+        })
     }
 }
 
@@ -944,16 +1487,31 @@ where
             for (input, result) in inputs.iter().zip_eq(results) {
                 qi.apply(result.into(), 1, input.into());
             }
-            true
-        } else if let Some(dir) = self.save_dir.read() {
-            if let Some(mut f) = self.save_file(SaveKind::ResQi, b).open_file(dir.clone()) {
-                QuasiInverse::stream_quasi_inverse(self.prime(), &mut f, results, inputs).unwrap();
+            return true;
+        }
+
+        match self.resident_quasi_inverse(b) {
+            Ok(Some(qi)) => {
+                for (input, result) in inputs.iter().zip_eq(results) {
+                    qi.apply(result.into(), 1, input.into());
+                }
                 true
-            } else {
+            }
+            Ok(None) => {
+                if let Some(dir) = self.save_dir.read()
+                    && let Some(mut f) = self.save_file(SaveKind::ResQi, b).open_file(dir.clone())
+                {
+                    QuasiInverse::stream_quasi_inverse(self.prime(), &mut f, results, inputs)
+                        .unwrap();
+                    true
+                } else {
+                    false
+                }
+            }
+            Err(e) => {
+                tracing::error!("{e:#}");
                 false
             }
-        } else {
-            false
         }
     }
 
@@ -1087,18 +1645,28 @@ pub(crate) mod secondary {
     where
         CC::Algebra: PairAlgebra,
     {
+        /// # Panics
+        /// Panics if the algebra is not a pair algebra. See [`try_new`](Self::try_new).
         pub fn new(cc: Arc<CC>) -> Self {
+            Self::try_new(cc).unwrap_or_else(|e| panic!("{e:#}"))
+        }
+
+        /// Same as [`new`](Self::new), but returns an error if the algebra does not admit a lift
+        /// to a pair algebra, or if the save directories cannot be created.
+        pub fn try_new(cc: Arc<CC>) -> anyhow::Result<Self> {
+            cc.algebra().check_pair_algebra()?;
+
             if let Some(p) = cc.save_dir().write() {
                 for subdir in SaveKind::secondary_data() {
-                    subdir.create_dir(p).unwrap();
+                    subdir.create_dir(p)?;
                 }
             }
 
-            Self {
+            Ok(Self {
                 underlying: cc,
                 homotopies: OnceBiVec::new(2),
                 intermediates: DashMap::new(),
-            }
+            })
         }
 
         pub fn homotopy(&self, s: i32) -> &SecondaryHomotopy<CC::Algebra> {
@@ -1133,7 +1701,12 @@ pub(crate) mod secondary {
                         target_vec.copy_from_slice(&row);
 
                         let source = BidegreeElement::new(b, source_vec);
-                        sseq.add_differential(2, &source, target_vec.as_slice());
+                        sseq.add_differential_justified(
+                            2,
+                            &source,
+                            target_vec.as_slice(),
+                            sseq::Justification::Secondary,
+                        );
 
                         source_vec = source.into_vec();
                     }
@@ -1196,4 +1769,132 @@ mod tests {
         assert!(res.apply_quasi_inverse(&mut [w.as_slice_mut()], b, &[v.as_slice()]));
         assert!(w.is_zero());
     }
+
+    #[test]
+    fn test_memory_budget() {
+        use crate::resolution_homomorphism::ResolutionHomomorphism;
+
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let max = Bidegree::n_s(20, 6);
+        let budget = 1 << 12;
+
+        let mut res =
+            construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        res.memory_budget = Some(budget);
+        res.compute_through_stem(max);
+        for b in res.iter_stem() {
+            assert!(res.differential(b.s()).quasi_inverse(b.t()).is_none());
+        }
+        {
+            let resident = res.resident.lock().unwrap();
+            assert!(resident.kernel_size + resident.quasi_inverse_size <= budget);
+        }
+
+        let fresh = construct_standard::<false, _, _>("S_2", None).unwrap();
+        fresh.compute_through_stem(max + Bidegree::n_s(4, 0));
+
+        // Extending a reloaded resolution reads the evicted kernels back from disk.
+        let mut reloaded =
+            construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        reloaded.memory_budget = Some(budget);
+        reloaded.compute_through_stem(max + Bidegree::n_s(4, 0));
+        for b in fresh.iter_stem() {
+            assert_eq!(
+                reloaded.number_of_gens_in_bidegree(b),
+                fresh.number_of_gens_in_bidegree(b),
+                "Ext differs at {b}"
+            );
+        }
+
+        // Lifting a product uses the quasi-inverses of the target, which are reloaded.
+        let h1 = |res: Arc<Resolution<_>>| {
+            let hom = ResolutionHomomorphism::from_class(
+                "h1".to_string(),
+                Arc::clone(&res),
+                res,
+                Bidegree::s_t(1, 2),
+                &[1],
+            );
+            hom.extend_all();
+            hom
+        };
+        let (ours, theirs) = (h1(Arc::new(res)), h1(Arc::new(fresh)));
+        for b in ours.source.iter_stem() {
+            let target = b - ours.shift;
+            if target.s() < 0 || !ours.target.has_computed_bidegree(target) {
+                continue;
+            }
+            assert_eq!(
+                ours.get_map(b.s()).hom_k(target.t()),
+                theirs.get_map(b.s()).hom_k(target.t()),
+                "Product differs at {b}"
+            );
+        }
+
+        let resident = ours.target.resident.lock().unwrap();
+        assert!(!resident.quasi_inverses.is_empty());
+        assert!(resident.kernel_size + resident.quasi_inverse_size <= budget);
+    }
+    #[test]
+    fn test_memory_budget_streams_large_quasi_inverse() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let b = Bidegree::s_t(2, 4);
+
+        let mut res =
+            construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        res.memory_budget = Some(0);
+        res.compute_through_bidegree(b);
+
+        let fresh = construct_standard::<false, _, _>("S_2", None).unwrap();
+        fresh.compute_through_bidegree(b);
+
+        let dim = res.module(1).dimension(b.t());
+        let inputs = (0..dim)
+            .map(|i| {
+                let mut v = FpVector::new(res.prime(), dim);
+                v.set_entry(i, 1);
+                v
+            })
+            .collect::<Vec<_>>();
+        let mut ours = vec![FpVector::new(res.prime(), res.module(2).dimension(b.t())); dim];
+        let mut theirs = ours.clone();
+
+        // The quasi-inverse does not fit in the budget, so it is streamed from disk.
+        assert!(res.apply_quasi_inverse(&mut ours, b, &inputs));
+        assert!(res.resident.lock().unwrap().quasi_inverses.is_empty());
+
+        assert!(fresh.apply_quasi_inverse(&mut theirs, b, &inputs));
+        assert_eq!(ours, theirs);
+    }
+    #[test]
+    fn test_memory_budget_corrupt_quasi_inverse() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let b = Bidegree::s_t(2, 4);
+
+        let res = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        res.compute_through_bidegree(b);
+        drop(res);
+
+        // Keep the header but cut off the quasi-inverse itself
+        let path = tempdir.path().join("res_qis/2_4_res_qi");
+        let len = std::fs::metadata(&path).unwrap().len();
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len.min(20))
+            .unwrap();
+
+        let mut res =
+            construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+        res.memory_budget = Some(1 << 30);
+        res.compute_through_bidegree(b);
+
+        // This must not panic when dropping the partially read file.
+        let err = res.resident_quasi_inverse(b).err().unwrap();
+        assert!(
+            format!("{err:#}").contains("Failed to read quasi-inverse at"),
+            "{err:#}"
+        );
+    }
 }
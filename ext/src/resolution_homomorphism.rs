@@ -20,7 +20,7 @@ use sseq::coordinates::{Bidegree, BidegreeGenerator, BidegreeRange};
 
 use crate::{
     chain_complex::{AugmentedChainComplex, BoundedChainComplex, ChainComplex, FreeChainComplex},
-    save::{SaveDirectory, SaveKind},
+    save::{SaveDirectory, SaveKind, SaveManifest},
 };
 
 pub type ResolutionHomomorphism<CC1, CC2> = MuResolutionHomomorphism<false, CC1, CC2>;
@@ -49,25 +49,37 @@ where
     CC2: ChainComplex<Algebra = CC1::Algebra>,
 {
     pub fn new(name: String, source: Arc<CC1>, target: Arc<CC2>, shift: Bidegree) -> Self {
+        Self::try_new(name, source, target, shift).unwrap_or_else(|e| panic!("{e:#}"))
+    }
+
+    /// Fallible version of [`new`](Self::new), which returns an error instead of panicking if the
+    /// save directory of the homomorphism belongs to a different computation.
+    pub fn try_new(
+        name: String,
+        source: Arc<CC1>,
+        target: Arc<CC2>,
+        shift: Bidegree,
+    ) -> anyhow::Result<Self> {
         let save_dir = if source.save_dir().is_some() && !name.is_empty() {
             let mut save_dir = source.save_dir().clone();
             save_dir.push(format!("products/{name}"));
-            SaveKind::ChainMap
-                .create_dir(save_dir.write().unwrap())
-                .unwrap();
+            save_dir.check_manifest(&SaveManifest::new(&*source.algebra()))?;
+            if let Some(dir) = save_dir.write() {
+                SaveKind::ChainMap.create_dir(dir)?;
+            }
             save_dir
         } else {
             SaveDirectory::None
         };
 
-        Self {
+        Ok(Self {
             name,
             source,
             target,
             maps: OnceBiVec::new(shift.s()),
             shift,
             save_dir,
-        }
+        })
     }
 
     pub fn name(&self) -> &str {
@@ -82,7 +94,10 @@ where
         self.maps.len()
     }
 
-    fn get_map_ensure_length(&self, input_s: i32) -> &MuFreeModuleHomomorphism<U, CC2::Module> {
+    pub(crate) fn get_map_ensure_length(
+        &self,
+        input_s: i32,
+    ) -> &MuFreeModuleHomomorphism<U, CC2::Module> {
         self.maps.extend(input_s, |input_s| {
             let output_s = input_s - self.shift.s();
             Arc::new(MuFreeModuleHomomorphism::new(
@@ -354,7 +369,23 @@ where
         shift: Bidegree,
         class: &[u32],
     ) -> Self {
-        let result = Self::new(name, source, target, shift);
+        Self::try_from_class(name, source, target, shift, class).unwrap_or_else(|e| panic!("{e:#}"))
+    }
+
+    /// Fallible version of [`from_class`](Self::from_class), which returns an error instead of
+    /// panicking if the save directory of the homomorphism belongs to a different computation,
+    /// e.g. a product with the same name but defined by a different class.
+    pub fn try_from_class(
+        name: String,
+        source: Arc<CC1>,
+        target: Arc<CC2>,
+        shift: Bidegree,
+        class: &[u32],
+    ) -> anyhow::Result<Self> {
+        let result = Self::try_new(name, source, target, shift)?;
+        result
+            .save_dir
+            .check_manifest(&SaveManifest::new(&*result.algebra()).with_product(shift, class))?;
 
         let num_gens = result
             .source
@@ -368,7 +399,7 @@ where
         }
 
         result.extend_step(shift, Some(&matrix));
-        result
+        Ok(result)
     }
 
     /// Extend the [`MuResolutionHomomorphism`] to be defined on `(input_s, input_t)`. The resulting
@@ -0,0 +1,483 @@
+//! A save backend that stores all save files in a single append-only archive.
+//!
+//! A large resolution produces a very large number of small save files, which can be problematic on
+//! file systems with inode quotas. Instead, an archive stores each save file as a record in a single
+//! file, and keeps an index of the records in memory. The contents of a record is exactly what would
+//! have been written to the corresponding save file, including the header and checksum.
+//!
+//! Records are only ever appended. Overwriting a save file appends a new record that supersedes the
+//! old one, and deleting a save file appends a tombstone. A record is written in one go after the
+//! data is complete, so if the program is terminated halfway, only the last record is incomplete.
+//! This is detected and discarded when the archive is next opened. See `SAVE-FORMAT.md` for the
+//! details of the format.
+
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use anyhow::Context;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+const ARCHIVE_MAGIC: [u8; 8] = *b"EXTARCHV";
+const ARCHIVE_VERSION: u32 = 1;
+const RECORD_MAGIC: u32 = 0x5EC0_4D00;
+
+const FLAG_COMPRESSED: u32 = 1;
+const FLAG_DELETED: u32 = 2;
+
+/// The extension that marks a save path as an archive.
+pub const ARCHIVE_EXTENSION: &str = "archive";
+
+/// The key of a record. The `prefix` is the subdirectory of the save directory the file would live
+/// in, e.g. `products/h0` for the data of a product. The `idx` is `u32::MAX` if there is no index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub prefix: String,
+    pub kind: u32,
+    pub algebra: u32,
+    pub s: i32,
+    pub t: i32,
+    pub idx: u32,
+}
+
+impl Key {
+    fn write(&self, buffer: &mut impl Write) -> io::Result<()> {
+        buffer.write_u32::<LittleEndian>(self.prefix.len() as u32)?;
+        buffer.write_all(self.prefix.as_bytes())?;
+        buffer.write_u32::<LittleEndian>(self.kind)?;
+        buffer.write_u32::<LittleEndian>(self.algebra)?;
+        buffer.write_i32::<LittleEndian>(self.s)?;
+        buffer.write_i32::<LittleEndian>(self.t)?;
+        buffer.write_u32::<LittleEndian>(self.idx)
+    }
+
+    fn read(buffer: &mut impl Read) -> io::Result<Self> {
+        let len = buffer.read_u32::<LittleEndian>()? as usize;
+        let mut prefix = vec![0; len];
+        buffer.read_exact(&mut prefix)?;
+        let prefix =
+            String::from_utf8(prefix).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Self {
+            prefix,
+            kind: buffer.read_u32::<LittleEndian>()?,
+            algebra: buffer.read_u32::<LittleEndian>()?,
+            s: buffer.read_i32::<LittleEndian>()?,
+            t: buffer.read_i32::<LittleEndian>()?,
+            idx: buffer.read_u32::<LittleEndian>()?,
+        })
+    }
+}
+
+/// The location of the payload of a record in the archive.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    offset: u64,
+    len: u64,
+    compressed: bool,
+}
+
+#[derive(Debug)]
+pub struct Archive {
+    path: PathBuf,
+    inner: Mutex<ArchiveInner>,
+}
+
+#[derive(Debug)]
+struct ArchiveInner {
+    file: File,
+    writable: bool,
+    /// The length of the valid part of the archive, which is where the next record is written.
+    len: u64,
+    index: HashMap<Key, Entry>,
+}
+
+/// The archives that are currently open, indexed by their path. An archive is opened the first
+/// time it is used and is never closed.
+fn archives() -> &'static Mutex<HashMap<PathBuf, Arc<Archive>>> {
+    static ARCHIVES: LazyLock<Mutex<HashMap<PathBuf, Arc<Archive>>>> =
+        LazyLock::new(Default::default);
+    &ARCHIVES
+}
+
+/// If `dir` lies in an archive, return the path of the archive and the prefix of `dir` in the
+/// archive.
+pub fn find(dir: &Path) -> Option<(&Path, String)> {
+    let archive = dir
+        .ancestors()
+        .find(|p| p.extension().is_some_and(|e| e == ARCHIVE_EXTENSION))?;
+    let prefix = dir
+        .strip_prefix(archive)
+        .unwrap()
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some((archive, prefix))
+}
+
+/// Get the archive at `path`, opening or creating it if necessary.
+pub fn get(path: &Path) -> anyhow::Result<Arc<Archive>> {
+    let mut archives = archives().lock().unwrap();
+    if let Some(archive) = archives.get(path) {
+        return Ok(Arc::clone(archive));
+    }
+    let archive = Arc::new(Archive::open(path.to_owned())?);
+    archives.insert(path.to_owned(), Arc::clone(&archive));
+    Ok(archive)
+}
+
+impl Archive {
+    fn open(path: PathBuf) -> anyhow::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {parent:?}"))?;
+        }
+        let (mut file, writable) = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+        {
+            Ok(f) => (f, true),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => (
+                File::open(&path).with_context(|| format!("Failed to open archive {path:?}"))?,
+                false,
+            ),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to open archive {path:?}"));
+            }
+        };
+
+        let file_len = file.metadata()?.len();
+        if file_len == 0 {
+            anyhow::ensure!(writable, "Archive {path:?} is empty and read-only");
+            file.write_all(&ARCHIVE_MAGIC)?;
+            file.write_u32::<LittleEndian>(ARCHIVE_VERSION)?;
+        } else {
+            let mut magic = [0; 8];
+            file.read_exact(&mut magic)
+                .with_context(|| format!("Failed to read header of archive {path:?}"))?;
+            anyhow::ensure!(magic == ARCHIVE_MAGIC, "{path:?} is not an archive");
+            let version = file.read_u32::<LittleEndian>()?;
+            anyhow::ensure!(
+                version == ARCHIVE_VERSION,
+                "Unsupported archive version {version} in {path:?}"
+            );
+        }
+
+        let (index, len) = Self::read_index(&mut file, file_len.max(12))
+            .with_context(|| format!("Failed to read index of archive {path:?}"))?;
+        if len < file_len {
+            tracing::warn!(archive = ?path, len, file_len, "discarding incomplete record");
+            if writable {
+                file.set_len(len)?;
+            }
+        }
+        tracing::info!(archive = ?path, records = index.len(), "opened archive");
+
+        Ok(Self {
+            path,
+            inner: Mutex::new(ArchiveInner {
+                file,
+                writable,
+                len,
+                index,
+            }),
+        })
+    }
+
+    /// Read the records of the archive, returning the index and the length of the valid part of
+    /// the archive. The last record is allowed to be incomplete, in which case it is ignored.
+    fn read_index(file: &mut File, file_len: u64) -> anyhow::Result<(HashMap<Key, Entry>, u64)> {
+        let mut index = HashMap::new();
+        let mut reader = io::BufReader::new(file);
+        let mut offset = reader.seek(SeekFrom::Start(12))?;
+
+        while offset < file_len {
+            let (key, flags, len) = match Self::read_record_header(&mut reader) {
+                Ok(x) => x,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            };
+            let payload = reader.stream_position()?;
+            if payload + len > file_len {
+                break;
+            }
+            reader.seek_relative(len as i64)?;
+            offset = payload + len;
+
+            if flags & FLAG_DELETED != 0 {
+                index.remove(&key);
+            } else {
+                index.insert(
+                    key,
+                    Entry {
+                        offset: payload,
+                        len,
+                        compressed: flags & FLAG_COMPRESSED != 0,
+                    },
+                );
+            }
+        }
+        Ok((index, offset))
+    }
+
+    fn read_record_header(reader: &mut impl Read) -> io::Result<(Key, u32, u64)> {
+        let magic = reader.read_u32::<LittleEndian>()?;
+        if magic != RECORD_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid record magic {magic:#010x}"),
+            ));
+        }
+        let flags = reader.read_u32::<LittleEndian>()?;
+        let key = Key::read(reader)?;
+        let len = reader.read_u64::<LittleEndian>()?;
+        Ok((key, flags, len))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The keys of all records in the archive.
+    pub fn keys(&self) -> Vec<Key> {
+        self.inner.lock().unwrap().index.keys().cloned().collect()
+    }
+
+    pub fn contains(&self, key: &Key) -> bool {
+        self.inner.lock().unwrap().index.contains_key(key)
+    }
+
+    /// Read the contents of the record with the given key, decompressing if necessary.
+    pub fn read(&self, key: &Key) -> Option<Vec<u8>> {
+        self.try_read(key)
+            .map(|r| r.unwrap_or_else(|e| panic!("Error when reading from {:?}: {e}", self.path)))
+    }
+
+    /// Read the contents of the record with the given key, decompressing if necessary. This
+    /// returns an error of kind [`io::ErrorKind::Unsupported`] if the record is compressed and the
+    /// `zstd` feature is disabled.
+    pub fn try_read(&self, key: &Key) -> Option<io::Result<Vec<u8>>> {
+        let (data, compressed) = {
+            let mut inner = self.inner.lock().unwrap();
+            let entry = *inner.index.get(key)?;
+            let mut data = vec![0; entry.len as usize];
+            if let Err(e) = inner
+                .file
+                .seek(SeekFrom::Start(entry.offset))
+                .and_then(|_| inner.file.read_exact(&mut data))
+            {
+                return Some(Err(e));
+            }
+            (data, entry.compressed)
+        };
+        if !compressed {
+            return Some(Ok(data));
+        }
+        #[cfg(feature = "zstd")]
+        {
+            Some(zstd::stream::decode_all(&data[..]))
+        }
+        #[cfg(not(feature = "zstd"))]
+        Some(Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Reading compressed records requires the zstd feature",
+        )))
+    }
+
+    /// Append a record with the given key and contents, superseding any existing record with the
+    /// same key. The contents are compressed if `level` is not `None`.
+    pub fn write(&self, key: Key, data: &[u8], level: Option<i32>) -> io::Result<()> {
+        #[cfg(feature = "zstd")]
+        let compressed;
+        let (flags, data) = match level {
+            None => (0, data),
+            #[cfg(feature = "zstd")]
+            Some(level) => {
+                compressed = zstd::bulk::compress(data, level)?;
+                (FLAG_COMPRESSED, &compressed[..])
+            }
+            #[cfg(not(feature = "zstd"))]
+            Some(_) => unreachable!(),
+        };
+        let mut inner = self.inner.lock().unwrap();
+        let offset = inner.append(&key, flags, data)?;
+        inner.index.insert(
+            key,
+            Entry {
+                offset,
+                len: data.len() as u64,
+                compressed: flags & FLAG_COMPRESSED != 0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Delete the record with the given key. This does nothing if there is no such record.
+    pub fn delete(&self, key: &Key) -> io::Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.index.contains_key(key) {
+            inner.append(key, FLAG_DELETED, &[])?;
+            inner.index.remove(key);
+        }
+        Ok(())
+    }
+}
+
+impl ArchiveInner {
+    /// Append a record and return the offset of its payload.
+    fn append(&mut self, key: &Key, flags: u32, data: &[u8]) -> io::Result<u64> {
+        if !self.writable {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Archive is read-only",
+            ));
+        }
+        let mut record = Vec::with_capacity(data.len() + key.prefix.len() + 36);
+        record.write_u32::<LittleEndian>(RECORD_MAGIC)?;
+        record.write_u32::<LittleEndian>(flags)?;
+        key.write(&mut record)?;
+        record.write_u64::<LittleEndian>(data.len() as u64)?;
+        let offset = self.len + record.len() as u64;
+        record.extend_from_slice(data);
+
+        // We write the whole record in one go, so that terminating the program halfway leaves at
+        // most one incomplete record at the end of the archive.
+        self.file.seek(SeekFrom::Start(self.len))?;
+        self.file.write_all(&record)?;
+        self.len += record.len() as u64;
+        Ok(offset)
+    }
+}
+
+/// The writer returned by [`SaveFile::create_file`](super::SaveFile::create_file) for files in an
+/// archive. The data is collected in memory and written to the archive when it is finished.
+pub struct ArchiveWriter {
+    archive: Arc<Archive>,
+    key: Key,
+    level: Option<i32>,
+    buffer: Vec<u8>,
+}
+
+impl ArchiveWriter {
+    pub fn new(archive: Arc<Archive>, key: Key, level: Option<i32>) -> Self {
+        Self {
+            archive,
+            key,
+            level,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl Write for ArchiveWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.buffer.write_all(buf)
+    }
+}
+
+impl super::Finish for ArchiveWriter {
+    fn finish(&mut self) -> io::Result<()> {
+        self.archive.write(
+            self.key.clone(),
+            &std::mem::take(&mut self.buffer),
+            self.level,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(prefix: &str, s: i32) -> Key {
+        Key {
+            prefix: prefix.to_owned(),
+            kind: 1,
+            algebra: 2,
+            s,
+            t: s + 1,
+            idx: u32::MAX,
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(
+            find(Path::new("/save/res.archive/products/h0")),
+            Some((Path::new("/save/res.archive"), "products/h0".to_owned()))
+        );
+        assert_eq!(
+            find(Path::new("/save/res.archive")),
+            Some((Path::new("/save/res.archive"), String::new()))
+        );
+        assert_eq!(find(Path::new("/save/res")), None);
+    }
+
+    #[test]
+    fn test_round_trip_and_delete() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let path = tempdir.path().join("res.archive");
+
+        let archive = get(&path).unwrap();
+        archive.write(key("", 0), b"first", None).unwrap();
+        archive
+            .write(key("products/h0", 1), b"product", None)
+            .unwrap();
+        archive.write(key("", 0), b"second", None).unwrap();
+        archive.write(key("", 2), b"deleted", None).unwrap();
+        archive.delete(&key("", 2)).unwrap();
+        // Deleting a missing record does nothing
+        archive.delete(&key("", 3)).unwrap();
+
+        assert_eq!(archive.read(&key("", 0)).unwrap(), b"second");
+        assert_eq!(archive.read(&key("products/h0", 1)).unwrap(), b"product");
+        assert!(!archive.contains(&key("", 2)));
+        assert!(archive.read(&key("", 2)).is_none());
+
+        // Opening the archive again replays the overwrites and tombstones.
+        let reopened = Archive::open(path).unwrap();
+        let mut keys = reopened.keys();
+        keys.sort_by_key(|k| k.s);
+        assert_eq!(keys, [key("", 0), key("products/h0", 1)]);
+        assert_eq!(reopened.read(&key("", 0)).unwrap(), b"second");
+        assert_eq!(reopened.read(&key("products/h0", 1)).unwrap(), b"product");
+    }
+
+    #[test]
+    fn test_incomplete_record() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let path = tempdir.path().join("res.archive");
+
+        let archive = Archive::open(path.clone()).unwrap();
+        archive.write(key("", 0), b"complete", None).unwrap();
+        archive.write(key("", 1), b"truncated", None).unwrap();
+        drop(archive);
+
+        let len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 3)
+            .unwrap();
+
+        let reopened = Archive::open(path).unwrap();
+        assert_eq!(reopened.keys(), [key("", 0)]);
+        assert_eq!(reopened.read(&key("", 0)).unwrap(), b"complete");
+    }
+}
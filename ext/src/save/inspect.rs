@@ -0,0 +1,546 @@
+//! Inspect a save directory and find save files that are corrupt or missing.
+//!
+//! This is useful when a long computation dies halfway, in which case the save file that was being
+//! written may be corrupt. Deleting the corrupt files lets a resumed computation recompute them.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+use byteorder::{LittleEndian, ReadBytesExt};
+use sseq::coordinates::Bidegree;
+
+use super::{SaveKind, archive, lock::LOCK_EXTENSION, manifest, remove_file_if_exists};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The header and checksum are valid.
+    Complete,
+    /// The file is corrupt, for the given reason. Such files can be deleted and recomputed.
+    Corrupt(String),
+    /// The file cannot be checked, e.g. because it is compressed and the `zstd` feature is
+    /// disabled. Such files are never deleted.
+    Unreadable(String),
+}
+
+/// Where a save file is stored.
+#[derive(Debug, Clone)]
+enum Location {
+    File(PathBuf),
+    Archive(Arc<archive::Archive>, archive::Key),
+}
+
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub kind: SaveKind,
+    /// The subdirectory of the save directory that contains the file, e.g. `products/h0`.
+    pub prefix: String,
+    pub b: Bidegree,
+    pub idx: Option<usize>,
+    /// The path to the file. For files in an archive, this is the path the file would have if the
+    /// archive were a directory.
+    pub path: PathBuf,
+    pub status: FileStatus,
+    algebra: Option<u32>,
+    location: Location,
+}
+
+/// The result of inspecting a save directory.
+#[derive(Debug, Default)]
+pub struct SaveReport {
+    pub files: Vec<FileReport>,
+    /// Files in the save directory that are not save files.
+    pub unrecognized: Vec<PathBuf>,
+    /// Lock files of save files that are being computed. If no process is using the save
+    /// directory, these are left over from a process that crashed and should be deleted.
+    pub locks: Vec<PathBuf>,
+}
+
+/// Inspect all save files in `dir`, which may be a save directory or an archive. This checks that
+/// the header of each file matches its location and that the checksum is valid.
+///
+/// If `algebra_magic` is `None`, then every file is expected to have the algebra magic recorded in
+/// the manifest of `dir`, or the one used by the majority of the files if there is no manifest.
+pub fn inspect(dir: &Path, algebra_magic: Option<u32>) -> anyhow::Result<SaveReport> {
+    let mut report = SaveReport::default();
+    match archive::find(dir) {
+        Some((path, prefix)) => {
+            anyhow::ensure!(path.is_file(), "Archive {path:?} does not exist");
+            let archive = archive::get(path)?;
+            inspect_archive(&archive, &prefix, &mut report);
+        }
+        None => {
+            anyhow::ensure!(dir.is_dir(), "{dir:?} is not a directory");
+            inspect_dir(dir, Path::new(""), &mut report)?;
+        }
+    }
+
+    let algebra_magic = match algebra_magic {
+        Some(magic) => Some(magic),
+        None => manifest::read_manifest(dir)?.map(|m| m.algebra_magic),
+    };
+    let algebra_magic = algebra_magic.or_else(|| {
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for file in &report.files {
+            if let Some(magic) = file.algebra {
+                *counts.entry(magic).or_default() += 1;
+            }
+        }
+        counts.into_iter().max_by_key(|&(_, c)| c).map(|(m, _)| m)
+    });
+    if let Some(expected) = algebra_magic {
+        for file in &mut report.files {
+            if let Some(magic) = file.algebra
+                && magic != expected
+            {
+                file.status = FileStatus::Corrupt(format!(
+                    "Invalid header: algebra was {magic:#06x} but expected {expected:#06x}"
+                ));
+            }
+        }
+    }
+    report
+        .files
+        .sort_by_key(|f| (f.prefix.clone(), f.kind.name(), f.b.s(), f.b.t(), f.idx));
+    Ok(report)
+}
+
+fn inspect_dir(root: &Path, prefix: &Path, report: &mut SaveReport) -> anyhow::Result<()> {
+    let dir = root.join(prefix);
+    let mut entries = std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read directory {dir:?}"))?
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        if !entry.file_type()?.is_dir() {
+            if entry.file_name() != manifest::MANIFEST_NAME {
+                report.unrecognized.push(path);
+            }
+            continue;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        match SaveKind::all().find(|k| name == format!("{}s", k.name())) {
+            Some(kind) => inspect_kind_dir(kind, &path, prefix, report)?,
+            None => inspect_dir(root, &prefix.join(&*name), report)?,
+        }
+    }
+    Ok(())
+}
+
+fn inspect_kind_dir(
+    kind: SaveKind,
+    dir: &Path,
+    prefix: &Path,
+    report: &mut SaveReport,
+) -> anyhow::Result<()> {
+    let mut entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {dir:?}"))?
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if let Some(name) = name.strip_suffix(&format!(".{LOCK_EXTENSION}"))
+            && parse_file_name(kind, name).is_some()
+        {
+            report.locks.push(path);
+            continue;
+        }
+        let (name, compressed) = match name.strip_suffix(".zst") {
+            Some(name) => (name, true),
+            None => (&*name, false),
+        };
+        let Some((b, idx)) = parse_file_name(kind, name).filter(|_| entry.path().is_file()) else {
+            report.unrecognized.push(path);
+            continue;
+        };
+
+        let (status, algebra) = match read_file(&path, compressed) {
+            Ok(data) => check(kind, b, idx, &data),
+            Err(status) => (status, None),
+        };
+        report.files.push(FileReport {
+            kind,
+            prefix: prefix_string(prefix),
+            b,
+            idx,
+            path: path.clone(),
+            status,
+            algebra,
+            location: Location::File(path),
+        });
+    }
+    Ok(())
+}
+
+fn inspect_archive(archive: &Arc<archive::Archive>, prefix: &str, report: &mut SaveReport) {
+    for key in archive.keys() {
+        let key_prefix = if prefix.is_empty() {
+            Some(&*key.prefix)
+        } else if key.prefix == prefix {
+            Some("")
+        } else {
+            key.prefix
+                .strip_prefix(prefix)
+                .and_then(|p| p.strip_prefix('/'))
+        };
+        let Some(key_prefix) = key_prefix else {
+            continue;
+        };
+        let Some(kind) = SaveKind::from_magic(key.kind) else {
+            if key.kind != manifest::MANIFEST_MAGIC {
+                report.unrecognized.push(key_path(archive, &key));
+            }
+            continue;
+        };
+        let b = Bidegree::s_t(key.s, key.t);
+        let idx = (key.idx != u32::MAX).then_some(key.idx as usize);
+
+        let (status, algebra) = match archive.try_read(&key) {
+            Some(Ok(data)) => check(kind, b, idx, &data),
+            Some(Err(e)) if e.kind() == io::ErrorKind::Unsupported => {
+                (FileStatus::Unreadable(e.to_string()), None)
+            }
+            Some(Err(e)) => (FileStatus::Corrupt(format!("Failed to read: {e}")), None),
+            None => continue,
+        };
+        report.files.push(FileReport {
+            kind,
+            prefix: key_prefix.to_owned(),
+            b,
+            idx,
+            path: key_path(archive, &key),
+            status,
+            algebra,
+            location: Location::Archive(Arc::clone(archive), key),
+        });
+    }
+}
+
+fn key_path(archive: &archive::Archive, key: &archive::Key) -> PathBuf {
+    let mut path = archive.path().join(&key.prefix);
+    let name = SaveKind::from_magic(key.kind).map_or("unknown", SaveKind::name);
+    if key.idx == u32::MAX {
+        path.push(format!("{name}s/{}_{}_{name}", key.s, key.t));
+    } else {
+        path.push(format!("{name}s/{}_{}_{}_{name}", key.s, key.t, key.idx));
+    }
+    path
+}
+
+fn prefix_string(prefix: &Path) -> String {
+    prefix
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Parse a file name of the form `{s}_{t}_{name}` or `{s}_{t}_{idx}_{name}`.
+fn parse_file_name(kind: SaveKind, name: &str) -> Option<(Bidegree, Option<usize>)> {
+    let rest = name.strip_suffix(kind.name())?.strip_suffix('_')?;
+    let parts: Vec<&str> = rest.split('_').collect();
+    let s = parts.first()?.parse().ok()?;
+    let t = parts.get(1)?.parse().ok()?;
+    let idx = match parts.len() {
+        2 => None,
+        3 => Some(parts[2].parse().ok()?),
+        _ => return None,
+    };
+    Some((Bidegree::s_t(s, t), idx))
+}
+
+pub(super) fn read_file(path: &Path, compressed: bool) -> Result<Vec<u8>, FileStatus> {
+    let data = std::fs::read(path).map_err(|e| FileStatus::Unreadable(e.to_string()))?;
+    if !compressed {
+        return Ok(data);
+    }
+    #[cfg(feature = "zstd")]
+    {
+        zstd::stream::decode_all(&data[..])
+            .map_err(|e| FileStatus::Corrupt(format!("Failed to decompress: {e}")))
+    }
+    #[cfg(not(feature = "zstd"))]
+    Err(FileStatus::Unreadable(
+        "Reading compressed files requires the zstd feature".to_owned(),
+    ))
+}
+
+/// Check the header and checksum of a save file, returning the status and the algebra magic in
+/// the header.
+pub(super) fn check(
+    kind: SaveKind,
+    b: Bidegree,
+    idx: Option<usize>,
+    data: &[u8],
+) -> (FileStatus, Option<u32>) {
+    // The header is 16 bytes and the checksum is 4 bytes
+    if data.len() < 20 {
+        return (
+            FileStatus::Corrupt(format!("File too short ({} bytes)", data.len())),
+            None,
+        );
+    }
+    let (contents, checksum) = data.split_at(data.len() - 4);
+    let mut header = contents;
+    let mut read = || header.read_u32::<LittleEndian>().unwrap();
+    let magic = read();
+    let algebra = read();
+    let s = read();
+    let t = read();
+
+    let expected_t = b.t() as u32 + ((idx.unwrap_or(0) as u32) << 16);
+    let status = if magic != kind.magic() {
+        FileStatus::Corrupt(format!(
+            "Invalid header: magic was {magic:#010x} but expected {:#010x}",
+            kind.magic()
+        ))
+    } else if s != b.s() as u32 {
+        FileStatus::Corrupt(format!("Invalid header: s was {s} but expected {}", b.s()))
+    } else if t != expected_t {
+        FileStatus::Corrupt(format!(
+            "Invalid header: t was {t} but expected {expected_t}"
+        ))
+    } else {
+        let mut adler = adler::Adler32::new();
+        adler.write_slice(contents);
+        let checksum = u32::from_le_bytes(checksum.try_into().unwrap());
+        if adler.checksum() == checksum {
+            FileStatus::Complete
+        } else {
+            FileStatus::Corrupt("Invalid file checksum".to_owned())
+        }
+    };
+    (status, Some(algebra))
+}
+
+impl FileReport {
+    /// Delete the file.
+    pub fn delete(&self) -> io::Result<()> {
+        match &self.location {
+            Location::File(path) => remove_file_if_exists(path),
+            Location::Archive(archive, key) => archive.delete(key),
+        }
+    }
+}
+
+impl SaveReport {
+    /// The files that are corrupt.
+    pub fn corrupt(&self) -> impl Iterator<Item = &FileReport> {
+        self.files
+            .iter()
+            .filter(|f| matches!(f.status, FileStatus::Corrupt(_)))
+    }
+
+    /// The bidegrees that are missing for each kind of data that is expected to be present for
+    /// every computed bidegree (see [`SaveKind::is_persistent`]). In each homological degree `s`,
+    /// these are the bidegrees below the largest `t` for which there is a file.
+    pub fn missing(&self) -> BTreeMap<(String, &'static str), Vec<Bidegree>> {
+        let mut present: BTreeMap<(String, &'static str), BTreeSet<(i32, i32)>> = BTreeMap::new();
+        for file in &self.files {
+            if file.kind.is_persistent() && file.idx.is_none() {
+                present
+                    .entry((file.prefix.clone(), file.kind.name()))
+                    .or_default()
+                    .insert((file.b.s(), file.b.t()));
+            }
+        }
+
+        let mut result = BTreeMap::new();
+        for (key, present) in present {
+            let min_t = present.iter().map(|&(_, t)| t).min().unwrap();
+            let mut max_t: BTreeMap<i32, i32> = BTreeMap::new();
+            for &(s, t) in &present {
+                let m = max_t.entry(s).or_insert(t);
+                *m = (*m).max(t);
+            }
+            let missing: Vec<Bidegree> = max_t
+                .into_iter()
+                .flat_map(|(s, max_t)| (min_t..max_t).map(move |t| (s, t)))
+                .filter(|b| !present.contains(b))
+                .map(|(s, t)| Bidegree::s_t(s, t))
+                .collect();
+            if !missing.is_empty() {
+                result.insert(key, missing);
+            }
+        }
+        result
+    }
+
+    /// Delete all corrupt files, returning the number of files deleted. A resumed computation
+    /// will then recompute them.
+    pub fn delete_corrupt(&self) -> io::Result<usize> {
+        let mut count = 0;
+        for file in self.corrupt() {
+            file.delete()?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Delete all lock files, returning the number of files deleted. This must only be done if no
+    /// process is using the save directory.
+    pub fn delete_locks(&self) -> io::Result<usize> {
+        for path in &self.locks {
+            remove_file_if_exists(path)?;
+        }
+        Ok(self.locks.len())
+    }
+}
+
+impl fmt::Display for SaveReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[derive(Default)]
+        struct Counts {
+            complete: usize,
+            corrupt: usize,
+            unreadable: usize,
+        }
+
+        let mut counts: BTreeMap<(&str, &str), Counts> = BTreeMap::new();
+        for file in &self.files {
+            let c = counts.entry((&file.prefix, file.kind.name())).or_default();
+            match file.status {
+                FileStatus::Complete => c.complete += 1,
+                FileStatus::Corrupt(_) => c.corrupt += 1,
+                FileStatus::Unreadable(_) => c.unreadable += 1,
+            }
+        }
+        let missing = self.missing();
+
+        for ((prefix, name), c) in &counts {
+            let dir = if prefix.is_empty() {
+                format!("{name}s")
+            } else {
+                format!("{prefix}/{name}s")
+            };
+            write!(f, "{dir}: {} complete, {} corrupt", c.complete, c.corrupt)?;
+            if c.unreadable > 0 {
+                write!(f, ", {} unreadable", c.unreadable)?;
+            }
+            if let Some(m) = missing.get(&(prefix.to_string(), *name)) {
+                write!(f, ", {} missing", m.len())?;
+            }
+            writeln!(f)?;
+        }
+
+        for file in &self.files {
+            match &file.status {
+                FileStatus::Complete => {}
+                FileStatus::Corrupt(reason) => writeln!(f, "Corrupt: {:?}: {reason}", file.path)?,
+                FileStatus::Unreadable(reason) => {
+                    writeln!(f, "Unreadable: {:?}: {reason}", file.path)?
+                }
+            }
+        }
+        for ((prefix, name), bidegrees) in &missing {
+            let dir = if prefix.is_empty() {
+                format!("{name}s")
+            } else {
+                format!("{prefix}/{name}s")
+            };
+            write!(f, "Missing in {dir}:")?;
+            for b in bidegrees {
+                write!(f, " ({}, {})", b.s(), b.t())?;
+            }
+            writeln!(f)?;
+        }
+        for path in &self.locks {
+            writeln!(f, "Locked: {path:?}")?;
+        }
+        for path in &self.unrecognized {
+            writeln!(f, "Unrecognized: {path:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::WriteBytesExt;
+
+    use super::*;
+
+    /// A save file of kind `kind` at `b` with the given contents, a valid header and checksum.
+    fn save_file(kind: SaveKind, b: Bidegree, contents: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.write_u32::<LittleEndian>(kind.magic()).unwrap();
+        data.write_u32::<LittleEndian>(0xA1).unwrap();
+        data.write_i32::<LittleEndian>(b.s()).unwrap();
+        data.write_i32::<LittleEndian>(b.t()).unwrap();
+        data.extend_from_slice(contents);
+        let mut adler = adler::Adler32::new();
+        adler.write_slice(&data);
+        data.write_u32::<LittleEndian>(adler.checksum()).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_check_complete() {
+        let b = Bidegree::s_t(2, 5);
+        let data = save_file(SaveKind::Kernel, b, &[1, 2, 3]);
+        assert_eq!(
+            check(SaveKind::Kernel, b, None, &data),
+            (FileStatus::Complete, Some(0xA1))
+        );
+    }
+
+    #[test]
+    fn test_check_truncated() {
+        let b = Bidegree::s_t(2, 5);
+        let data = save_file(SaveKind::Kernel, b, &[1, 2, 3]);
+
+        // Losing the end of the file invalidates the checksum
+        let (status, _) = check(SaveKind::Kernel, b, None, &data[..data.len() - 2]);
+        assert_eq!(
+            status,
+            FileStatus::Corrupt("Invalid file checksum".to_owned())
+        );
+
+        // A file that does not even contain the header and checksum
+        assert_eq!(
+            check(SaveKind::Kernel, b, None, &data[..10]),
+            (
+                FileStatus::Corrupt("File too short (10 bytes)".to_owned()),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_bad_checksum() {
+        let b = Bidegree::s_t(2, 5);
+        let mut data = save_file(SaveKind::Kernel, b, &[1, 2, 3]);
+        data[17] ^= 1;
+        assert_eq!(
+            check(SaveKind::Kernel, b, None, &data),
+            (
+                FileStatus::Corrupt("Invalid file checksum".to_owned()),
+                Some(0xA1)
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_wrong_location() {
+        let b = Bidegree::s_t(2, 5);
+        let data = save_file(SaveKind::Kernel, b, &[1, 2, 3]);
+
+        let (status, _) = check(SaveKind::Differential, b, None, &data);
+        assert!(matches!(status, FileStatus::Corrupt(e) if e.starts_with("Invalid header: magic")));
+        let (status, _) = check(SaveKind::Kernel, Bidegree::s_t(3, 5), None, &data);
+        assert_eq!(
+            status,
+            FileStatus::Corrupt("Invalid header: s was 2 but expected 3".to_owned())
+        );
+    }
+}
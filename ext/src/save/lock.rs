@@ -0,0 +1,337 @@
+//! Lock files that let several processes share a save directory, possibly on different machines
+//! with a shared filesystem. A process claims a save file by creating a lock file next to it, and
+//! only the process holding the lock may write or read the file.
+//!
+//! A lock file records the host name and process id of its owner, and the owner refreshes its
+//! modification time every [`HEARTBEAT_INTERVAL`]. Lock files are removed when the process exits
+//! normally or is terminated with Ctrl-C. If a process crashes, its lock files are left behind,
+//! and another process breaks such a lock when it tries to claim the save file and either
+//!  - the owner ran on the same host and no longer exists; or
+//!  - the lock file has not been refreshed for [`LOCK_TIMEOUT`].
+//!
+//! The second check compares the modification time with the local clock, so the clocks of the
+//! machines sharing the save directory must roughly agree. Lock files are also listed by
+//! [`inspect`](super::inspect).
+
+use std::{
+    fmt,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        LazyLock, Once,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime},
+};
+
+use algebra::Algebra;
+
+use super::{SaveFile, open_files};
+
+/// The extension of lock files.
+pub(super) const LOCK_EXTENSION: &str = "lock";
+
+/// How often the owner of a lock refreshes the modification time of the lock file.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a lock file may go without being refreshed before it is considered stale. This is much
+/// longer than [`HEARTBEAT_INTERVAL`] to allow for slow filesystems and clock differences.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A claim on a save file. The lock file is deleted when this is dropped.
+#[derive(Debug)]
+pub struct SaveLock {
+    path: PathBuf,
+}
+
+/// The process holding a lock, as recorded in its lock file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockOwner {
+    pub host: String,
+    pub pid: u32,
+}
+
+impl LockOwner {
+    /// The current process.
+    pub fn current() -> Self {
+        Self {
+            host: hostname().to_owned(),
+            pid: std::process::id(),
+        }
+    }
+
+    fn read(path: &Path) -> Option<Self> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        let mut lines = contents.lines();
+        let host = lines.next()?.to_owned();
+        let pid = lines.next()?.parse().ok()?;
+        Some(Self { host, pid })
+    }
+
+    /// Whether the owner is known to have exited. We can only tell if it ran on this host.
+    fn is_dead(&self) -> bool {
+        !self.host.is_empty() && self.host == hostname() && !process_exists(self.pid)
+    }
+}
+
+impl fmt::Display for LockOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "process {} on {}", self.pid, self.host)
+    }
+}
+
+/// The host name of this machine, or the empty string if it cannot be determined.
+fn hostname() -> &'static str {
+    static HOSTNAME: LazyLock<String> = LazyLock::new(|| {
+        ["/proc/sys/kernel/hostname", "/etc/hostname"]
+            .into_iter()
+            .find_map(|path| std::fs::read_to_string(path).ok())
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .map(|host| host.trim().to_owned())
+            .unwrap_or_default()
+    });
+    &HOSTNAME
+}
+
+/// Whether a process with id `pid` exists on this host. If we cannot tell, we assume it does.
+fn process_exists(pid: u32) -> bool {
+    let proc = Path::new("/proc");
+    !proc.join("self").exists() || proc.join(pid.to_string()).exists()
+}
+
+/// Refresh the modification time of the lock file at `path`.
+fn touch(path: &Path) -> io::Result<()> {
+    std::fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+}
+
+/// Start the thread that refreshes the lock files held by this process, if it is not running yet.
+fn start_heartbeat() {
+    static HEARTBEAT: Once = Once::new();
+    HEARTBEAT.call_once(|| {
+        std::thread::Builder::new()
+            .name("lock heartbeat".to_owned())
+            .spawn(|| {
+                loop {
+                    std::thread::sleep(HEARTBEAT_INTERVAL);
+                    let open_files = open_files().lock().unwrap();
+                    for path in &*open_files {
+                        if path.extension().is_some_and(|ext| ext == LOCK_EXTENSION)
+                            && let Err(e) = touch(path)
+                        {
+                            tracing::warn!(file = ?path, "failed to refresh lock: {e}");
+                        }
+                    }
+                }
+            })
+            .expect("Error starting lock heartbeat thread");
+    });
+}
+
+/// Remove the lock file at `path` if it is stale. Returns whether there may no longer be a lock
+/// file, i.e. whether it is worth trying to create it again.
+fn break_stale_lock(path: &Path) -> io::Result<bool> {
+    let stale_state = |path: &Path| -> io::Result<Option<(Option<LockOwner>, SystemTime)>> {
+        let modified = match std::fs::metadata(path) {
+            Ok(metadata) => metadata.modified()?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(Some((LockOwner::read(path), modified)))
+    };
+
+    let Some(state) = stale_state(path)? else {
+        return Ok(true);
+    };
+    let expired = state.1.elapsed().is_ok_and(|age| age > LOCK_TIMEOUT);
+    let dead = state.0.as_ref().is_some_and(LockOwner::is_dead);
+    if !expired && !dead {
+        return Ok(false);
+    }
+
+    // Another process may break the same lock and create a new one at the same time, so we move
+    // the lock out of the way and check that we moved the stale one before deleting it.
+    static TOMBSTONES: AtomicU64 = AtomicU64::new(0);
+    let mut tombstone = path.as_os_str().to_owned();
+    tombstone.push(format!(
+        ".stale.{}.{}",
+        std::process::id(),
+        TOMBSTONES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tombstone = PathBuf::from(tombstone);
+    match std::fs::rename(path, &tombstone) {
+        Ok(()) => (),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e),
+    }
+
+    if stale_state(&tombstone)?.as_ref() != Some(&state) {
+        // This is a live lock, so we put it back. This fails if yet another process has claimed
+        // the save file in the meantime, in which case there is nothing we can do.
+        let restored = std::fs::hard_link(&tombstone, path);
+        std::fs::remove_file(&tombstone)?;
+        if let Err(e) = restored {
+            tracing::error!(file = ?path, "failed to restore lock: {e}");
+        }
+        return Ok(false);
+    }
+
+    std::fs::remove_file(&tombstone)?;
+    tracing::warn!(
+        file = ?path,
+        owner = ?state.0,
+        expired,
+        dead,
+        "broke stale lock"
+    );
+    Ok(true)
+}
+
+impl<A: Algebra> SaveFile<A> {
+    fn lock_path(&self, dir: PathBuf) -> PathBuf {
+        let mut path = self.get_save_path(dir);
+        path.set_extension(LOCK_EXTENSION);
+        path
+    }
+
+    /// Try to claim the save file in the save directory `dir`. Returns `None` if it is already
+    /// claimed, either by another process or by this one. A stale lock left behind by a crashed
+    /// process is broken first, see the [module documentation](self).
+    pub fn try_lock(&self, dir: PathBuf) -> io::Result<Option<SaveLock>> {
+        if self.find_in_archive(&dir).is_some() {
+            return Err(io::Error::other(
+                "Save files in an archive cannot be locked",
+            ));
+        }
+        let path = self.lock_path(dir);
+
+        loop {
+            // The Ctrl-C handler deletes the files in `open_files`. We hold its lock while creating
+            // the file, so that the file is registered before the handler can run.
+            let mut open_files = open_files().lock().unwrap();
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut f) => {
+                    open_files.insert(path.clone());
+                    drop(open_files);
+                    let owner = LockOwner::current();
+                    writeln!(f, "{}\n{}", owner.host, owner.pid)?;
+                    start_heartbeat();
+                    tracing::info!(file = ?path, "locked");
+                    return Ok(Some(SaveLock { path }));
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    drop(open_files);
+                    if !break_stale_lock(&path)? {
+                        return Ok(None);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// The path of the lock file of the save file in the save directory `dir`, and its owner if it
+    /// can be read.
+    pub fn lock_owner(&self, dir: PathBuf) -> (PathBuf, Option<LockOwner>) {
+        let path = self.lock_path(dir);
+        let owner = LockOwner::read(&path);
+        (path, owner)
+    }
+}
+
+impl Drop for SaveLock {
+    fn drop(&mut self) {
+        let mut open_files = open_files().lock().unwrap();
+        // If we stopped refreshing the lock for too long, another process may have broken it and
+        // claimed the save file itself. In that case the lock file is no longer ours to remove.
+        if LockOwner::read(&self.path).is_some_and(|owner| owner != LockOwner::current()) {
+            tracing::error!(file = ?self.path, "lock was broken by another process");
+        } else if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::error!(file = ?self.path, "failed to remove lock: {e}");
+        }
+        open_files.remove(&self.path);
+        tracing::info!(file = ?self.path, "unlocked");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use algebra::MilnorAlgebra;
+    use sseq::coordinates::Bidegree;
+
+    use super::*;
+    use crate::save::SaveKind;
+
+    fn save_file(dir: &Path) -> SaveFile<MilnorAlgebra> {
+        SaveKind::Differential.create_dir(dir).unwrap();
+        SaveFile {
+            kind: SaveKind::Differential,
+            algebra: Arc::new(MilnorAlgebra::new(fp::prime::TWO, false)),
+            b: Bidegree::s_t(1, 2),
+            idx: None,
+        }
+    }
+
+    #[test]
+    fn test_lock_released_on_drop() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let dir = tempdir.path().to_owned();
+        let file = save_file(&dir);
+
+        let lock = file.try_lock(dir.clone()).unwrap().unwrap();
+        let (path, owner) = file.lock_owner(dir.clone());
+        assert!(path.exists());
+        assert_eq!(owner, Some(LockOwner::current()));
+        assert!(open_files().lock().unwrap().contains(&path));
+        // We cannot claim the file twice, even from the same process.
+        assert!(file.try_lock(dir.clone()).unwrap().is_none());
+
+        drop(lock);
+        assert!(!path.exists());
+        assert!(!open_files().lock().unwrap().contains(&path));
+        assert!(file.try_lock(dir).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_break_expired_lock() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let dir = tempdir.path().to_owned();
+        let file = save_file(&dir);
+
+        // A lock held by a process on another machine that stopped refreshing it.
+        let path = file.lock_path(dir.clone());
+        std::fs::write(&path, "elsewhere\n1\n").unwrap();
+        assert!(file.try_lock(dir.clone()).unwrap().is_none());
+
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * LOCK_TIMEOUT)
+            .unwrap();
+        let _lock = file.try_lock(dir.clone()).unwrap().unwrap();
+        assert_eq!(file.lock_owner(dir).1, Some(LockOwner::current()));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_break_lock_of_dead_process() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let dir = tempdir.path().to_owned();
+        let file = save_file(&dir);
+
+        // Process ids are smaller than 2^22 on Linux, so this process does not exist.
+        let path = file.lock_path(dir.clone());
+        std::fs::write(&path, format!("{}\n{}\n", hostname(), u32::MAX)).unwrap();
+        let _lock = file.try_lock(dir.clone()).unwrap().unwrap();
+        assert_eq!(file.lock_owner(dir).1, Some(LockOwner::current()));
+    }
+}
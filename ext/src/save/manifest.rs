@@ -0,0 +1,513 @@
+//! Every save directory contains a manifest that records what the saved data is about, namely the
+//! version of the save format, the algebra and the module being resolved. This lets us refuse to
+//! open a save directory that belongs to a different computation, instead of silently producing
+//! wrong results.
+
+use std::path::{Path, PathBuf};
+
+use algebra::Algebra;
+use anyhow::Context;
+use fp::prime::Prime;
+use serde_json::{Map, Value, json};
+use sseq::coordinates::Bidegree;
+
+use super::{SaveKind, archive};
+
+/// The current version of the save format. This should be incremented whenever the format of the
+/// save files changes, and [`migrate`] should be updated to convert save directories of the
+/// previous version.
+///
+/// Version 0 refers to save directories without a manifest, whose save files have the same format
+/// as version 1.
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+
+/// The name of the manifest file in a save directory.
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// The kind magic of the manifest record in an archive.
+pub(super) const MANIFEST_MAGIC: u32 = 0x4D414E46;
+
+/// The manifest of a save directory.
+///
+/// The manifest is built up incrementally: a resolution only knows its algebra, while
+/// [`construct`](crate::utils::construct) also records the module specification. When a save
+/// directory is opened, every field recorded in both the existing manifest and the expected one
+/// must agree, and fields only known to the latter are added to the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveManifest {
+    pub format_version: u32,
+    pub prime: u32,
+    pub algebra_magic: u32,
+    /// The profile of the algebra if it is a sub-Hopf algebra, which the magic does not capture.
+    /// See [`Algebra::profile_description`].
+    pub profile: Option<String>,
+    /// The basis of the algebra, e.g. `milnor`.
+    pub algebra: Option<String>,
+    /// The json specification of the module, with the keys of objects sorted.
+    pub module: Option<Value>,
+    /// For the save directory of a product, the shift and the class defining the product.
+    pub product: Option<Value>,
+}
+
+impl SaveManifest {
+    /// A manifest that only records the algebra.
+    pub fn new(algebra: &impl Algebra) -> Self {
+        Self {
+            format_version: SAVE_FORMAT_VERSION,
+            prime: algebra.prime().as_u32(),
+            algebra_magic: algebra.magic(),
+            profile: algebra.profile_description(),
+            algebra: None,
+            module: None,
+            product: None,
+        }
+    }
+
+    /// Record the module being resolved and the basis of the algebra.
+    pub fn with_module(mut self, algebra: impl std::fmt::Display, module: &Value) -> Self {
+        self.algebra = Some(algebra.to_string());
+        self.module = Some(canonicalize(module));
+        self
+    }
+
+    /// Record the product defined by `class` in bidegree `shift`.
+    pub fn with_product(mut self, shift: Bidegree, class: &[u32]) -> Self {
+        self.product = Some(json!({
+            "s": shift.s(),
+            "t": shift.t(),
+            "class": class,
+        }));
+        self
+    }
+
+    /// A hash of the module specification, which is stable across platforms and versions.
+    pub fn module_hash(&self) -> Option<String> {
+        self.module
+            .as_ref()
+            .map(|m| format!("{:016x}", fnv1a(m.to_string().as_bytes())))
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut json = json!({
+            "format_version": self.format_version,
+            "library_version": env!("CARGO_PKG_VERSION"),
+            "prime": self.prime,
+            "algebra_magic": self.algebra_magic,
+        });
+        if let Some(profile) = &self.profile {
+            json["profile"] = Value::from(profile.as_str());
+        }
+        if let Some(algebra) = &self.algebra {
+            json["algebra"] = Value::from(algebra.as_str());
+        }
+        if let Some(module) = &self.module {
+            json["module_hash"] = Value::from(self.module_hash().unwrap());
+            json["module"] = module.clone();
+        }
+        if let Some(product) = &self.product {
+            json["product"] = product.clone();
+        }
+        json
+    }
+
+    pub fn from_json(json: &Value) -> anyhow::Result<Self> {
+        let get_u32 = |field: &str| -> anyhow::Result<u32> {
+            json[field]
+                .as_u64()
+                .and_then(|x| u32::try_from(x).ok())
+                .with_context(|| format!("Manifest is missing an integer `{field}` field"))
+        };
+        let optional = |field: &str| Some(&json[field]).filter(|x| !x.is_null()).cloned();
+
+        let manifest = Self {
+            format_version: get_u32("format_version")?,
+            prime: get_u32("prime")?,
+            algebra_magic: get_u32("algebra_magic")?,
+            profile: json["profile"].as_str().map(str::to_owned),
+            algebra: json["algebra"].as_str().map(str::to_owned),
+            module: optional("module"),
+            product: optional("product"),
+        };
+        if let Some(hash) = json["module_hash"].as_str() {
+            anyhow::ensure!(
+                manifest.module_hash().as_deref() == Some(hash),
+                "Manifest module hash does not match the module"
+            );
+        }
+        Ok(manifest)
+    }
+
+    /// Check that `other` describes the same computation as `self`, and record the fields of
+    /// `other` that `self` does not know about. Returns whether `self` was modified.
+    fn merge(&mut self, other: &Self) -> anyhow::Result<bool> {
+        anyhow::ensure!(
+            self.prime == other.prime,
+            "Saved data is at the prime {} but expected prime {}",
+            self.prime,
+            other.prime
+        );
+        anyhow::ensure!(
+            self.algebra_magic == other.algebra_magic,
+            "Saved data is over the algebra with magic {:#06x} but expected {:#06x}",
+            self.algebra_magic,
+            other.algebra_magic
+        );
+
+        let mut changed = false;
+        macro_rules! merge_field {
+            ($field:ident, $name:literal, $describe:expr) => {
+                match (&self.$field, &other.$field) {
+                    (Some(x), Some(y)) if x != y => {
+                        anyhow::bail!(
+                            "Saved data is for the {} {} but expected {}",
+                            $name,
+                            $describe(self),
+                            $describe(other)
+                        );
+                    }
+                    (None, Some(y)) => {
+                        self.$field = Some(y.clone());
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            };
+        }
+        merge_field!(profile, "profile", |m: &Self| m.profile.clone().unwrap());
+        merge_field!(algebra, "algebra", |m: &Self| m.algebra.clone().unwrap());
+        merge_field!(module, "module", |m: &Self| format!(
+            "with hash {}",
+            m.module_hash().unwrap()
+        ));
+        merge_field!(product, "product", |m: &Self| m
+            .product
+            .as_ref()
+            .unwrap()
+            .to_string());
+        Ok(changed)
+    }
+}
+
+/// Sort the keys of all objects in `value`, so that equal specifications serialize equally.
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let map: Map<String, Value> = keys
+                .into_iter()
+                .map(|k| (k.clone(), canonicalize(&map[k])))
+                .collect();
+            Value::Object(map)
+        }
+        Value::Array(v) => Value::Array(v.iter().map(canonicalize).collect()),
+        _ => value.clone(),
+    }
+}
+
+/// The 64-bit FNV-1a hash. Unlike the hashers in the standard library, this is guaranteed to be
+/// stable.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+fn manifest_key(prefix: String) -> archive::Key {
+    archive::Key {
+        prefix,
+        kind: MANIFEST_MAGIC,
+        algebra: 0,
+        s: 0,
+        t: 0,
+        idx: u32::MAX,
+    }
+}
+
+/// Read the manifest of the save directory `dir`, if there is one.
+pub fn read_manifest(dir: &Path) -> anyhow::Result<Option<SaveManifest>> {
+    let data = match archive::find(dir) {
+        Some((path, prefix)) => {
+            if !path.exists() {
+                return Ok(None);
+            }
+            archive::get(path)?.read(&manifest_key(prefix))
+        }
+        None => match std::fs::read(dir.join(MANIFEST_NAME)) {
+            Ok(data) => Some(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read manifest in {dir:?}"));
+            }
+        },
+    };
+    let Some(data) = data else {
+        return Ok(None);
+    };
+    let json: Value = serde_json::from_slice(&data)
+        .with_context(|| format!("Failed to parse manifest in {dir:?}"))?;
+    SaveManifest::from_json(&json)
+        .with_context(|| format!("Invalid manifest in {dir:?}"))
+        .map(Some)
+}
+
+fn write_manifest(dir: &Path, manifest: &SaveManifest) -> anyhow::Result<()> {
+    let data = serde_json::to_vec_pretty(&manifest.to_json())?;
+    match archive::find(dir) {
+        Some((path, prefix)) => archive::get(path)?.write(manifest_key(prefix), &data, None)?,
+        None => {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory {dir:?}"))?;
+            // Write to a temporary file first so that the manifest is never partially written. The
+            // file name is unique to this process, since several processes may share the save
+            // directory.
+            let tmp = dir.join(format!("{MANIFEST_NAME}.{}.tmp", std::process::id()));
+            std::fs::write(&tmp, data)
+                .and_then(|()| std::fs::rename(&tmp, dir.join(MANIFEST_NAME)))
+                .with_context(|| format!("Failed to write manifest in {dir:?}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether the save directory `dir` contains any save files.
+fn has_save_data(dir: &Path) -> anyhow::Result<bool> {
+    if let Some((path, prefix)) = archive::find(dir) {
+        if !path.exists() {
+            return Ok(false);
+        }
+        return Ok(archive::get(path)?
+            .keys()
+            .iter()
+            .any(|k| k.kind != MANIFEST_MAGIC && k.prefix == prefix));
+    }
+    for kind in SaveKind::all() {
+        let subdir = dir.join(format!("{}s", kind.name()));
+        if let Ok(mut entries) = subdir.read_dir()
+            && entries.next().is_some()
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Check that the save directory `dir` is compatible with `manifest`. If `write` is true, the
+/// manifest is written if there is none, and updated with any new information in `manifest`.
+pub(super) fn check_manifest(
+    dir: &Path,
+    manifest: &SaveManifest,
+    write: bool,
+) -> anyhow::Result<()> {
+    match read_manifest(dir)? {
+        Some(mut existing) => {
+            anyhow::ensure!(
+                existing.format_version <= SAVE_FORMAT_VERSION,
+                "Save directory {dir:?} has format version {}, which is newer than the version \
+                 supported by this program ({SAVE_FORMAT_VERSION})",
+                existing.format_version
+            );
+            anyhow::ensure!(
+                existing.format_version == SAVE_FORMAT_VERSION,
+                "Save directory {dir:?} has format version {}, but the current version is \
+                 {SAVE_FORMAT_VERSION}. Use `ext::save::migrate` (e.g. via the `migrate_save` \
+                 example) to upgrade it",
+                existing.format_version
+            );
+            let changed = existing.merge(manifest).with_context(|| {
+                format!("Save directory {dir:?} belongs to a different computation")
+            })?;
+            if changed && write {
+                write_manifest(dir, &existing)?;
+            }
+        }
+        None => {
+            anyhow::ensure!(
+                !has_save_data(dir)?,
+                "Save directory {dir:?} contains save data but no manifest, so it was probably \
+                 created by an older version. If it belongs to this computation, use \
+                 `ext::save::migrate` (e.g. via the `migrate_save` example) to add a manifest"
+            );
+            if write {
+                write_manifest(dir, manifest)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Upgrade the save directory `dir` to the current save format, and record `manifest` as its
+/// manifest. The caller is responsible for ensuring that the data in `dir` was computed for the
+/// computation described by `manifest`.
+///
+/// Returns the format version of the save directory before the migration.
+pub fn migrate(dir: &Path, manifest: &SaveManifest) -> anyhow::Result<u32> {
+    let (version, mut new_manifest) = match read_manifest(dir)? {
+        Some(existing) => (existing.format_version, existing),
+        None => (0, manifest.clone()),
+    };
+    anyhow::ensure!(
+        version <= SAVE_FORMAT_VERSION,
+        "Save directory {dir:?} has format version {version}, which is newer than the version \
+         supported by this program ({SAVE_FORMAT_VERSION})"
+    );
+
+    // Convert the save files one version at a time. Version 0 only differs from version 1 by the
+    // absence of a manifest, which is written below.
+    for from in version..SAVE_FORMAT_VERSION {
+        match from {
+            0 => {}
+            _ => unreachable!("No migration from version {from}"),
+        }
+    }
+
+    new_manifest.format_version = SAVE_FORMAT_VERSION;
+    new_manifest
+        .merge(manifest)
+        .with_context(|| format!("Save directory {dir:?} belongs to a different computation"))?;
+    write_manifest(dir, &new_manifest)?;
+
+    // The manifests of products record the class defining the product, which we do not know.
+    // Instead, we write a manifest without this information, and the class is recorded the next
+    // time the product is used.
+    let product_manifest = SaveManifest {
+        algebra: None,
+        module: None,
+        product: None,
+        ..new_manifest
+    };
+    for product_dir in product_dirs(dir)? {
+        if read_manifest(&product_dir)?.is_none() {
+            write_manifest(&product_dir, &product_manifest)?;
+        }
+    }
+    Ok(version)
+}
+
+/// The save directories of the products in the save directory `dir`.
+fn product_dirs(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let products = dir.join("products");
+    if let Some((path, prefix)) = archive::find(&products) {
+        let mut dirs: Vec<PathBuf> = archive::get(path)?
+            .keys()
+            .into_iter()
+            .filter_map(|k| {
+                let name = k.prefix.strip_prefix(&prefix)?.strip_prefix('/')?;
+                Some(products.join(name.split('/').next()?))
+            })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        return Ok(dirs);
+    }
+    match products.read_dir() {
+        Ok(entries) => Ok(entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read directory {products:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use algebra::MilnorAlgebra;
+
+    use super::*;
+
+    fn manifest() -> SaveManifest {
+        SaveManifest::new(&MilnorAlgebra::new(fp::prime::TWO, false))
+            .with_module("milnor", &json!({"p": 2, "gens": {"x0": 0}}))
+    }
+
+    fn error(result: anyhow::Result<impl std::fmt::Debug>) -> String {
+        format!("{:#}", result.unwrap_err())
+    }
+
+    #[test]
+    fn test_check_manifest_mismatch() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let dir = tempdir.path();
+        check_manifest(dir, &manifest(), true).unwrap();
+        assert_eq!(read_manifest(dir).unwrap(), Some(manifest()));
+        check_manifest(dir, &manifest(), true).unwrap();
+
+        let adem = SaveManifest {
+            algebra: Some("adem".to_owned()),
+            ..manifest()
+        };
+        assert!(
+            error(check_manifest(dir, &adem, true))
+                .ends_with("Saved data is for the algebra milnor but expected adem")
+        );
+
+        let magic = SaveManifest {
+            algebra_magic: manifest().algebra_magic + 1,
+            ..manifest()
+        };
+        assert!(error(check_manifest(dir, &magic, true)).contains("belongs to a different"));
+        assert_eq!(read_manifest(dir).unwrap(), Some(manifest()));
+    }
+
+    #[test]
+    fn test_check_manifest_version() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let dir = tempdir.path();
+
+        let newer = SaveManifest {
+            format_version: SAVE_FORMAT_VERSION + 1,
+            ..manifest()
+        };
+        write_manifest(dir, &newer).unwrap();
+        assert!(error(check_manifest(dir, &manifest(), true)).contains("which is newer"));
+        assert!(error(migrate(dir, &manifest())).contains("which is newer"));
+
+        let older = SaveManifest {
+            format_version: 0,
+            ..manifest()
+        };
+        write_manifest(dir, &older).unwrap();
+        assert!(error(check_manifest(dir, &manifest(), true)).contains("ext::save::migrate"));
+        assert_eq!(migrate(dir, &manifest()).unwrap(), 0);
+        check_manifest(dir, &manifest(), false).unwrap();
+    }
+
+    #[test]
+    fn test_migrate() {
+        let tempdir = tempfile::TempDir::new().unwrap();
+        let dir = tempdir.path();
+
+        // Save data without a manifest, as written by an older version.
+        SaveKind::Kernel.create_dir(dir).unwrap();
+        std::fs::write(dir.join("kernels/0_0_kernel"), []).unwrap();
+        let product_dir = dir.join("products/h0");
+        std::fs::create_dir_all(&product_dir).unwrap();
+        assert!(error(check_manifest(dir, &manifest(), true)).contains("no manifest"));
+
+        // Refuse to record a manifest for a different computation
+        write_manifest(dir, &manifest()).unwrap();
+        let adem = SaveManifest {
+            algebra: Some("adem".to_owned()),
+            ..manifest()
+        };
+        assert!(error(migrate(dir, &adem)).contains("belongs to a different"));
+        std::fs::remove_file(dir.join(MANIFEST_NAME)).unwrap();
+
+        assert_eq!(migrate(dir, &manifest()).unwrap(), 0);
+        assert_eq!(read_manifest(dir).unwrap(), Some(manifest()));
+        check_manifest(dir, &manifest(), false).unwrap();
+
+        // Products do not record the module, which is already recorded in the parent.
+        let product = read_manifest(&product_dir).unwrap().unwrap();
+        assert_eq!(product.module, None);
+        assert_eq!(product.algebra_magic, manifest().algebra_magic);
+
+        // A migrated save directory is already at the current version
+        assert_eq!(migrate(dir, &manifest()).unwrap(), SAVE_FORMAT_VERSION);
+    }
+}
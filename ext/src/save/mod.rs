@@ -11,6 +11,15 @@ use anyhow::Context;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use sseq::coordinates::Bidegree;
 
+mod archive;
+mod inspect;
+mod lock;
+mod manifest;
+
+pub use inspect::{FileReport, FileStatus, SaveReport, inspect};
+pub use lock::{LockOwner, SaveLock};
+pub use manifest::{MANIFEST_NAME, SAVE_FORMAT_VERSION, SaveManifest, migrate, read_manifest};
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum SaveDirectory {
     None,
@@ -58,6 +67,25 @@ impl SaveDirectory {
     pub fn is_some(&self) -> bool {
         !self.is_none()
     }
+
+    /// Whether we write to an archive. Save files in an archive cannot be locked, so such a save
+    /// directory cannot be shared by several processes.
+    pub fn is_archive(&self) -> bool {
+        self.write().is_some_and(|p| archive::find(p).is_some())
+    }
+
+    /// Check that the save directories are compatible with `manifest`, and record it in the write
+    /// directory. See [`SaveManifest`] for details.
+    pub fn check_manifest(&self, manifest: &SaveManifest) -> anyhow::Result<()> {
+        match self {
+            Self::None => Ok(()),
+            Self::Combined(d) => manifest::check_manifest(&d.0, manifest, true),
+            Self::Split { read, write } => {
+                manifest::check_manifest(&read.0, manifest, false)?;
+                manifest::check_manifest(&write.0, manifest, true)
+            }
+        }
+    }
 }
 
 impl From<Option<PathBuf>> for SaveDirectory {
@@ -121,8 +149,8 @@ pub enum SaveKind {
     /// A chain homotopy
     ChainHomotopy,
 
-    /// The differential with Nassau's algorithm. This does not store the chain map data because we
-    /// always only resolve the sphere
+    /// The differential with Nassau's algorithm. This does not store the chain map data, which is
+    /// only non-trivial in homological degree 0 and is cheap to recompute.
     NassauDifferential,
 
     /// The quasi-inverse data in Nassau's algorithm
@@ -181,7 +209,45 @@ impl SaveKind {
         KINDS.iter().copied()
     }
 
+    /// All kinds of save data.
+    pub fn all() -> impl Iterator<Item = Self> {
+        use SaveKind::*;
+        static KINDS: [SaveKind; 11] = [
+            Kernel,
+            Differential,
+            ResQi,
+            AugmentationQi,
+            SecondaryComposite,
+            SecondaryIntermediate,
+            SecondaryHomotopy,
+            ChainMap,
+            ChainHomotopy,
+            NassauDifferential,
+            NassauQi,
+        ];
+        KINDS.iter().copied()
+    }
+
+    pub fn from_magic(magic: u32) -> Option<Self> {
+        Self::all().find(|k| k.magic() == magic)
+    }
+
+    /// Whether data of this kind is kept for every bidegree that has been computed. Other kinds of
+    /// data are deleted once they are no longer needed, so gaps in their coverage are expected.
+    pub fn is_persistent(self) -> bool {
+        use SaveKind::*;
+        matches!(
+            self,
+            Differential | ResQi | AugmentationQi | NassauDifferential | NassauQi
+        )
+    }
+
     pub fn create_dir(self, p: &std::path::Path) -> anyhow::Result<()> {
+        if let Some((archive, _)) = archive::find(p) {
+            // This opens the archive, so that errors are reported early.
+            archive::get(archive)?;
+            return Ok(());
+        }
         let mut p = p.to_owned();
 
         p.push(format!("{}s", self.name()));
@@ -195,26 +261,151 @@ impl SaveKind {
     }
 }
 
+/// The zstd compression level used for new save files, or `None` if they are written uncompressed.
+/// This is initialized from the `SAVE_COMPRESSION_LEVEL` environment variable.
+static COMPRESSION_LEVEL: LazyLock<Mutex<Option<i32>>> =
+    LazyLock::new(|| Mutex::new(compression_level_from_env()));
+
+fn compression_level_from_env() -> Option<i32> {
+    let val = std::env::var("SAVE_COMPRESSION_LEVEL").ok()?;
+    let parsed: Option<i32> = str::parse(&val).ok();
+    if parsed.is_none() {
+        tracing::warn!(
+            value = val,
+            "invalid argument for `SAVE_COMPRESSION_LEVEL`, expected an integer. Writing \
+             uncompressed save files"
+        );
+    }
+    #[cfg(not(feature = "zstd"))]
+    if parsed.is_some() {
+        tracing::warn!(
+            "`SAVE_COMPRESSION_LEVEL` is set but the `zstd` feature is disabled. Ignoring"
+        );
+        return None;
+    }
+    parsed
+}
+
+/// The zstd compression level used when writing new save files, or `None` if save files are
+/// written uncompressed. This defaults to the value of the `SAVE_COMPRESSION_LEVEL` environment
+/// variable, and is always `None` if the `zstd` feature is disabled.
+pub fn compression_level() -> Option<i32> {
+    *COMPRESSION_LEVEL.lock().unwrap()
+}
+
+/// Set the zstd compression level used when writing new save files. If `level` is `None`, then
+/// save files are written uncompressed. Files that are already open are not affected.
+///
+/// # Panics
+/// This panics if `level` is not `None` and the `zstd` feature is disabled.
+pub fn set_compression_level(level: Option<i32>) {
+    #[cfg(not(feature = "zstd"))]
+    assert!(
+        level.is_none(),
+        "Writing compressed save files requires the zstd feature"
+    );
+    *COMPRESSION_LEVEL.lock().unwrap() = level;
+}
+
+/// A writer that has to be finalized before it is closed, e.g. to write the epilogue of a
+/// compressed stream. The default implementation simply flushes the writer.
+pub trait Finish: io::Write {
+    fn finish(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+impl<W: io::Write> Finish for io::BufWriter<W> {}
+
+#[cfg(feature = "zstd")]
+impl<W: io::Write> Finish for zstd::stream::write::Encoder<'_, W> {
+    fn finish(&mut self) -> io::Result<()> {
+        self.do_finish()?;
+        self.get_mut().flush()
+    }
+}
+
+/// The writer underlying a save file, which compresses the data if requested.
+enum SaveWriter {
+    Uncompressed(io::BufWriter<File>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, File>),
+    Archive(archive::ArchiveWriter),
+}
+
+impl io::Write for SaveWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Uncompressed(w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.write(buf),
+            Self::Archive(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Uncompressed(w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.flush(),
+            Self::Archive(w) => w.flush(),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Uncompressed(w) => w.write_all(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.write_all(buf),
+            Self::Archive(w) => w.write_all(buf),
+        }
+    }
+}
+
+impl Finish for SaveWriter {
+    fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Uncompressed(w) => w.finish(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(w) => w.finish(),
+            Self::Archive(w) => w.finish(),
+        }
+    }
+}
+
 /// In addition to checking the checksum, we also keep track of which files are open, and we delete
 /// the open files if the program is terminated halfway.
-pub struct ChecksumWriter<T: io::Write> {
+pub struct ChecksumWriter<T: Finish> {
     writer: T,
     path: PathBuf,
+    /// Whether `path` is in [`open_files`]. This is false for files in an archive, which are only
+    /// written when they are finished.
+    tracked: bool,
     adler: adler::Adler32,
 }
 
-impl<T: io::Write> ChecksumWriter<T> {
+impl<T: Finish> ChecksumWriter<T> {
     pub fn new(path: PathBuf, writer: T) -> Self {
         Self {
             path,
             writer,
+            tracked: true,
+            adler: adler::Adler32::new(),
+        }
+    }
+
+    fn new_untracked(path: PathBuf, writer: T) -> Self {
+        Self {
+            path,
+            writer,
+            tracked: false,
             adler: adler::Adler32::new(),
         }
     }
 }
 
 /// We only implement the functions required and the ones we actually use.
-impl<T: io::Write> io::Write for ChecksumWriter<T> {
+impl<T: Finish> io::Write for ChecksumWriter<T> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let bytes_written = self.writer.write(buf)?;
         self.adler.write_slice(&buf[0..bytes_written]);
@@ -232,7 +423,7 @@ impl<T: io::Write> io::Write for ChecksumWriter<T> {
     }
 }
 
-impl<T: io::Write> std::ops::Drop for ChecksumWriter<T> {
+impl<T: Finish> std::ops::Drop for ChecksumWriter<T> {
     fn drop(&mut self) {
         if !std::thread::panicking() {
             // We may not have finished writing, so the data is wrong. It should not be given a
@@ -240,9 +431,11 @@ impl<T: io::Write> std::ops::Drop for ChecksumWriter<T> {
             self.writer
                 .write_u32::<LittleEndian>(self.adler.checksum())
                 .unwrap();
-            self.writer.flush().unwrap();
+            // The file is only complete after it is finished, so we must do this before removing
+            // it from the list of open files.
+            self.writer.finish().unwrap();
             assert!(
-                open_files().lock().unwrap().remove(&self.path),
+                !self.tracked || open_files().lock().unwrap().remove(&self.path),
                 "File {:?} already dropped",
                 self.path
             );
@@ -254,6 +447,8 @@ impl<T: io::Write> std::ops::Drop for ChecksumWriter<T> {
 pub struct ChecksumReader<T: io::Read> {
     reader: T,
     adler: adler::Adler32,
+    /// Whether to verify the checksum when the reader is dropped.
+    verify: bool,
 }
 
 impl<T: io::Read> ChecksumReader<T> {
@@ -261,8 +456,15 @@ impl<T: io::Read> ChecksumReader<T> {
         Self {
             reader,
             adler: adler::Adler32::new(),
+            verify: true,
         }
     }
+
+    /// Close the reader without verifying the checksum. This is used when we stop reading the file
+    /// early, e.g. because its contents turned out to be invalid.
+    pub fn abort(mut self) {
+        self.verify = false;
+    }
 }
 
 /// We only implement the functions required and the ones we actually use.
@@ -282,7 +484,7 @@ impl<T: io::Read> io::Read for ChecksumReader<T> {
 
 impl<T: io::Read> std::ops::Drop for ChecksumReader<T> {
     fn drop(&mut self) {
-        if !std::thread::panicking() {
+        if self.verify && !std::thread::panicking() {
             // If we are panicking, we may not have read everything, and panic in panic
             // is bad.
             assert_eq!(
@@ -297,9 +499,9 @@ impl<T: io::Read> std::ops::Drop for ChecksumReader<T> {
     }
 }
 
-/// Open the file pointed to by `path` as a `Box<dyn Read>`. If the file does not exist, look for
+/// Open the file pointed to by `path` as a [`ChecksumReader`]. If the file does not exist, look for
 /// compressed versions.
-fn open_file(path: PathBuf) -> Option<Box<dyn io::Read>> {
+fn open_file(path: PathBuf) -> Option<ChecksumReader<Box<dyn io::Read>>> {
     use io::BufRead;
 
     // We should try in decreasing order of access speed.
@@ -316,7 +518,7 @@ fn open_file(path: PathBuf) -> Option<Box<dyn io::Read>> {
                     .unwrap_or_else(|e| panic!("Error when deleting empty file {path:?}: {e}"));
                 return None;
             }
-            return Some(Box::new(ChecksumReader::new(reader)));
+            return Some(ChecksumReader::new(Box::new(reader)));
         }
         Err(e) => {
             if e.kind() != io::ErrorKind::NotFound {
@@ -331,7 +533,7 @@ fn open_file(path: PathBuf) -> Option<Box<dyn io::Read>> {
         path.set_extension("zst");
         match File::open(&path) {
             Ok(f) => {
-                return Some(Box::new(ChecksumReader::new(
+                return Some(ChecksumReader::new(Box::new(
                     zstd::stream::Decoder::new(f).unwrap(),
                 )));
             }
@@ -419,10 +621,37 @@ impl<A: Algebra> SaveFile<A> {
         dir
     }
 
-    pub fn open_file(&self, dir: PathBuf) -> Option<Box<dyn io::Read>> {
+    /// The key of the save file in an archive, where `prefix` is the location of the save
+    /// directory in the archive.
+    fn archive_key(&self, prefix: String) -> archive::Key {
+        archive::Key {
+            prefix,
+            kind: self.kind.magic(),
+            algebra: self.algebra.magic(),
+            s: self.b.s(),
+            t: self.b.t(),
+            idx: self.idx.map_or(u32::MAX, |i| i as u32),
+        }
+    }
+
+    /// If `dir` lies in an archive, return the archive and the key of the save file in it.
+    fn find_in_archive(&self, dir: &Path) -> Option<(Arc<archive::Archive>, archive::Key)> {
+        let (path, prefix) = archive::find(dir)?;
+        let archive = archive::get(path).unwrap_or_else(|e| panic!("{e:#}"));
+        Some((archive, self.archive_key(prefix)))
+    }
+
+    pub fn open_file(&self, dir: PathBuf) -> Option<ChecksumReader<Box<dyn io::Read>>> {
+        let archive = self.find_in_archive(&dir);
         let file_path = self.get_save_path(dir);
         let path_string = file_path.to_string_lossy().into_owned();
-        if let Some(mut f) = open_file(file_path) {
+        let file = match archive {
+            Some((archive, key)) => archive.read(&key).map(|data| {
+                ChecksumReader::new(Box::new(io::Cursor::new(data)) as Box<dyn io::Read>)
+            }),
+            None => open_file(file_path),
+        };
+        if let Some(mut f) = file {
             self.validate_header(&mut f).unwrap();
             tracing::info!(file = path_string, "success open for reading");
             Some(f)
@@ -433,6 +662,9 @@ impl<A: Algebra> SaveFile<A> {
     }
 
     pub fn exists(&self, dir: PathBuf) -> bool {
+        if let Some((archive, key)) = self.find_in_archive(&dir) {
+            return archive.contains(&key);
+        }
         let path = self.get_save_path(dir);
         if path.exists() {
             return true;
@@ -448,19 +680,100 @@ impl<A: Algebra> SaveFile<A> {
         false
     }
 
+    /// Delete the save file, both the uncompressed and compressed versions.
     pub fn delete_file(&self, dir: PathBuf) -> io::Result<()> {
-        let p = self.get_save_path(dir);
-        match std::fs::remove_file(p) {
-            Ok(()) => Ok(()),
-            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
-            Err(e) => Err(e),
+        if let Some((archive, key)) = self.find_in_archive(&dir) {
+            return archive.delete(&key);
         }
+        let mut p = self.get_save_path(dir);
+        remove_file_if_exists(&p)?;
+        p.set_extension("zst");
+        remove_file_if_exists(&p)
+    }
+
+    /// Copy the save file from the save directory `from` to the save directory `to`, checking its
+    /// header and checksum. The copy is compressed according to [`compression_level`]. Returns
+    /// `false` if the file does not exist in `from`.
+    pub fn copy_file(&self, from: PathBuf, to: PathBuf) -> anyhow::Result<bool> {
+        let data = if let Some((archive, key)) = self.find_in_archive(&from) {
+            archive.read(&key)
+        } else {
+            let path = self.get_save_path(from);
+            let mut zst = path.clone();
+            zst.set_extension("zst");
+            match [(path, false), (zst, true)]
+                .into_iter()
+                .find(|(p, _)| p.exists())
+            {
+                Some((p, compressed)) => Some(
+                    inspect::read_file(&p, compressed)
+                        .map_err(|e| anyhow::anyhow!("Failed to read {p:?}: {e:?}"))?,
+                ),
+                None => None,
+            }
+        };
+        let Some(data) = data else {
+            return Ok(false);
+        };
+
+        let (status, algebra) = inspect::check(self.kind, self.b, self.idx, &data);
+        anyhow::ensure!(
+            status == inspect::FileStatus::Complete,
+            "Failed to copy {} file at {}: {status:?}",
+            self.kind.name(),
+            self.b
+        );
+        anyhow::ensure!(
+            algebra == Some(self.algebra.magic()),
+            "Failed to copy {} file at {}: wrong algebra",
+            self.kind.name(),
+            self.b
+        );
+
+        // Strip the header, which is rewritten by `create_file`, and the checksum
+        let mut f = self.create_file(to, false);
+        io::Write::write_all(&mut f, &data[16..data.len() - 4])?;
+        Ok(true)
     }
 
+    /// Create a save file and write the header. If [`compression_level`] is not `None`, then the
+    /// file is compressed with zstd and saved with a `.zst` extension. If `dir` lies in an archive,
+    /// the file is instead added to the archive when the writer is dropped.
+    ///
     /// # Arguments
-    ///  - `overwrite`: Whether to overwrite a file if it already exists.
+    ///  - `overwrite`: Whether to overwrite a file if it already exists. In this case, we also
+    ///    delete the version of the file with the other compression, which would otherwise be
+    ///    read in place of the new file.
     pub fn create_file(&self, dir: PathBuf, overwrite: bool) -> impl io::Write + use<A> {
-        let p = self.get_save_path(dir);
+        let level = compression_level();
+        if let Some((archive, key)) = self.find_in_archive(&dir) {
+            let p = self.get_save_path(dir);
+            tracing::info!(file = ?p, "open for writing");
+            assert!(
+                overwrite || !archive.contains(&key),
+                "Failed to create save file {p:?}: file exists"
+            );
+            let writer = SaveWriter::Archive(archive::ArchiveWriter::new(archive, key, level));
+            let mut f = ChecksumWriter::new_untracked(p, writer);
+            self.write_header(&mut f).unwrap();
+            return f;
+        }
+
+        let mut p = self.get_save_path(dir);
+        if level.is_some() {
+            if overwrite {
+                remove_file_if_exists(&p)
+                    .with_context(|| format!("Failed to remove old save file {p:?}"))
+                    .unwrap();
+            }
+            p.set_extension("zst");
+        } else if overwrite {
+            let mut zst = p.clone();
+            zst.set_extension("zst");
+            remove_file_if_exists(&zst)
+                .with_context(|| format!("Failed to remove old save file {zst:?}"))
+                .unwrap();
+        }
         tracing::info!(file = ?p, "open for writing");
 
         // We need to do this before creating any file. The ctrlc handler does not block other threads
@@ -479,12 +792,27 @@ impl<A: Algebra> SaveFile<A> {
             .open(&p)
             .with_context(|| format!("Failed to create save file {p:?}"))
             .unwrap();
-        let mut f = ChecksumWriter::new(p, io::BufWriter::new(f));
+        let writer = match level {
+            None => SaveWriter::Uncompressed(io::BufWriter::new(f)),
+            #[cfg(feature = "zstd")]
+            Some(level) => SaveWriter::Zstd(zstd::stream::write::Encoder::new(f, level).unwrap()),
+            #[cfg(not(feature = "zstd"))]
+            Some(_) => unreachable!(),
+        };
+        let mut f = ChecksumWriter::new(p, writer);
         self.write_header(&mut f).unwrap();
         f
     }
 }
 
+fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct AbsolutePath(PathBuf);
 
@@ -1,4 +1,8 @@
-use std::{io, sync::Arc};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use algebra::{
     Algebra,
@@ -8,6 +12,7 @@ use algebra::{
     },
     pair_algebra::PairAlgebra,
 };
+use anyhow::Context;
 use bivec::BiVec;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use dashmap::DashMap;
@@ -45,6 +50,113 @@ pub type CompositeData<A> = Vec<(
     Arc<FreeModuleHomomorphism<FreeModule<A>>>,
 )>;
 
+/// An independent unit of work of a [`SecondaryLift`]. See [`SecondaryLift::jobs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecondaryJob {
+    /// Compute the composites of the generators in a bidegree.
+    Composite(Bidegree),
+    /// Compute the intermediates of the generators in a bidegree.
+    Intermediate(Bidegree),
+}
+
+impl SecondaryJob {
+    pub fn degree(self) -> Bidegree {
+        match self {
+            Self::Composite(b) | Self::Intermediate(b) => b,
+        }
+    }
+
+    pub fn phase(self) -> ShardPhase {
+        match self {
+            Self::Composite(_) => ShardPhase::Composites,
+            Self::Intermediate(_) => ShardPhase::Intermediates,
+        }
+    }
+}
+
+impl std::fmt::Display for SecondaryJob {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Composite(b) => write!(f, "composite {b}"),
+            Self::Intermediate(b) => write!(f, "intermediate {b}"),
+        }
+    }
+}
+
+/// The kind of [`SecondaryJob`] run by a [`Shard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShardPhase {
+    Composites,
+    Intermediates,
+}
+
+/// A part of a partition of the jobs of one phase into `count` parts of roughly equal size, e.g.
+/// for running the jobs as a cluster array job. This is written as `phase:index/count`, where
+/// `phase` is `composites` or `intermediates` and `index` ranges from `0` to `count - 1`.
+///
+/// The intermediate jobs use the composites, so all shards of the `composites` phase have to be
+/// done before any shard of the `intermediates` phase is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    pub phase: ShardPhase,
+    pub index: usize,
+    pub count: usize,
+}
+
+impl Shard {
+    /// The jobs in this shard. The jobs of the phase of the shard are assigned to shards in a
+    /// round-robin fashion, so that the order of the jobs is preserved within each shard.
+    pub fn select(
+        self,
+        jobs: impl IntoIterator<Item = SecondaryJob>,
+    ) -> impl Iterator<Item = SecondaryJob> {
+        jobs.into_iter()
+            .filter(move |job| job.phase() == self.phase)
+            .skip(self.index)
+            .step_by(self.count)
+    }
+}
+
+impl std::str::FromStr for Shard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (phase, range) = s
+            .split_once(':')
+            .with_context(|| format!("Invalid shard {s:?}. Expected phase:index/count"))?;
+        let phase = match phase.trim() {
+            "composites" => ShardPhase::Composites,
+            "intermediates" => ShardPhase::Intermediates,
+            _ => {
+                anyhow::bail!("Invalid shard {s:?}. Phase must be one of composites, intermediates")
+            }
+        };
+        let (index, count) = range
+            .split_once('/')
+            .with_context(|| format!("Invalid shard {s:?}. Expected phase:index/count"))?;
+        let shard = Self {
+            phase,
+            index: index.trim().parse()?,
+            count: count.trim().parse()?,
+        };
+        anyhow::ensure!(
+            shard.index < shard.count,
+            "Invalid shard {s:?}. Index must be smaller than count"
+        );
+        Ok(shard)
+    }
+}
+
+impl std::fmt::Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let phase = match self.phase {
+            ShardPhase::Composites => "composites",
+            ShardPhase::Intermediates => "intermediates",
+        };
+        write!(f, "{phase}:{}/{}", self.index, self.count)
+    }
+}
+
 /// A homotopy of a map A -> M of pair modules. We assume this map does not hit generators.
 pub struct SecondaryComposite<A: PairAlgebra> {
     target: Arc<FreeModule<A>>,
@@ -243,61 +355,97 @@ impl<A: PairAlgebra + Send + Sync> SecondaryHomotopy<A> {
     /// Add composites up to and including the specified degree
     #[tracing::instrument(skip(self, maps, dir), fields(source = %self.source, target = %self.target))]
     pub fn add_composite(&self, s: i32, degree: i32, maps: CompositeData<A>, dir: &SaveDirectory) {
-        for (_, d1, d0) in &maps {
+        self.extend_composites(s, degree, &maps, dir.read(), dir.write());
+    }
+
+    /// Add composites up to and including the specified degree, loading them from `dir` where
+    /// possible. Unlike [`SecondaryHomotopy::add_composite`], composites that are not found are
+    /// computed but not saved, so this is safe to use while other processes are writing to `dir`.
+    pub(crate) fn load_composites(
+        &self,
+        s: i32,
+        degree: i32,
+        maps: CompositeData<A>,
+        dir: &SaveDirectory,
+    ) {
+        self.extend_composites(s, degree, &maps, dir.read(), None);
+    }
+
+    fn extend_composites(
+        &self,
+        s: i32,
+        degree: i32,
+        maps: &CompositeData<A>,
+        read: Option<&PathBuf>,
+        write: Option<&PathBuf>,
+    ) {
+        for (_, d1, d0) in maps {
             assert!(Arc::ptr_eq(&d1.target(), &d0.source()));
             assert!(Arc::ptr_eq(&d0.target(), &self.target));
             assert_eq!(d1.degree_shift() + d0.degree_shift(), self.shift_t);
         }
 
         let tracing_span = tracing::Span::current();
-        let f = |t, idx| {
-            let _tracing_guard = tracing_span.enter();
-            let g = BidegreeGenerator::s_t(s, t, idx);
-            let save_file = SaveFile {
-                algebra: self.target.algebra(),
-                kind: SaveKind::SecondaryComposite,
-                b: g.degree(),
-                idx: Some(g.idx()),
-            };
-            if let Some(dir) = dir.read()
-                && let Some(mut f) = save_file.open_file(dir.to_owned())
-            {
-                return SecondaryComposite::from_bytes(
-                    Arc::clone(&self.target),
-                    g.t() - self.shift_t,
-                    self.hit_generator,
-                    &mut f,
-                )
-                .unwrap();
-            }
+        self.composites.maybe_par_extend(degree, |t| {
+            (0..self.source.number_of_gens_in_degree(t))
+                .into_maybe_par_iter()
+                .map(|i| {
+                    let _tracing_guard = tracing_span.enter();
+                    self.get_composite(BidegreeGenerator::s_t(s, t, i), maps, read, write)
+                })
+                .collect()
+        });
+    }
 
-            let mut composite = SecondaryComposite::new(
+    /// Load the composite of the generator `g` from `read`, or compute it and save it to `write`.
+    pub(crate) fn get_composite(
+        &self,
+        g: BidegreeGenerator,
+        maps: &CompositeData<A>,
+        read: Option<&PathBuf>,
+        write: Option<&PathBuf>,
+    ) -> SecondaryComposite<A> {
+        let save_file = self.composite_save_file(g);
+        if let Some(dir) = read
+            && let Some(mut f) = save_file.open_file(dir.to_owned())
+        {
+            return SecondaryComposite::from_bytes(
                 Arc::clone(&self.target),
                 g.t() - self.shift_t,
                 self.hit_generator,
-            );
+                &mut f,
+            )
+            .unwrap();
+        }
 
-            tracing::info_span!("Computing composite", %g).in_scope(|| {
-                for (coef, d1, d0) in &maps {
-                    composite.add_composite(*coef, g.t(), g.idx(), d1, d0);
-                }
-                composite.finalize();
-            });
+        let mut composite = SecondaryComposite::new(
+            Arc::clone(&self.target),
+            g.t() - self.shift_t,
+            self.hit_generator,
+        );
 
-            if let Some(dir) = dir.write() {
-                let mut f = save_file.create_file(dir.to_owned(), false);
-                composite.to_bytes(&mut f).unwrap();
+        tracing::info_span!("Computing composite", %g).in_scope(|| {
+            for (coef, d1, d0) in maps {
+                composite.add_composite(*coef, g.t(), g.idx(), d1, d0);
             }
+            composite.finalize();
+        });
 
-            composite
-        };
+        if let Some(dir) = write {
+            let mut f = save_file.create_file(dir.to_owned(), false);
+            composite.to_bytes(&mut f).unwrap();
+        }
 
-        self.composites.maybe_par_extend(degree, |t| {
-            (0..self.source.number_of_gens_in_degree(t))
-                .into_maybe_par_iter()
-                .map(|i| f(t, i))
-                .collect()
-        });
+        composite
+    }
+
+    pub(crate) fn composite_save_file(&self, g: BidegreeGenerator) -> SaveFile<A> {
+        SaveFile {
+            algebra: self.target.algebra(),
+            kind: SaveKind::SecondaryComposite,
+            b: g.degree(),
+            idx: Some(g.idx()),
+        }
     }
 
     /// Compute the image of an element in the source under the homotopy, writing the result in
@@ -525,6 +673,161 @@ pub trait SecondaryLift: Sync + Sized {
             })
     }
 
+    /// The units of work that make up the computation of the composites and intermediates, which
+    /// is the bulk of the work of [`SecondaryLift::extend_all`]. The jobs can be run in any
+    /// order and in separate processes with [`SecondaryLift::run_job`], and their results are
+    /// saved to the save directory. [`SecondaryLift::extend_all`] then picks up the results.
+    ///
+    /// An intermediate job in filtration `s` uses the composites in filtration `s - 1`, and can
+    /// only be run once all of these have been saved. The composite jobs are listed before the
+    /// intermediate jobs.
+    fn jobs(&self) -> Vec<SecondaryJob> {
+        self.initialize_homotopies();
+        let shift = self.shift();
+        let max = self.max();
+        let homotopies = self.homotopies();
+
+        let bidegrees = |s: i32| {
+            let homotopy = &homotopies[s];
+            (homotopy.composites.min_degree()..max.t(s))
+                .filter(move |&t| homotopy.source.number_of_gens_in_degree(t) > 0)
+                .map(move |t| Bidegree::s_t(s, t))
+        };
+
+        let composites = homotopies.range().flat_map(bidegrees);
+        let intermediates = (shift.s() + 1..homotopies.len()).flat_map(bidegrees);
+        composites
+            .map(SecondaryJob::Composite)
+            .chain(intermediates.map(SecondaryJob::Intermediate))
+            .collect()
+    }
+
+    /// The save files written by `job`.
+    fn job_files(&self, job: SecondaryJob) -> Vec<SaveFile<Self::Algebra>> {
+        let b = job.degree();
+        let kind = match job {
+            SecondaryJob::Composite(_) => SaveKind::SecondaryComposite,
+            SecondaryJob::Intermediate(_) => SaveKind::SecondaryIntermediate,
+        };
+        (0..self.source().module(b.s()).number_of_gens_in_degree(b.t()))
+            .map(|i| SaveFile {
+                algebra: self.algebra(),
+                kind,
+                b,
+                idx: Some(i),
+            })
+            .collect()
+    }
+
+    /// Whether the results of `job` are in the save directory. An intermediate job is also done if
+    /// the homotopy it is used for has been computed.
+    fn is_job_done(&self, job: SecondaryJob) -> bool {
+        let Some(dir) = self.save_dir().read() else {
+            return false;
+        };
+        if let SecondaryJob::Intermediate(b) = job {
+            let homotopy = SaveFile {
+                algebra: self.algebra(),
+                kind: SaveKind::SecondaryHomotopy,
+                b,
+                idx: None,
+            };
+            if homotopy.exists(dir.to_owned()) {
+                return true;
+            }
+        }
+        self.job_files(job).iter().all(|f| f.exists(dir.to_owned()))
+    }
+
+    /// Run `job` and save the results to the save directory. This does nothing if the job is
+    /// already done.
+    #[tracing::instrument(skip(self), fields(%job))]
+    fn run_job(&self, job: SecondaryJob) -> anyhow::Result<()> {
+        let Some(dir) = self.save_dir().write() else {
+            anyhow::bail!("Secondary jobs can only be run with a save directory");
+        };
+        self.initialize_homotopies();
+        let homotopies = self.homotopies();
+        let b = job.degree();
+        let min_s = match job {
+            SecondaryJob::Composite(_) => homotopies.min_degree(),
+            SecondaryJob::Intermediate(_) => homotopies.min_degree() + 1,
+        };
+        anyhow::ensure!(
+            (min_s..homotopies.len()).contains(&b.s()) && b.t() < self.max().t(b.s()),
+            "Job {job} is out of range"
+        );
+        if self.is_job_done(job) {
+            return Ok(());
+        }
+
+        match job {
+            SecondaryJob::Composite(b) => {
+                let maps = self.composite(b.s());
+                let files = self.job_files(job);
+                files.into_maybe_par_iter().enumerate().for_each(|(i, f)| {
+                    if !f.exists(dir.to_owned()) {
+                        homotopies[b.s()].get_composite(
+                            BidegreeGenerator::new(b, i),
+                            &maps,
+                            None,
+                            Some(dir),
+                        );
+                    }
+                });
+            }
+            SecondaryJob::Intermediate(b) => {
+                // Otherwise every intermediate job would recompute the missing composites.
+                let missing = self
+                    .jobs()
+                    .into_iter()
+                    .filter(|j| matches!(j, SecondaryJob::Composite(c) if c.s() == b.s() - 1))
+                    .find(|&j| !self.is_job_done(j));
+                if let Some(missing) = missing {
+                    anyhow::bail!("Job {job} requires {missing} to be done first");
+                }
+                homotopies[b.s() - 1].load_composites(
+                    b.s() - 1,
+                    self.max().t(b.s() - 1) - 1,
+                    self.composite(b.s() - 1),
+                    self.save_dir(),
+                );
+                let files = self.job_files(job);
+                files.into_maybe_par_iter().enumerate().for_each(|(i, f)| {
+                    if !f.exists(dir.to_owned()) {
+                        self.get_intermediate(BidegreeGenerator::new(b, i));
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the results of `job` from the save directory `from`, e.g. one used by another process
+    /// to run the job, to our save directory. Returns whether the job is done afterwards.
+    fn merge_job(&self, job: SecondaryJob, from: &Path) -> anyhow::Result<bool> {
+        let Some(dir) = self.save_dir().write() else {
+            anyhow::bail!("Secondary jobs can only be merged into a save directory");
+        };
+        if self.is_job_done(job) {
+            return Ok(true);
+        }
+        for f in self.job_files(job) {
+            if !f.exists(dir.to_owned()) {
+                f.copy_file(from.to_owned(), dir.to_owned())?;
+            }
+        }
+        Ok(self.is_job_done(job))
+    }
+
+    /// Run the jobs in `shard`. See [`SecondaryLift::jobs`].
+    fn run_shard(&self, shard: Shard) -> anyhow::Result<()> {
+        for job in shard.select(self.jobs()) {
+            self.run_job(job)?;
+        }
+        Ok(())
+    }
+
     fn compute_homotopy_step(&self, b: Bidegree) -> std::ops::Range<i32> {
         self.try_compute_homotopy_step(b).unwrap()
     }
@@ -2,30 +2,225 @@
 use std::{path::PathBuf, sync::Arc};
 
 use algebra::{
-    AlgebraType, MilnorAlgebra, SteenrodAlgebra,
-    module::{FDModule, Module, SteenrodModule, steenrod_module},
+    AlgebraType, FiniteDimensionalAlgebra, MotivicMilnorAlgebra, SteenrodAlgebra, SubHopfAlgebra,
+    module::{
+        FDModule, FreeModule, Module, SteenrodModule, homomorphism::FreeModuleHomomorphism,
+        steenrod_module,
+    },
 };
 use anyhow::{Context, anyhow};
+use fp::{
+    prime::ValidPrime,
+    vector::{FpSlice, FpSliceMut},
+};
 use serde_json::Value;
 use sseq::coordinates::{Bidegree, BidegreeGenerator};
 
 use crate::{
-    CCC,
+    CCC, FiniteDimensionalCCC, MotivicCCC,
     chain_complex::{AugmentedChainComplex, BoundedChainComplex, ChainComplex, FiniteChainComplex},
+    progress::ProgressEvent,
     resolution::{Resolution, UnstableResolution},
-    save::SaveDirectory,
+    save::{SaveDirectory, SaveFile, SaveKind, SaveManifest},
 };
 
-// We build docs with --all-features so the docs are at the feature = "nassau" version
-#[cfg(not(feature = "nassau"))]
-pub type QueryModuleResolution = Resolution<CCC>;
+/// The algorithm used to compute a minimal resolution.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ResolutionBackend {
+    /// The usual minimal resolution algorithm, see [`resolution`](crate::resolution).
+    #[default]
+    Standard,
+    /// Nassau's algorithm, see [`nassau`](crate::nassau).
+    Nassau,
+}
+
+impl std::fmt::Display for ResolutionBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Standard => "standard",
+                Self::Nassau => "nassau",
+            }
+        )
+    }
+}
+
+impl std::str::FromStr for ResolutionBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Self::Standard),
+            "nassau" => Ok(Self::Nassau),
+            _ => Err(anyhow!("Invalid resolution backend: {s}")),
+        }
+    }
+}
+
+/// The type returned by [`query_module`]. This is either a standard [`Resolution`] or a
+/// [`nassau::Resolution`](crate::nassau::Resolution), depending on the [`ResolutionBackend`]
+/// requested in the module specification. In either case, it is an augmented free chain complex
+/// over [`SteenrodAlgebra`] and supports the `compute_through_stem` function.
+pub enum QueryModuleResolution {
+    Standard(Resolution<CCC>),
+    Nassau(crate::nassau::Resolution<SteenrodModule>),
+}
+
+impl From<Resolution<CCC>> for QueryModuleResolution {
+    fn from(resolution: Resolution<CCC>) -> Self {
+        Self::Standard(resolution)
+    }
+}
+
+impl From<crate::nassau::Resolution<SteenrodModule>> for QueryModuleResolution {
+    fn from(resolution: crate::nassau::Resolution<SteenrodModule>) -> Self {
+        Self::Nassau(resolution)
+    }
+}
+
+/// Dispatch a method call to the underlying resolution.
+macro_rules! dispatch {
+    ($self:expr, $res:ident => $e:expr) => {
+        match $self {
+            QueryModuleResolution::Standard($res) => $e,
+            QueryModuleResolution::Nassau($res) => $e,
+        }
+    };
+}
+
+impl QueryModuleResolution {
+    pub fn backend(&self) -> ResolutionBackend {
+        match self {
+            Self::Standard(_) => ResolutionBackend::Standard,
+            Self::Nassau(_) => ResolutionBackend::Nassau,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        dispatch!(self, res => res.name())
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        dispatch!(self, res => res.set_name(name))
+    }
+
+    pub fn compute_through_stem(&self, max: Bidegree) {
+        dispatch!(self, res => res.compute_through_stem(max))
+    }
+
+    /// See [`Resolution::compute_through_stem_with_progress`]. The Nassau backend does not record
+    /// the matrix sizes and timings of each bidegree.
+    pub fn compute_through_stem_with_progress(
+        &self,
+        max: Bidegree,
+        cb: impl FnMut(ProgressEvent),
+    ) -> anyhow::Result<()> {
+        dispatch!(self, res => res.compute_through_stem_with_progress(max, cb))
+    }
+
+    /// See [`Resolution::compute_through_bidegree_with_progress`]. The Nassau backend does not
+    /// record the matrix sizes and timings of each bidegree.
+    pub fn compute_through_bidegree_with_progress(
+        &self,
+        max: Bidegree,
+        cb: impl FnMut(ProgressEvent),
+    ) -> anyhow::Result<()> {
+        dispatch!(self, res => res.compute_through_bidegree_with_progress(max, cb))
+    }
+}
+
+impl ChainComplex for QueryModuleResolution {
+    type Algebra = SteenrodAlgebra;
+    type Homomorphism = FreeModuleHomomorphism<FreeModule<SteenrodAlgebra>>;
+    type Module = FreeModule<SteenrodAlgebra>;
+
+    fn prime(&self) -> ValidPrime {
+        dispatch!(self, res => res.prime())
+    }
+
+    fn algebra(&self) -> Arc<Self::Algebra> {
+        dispatch!(self, res => res.algebra())
+    }
+
+    fn min_degree(&self) -> i32 {
+        dispatch!(self, res => res.min_degree())
+    }
+
+    fn zero_module(&self) -> Arc<Self::Module> {
+        dispatch!(self, res => res.zero_module())
+    }
+
+    fn module(&self, s: i32) -> Arc<Self::Module> {
+        dispatch!(self, res => res.module(s))
+    }
+
+    fn differential(&self, s: i32) -> Arc<Self::Homomorphism> {
+        dispatch!(self, res => res.differential(s))
+    }
+
+    fn has_computed_bidegree(&self, b: Bidegree) -> bool {
+        dispatch!(self, res => res.has_computed_bidegree(b))
+    }
+
+    fn compute_through_bidegree(&self, b: Bidegree) {
+        dispatch!(self, res => res.compute_through_bidegree(b))
+    }
+
+    fn next_homological_degree(&self) -> i32 {
+        dispatch!(self, res => res.next_homological_degree())
+    }
+
+    fn apply_quasi_inverse<T, S>(&self, results: &mut [T], b: Bidegree, inputs: &[S]) -> bool
+    where
+        for<'a> &'a mut T: Into<FpSliceMut<'a>>,
+        for<'a> &'a S: Into<FpSlice<'a>>,
+    {
+        dispatch!(self, res => res.apply_quasi_inverse(results, b, inputs))
+    }
+
+    fn save_dir(&self) -> &SaveDirectory {
+        dispatch!(self, res => res.save_dir())
+    }
+
+    fn save_file(&self, kind: SaveKind, b: Bidegree) -> SaveFile<Self::Algebra> {
+        dispatch!(self, res => res.save_file(kind, b))
+    }
+}
 
-/// The type returned by [`query_module`]. The value of this type depends on whether
-/// [`nassau`](crate::nassau) is enabled. In any case, it is an augmented free chain complex over
-/// either [`SteenrodAlgebra`] or [`MilnorAlgebra`] and supports the `compute_through_stem`
-/// function.
-#[cfg(feature = "nassau")]
-pub type QueryModuleResolution = crate::nassau::Resolution<FDModule<MilnorAlgebra>>;
+impl AugmentedChainComplex for QueryModuleResolution {
+    type ChainMap = FreeModuleHomomorphism<SteenrodModule>;
+    type TargetComplex = CCC;
+
+    fn target(&self) -> Arc<Self::TargetComplex> {
+        dispatch!(self, res => res.target())
+    }
+
+    fn chain_map(&self, s: i32) -> Arc<Self::ChainMap> {
+        dispatch!(self, res => res.chain_map(s))
+    }
+}
+
+/// The type returned by [`construct_any`] and [`query_any_module_only`]. This is a resolution over
+/// the Steenrod algebra, a motivic Steenrod algebra or a finite dimensional algebra, depending on
+/// the module specification. These are resolutions over different algebras, so the caller has to
+/// match on the variant to use them.
+pub enum AnyResolution {
+    Steenrod(QueryModuleResolution),
+    Motivic(Resolution<MotivicCCC>),
+    FiniteDimensional(Resolution<FiniteDimensionalCCC>),
+}
+
+impl AnyResolution {
+    pub fn set_name(&mut self, name: String) {
+        match self {
+            Self::Steenrod(res) => res.set_name(name),
+            Self::Motivic(res) => res.set_name(name),
+            Self::FiniteDimensional(res) => res.set_name(name),
+        }
+    }
+}
 
 const STATIC_MODULES_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../ext/steenrod_modules");
 
@@ -36,6 +231,8 @@ pub struct Config {
     module: Value,
     /// The basis for the Steenrod algebra
     algebra: AlgebraType,
+    /// The algorithm used to resolve the module
+    backend: ResolutionBackend,
 }
 
 /// Given a module specification string, load a json description of the module as described
@@ -64,23 +261,49 @@ pub fn parse_module_name(module_name: &str) -> anyhow::Result<Value> {
     Ok(module)
 }
 
+/// Resolve over a named sub-Hopf algebra of the Steenrod algebra instead, as specified by a suffix
+/// such as `@A(2)`. This records the subalgebra as the profile of the module specification.
+fn set_subalgebra(module: &mut Value, subalgebra: SubHopfAlgebra) -> anyhow::Result<()> {
+    if !module["profile"].is_null() {
+        return Err(anyhow!(
+            "Cannot resolve over {subalgebra}: the module already specifies a profile"
+        ));
+    }
+    module["profile"] = Value::from(subalgebra.to_string());
+    Ok(())
+}
+
 impl TryFrom<&str> for Config {
     type Error = anyhow::Error;
 
     fn try_from(spec: &str) -> Result<Self, Self::Error> {
         let mut args = spec.split('@');
         let module_name = args.next().unwrap();
-        let algebra = match args.next() {
-            Some(x) => x
-                .parse()
-                .with_context(|| format!("Invalid algebra type: {x}"))?,
-            None => AlgebraType::Milnor,
-        };
+        let mut algebra = AlgebraType::Milnor;
+        let mut backend = ResolutionBackend::default();
+        let mut subalgebra = None;
+        for x in args {
+            if let Ok(b) = x.parse() {
+                backend = b;
+            } else if let Ok(a) = x.parse::<SubHopfAlgebra>() {
+                subalgebra = Some(a);
+            } else {
+                algebra = x
+                    .parse()
+                    .with_context(|| format!("Invalid algebra type: {x}"))?;
+            }
+        }
+
+        let mut module = parse_module_name(module_name)
+            .with_context(|| format!("Failed to load module: {module_name}"))?;
+        if let Some(subalgebra) = subalgebra {
+            set_subalgebra(&mut module, subalgebra)?;
+        }
 
         Ok(Self {
-            module: parse_module_name(module_name)
-                .with_context(|| format!("Failed to load module: {module_name}"))?,
+            module,
             algebra,
+            backend,
         })
     }
 }
@@ -92,18 +315,25 @@ where
 {
     type Error = anyhow::Error;
 
-    fn try_from(mut spec: (&str, T)) -> Result<Self, Self::Error> {
+    fn try_from(spec: (&str, T)) -> Result<Self, Self::Error> {
         let algebra = spec.1.try_into()?;
-        if spec.0.contains('@') {
-            if spec.0.ends_with(&*algebra.to_string()) {
-                spec.0 = &spec.0[0..spec.0.len() - algebra.to_string().len() - 1];
-            } else {
+        let mut args = spec.0.split('@');
+        let module_name = args.next().unwrap();
+        let mut backend = ResolutionBackend::default();
+        let mut module = parse_module_name(module_name)?;
+        for x in args {
+            if let Ok(b) = x.parse() {
+                backend = b;
+            } else if let Ok(subalgebra) = x.parse::<SubHopfAlgebra>() {
+                set_subalgebra(&mut module, subalgebra)?;
+            } else if x != algebra.to_string() {
                 return Err(anyhow!("Invalid algebra supplied. Must be {}", algebra));
             }
         }
         Ok(Self {
-            module: parse_module_name(spec.0)?,
+            module,
             algebra,
+            backend,
         })
     }
 }
@@ -115,10 +345,35 @@ impl<T: TryInto<AlgebraType>> TryFrom<(Value, T)> for Config {
         Ok(Self {
             module: spec.0,
             algebra: spec.1.try_into()?,
+            backend: ResolutionBackend::default(),
         })
     }
 }
 
+/// A module specification without an algebra, which defaults to the Milnor basis as for module
+/// names. This is mostly useful for modules over a finite dimensional algebra, see
+/// [`construct_finite_dimensional`], which define their own algebra, so that the basis of the
+/// Steenrod algebra is irrelevant.
+impl From<Value> for Config {
+    fn from(module: Value) -> Self {
+        Self {
+            module,
+            algebra: AlgebraType::Milnor,
+            backend: ResolutionBackend::default(),
+        }
+    }
+}
+
+impl Config {
+    /// The manifest recorded in save directories of resolutions of this module. This records the
+    /// prime, the algebra and a fingerprint of the module specification. If `unstable` is true,
+    /// this is the manifest of an unstable resolution.
+    pub fn save_manifest(&self, unstable: bool) -> anyhow::Result<SaveManifest> {
+        let algebra = SteenrodAlgebra::from_json(&self.module, self.algebra, unstable)?;
+        Ok(SaveManifest::new(&algebra).with_module(self.algebra, &self.module))
+    }
+}
+
 /// This constructs a resolution resolving a module according to the specifications
 ///
 /// # Arguments
@@ -127,17 +382,22 @@ impl<T: TryInto<AlgebraType>> TryFrom<(Value, T)> for Config {
 ///    - A [`Config`] object itself
 ///    - `(json, algebra)`: The first argument is a [`serde_json::Value`] that specifies the
 ///      module; the second argument is either a string (`"milnor"` or `"adem"`) or an
-///      [`algebra::AlgebraType`] object.
+///      [`algebra::AlgebraType`] object. The motivic algebras are only accepted by
+///      [`construct_motivic`] and [`construct_any`].
+///    - `json`: A [`serde_json::Value`] as above, over the Milnor basis. Modules that define their
+///      own [finite dimensional algebra](../index.html#module-specification) are only accepted by
+///      [`construct_finite_dimensional`] and [`construct_any`].
 ///    - `(module_name, algebra)`: The first argument is the name of the module and the second is
 ///      as above. Modules are searched in the current directory, `$CWD/steenrod_modules` and
 ///      `ext/steenrod_modules`. The modules can be shifted by appending e.g. `S_2[2]`.
 ///    - `module_spec`, a single `&str` of the form `module_name@algebra`, where `module_name` and
-///      `algebra` are as above.
+///      `algebra` are as above. One can additionally append `@nassau` or `@standard` to choose the
+///      [`ResolutionBackend`]. This suffix is also accepted in `(module_name, algebra)`.
 ///  - `save_file`: The save file for the module. If it points to an invalid save file, an error is
 ///    returned.
 ///
-/// This dispatches to either [`construct_nassau`] or [`construct_standard`] depending on whether
-/// the `nassau` feature is enabled.
+/// This dispatches to either [`construct_nassau`] or [`construct_standard`] depending on the
+/// [`ResolutionBackend`] of the specification.
 pub fn construct<T, E>(
     module_spec: T,
     save_dir: impl Into<SaveDirectory>,
@@ -146,54 +406,150 @@ where
     anyhow::Error: From<E>,
     T: TryInto<Config, Error = E>,
 {
-    #[cfg(feature = "nassau")]
-    {
-        construct_nassau(module_spec, save_dir)
-    }
+    let config: Config = module_spec.try_into()?;
+    Ok(match config.backend {
+        ResolutionBackend::Standard => {
+            construct_standard::<false, Config, std::convert::Infallible>(config, save_dir)?.into()
+        }
+        ResolutionBackend::Nassau => {
+            construct_nassau::<Config, std::convert::Infallible>(config, save_dir)?.into()
+        }
+    })
+}
 
-    #[cfg(not(feature = "nassau"))]
-    {
-        construct_standard(module_spec, save_dir)
+/// Construct a resolution of a module over a motivic Steenrod algebra. The module specification
+/// is as in [`construct`], with the algebra `c-motivic` or `r-motivic`. Only the standard backend
+/// is supported, and cofibers are not.
+pub fn construct_motivic<T, E>(
+    module_spec: T,
+    save_dir: impl Into<SaveDirectory>,
+) -> anyhow::Result<Resolution<MotivicCCC>>
+where
+    anyhow::Error: From<E>,
+    T: TryInto<Config, Error = E>,
+{
+    let Config {
+        module: json,
+        algebra: algebra_type,
+        backend,
+    } = module_spec.try_into()?;
+
+    let algebra_type = algebra_type.for_module(&json)?;
+    let base = algebra_type
+        .motivic_base()
+        .with_context(|| format!("Algebra {algebra_type} is not a motivic Steenrod algebra"))?;
+    anyhow::ensure!(
+        backend == ResolutionBackend::Standard,
+        "Nassau's algorithm requires Milnor's basis of the classical Steenrod algebra"
+    );
+    anyhow::ensure!(
+        json["cofiber"].is_null(),
+        "Cofiber not supported over the motivic Steenrod algebras"
+    );
+
+    let algebra = Arc::new(MotivicMilnorAlgebra::from_json(&json, base)?);
+    let save_dir = save_dir.into();
+    save_dir.check_manifest(&SaveManifest::new(&*algebra).with_module(algebra_type, &json))?;
+
+    let module = Arc::new(steenrod_module::motivic_from_json(algebra, &json)?);
+    Resolution::new_with_save(Arc::new(FiniteChainComplex::ccdz(module)), save_dir)
+}
+
+/// Construct a resolution of a module over a finite dimensional algebra defined by the module
+/// itself, see [here](../index.html#module-specification). The basis of the Steenrod algebra in
+/// the specification is irrelevant. Only the standard backend is supported, and cofibers are not.
+pub fn construct_finite_dimensional<T, E>(
+    module_spec: T,
+    save_dir: impl Into<SaveDirectory>,
+) -> anyhow::Result<Resolution<FiniteDimensionalCCC>>
+where
+    anyhow::Error: From<E>,
+    T: TryInto<Config, Error = E>,
+{
+    let Config {
+        module: json,
+        algebra: algebra_type,
+        backend,
+    } = module_spec.try_into()?;
+
+    anyhow::ensure!(
+        backend == ResolutionBackend::Standard,
+        "Nassau's algorithm requires Milnor's basis of the classical Steenrod algebra"
+    );
+    anyhow::ensure!(
+        json["cofiber"].is_null(),
+        "Cofiber not supported over finite dimensional algebras"
+    );
+
+    let algebra = Arc::new(FiniteDimensionalAlgebra::from_module_json(&json)?);
+    let save_dir = save_dir.into();
+    save_dir.check_manifest(&SaveManifest::new(&*algebra).with_module(algebra_type, &json))?;
+
+    let module = Arc::new(steenrod_module::generic_from_json(algebra, &json)?);
+    Resolution::new_with_save(Arc::new(FiniteChainComplex::ccdz(module)), save_dir)
+}
+
+/// Construct a resolution of a module over whichever algebra its specification asks for. This
+/// dispatches to [`construct_finite_dimensional`] if the module defines its own algebra, to
+/// [`construct_motivic`] for the motivic Steenrod algebras and to [`construct`] otherwise.
+pub fn construct_any<T, E>(
+    module_spec: T,
+    save_dir: impl Into<SaveDirectory>,
+) -> anyhow::Result<AnyResolution>
+where
+    anyhow::Error: From<E>,
+    T: TryInto<Config, Error = E>,
+{
+    let mut config: Config = module_spec.try_into()?;
+    if FiniteDimensionalAlgebra::is_defined_by_module(&config.module) {
+        return Ok(AnyResolution::FiniteDimensional(
+            construct_finite_dimensional::<Config, std::convert::Infallible>(config, save_dir)?,
+        ));
     }
+    config.algebra = config.algebra.for_module(&config.module)?;
+    Ok(if config.algebra.motivic_base().is_some() {
+        AnyResolution::Motivic(construct_motivic::<Config, std::convert::Infallible>(
+            config, save_dir,
+        )?)
+    } else {
+        AnyResolution::Steenrod(construct::<Config, std::convert::Infallible>(
+            config, save_dir,
+        )?)
+    })
 }
 
 /// See [`construct`]
 pub fn construct_nassau<T, E>(
     module_spec: T,
     save_dir: impl Into<SaveDirectory>,
-) -> anyhow::Result<crate::nassau::Resolution<FDModule<MilnorAlgebra>>>
+) -> anyhow::Result<crate::nassau::Resolution<SteenrodModule>>
 where
     anyhow::Error: From<E>,
     T: TryInto<Config, Error = E>,
 {
+    let config: Config = module_spec.try_into()?;
+    let manifest = config.save_manifest(false)?;
     let Config {
         module: json,
         algebra,
-    } = module_spec.try_into()?;
+        ..
+    } = config;
 
-    if algebra == AlgebraType::Adem {
-        return Err(anyhow!("Nassau's algorithm requires Milnor's basis"));
-    }
-    if !json["profile"].is_null() {
-        return Err(anyhow!(
-            "Nassau's algorithm does not support non-trivial profile"
-        ));
-    }
-    if json["p"].as_i64() != Some(2) {
-        return Err(anyhow!("Nassau's algorithm does not support odd primes"));
-    }
-    if json["type"].as_str() != Some("finite dimensional module") {
+    if algebra != AlgebraType::Milnor {
         return Err(anyhow!(
-            "Nassau's algorithm only supports finite dimensional modules"
+            "Nassau's algorithm requires Milnor's basis of the classical Steenrod algebra"
         ));
     }
-
-    let algebra = Arc::new(MilnorAlgebra::new(fp::prime::TWO, false));
-    let module = Arc::new(FDModule::from_json(Arc::clone(&algebra), &json)?);
-
     if !json["cofiber"].is_null() {
         return Err(anyhow!("Nassau's algorithm does not support cofiber"));
     }
+
+    let save_dir = save_dir.into();
+    save_dir.check_manifest(&manifest)?;
+
+    let algebra = Arc::new(SteenrodAlgebra::from_json(&json, algebra, false)?);
+    let module = Arc::new(steenrod_module::from_json(algebra, &json)?);
+
     crate::nassau::Resolution::new_with_save(module, save_dir)
 }
 
@@ -207,10 +563,14 @@ where
     T: TryInto<Config, Error = E>,
     SteenrodAlgebra: algebra::MuAlgebra<U>,
 {
+    let config: Config = module_spec.try_into()?;
+    let save_dir = save_dir.into();
+    save_dir.check_manifest(&config.save_manifest(U)?)?;
     let Config {
         module: json,
         algebra,
-    } = module_spec.try_into()?;
+        ..
+    } = config;
 
     let algebra = Arc::new(SteenrodAlgebra::from_json(&json, algebra, U)?);
     let module = Arc::new(steenrod_module::from_json(Arc::clone(&algebra), &json)?);
@@ -373,14 +733,76 @@ pub fn unicode_num(n: usize) -> char {
 ///   they must be accessed via `apply_quasi_inverse`.
 ///
 /// # Returns
-/// A [`QueryModuleResolution`]. Note that this type depends on whether the `nassau` feature is
-/// enabled.
+/// A [`QueryModuleResolution`], whose variant is determined by the module specification.
 pub fn query_module_only(
     prompt: &str,
     algebra: Option<AlgebraType>,
     load_quasi_inverse: bool,
 ) -> anyhow::Result<QueryModuleResolution> {
-    let (name, module): (String, Config) = query::with_default(prompt, "S_2", |s| {
+    let (name, module) = query_config(prompt, algebra);
+    let save_dir = query_save_dir(prompt);
+
+    let mut resolution =
+        construct(module, save_dir).context("Failed to load module from save file")?;
+
+    let load_quasi_inverse = load_quasi_inverse && resolution.save_dir().is_none();
+
+    match &mut resolution {
+        QueryModuleResolution::Standard(res) => configure_standard(res, load_quasi_inverse)?,
+        QueryModuleResolution::Nassau(_) => {
+            anyhow::ensure!(
+                !load_quasi_inverse,
+                "Quasi inverse loading not supported with Nassau. Please use a save directory \
+                 instead"
+            );
+            anyhow::ensure!(
+                memory_budget().is_none(),
+                "MEMORY_BUDGET not supported with Nassau"
+            );
+            anyhow::ensure!(
+                !distributed_resolution(),
+                "DISTRIBUTED_RESOLUTION not supported with Nassau"
+            );
+        }
+    }
+
+    resolution.set_name(name);
+
+    Ok(resolution)
+}
+
+/// Query the user for a module and its save directory, like [`query_module_only`], but also
+/// accept modules over the motivic Steenrod algebras and over finite dimensional algebras.
+pub fn query_any_module_only(prompt: &str) -> anyhow::Result<AnyResolution> {
+    let (name, module) = query_config(prompt, None);
+    let save_dir = query_save_dir(prompt);
+
+    let mut resolution =
+        construct_any(module, save_dir).context("Failed to load module from save file")?;
+    match &mut resolution {
+        AnyResolution::Steenrod(QueryModuleResolution::Standard(res)) => {
+            configure_standard(res, false)?
+        }
+        AnyResolution::Steenrod(QueryModuleResolution::Nassau(_)) => {
+            anyhow::ensure!(
+                memory_budget().is_none(),
+                "MEMORY_BUDGET not supported with Nassau"
+            );
+            anyhow::ensure!(
+                !distributed_resolution(),
+                "DISTRIBUTED_RESOLUTION not supported with Nassau"
+            );
+        }
+        AnyResolution::Motivic(res) => configure_standard(res, false)?,
+        AnyResolution::FiniteDimensional(res) => configure_standard(res, false)?,
+    }
+    resolution.set_name(name);
+
+    Ok(resolution)
+}
+
+fn query_config(prompt: &str, algebra: Option<AlgebraType>) -> (String, Config) {
+    query::with_default(prompt, "S_2", |s| {
         Result::<_, anyhow::Error>::Ok((
             s.to_owned(),
             match algebra {
@@ -388,31 +810,46 @@ pub fn query_module_only(
                 None => s.try_into()?,
             },
         ))
-    });
+    })
+}
 
-    let save_dir = query::optional(&format!("{prompt} save directory"), |x| {
+fn query_save_dir(prompt: &str) -> Option<PathBuf> {
+    query::optional(&format!("{prompt} save directory"), |x| {
         core::result::Result::<PathBuf, std::convert::Infallible>::Ok(PathBuf::from(x))
-    });
-
-    let mut resolution =
-        construct(module, save_dir).context("Failed to load module from save file")?;
+    })
+}
 
-    let load_quasi_inverse = load_quasi_inverse && resolution.save_dir().is_none();
+/// Apply the settings of a standard resolution that are read from the environment, namely
+/// [`memory_budget`] and [`distributed_resolution`].
+fn configure_standard<CC: ChainComplex>(
+    res: &mut crate::resolution::MuResolution<false, CC>,
+    load_quasi_inverse: bool,
+) -> anyhow::Result<()> {
+    res.load_quasi_inverse = load_quasi_inverse;
 
-    #[cfg(not(feature = "nassau"))]
-    {
-        resolution.load_quasi_inverse = load_quasi_inverse;
+    if let Some(budget) = memory_budget() {
+        anyhow::ensure!(
+            res.should_save && matches!(res.save_dir(), SaveDirectory::Combined(_)),
+            "MEMORY_BUDGET requires a save directory that is both read from and written to, since \
+             evicted kernels and quasi-inverses are reloaded from it"
+        );
+        res.memory_budget = Some(budget);
     }
 
-    #[cfg(feature = "nassau")]
-    assert!(
-        !load_quasi_inverse,
-        "Quasi inverse loading not support with Nassau. Please use a save directory instead"
-    );
-
-    resolution.set_name(name);
+    if distributed_resolution() {
+        anyhow::ensure!(
+            res.save_dir().write().is_some(),
+            "DISTRIBUTED_RESOLUTION requires a save directory"
+        );
+        anyhow::ensure!(
+            !res.save_dir().is_archive(),
+            "DISTRIBUTED_RESOLUTION cannot be used with an archive save directory, since save \
+             files in an archive cannot be locked"
+        );
+        res.distributed = true;
+    }
 
-    Ok(resolution)
+    Ok(())
 }
 
 /// Query the user for a module and a bidegree, and return a resolution resolved up to said
@@ -492,19 +929,16 @@ pub fn get_unit(
             bivec::BiVec::from_vec(0, vec![1]),
         );
 
-        #[cfg(feature = "nassau")]
-        {
-            Arc::new(crate::nassau::Resolution::new_with_save(
-                Arc::new(module),
-                save_dir,
-            )?)
-        }
-
-        #[cfg(not(feature = "nassau"))]
-        {
-            let cc = FiniteChainComplex::ccdz(Arc::new(steenrod_module::erase(module)));
-            Arc::new(Resolution::new_with_save(Arc::new(cc), save_dir)?)
-        }
+        let module = Arc::new(steenrod_module::erase(module));
+        Arc::new(match resolution.backend() {
+            ResolutionBackend::Standard => {
+                let cc = FiniteChainComplex::ccdz(module);
+                Resolution::new_with_save(Arc::new(cc), save_dir)?.into()
+            }
+            ResolutionBackend::Nassau => {
+                crate::nassau::Resolution::new_with_save(module, save_dir)?.into()
+            }
+        })
     };
 
     Ok((is_unit, unit))
@@ -550,6 +984,11 @@ mod logging {
     }
 
     impl<T: io::Write> LogWriter<T> {
+        /// Return the number of bytes written so far
+        pub fn bytes_written(&self) -> u64 {
+            self.bytes
+        }
+
         /// Return the throughput in MiB/s
         pub fn into_throughput(mut self) -> Throughput {
             self.writer.flush().unwrap();
@@ -666,3 +1105,55 @@ pub fn secondary_job() -> Option<i32> {
     }
     parsed
 }
+
+/// Whether the DISTRIBUTED_RESOLUTION environment variable is set to `1`.
+///
+/// If so, the resolutions returned by [`query_module_only`] are
+/// [`distributed`](crate::resolution::MuResolution::distributed), so that several processes can
+/// resolve the module in the same save directory at the same time.
+pub fn distributed_resolution() -> bool {
+    match std::env::var("DISTRIBUTED_RESOLUTION").as_deref() {
+        Err(_) | Ok("0") => false,
+        Ok("1") => true,
+        Ok(val) => {
+            eprintln!(
+                "Invalid argument for `DISTRIBUTED_RESOLUTION`. Expected 0 or 1 but found {val}"
+            );
+            false
+        }
+    }
+}
+
+/// The value of the MEMORY_BUDGET environment variable, converted from MiB to bytes.
+///
+/// If set, the resolutions returned by [`query_module_only`] have this
+/// [`memory_budget`](crate::resolution::MuResolution::memory_budget), which limits how many
+/// kernels and quasi-inverses are cached in memory. This requires a save directory and does not
+/// bound the memory used by the differentials.
+pub fn memory_budget() -> Option<usize> {
+    let val = std::env::var("MEMORY_BUDGET").ok()?;
+    let parsed: Option<usize> = str::parse(&val).ok();
+    if parsed.is_none() {
+        eprintln!(
+            "Invalid argument for `MEMORY_BUDGET`. Expected non-negative integer but found {val}"
+        );
+    }
+    Some(parsed? << 20)
+}
+
+/// The value of the SECONDARY_SHARD environment variable.
+///
+/// This is used for distributing the secondary scripts at a finer level than [`secondary_job`].
+/// If set to `phase:index/count`, only the jobs in the given [`Shard`](crate::secondary::Shard) of
+/// the [`SecondaryLift::jobs`](crate::secondary::SecondaryLift::jobs) will be run. For a cluster
+/// array job, `index` would typically be the array task id.
+pub fn secondary_shard() -> Option<crate::secondary::Shard> {
+    let val = std::env::var("SECONDARY_SHARD").ok()?;
+    match val.parse() {
+        Ok(shard) => Some(shard),
+        Err(e) => {
+            eprintln!("Invalid argument for `SECONDARY_SHARD`: {e}");
+            None
+        }
+    }
+}
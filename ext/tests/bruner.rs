@@ -0,0 +1,99 @@
+use std::{path::PathBuf, sync::Arc};
+
+use algebra::module::homomorphism::{FullModuleHomomorphism, IdentityHomomorphism};
+use ext::{
+    bruner::read_bruner_resolution,
+    chain_complex::{AugmentedChainComplex, ChainComplex, FreeChainComplex},
+    ext_algebra::ExtAlgebra,
+    resolution_homomorphism::ResolutionHomomorphism,
+    utils::construct,
+};
+use rstest::rstest;
+use sseq::coordinates::{Bidegree, BidegreeGenerator};
+
+fn data_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples/bruner_data")
+}
+
+#[rstest]
+#[case("S_2@milnor")]
+#[case("S_2@adem")]
+fn test_bruner_identity(#[case] spec: &str) {
+    let resolution = Arc::new(construct(spec, None).unwrap());
+    let max = Bidegree::n_s(20, 7);
+    resolution.compute_through_stem(max);
+
+    let bruner = Arc::new(read_bruner_resolution(&data_dir(), resolution.target()).unwrap());
+    for b in resolution.iter_stem() {
+        if b.s() <= max.s() && b.n() <= max.n() {
+            assert_eq!(
+                bruner.number_of_gens_in_bidegree(b),
+                resolution.number_of_gens_in_bidegree(b),
+                "Number of generators differs at {b}"
+            );
+        }
+    }
+
+    // Lifting the identity gives an isomorphism on Ext
+    let id = FullModuleHomomorphism::identity_homomorphism(resolution.target().module(0));
+    let hom = ResolutionHomomorphism::from_module_homomorphism(
+        String::new(),
+        bruner,
+        Arc::clone(&resolution),
+        &id,
+    );
+    hom.extend_through_stem(max);
+    for b in resolution.iter_stem() {
+        if b.s() > max.s() || b.n() > max.n() {
+            continue;
+        }
+        let mut matrix =
+            fp::matrix::Matrix::from_vec(resolution.prime(), &hom.get_map(b.s()).hom_k(b.t()));
+        let dim = resolution.number_of_gens_in_bidegree(b);
+        if dim > 0 {
+            assert_eq!(matrix.row_reduce(), dim, "Not an isomorphism at {b}");
+        }
+    }
+}
+
+#[test]
+fn test_bruner_products() {
+    let resolution = construct("S_2@milnor", None).unwrap();
+    let bruner = Arc::new(read_bruner_resolution(&data_dir(), resolution.target()).unwrap());
+    let ext = ExtAlgebra::without_unit(bruner);
+
+    let h = |n| ext.generator(BidegreeGenerator::new(Bidegree::n_s(n, 1), 0));
+    let (h0, h1, h2) = (h(0), h(1), h(3));
+
+    assert!(!ext.multiply(&h0, &h0).vec().is_zero());
+    assert!(ext.multiply(&h0, &h1).vec().is_zero());
+    assert!(!ext.multiply(&h1, &h1).vec().is_zero());
+    assert!(!ext.multiply(&h0, &h2).vec().is_zero());
+    assert!(ext.multiply(&h1, &h2).vec().is_zero());
+
+    // h1^3 = h0^2 h2
+    let h1_3 = ext.multiply(&ext.multiply(&h1, &h1), &h1);
+    let h0_2_h2 = ext.multiply(&ext.multiply(&h0, &h0), &h2);
+    assert_eq!(h1_3, h0_2_h2);
+}
+
+#[test]
+fn test_bruner_errors() {
+    let resolution = construct("S_2@milnor", None).unwrap();
+    let dir = tempfile::TempDir::new().unwrap();
+
+    let err = read_bruner_resolution(dir.path(), resolution.target())
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("No hDiff.0"), "{err}");
+
+    std::fs::write(dir.path().join("hDiff.0"), "1 10\n0\n1\n0 0 1 i(0).\n").unwrap();
+    std::fs::write(dir.path().join("hDiff.1"), "1 10\n1\n1\n3 1 1 i(1).\n").unwrap();
+    let err = read_bruner_resolution(dir.path(), resolution.target())
+        .err()
+        .unwrap();
+    assert!(
+        format!("{err:#}").contains("refers to non-existent generator 3"),
+        "{err:#}"
+    );
+}
@@ -0,0 +1,106 @@
+use std::{path::Path, process::Command};
+
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    utils::construct_standard,
+};
+use fp::vector::FpVector;
+use sseq::coordinates::Bidegree;
+
+/// If set, the test binary is a worker process computing a resolution in the given directory.
+const WORKER_DIR: &str = "EXT_DISTRIBUTED_RESOLUTION_WORKER_DIR";
+const WORKER_MAX_N: &str = "EXT_DISTRIBUTED_RESOLUTION_WORKER_MAX_N";
+
+const MAX_S: i32 = 8;
+
+fn worker(dir: &Path, max_n: i32) {
+    let mut resolution = construct_standard::<false, _, _>("S_2", Some(dir.into())).unwrap();
+    resolution.distributed = true;
+    resolution.compute_through_stem(Bidegree::n_s(max_n, MAX_S));
+}
+
+/// Resolve a module with several processes sharing a save directory. The processes are copies of
+/// this test binary running only this test. They resolve to different stems, so that some of them
+/// stop at an edge that the others resolve past.
+#[test]
+fn test_distributed_resolution() {
+    if let Some(dir) = std::env::var_os(WORKER_DIR) {
+        let max_n = std::env::var(WORKER_MAX_N).unwrap().parse().unwrap();
+        worker(Path::new(&dir), max_n);
+        return;
+    }
+
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let exe = std::env::current_exe().unwrap();
+    let workers: Vec<_> = [30, 30, 25, 20]
+        .into_iter()
+        .map(|max_n: i32| {
+            Command::new(&exe)
+                .args(["test_distributed_resolution", "--exact", "--test-threads=1"])
+                .env(WORKER_DIR, tempdir.path())
+                .env(WORKER_MAX_N, max_n.to_string())
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for mut worker in workers {
+        assert!(worker.wait().unwrap().success());
+    }
+
+    let report = ext::save::inspect(tempdir.path(), None).unwrap();
+    assert!(report.locks.is_empty(), "{report}");
+    assert!(report.unrecognized.is_empty(), "{report}");
+    assert!(report.corrupt().next().is_none(), "{report}");
+
+    let max = Bidegree::n_s(30, MAX_S);
+    let loaded = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    loaded.compute_through_stem(max);
+    let fresh = construct_standard::<false, _, _>("S_2", None).unwrap();
+    fresh.compute_through_stem(max);
+
+    assert_eq!(
+        loaded.graded_dimension_string(),
+        fresh.graded_dimension_string()
+    );
+    for b in fresh.iter_stem() {
+        if b.s() == 0 {
+            continue;
+        }
+        let (d_loaded, d_fresh) = (loaded.differential(b.s()), fresh.differential(b.s()));
+        for idx in 0..fresh.number_of_gens_in_bidegree(b) {
+            // The outputs of generators computed by a process that stopped at a smaller stem are
+            // shorter, since the target was smaller at the time.
+            let (x, y) = (d_loaded.output(b.t(), idx), d_fresh.output(b.t(), idx));
+            let len = x.len().max(y.len());
+            let pad = |v: &FpVector| {
+                let mut entries: Vec<u32> = v.iter().collect();
+                entries.resize(len, 0);
+                entries
+            };
+            assert_eq!(pad(x), pad(y), "Differential differs at {b}");
+        }
+    }
+}
+
+/// Workers that fail to claim a bidegree report the error to the caller instead of panicking.
+#[test]
+fn test_distributed_without_save_dir() {
+    let mut resolution = construct_standard::<false, _, _>("S_2", None).unwrap();
+    resolution.distributed = true;
+    let max = Bidegree::n_s(10, 4);
+
+    let err = resolution
+        .compute_through_stem_with_progress(max, |_| ())
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("requires a save directory"),
+        "{err:#}"
+    );
+    let err = resolution
+        .compute_through_bidegree_with_progress(Bidegree::s_t(4, 10), |_| ())
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("requires a save directory"),
+        "{err:#}"
+    );
+}
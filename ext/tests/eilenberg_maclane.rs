@@ -0,0 +1,76 @@
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    utils::{construct, construct_standard},
+};
+use serde_json::json;
+use sseq::coordinates::Bidegree;
+
+/// $K(\mathbb{Z}/2, 1) = \mathbb{RP}^\infty$, stably and unstably.
+#[test]
+fn rp_inf() {
+    let max = Bidegree::n_s(20, 8);
+    let em = json!({
+        "p": 2,
+        "type": "eilenberg maclane",
+        "n": 1,
+        "max": 30,
+        "reduced": true,
+    });
+    let a = construct((em.clone(), "milnor"), None).unwrap();
+    let b = construct("RP_inf", None).unwrap();
+    a.compute_through_stem(max);
+    b.compute_through_stem(max);
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+
+    let max = Bidegree::s_t(20, 20);
+    let a = construct_standard::<true, _, _>((em, "adem"), None).unwrap();
+    let b = construct_standard::<true, _, _>(("RP_inf", "adem"), None).unwrap();
+    a.compute_through_bidegree(max);
+    b.compute_through_bidegree(max);
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+}
+
+/// $K(\mathbb{Z}/3, 1) = B\mathbb{Z}/3$.
+#[test]
+fn bz3() {
+    let max = Bidegree::n_s(40, 6);
+    let em = json!({
+        "p": 3,
+        "type": "eilenberg maclane",
+        "n": 1,
+        "max": 50,
+        "reduced": true,
+    });
+    let polynomial = json!({
+        "p": 3,
+        "type": "polynomial algebra",
+        "polynomial": {"y": 2},
+        "exterior": {"x": 1},
+        "actions": ["b x = y", "P1 y = y^3"],
+        "reduced": true,
+    });
+    let a = construct((em, "adem"), None).unwrap();
+    let b = construct((polynomial, "milnor"), None).unwrap();
+    a.compute_through_stem(max);
+    b.compute_through_stem(max);
+
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+}
+
+#[test]
+fn invalid() {
+    let json = json!({
+        "p": 2,
+        "type": "eilenberg maclane",
+        "n": 0,
+        "max": 10,
+    });
+    assert!(construct((json, "milnor"), None).is_err());
+
+    let json = json!({
+        "p": 2,
+        "type": "eilenberg maclane",
+        "n": 2,
+    });
+    assert!(construct((json, "milnor"), None).is_err());
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use algebra::module::Module;
+use ext::{
+    chain_complex::{ChainComplex, ChainHomotopy},
+    export::{
+        export_chain_homotopy, export_chain_map, export_resolution, import_chain_homotopy,
+        import_chain_map, import_resolution,
+    },
+    resolution_homomorphism::ResolutionHomomorphism,
+    utils::construct,
+};
+use fp::matrix::Matrix;
+use rstest::rstest;
+use serde_json::Value;
+use sseq::coordinates::Bidegree;
+
+/// Serialize and parse `json`, so that we test what other programs see.
+fn roundtrip(json: &Value) -> Value {
+    serde_json::from_str(&serde_json::to_string(json).unwrap()).unwrap()
+}
+
+#[rstest]
+#[case("S_2@milnor", 20)]
+#[case("S_2@adem", 20)]
+#[case("C2@milnor", 20)]
+#[case("S_3@milnor", 40)]
+fn test_export_resolution(#[case] spec: &str, #[case] max_t: i32) {
+    let resolution = Arc::new(construct(spec, None).unwrap());
+    resolution.compute_through_bidegree(Bidegree::s_t(6, max_t));
+
+    let json = roundtrip(&export_resolution(&*resolution));
+    assert_eq!(json["type"], "resolution");
+    assert_eq!(json["modules"].as_array().unwrap().len(), 7);
+
+    let imported = Arc::new(import_resolution(&json, resolution.algebra()).unwrap());
+    assert_eq!(imported.next_homological_degree(), 7);
+    assert_eq!(export_resolution(&*imported), json);
+    for s in 0..=6 {
+        let (ours, theirs) = (resolution.module(s), imported.module(s));
+        assert_eq!(ours.max_computed_degree(), theirs.max_computed_degree());
+        for t in 0..=max_t {
+            let num_gens = ours.number_of_gens_in_degree(t);
+            assert_eq!(num_gens, theirs.number_of_gens_in_degree(t));
+            if s == 0 {
+                continue;
+            }
+            for idx in 0..num_gens {
+                assert_eq!(
+                    resolution.differential(s).output(t, idx),
+                    imported.differential(s).output(t, idx),
+                    "Differential differs on ({s}, {t}, {idx})"
+                );
+            }
+        }
+    }
+
+    // The imported resolution can be the source of chain maps. Lifting the identity gives the
+    // identity on Ext.
+    let hom = ResolutionHomomorphism::new(
+        String::new(),
+        Arc::clone(&imported),
+        Arc::clone(&resolution),
+        Bidegree::zero(),
+    );
+    let num_gens = resolution.module(0).number_of_gens_in_degree(0);
+    let mut identity = Matrix::new(resolution.prime(), num_gens, num_gens);
+    for i in 0..num_gens {
+        identity.row_mut(i).set_entry(i, 1);
+    }
+    hom.extend_step(Bidegree::zero(), Some(&identity));
+    hom.extend(Bidegree::s_t(5, max_t - 1));
+    for b in resolution.iter_stem() {
+        if b.s() > 5 || b.t() >= max_t {
+            continue;
+        }
+        let matrix = hom.get_map(b.s()).hom_k(b.t());
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, &v) in row.iter().enumerate() {
+                assert_eq!(v, u32::from(i == j), "Not the identity at {b}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_export_wrong_algebra() {
+    let resolution = construct("S_2@milnor", None).unwrap();
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+    let json = export_resolution(&resolution);
+
+    let adem = construct("S_2@adem", None).unwrap();
+    let err = import_resolution(&json, adem.algebra()).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("Exported data is over the algebra"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_export_chain_map_and_homotopy() {
+    let resolution = Arc::new(construct("S_2@milnor", None).unwrap());
+    let max = Bidegree::s_t(6, 20);
+    resolution.compute_through_bidegree(max);
+
+    let h0 = Arc::new(ResolutionHomomorphism::from_class(
+        "h0".to_string(),
+        Arc::clone(&resolution),
+        Arc::clone(&resolution),
+        Bidegree::s_t(1, 1),
+        &[1],
+    ));
+    h0.extend(max);
+    let h1 = Arc::new(ResolutionHomomorphism::from_class(
+        "h1".to_string(),
+        Arc::clone(&resolution),
+        Arc::clone(&resolution),
+        Bidegree::s_t(1, 2),
+        &[1],
+    ));
+    h1.extend(max);
+
+    let json = roundtrip(&export_chain_map(&*h0));
+    assert_eq!(json["name"], "h0");
+    let imported =
+        import_chain_map(&json, Arc::clone(&resolution), Arc::clone(&resolution)).unwrap();
+    assert_eq!(imported.shift, h0.shift);
+    assert_eq!(
+        imported.next_homological_degree(),
+        h0.next_homological_degree()
+    );
+    for s in 1..h0.next_homological_degree() {
+        let (ours, theirs) = (h0.get_map(s), imported.get_map(s));
+        assert_eq!(ours.next_degree(), theirs.next_degree());
+        for t in ours.min_degree()..ours.next_degree() {
+            for idx in 0..resolution.module(s).number_of_gens_in_degree(t) {
+                assert_eq!(ours.output(t, idx), theirs.output(t, idx));
+            }
+        }
+    }
+
+    // h1 h0 = 0, so the composite is null-homotopic.
+    let homotopy = ChainHomotopy::new(Arc::clone(&h1), Arc::clone(&h0));
+    homotopy.extend(Bidegree::s_t(5, 18));
+
+    let json = roundtrip(&export_chain_homotopy(&homotopy));
+    let imported = import_chain_homotopy(&json, h1, h0).unwrap();
+    assert_eq!(
+        imported.next_homological_degree(),
+        homotopy.next_homological_degree()
+    );
+    for s in 1..homotopy.next_homological_degree() {
+        let (ours, theirs) = (homotopy.homotopy(s), imported.homotopy(s));
+        assert_eq!(ours.next_degree(), theirs.next_degree());
+        for t in ours.min_degree()..ours.next_degree() {
+            for idx in 0..resolution.module(s).number_of_gens_in_degree(t) {
+                assert_eq!(ours.output(t, idx), theirs.output(t, idx));
+            }
+        }
+    }
+}
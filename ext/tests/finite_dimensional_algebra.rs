@@ -0,0 +1,131 @@
+use expect_test::expect;
+use ext::{
+    chain_complex::FreeChainComplex,
+    utils::{AnyResolution, construct, construct_any, construct_finite_dimensional},
+};
+use serde_json::{Value, json};
+use sseq::coordinates::Bidegree;
+
+fn module(p: u32, algebra: Value, gens: Value, actions: Value) -> Value {
+    json!({
+        "type": "finite dimensional module",
+        "p": p,
+        "algebra": algebra,
+        "gens": gens,
+        "actions": actions,
+    })
+}
+
+fn ext(json: Value, max: Bidegree) -> String {
+    let res = construct_finite_dimensional(json, None).unwrap();
+    res.compute_through_stem(max);
+    res.graded_dimension_string()
+}
+
+#[test]
+fn exterior_matches_profile() {
+    let max = Bidegree::n_s(30, 6);
+    let algebra = json!({
+        "name": "E(1)",
+        "generators": {"Q0": 1, "Q1": 5},
+        "relations": ["Q0^2", "Q1^2", "Q0 Q1 + Q1 Q0"],
+    });
+    let defined = ext(module(3, algebra, json!({"x0": 0}), json!([])), max);
+
+    let named = construct("S_3@E(1)", None).unwrap();
+    named.compute_through_stem(max);
+
+    assert_eq!(defined, named.graded_dimension_string());
+}
+
+#[test]
+fn table_matches_presentation() {
+    let max = Bidegree::n_s(12, 6);
+    let table = json!({
+        "basis": {"x": 1, "x2": 2, "x3": 3},
+        "products": ["x * x = x2", "x * x2 = x3", "x2 * x = x3"],
+    });
+    let presentation = json!({
+        "generators": {"x": 1},
+        "relations": ["x^4"],
+    });
+    let table = ext(module(2, table, json!({"x0": 0}), json!([])), max);
+    let presentation = ext(module(2, presentation, json!({"x0": 0}), json!([])), max);
+    assert_eq!(table, presentation);
+
+    // Ext over F_2[x]/x^4 is E[a] ⊗ F_2[b] with a in (0, 1) and b in (2, 2)
+    expect![[r#"
+                    ·             
+                ·                 
+                ·                 
+            ·                     
+            ·                     
+        ·                         
+        ·                         
+    "#]]
+    .assert_eq(&table);
+}
+
+#[test]
+fn free_module() {
+    // The algebra as a module over itself is free, so Ext is concentrated in degree 0.
+    let algebra = json!({
+        "generators": {"x": 1, "y": 2},
+        "relations": ["x^2 = y", "y^2"],
+    });
+    let module = module(
+        2,
+        algebra,
+        json!({"x0": 0, "x1": 1, "x2": 2, "x3": 3}),
+        json!(["x x0 = x1", "x x1 = x2", "x x2 = x3"]),
+    );
+    expect![[r#"
+        ·       
+    "#]]
+    .assert_eq(&ext(module, Bidegree::n_s(3, 2)));
+}
+
+#[test]
+fn invalid() {
+    let algebra = json!({
+        "generators": {"x": 1},
+        "relations": ["x^2"],
+    });
+    // x acts non-trivially on a class that x^2 should kill
+    let json = module(
+        2,
+        algebra.clone(),
+        json!({"x0": 0, "x1": 1, "x2": 2}),
+        json!(["x x0 = x1", "x x1 = x2"]),
+    );
+    assert!(construct_finite_dimensional(json, None).is_err());
+
+    let mut json = module(2, algebra, json!({"x0": 0}), json!([]));
+    json["profile"] = json!("A(1)");
+    assert!(construct_finite_dimensional(json, None).is_err());
+}
+
+#[test]
+fn dispatch() {
+    let algebra = json!({
+        "generators": {"x": 1},
+        "relations": ["x^2"],
+    });
+    let json = module(2, algebra, json!({"x0": 0}), json!([]));
+
+    let err = construct(json.clone(), None).err().unwrap();
+    assert!(
+        err.to_string()
+            .contains("its own finite dimensional algebra")
+    );
+    assert!(construct_finite_dimensional("S_2@milnor", None).is_err());
+
+    assert!(matches!(
+        construct_any(json, None).unwrap(),
+        AnyResolution::FiniteDimensional(_)
+    ));
+    assert!(matches!(
+        construct_any("S_2@milnor", None).unwrap(),
+        AnyResolution::Steenrod(_)
+    ));
+}
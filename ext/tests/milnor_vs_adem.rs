@@ -13,6 +13,8 @@ use sseq::coordinates::Bidegree;
 #[case("RP4", 30)]
 #[case("RP_inf", 30)]
 #[case("RP_-4_inf", 30)]
+#[case("CP_inf", 30)]
+#[case("K_Z2_2", 30)]
 #[case("Csigma", 30)]
 #[case("S_3", 30)]
 #[case("Calpha", 30)]
@@ -36,6 +38,7 @@ fn compare(#[case] module_name: &str, #[case] max_degree: i32) {
 #[case("C2[8]", 30)]
 #[case("RP4[4]", 30)]
 #[case("RP_inf[7]", 30)]
+#[case("K_Z2_2", 30)]
 #[case("Csigma[15]", 40)]
 #[case("S_3[10]", 50)]
 #[case("Calpha[15]", 50)]
@@ -1,6 +1,7 @@
 use ext::{
     chain_complex::{ChainComplex, FreeChainComplex},
-    utils::{construct_nassau, construct_standard},
+    progress::ProgressEvent,
+    utils::{ResolutionBackend, construct, construct_nassau, construct_standard},
 };
 use rstest::rstest;
 use sseq::coordinates::Bidegree;
@@ -11,6 +12,13 @@ use sseq::coordinates::Bidegree;
 #[case("C2", 30)]
 #[case("Joker", 30)]
 #[case("Csigma", 30)]
+#[case("Csigma[-3]", 30)]
+#[case("tmf2", 30)]
+#[case("A-mod-Sq1-Sq2", 30)]
+#[case("RP_-4_inf", 30)]
+#[case("S_3", 100)]
+#[case("C3", 60)]
+#[case("S_5", 60)]
 fn compare(#[case] module_name: &str, #[case] max_degree: i32) {
     let max = Bidegree::s_t(max_degree, max_degree);
     let a = construct_standard::<false, _, _>(module_name, None).unwrap();
@@ -21,3 +29,57 @@ fn compare(#[case] module_name: &str, #[case] max_degree: i32) {
 
     assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
 }
+
+#[rstest]
+#[case("S_2", ResolutionBackend::Standard)]
+#[case("S_2@adem", ResolutionBackend::Standard)]
+#[case("S_2@nassau", ResolutionBackend::Nassau)]
+#[case("C2@milnor@nassau", ResolutionBackend::Nassau)]
+#[case("C2@nassau@standard", ResolutionBackend::Standard)]
+fn backend_from_spec(#[case] spec: &str, #[case] backend: ResolutionBackend) {
+    let res = construct(spec, None).unwrap();
+    assert_eq!(res.backend(), backend);
+
+    let res = construct((spec, "milnor"), None);
+    if spec.contains("adem") {
+        assert!(res.is_err());
+    } else {
+        assert_eq!(res.unwrap().backend(), backend);
+    }
+}
+
+#[test]
+fn nassau_requires_milnor() {
+    assert!(construct("S_2@adem@nassau", None).is_err());
+}
+
+/// Both backends report the same bidegrees as started and finished.
+#[test]
+fn nassau_progress() {
+    let max = Bidegree::n_s(10, 4);
+    let finished = |spec| {
+        let res = construct(spec, None).unwrap();
+        let mut started = Vec::new();
+        let mut finished = Vec::new();
+        res.compute_through_stem_with_progress(max, |event| match event {
+            ProgressEvent::Started(b) => started.push(b),
+            ProgressEvent::Finished(b, stats) => {
+                assert!(
+                    started.contains(&b),
+                    "{spec}: {b} finished before it started"
+                );
+                assert_eq!(
+                    stats.num_new_gens,
+                    res.number_of_gens_in_bidegree(b),
+                    "{spec}: {b}"
+                );
+                finished.push(b);
+            }
+        })
+        .unwrap();
+        assert!(res.has_computed_bidegree(max));
+        finished.sort_by_key(|b| (b.s(), b.t()));
+        finished
+    };
+    assert_eq!(finished("S_2"), finished("S_2@nassau"));
+}
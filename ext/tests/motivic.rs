@@ -0,0 +1,199 @@
+use expect_test::expect;
+use ext::{
+    motivic::{
+        GeneratorWeights, coefficient_ext, coefficient_resolution_bound, forget_weights,
+        resolution_bound, tau_bockstein,
+    },
+    utils::{AnyResolution, construct, construct_any, construct_motivic},
+};
+use itertools::Itertools;
+use serde_json::json;
+use sseq::coordinates::{Bidegree, MultiDegree};
+
+#[test]
+fn c_motivic_sphere() {
+    let max = Bidegree::n_s(8, 4);
+    let res = construct_motivic("M2@c-motivic", None).unwrap();
+    res.compute_through_stem(resolution_bound(max));
+
+    let weights = GeneratorWeights::new(&res);
+    let summands = tau_bockstein(&res, &weights, max).unwrap();
+
+    expect![[r#"
+        (0, 0, 0): F_2[tau]
+        (0, 1, 0): F_2[tau]
+        (0, 2, 0): F_2[tau]
+        (0, 3, 0): F_2[tau]
+        (0, 4, 0): F_2[tau]
+        (1, 1, 1): F_2[tau]
+        (2, 2, 2): F_2[tau]
+        (3, 1, 2): F_2[tau]
+        (3, 2, 2): F_2[tau]
+        (3, 3, 3): F_2[tau]
+        (4, 4, 4): F_2
+        (6, 2, 4): F_2[tau]
+        (7, 1, 4): F_2[tau]
+        (7, 2, 4): F_2[tau]
+        (7, 3, 4): F_2[tau]
+        (7, 4, 4): F_2[tau]
+        (8, 2, 5): F_2[tau]
+        (8, 3, 5): F_2[tau]"#]]
+    .assert_eq(&summands.iter().join("\n"));
+}
+
+#[test]
+fn r_motivic_generators() {
+    let max = Bidegree::n_s(3, 3);
+    let res = construct_motivic("M2@r-motivic", None).unwrap();
+    res.compute_through_stem(resolution_bound(max));
+
+    let weights = GeneratorWeights::new(&res);
+    assert!(tau_bockstein(&res, &weights, max).is_err());
+
+    let sseq = weights.to_sseq();
+    let output = sseq
+        .iter_degrees()
+        .filter(|b| b.n() <= max.n() && b.s() <= max.s())
+        .map(|b| format!("{b}: {}", sseq.dimension(b)))
+        .join("\n");
+    expect![[r#"
+        (0, 0, 0): 1
+        (0, 1, 0): 1
+        (0, 2, 0): 1
+        (0, 3, 0): 1
+        (1, 1, 1): 1
+        (2, 2, 2): 1
+        (3, 1, 2): 1
+        (3, 2, 2): 1
+        (3, 3, 3): 1"#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn c_motivic_coefficient_ext() {
+    let max = Bidegree::n_s(8, 4);
+    let max_coweight = 3;
+    let res = construct_motivic("M2@c-motivic", None).unwrap();
+    res.compute_through_stem(resolution_bound(max));
+    res.compute_through_stem(coefficient_resolution_bound(max, 0));
+
+    let weights = GeneratorWeights::new(&res);
+    let summands = tau_bockstein(&res, &weights, max).unwrap();
+    let ext = coefficient_ext(&res, &weights, max, max_coweight).unwrap();
+
+    let mut count = 0;
+    for b in ext.iter_degrees() {
+        let [n, s, w] = b.coords();
+        let expected = summands
+            .iter()
+            .filter(|summand| {
+                let [n_, s_, w_] = summand.degree.coords();
+                (n_, s_) == (n, s) && w <= w_ && summand.torsion.is_none_or(|k| w_ - (k as i32) < w)
+            })
+            .count();
+        assert_eq!(ext.dimension(b), expected, "Dimension mismatch at {b}");
+        count += 1;
+    }
+    assert!(count > 0);
+}
+
+#[test]
+fn r_motivic_coefficient_ext() {
+    let max = Bidegree::n_s(2, 2);
+    let max_coweight = 1;
+    let res = construct_motivic("M2@r-motivic", None).unwrap();
+    res.compute_through_stem(coefficient_resolution_bound(max, max_coweight));
+
+    let weights = GeneratorWeights::new(&res);
+    let ext = coefficient_ext(&res, &weights, max, max_coweight).unwrap();
+
+    // Ext^0 is F_2[rho] since Sq^1 tau = rho and Sq^2 tau^2 = rho^2 tau, and e.g. rho h_0 = 0
+    // while rho h_1 and rho^4 h_2 are nonzero.
+    let output = ext
+        .iter_degrees()
+        .filter(|&b| ext.dimension(b) > 0)
+        .map(|b| format!("{b}: {}", ext.dimension(b)))
+        .join("\n");
+    expect![[r#"
+        (-1, 0, -1): 1
+        (-1, 1, -2): 1
+        (-1, 1, -1): 1
+        (-1, 2, -1): 1
+        (0, 0, 0): 1
+        (0, 1, -1): 2
+        (0, 1, 0): 2
+        (0, 2, 0): 2
+        (1, 1, 0): 2
+        (1, 1, 1): 1
+        (1, 2, 0): 1
+        (1, 2, 1): 1
+        (2, 1, 1): 1
+        (2, 2, 1): 1
+        (2, 2, 2): 1"#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn dispatch() {
+    // The motivic Steenrod algebras are not Steenrod algebras over F_2
+    let err = construct("M2@c-motivic", None).err().unwrap();
+    assert!(err.to_string().contains("not Steenrod algebras"));
+    assert!(construct_motivic("S_2@milnor", None).is_err());
+
+    assert!(matches!(
+        construct_any("M2@r-motivic", None).unwrap(),
+        AnyResolution::Motivic(_)
+    ));
+    assert!(matches!(
+        construct_any("S_2", None).unwrap(),
+        AnyResolution::Steenrod(_)
+    ));
+}
+
+#[test]
+fn c_motivic_products() {
+    let max = Bidegree::n_s(4, 3);
+    let res = construct_motivic("M2@c-motivic", None).unwrap();
+    res.compute_through_stem(resolution_bound(max));
+
+    let weights = GeneratorWeights::new(&res);
+    let products = weights.filtration_one_products(&res);
+    let (_, h1) = products.iter().find(|(name, _)| name == "h_1").unwrap();
+    assert_eq!(h1.b, MultiDegree::new([1, 1, 1]));
+    assert_eq!(
+        h1.matrices
+            .get(MultiDegree::new([0, 0, 0]))
+            .unwrap()
+            .to_vec(),
+        vec![vec![1]]
+    );
+
+    let (sseq, products) = forget_weights(&weights.to_sseq(), &products);
+    assert_eq!(sseq.dimension(Bidegree::n_s(3, 2)), 1);
+    let (_, h0) = products.iter().find(|(name, _)| name == "h_0").unwrap();
+    assert_eq!(
+        h0.matrices.get(Bidegree::n_s(3, 1)).unwrap().to_vec(),
+        vec![vec![1]]
+    );
+}
+
+#[test]
+fn c_motivic_module_relations() {
+    let module = |actions: &[&str]| {
+        json!({
+            "p": 2,
+            "type": "finite dimensional module",
+            "gens": { "x0": 0, "x1": 1, "x2": 2 },
+            "actions": actions,
+        })
+    };
+    assert!(construct_motivic((module(&["Sq1 x0 = x1"]), "c-motivic"), None).is_ok());
+    // Sq1 Sq1 = 0
+    assert!(
+        construct_motivic((module(&["Sq1 x0 = x1", "Sq1 x1 = x2"]), "c-motivic"), None).is_err()
+    );
+    // τ Sq1 = Sq1 τ
+    assert!(
+        construct_motivic((module(&["Sq1 x0 = x1", "tau x1 = x2"]), "c-motivic"), None).is_err()
+    );
+}
@@ -0,0 +1,79 @@
+use expect_test::expect;
+use ext::{chain_complex::FreeChainComplex, utils::construct};
+use rstest::rstest;
+use serde_json::json;
+use sseq::coordinates::Bidegree;
+
+#[rstest]
+#[case("S_2@A(1)", "ko")]
+#[case("S_2@A(2)", "tmf2")]
+#[case("S_2@A(2)@nassau", "tmf2")]
+fn named_matches_explicit(#[case] named: &str, #[case] explicit: &str) {
+    let max = Bidegree::n_s(20, 8);
+
+    let named = construct(named, None).unwrap();
+    named.compute_through_stem(max);
+
+    let explicit = construct(explicit, None).unwrap();
+    explicit.compute_through_stem(max);
+
+    assert_eq!(
+        named.graded_dimension_string(),
+        explicit.graded_dimension_string()
+    );
+}
+
+#[rstest]
+#[case("S_3@A(1)", 3, 0b11, &[1])]
+#[case("S_3@E(1)", 3, 0b11, &[])]
+#[case("S_3@P(2)", 3, 0, &[2, 1])]
+#[case("S_5@A(0)", 5, 0b1, &[])]
+fn odd_primes(#[case] named: &str, #[case] p: u32, #[case] q_part: u32, #[case] p_part: &[u32]) {
+    let max = Bidegree::n_s(30, 5);
+
+    let named = construct(named, None).unwrap();
+    named.compute_through_stem(max);
+
+    let explicit = json!({
+        "p": p,
+        "profile": { "truncated": true, "q_part": q_part, "p_part": p_part },
+        "type": "finite dimensional module",
+        "gens": { "x0": 0 },
+        "actions": [],
+    });
+    let explicit = construct((explicit, "milnor"), None).unwrap();
+    explicit.compute_through_stem(max);
+
+    assert_eq!(
+        named.graded_dimension_string(),
+        explicit.graded_dimension_string()
+    );
+}
+
+/// Ext over $P(1) = \mathbb{F}_3[P^1] / (P^1)^3$ is $E[h_0] \otimes P[b_0]$ with $h_0$ in
+/// $(n, s) = (3, 1)$ and $b_0$ in $(10, 2)$.
+#[test]
+fn odd_prime_p1() {
+    let res = construct("S_3@P(1)", None).unwrap();
+    res.compute_through_stem(Bidegree::n_s(20, 4));
+    expect![[r#"
+                                                · 
+                                  ·               
+                            ·                     
+              ·                                   
+        ·                                         
+    "#]]
+    .assert_eq(&res.graded_dimension_string());
+}
+
+#[rstest]
+#[case("S_2@P(1)")]
+#[case("S_2@adem@A(1)")]
+#[case("ko@A(1)")]
+#[case("S_2@A(31)")]
+#[case("S_3@E(31)")]
+#[case("S_3@P(4000000000)")]
+#[case("S_2@A(4294967295)")]
+fn invalid(#[case] spec: &str) {
+    assert!(construct(spec, None).is_err());
+}
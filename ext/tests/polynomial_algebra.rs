@@ -0,0 +1,73 @@
+use ext::{chain_complex::FreeChainComplex, utils::construct};
+use serde_json::json;
+use sseq::coordinates::Bidegree;
+
+#[test]
+fn rp_inf() {
+    let max = Bidegree::n_s(20, 8);
+    let polynomial = json!({
+        "p": 2,
+        "type": "polynomial algebra",
+        "polynomial": {"x": 1},
+        "actions": ["Sq1 x = x^2"],
+        "reduced": true,
+    });
+    let polynomial = construct((polynomial, "milnor"), None).unwrap();
+    polynomial.compute_through_stem(max);
+
+    let rp = construct("RP_inf", None).unwrap();
+    rp.compute_through_stem(max);
+
+    assert_eq!(
+        polynomial.graded_dimension_string(),
+        rp.graded_dimension_string()
+    );
+}
+
+/// $H^*(B\mathbb{Z}/3)$, which checks the Cartan formula at an odd prime.
+#[test]
+fn bz3_milnor_vs_adem() {
+    let max = Bidegree::n_s(40, 6);
+    let json = json!({
+        "p": 3,
+        "type": "polynomial algebra",
+        "polynomial": {"y": 2},
+        "exterior": {"x": 1},
+        "actions": ["b x = y", "P1 y = y^3"],
+        "reduced": true,
+    });
+    let a = construct((json.clone(), "adem"), None).unwrap();
+    let b = construct((json, "milnor"), None).unwrap();
+    a.compute_through_stem(max);
+    b.compute_through_stem(max);
+
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+}
+
+/// $H^*(BU(2))$, where $Sq^2 c_2 = c_1 c_2$ by the Wu formula.
+#[test]
+fn bu2_milnor_vs_adem() {
+    let max = Bidegree::n_s(30, 6);
+    let json = json!({
+        "p": 2,
+        "type": "polynomial algebra",
+        "polynomial": {"c1": 2, "c2": 4},
+        "actions": ["Sq2 c1 = c1^2", "Sq2 c2 = c1 c2", "Sq4 c2 = c2^2"],
+    });
+    let a = construct((json.clone(), "adem"), None).unwrap();
+    let b = construct((json, "milnor"), None).unwrap();
+    a.compute_through_stem(max);
+    b.compute_through_stem(max);
+
+    assert_eq!(a.graded_dimension_string(), b.graded_dimension_string());
+}
+
+#[test]
+fn motivic() {
+    let json = json!({
+        "p": 2,
+        "type": "polynomial algebra",
+        "polynomial": {"x": 2},
+    });
+    assert!(construct((json, "c-motivic"), None).is_err());
+}
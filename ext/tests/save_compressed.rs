@@ -0,0 +1,33 @@
+#![cfg(feature = "zstd")]
+
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    save,
+    utils::construct,
+};
+use sseq::coordinates::Bidegree;
+
+#[test]
+fn test_save_load_compressed() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+
+    save::set_compression_level(Some(3));
+    let resolution1 = construct("S_2", Some(tempdir.path().into())).unwrap();
+    resolution1.compute_through_bidegree(Bidegree::s_t(6, 20));
+
+    let mut dir = tempdir.path().to_owned();
+    dir.push("differentials");
+    assert!(dir.join("2_2_differential.zst").exists());
+    assert!(!dir.join("2_2_differential").exists());
+
+    // Reading compressed files does not depend on the compression level.
+    save::set_compression_level(None);
+    let resolution2 = construct("S_2", Some(tempdir.path().into())).unwrap();
+    resolution2.compute_through_bidegree(Bidegree::s_t(6, 20));
+
+    assert_eq!(
+        resolution1.graded_dimension_string(),
+        resolution2.graded_dimension_string()
+    );
+    assert!(!dir.join("2_2_differential").exists());
+}
@@ -3,13 +3,16 @@ use std::{
     sync::Arc,
 };
 
-use algebra::module::homomorphism::ModuleHomomorphism;
+use algebra::module::{Module, homomorphism::ModuleHomomorphism};
 use ext::{
-    chain_complex::{ChainComplex, FreeChainComplex},
-    save::{SaveDirectory, SaveKind},
+    chain_complex::{AugmentedChainComplex, ChainComplex, FreeChainComplex},
+    resolution::Resolution,
+    resolution_homomorphism::ResolutionHomomorphism,
+    save::{self, SaveDirectory, SaveKind},
     secondary::{SecondaryLift, SecondaryResolution},
-    utils::construct_standard,
+    utils::{Config, construct_nassau, construct_standard},
 };
+use fp::vector::FpVector;
 use sseq::coordinates::Bidegree;
 
 fn set_readonly(p: &Path, readonly: bool) {
@@ -100,7 +103,45 @@ fn test_save_load() {
 }
 
 #[test]
-#[should_panic(expected = "Invalid header: algebra was 0x20000 but expected 0x28000")]
+fn test_save_load_nassau_odd_prime() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution1 = construct_nassau("C3", Some(tempdir.path().into())).unwrap();
+    resolution1.compute_through_stem(Bidegree::n_s(40, 6));
+
+    let resolution2 = construct_nassau("C3", Some(tempdir.path().into())).unwrap();
+    resolution2.compute_through_stem(Bidegree::n_s(40, 6));
+
+    assert_eq!(
+        resolution1.graded_dimension_string(),
+        resolution2.graded_dimension_string()
+    );
+
+    // Lift the differentials of basis elements using the saved quasi-inverses
+    let p = resolution2.prime();
+    let b = Bidegree::s_t(3, 40);
+    let d = resolution2.differential(b.s());
+    let source_dim = resolution2.module(b.s()).dimension(b.t());
+    let target_dim = resolution2.module(b.s() - 1).dimension(b.t());
+
+    let inputs: Vec<FpVector> = (0..source_dim)
+        .map(|i| {
+            let mut v = FpVector::new(p, target_dim);
+            d.apply_to_basis_element(v.as_slice_mut(), 2, b.t(), i);
+            v
+        })
+        .collect();
+    let mut results = vec![FpVector::new(p, source_dim); source_dim];
+    assert!(resolution2.apply_quasi_inverse(&mut results, b, &inputs));
+
+    for (input, result) in inputs.iter().zip(&results) {
+        let mut dx = FpVector::new(p, target_dim);
+        d.apply(dx.as_slice_mut(), 1, b.t(), result.as_slice());
+        assert_eq!(&dx, input);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Saved data is over the algebra with magic 0x20000 but expected 0x28000")]
 fn wrong_algebra() {
     let tempdir = tempfile::TempDir::new().unwrap();
     let resolution1 =
@@ -187,10 +228,13 @@ fn test_save_load_split() {
     .unwrap();
     resolution.compute_through_stem(Bidegree::n_s(14, 8));
 
+    // The manifest is always written, but no save files should be.
     let contains_only_dirs = |p: &Path| {
         p.read_dir().unwrap().all(|dir| {
             let dir = dir.unwrap();
-            dir.file_type().unwrap().is_dir() && dir.path().read_dir().unwrap().next().is_none()
+            dir.file_name() == save::MANIFEST_NAME
+                || (dir.file_type().unwrap().is_dir()
+                    && dir.path().read_dir().unwrap().next().is_none())
         })
     };
 
@@ -304,3 +348,340 @@ fn test_checksum() {
         .unwrap()
         .compute_through_bidegree(Bidegree::s_t(2, 2));
 }
+
+#[test]
+fn test_save_load_archive() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let archive = tempdir.path().join("S_2.archive");
+
+    let mut resolution1 = construct_standard::<false, _, _>("S_2", Some(archive.clone())).unwrap();
+    resolution1.load_quasi_inverse = false;
+    resolution1.compute_through_stem(Bidegree::n_s(15, 8));
+
+    let lift1 = SecondaryResolution::new(Arc::new(resolution1));
+    lift1.initialize_homotopies();
+    lift1.compute_composites();
+    lift1.compute_homotopies();
+
+    // Everything is stored in the archive
+    let entries: Vec<_> = tempdir.path().read_dir().unwrap().collect();
+    assert_eq!(entries.len(), 1);
+    assert!(archive.is_file());
+    let len = archive.metadata().unwrap().len();
+
+    let mut resolution2 = construct_standard::<false, _, _>("S_2", Some(archive.clone())).unwrap();
+    resolution2.load_quasi_inverse = false;
+    resolution2.compute_through_stem(Bidegree::n_s(15, 8));
+
+    let lift2 = SecondaryResolution::new(Arc::new(resolution2));
+    lift2.initialize_homotopies();
+    lift2.compute_composites();
+    lift2.compute_homotopies();
+
+    // Check that we are not writing anything new.
+    assert_eq!(archive.metadata().unwrap().len(), len);
+
+    assert_eq!(
+        lift1.underlying().graded_dimension_string(),
+        lift2.underlying().graded_dimension_string()
+    );
+    assert_eq!(lift2.homotopy(3).homotopies.hom_k(16), vec![vec![1]]);
+}
+
+#[test]
+fn test_save_load_archive_nassau() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let archive = tempdir.path().join("C3.archive");
+
+    let resolution1 = construct_nassau("C3", Some(archive.clone())).unwrap();
+    resolution1.compute_through_stem(Bidegree::n_s(40, 6));
+    let len = archive.metadata().unwrap().len();
+
+    let resolution2 = construct_nassau("C3", Some(archive.clone())).unwrap();
+    resolution2.compute_through_stem(Bidegree::n_s(40, 6));
+    assert_eq!(archive.metadata().unwrap().len(), len);
+
+    assert_eq!(
+        resolution1.graded_dimension_string(),
+        resolution2.graded_dimension_string()
+    );
+}
+
+#[test]
+fn test_archive_incomplete_record() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let archive = tempdir.path().join("S_2.archive");
+
+    let resolution1 = construct_standard::<false, _, _>("S_2", Some(archive.clone())).unwrap();
+    resolution1.compute_through_bidegree(Bidegree::s_t(10, 10));
+
+    // Simulate a write that was interrupted halfway. Since the archive is opened once per
+    // process, we make a copy to force it to be reopened.
+    let copy = tempdir.path().join("S_2_copy.archive");
+    std::fs::copy(&archive, &copy).unwrap();
+    let file = std::fs::OpenOptions::new().write(true).open(&copy).unwrap();
+    let len = file.metadata().unwrap().len();
+    file.set_len(len - 5).unwrap();
+
+    let resolution2 = construct_standard::<false, _, _>("S_2", Some(copy)).unwrap();
+    resolution2.compute_through_bidegree(Bidegree::s_t(10, 10));
+
+    assert_eq!(
+        resolution1.graded_dimension_string(),
+        resolution2.graded_dimension_string()
+    );
+}
+
+#[test]
+fn test_inspect_repair() {
+    use std::{
+        fs::OpenOptions,
+        io::{Seek, SeekFrom, Write},
+    };
+
+    use ext::save::{FileStatus, inspect};
+
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution1 =
+        construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    resolution1.compute_through_bidegree(Bidegree::s_t(6, 20));
+
+    let report = inspect(tempdir.path(), None).unwrap();
+    assert!(
+        report
+            .files
+            .iter()
+            .all(|f| f.status == FileStatus::Complete)
+    );
+    assert!(report.missing().is_empty());
+
+    let mut dir = tempdir.path().to_owned();
+    dir.push("differentials");
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(dir.join("2_2_differential"))
+        .unwrap();
+    file.seek(SeekFrom::Start(41)).unwrap();
+    file.write_all(&[1]).unwrap();
+    drop(file);
+    std::fs::remove_file(dir.join("3_7_differential")).unwrap();
+    std::fs::write(dir.join("notes.txt"), "").unwrap();
+
+    let report = inspect(tempdir.path(), None).unwrap();
+    let corrupt: Vec<_> = report.corrupt().map(|f| (f.kind, f.b)).collect();
+    assert_eq!(corrupt, vec![(SaveKind::Differential, Bidegree::s_t(2, 2))]);
+    assert_eq!(
+        report.missing()[&(String::new(), "differential")],
+        vec![Bidegree::s_t(3, 7)]
+    );
+    assert_eq!(report.unrecognized, vec![dir.join("notes.txt")]);
+
+    assert_eq!(report.delete_corrupt().unwrap(), 1);
+    assert_eq!(inspect(tempdir.path(), None).unwrap().corrupt().count(), 0);
+
+    // The deleted files are recomputed
+    let resolution2 =
+        construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    resolution2.compute_through_bidegree(Bidegree::s_t(6, 20));
+    assert_eq!(
+        resolution1.graded_dimension_string(),
+        resolution2.graded_dimension_string()
+    );
+
+    let report = inspect(tempdir.path(), None).unwrap();
+    assert_eq!(report.corrupt().count(), 0);
+    assert!(report.missing().is_empty());
+}
+
+#[test]
+fn test_save_lock() {
+    use ext::save::{LockOwner, SaveFile, inspect};
+
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+
+    let file = SaveFile {
+        kind: SaveKind::Differential,
+        algebra: resolution.algebra(),
+        b: Bidegree::s_t(2, 6),
+        idx: None,
+    };
+    let dir = tempdir.path().to_owned();
+    let lock = file.try_lock(dir.clone()).unwrap().unwrap();
+    assert!(file.try_lock(dir.clone()).unwrap().is_none());
+    assert_eq!(
+        file.lock_owner(dir.clone()),
+        (
+            dir.join("differentials/2_6_differential.lock"),
+            Some(LockOwner::current())
+        )
+    );
+
+    let report = inspect(tempdir.path(), None).unwrap();
+    assert_eq!(
+        report.locks,
+        vec![dir.join("differentials/2_6_differential.lock")]
+    );
+    assert!(report.unrecognized.is_empty());
+
+    drop(lock);
+    assert!(inspect(tempdir.path(), None).unwrap().locks.is_empty());
+    assert!(file.try_lock(dir).unwrap().is_some());
+}
+
+#[test]
+fn test_inspect_archive() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let archive = tempdir.path().join("S_2.archive");
+    let resolution = construct_standard::<false, _, _>("S_2", Some(archive.clone())).unwrap();
+    resolution.compute_through_bidegree(Bidegree::s_t(6, 20));
+
+    let report = ext::save::inspect(&archive, None).unwrap();
+    assert_eq!(report.corrupt().count(), 0);
+    assert_eq!(
+        report
+            .files
+            .iter()
+            .filter(|f| f.kind == SaveKind::Differential)
+            .count(),
+        7 * 21
+    );
+}
+
+#[test]
+fn test_manifest_mismatch() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+
+    let manifest = save::read_manifest(tempdir.path()).unwrap().unwrap();
+    assert_eq!(manifest.format_version, save::SAVE_FORMAT_VERSION);
+    assert_eq!(manifest.prime, 2);
+    assert_eq!(manifest.algebra.as_deref(), Some("milnor"));
+
+    // Key order does not affect the fingerprint
+    let json: serde_json::Value = serde_json::from_str(
+        r#"{"type": "finite dimensional module", "gens": {"x0": 0}, "p": 2, "actions": []}"#,
+    )
+    .unwrap();
+    construct_standard::<false, _, _>((json, "milnor"), Some(tempdir.path().into())).unwrap();
+
+    let Err(err) = construct_standard::<false, _, _>("C2", Some(tempdir.path().into())) else {
+        panic!("Expected an error");
+    };
+    let err = format!("{err:#}");
+    assert!(err.contains("belongs to a different computation"), "{err}");
+    assert!(err.contains("Saved data is for the module"), "{err}");
+}
+
+#[test]
+fn test_manifest_migrate() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution =
+        Arc::new(construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap());
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+    let _ = ResolutionHomomorphism::from_class(
+        "h0".to_string(),
+        Arc::clone(&resolution),
+        resolution,
+        Bidegree::s_t(1, 1),
+        &[1],
+    );
+
+    // Simulate a save directory written by an older version
+    std::fs::remove_file(tempdir.path().join(save::MANIFEST_NAME)).unwrap();
+    std::fs::remove_file(tempdir.path().join("products/h0").join(save::MANIFEST_NAME)).unwrap();
+
+    let Err(err) = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())) else {
+        panic!("Expected an error");
+    };
+    assert!(format!("{err:#}").contains("no manifest"), "{err:#}");
+
+    let manifest = Config::try_from("S_2")
+        .unwrap()
+        .save_manifest(false)
+        .unwrap();
+    assert_eq!(save::migrate(tempdir.path(), &manifest).unwrap(), 0);
+    assert!(
+        save::read_manifest(&tempdir.path().join("products/h0"))
+            .unwrap()
+            .is_some()
+    );
+
+    let resolution = construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap();
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+    assert_eq!(save::migrate(tempdir.path(), &manifest).unwrap(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Saved data is for the product")]
+fn test_manifest_product() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution =
+        Arc::new(construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap());
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+
+    let _ = ResolutionHomomorphism::from_class(
+        "h0".to_string(),
+        Arc::clone(&resolution),
+        Arc::clone(&resolution),
+        Bidegree::s_t(1, 1),
+        &[1],
+    );
+    let _ = ResolutionHomomorphism::from_class(
+        "h0".to_string(),
+        Arc::clone(&resolution),
+        Arc::clone(&resolution),
+        Bidegree::s_t(1, 2),
+        &[1],
+    );
+}
+
+#[test]
+fn test_manifest_product_error() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let resolution =
+        Arc::new(construct_standard::<false, _, _>("S_2", Some(tempdir.path().into())).unwrap());
+    resolution.compute_through_bidegree(Bidegree::s_t(2, 5));
+
+    let h0 = |t| {
+        ResolutionHomomorphism::try_from_class(
+            "h0".to_string(),
+            Arc::clone(&resolution),
+            Arc::clone(&resolution),
+            Bidegree::s_t(1, t),
+            &[1],
+        )
+    };
+    h0(1).unwrap();
+    let Err(err) = h0(2) else {
+        panic!("Expected an error");
+    };
+    assert!(
+        format!("{err:#}").contains("Saved data is for the product"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn test_manifest_profile() {
+    let tempdir = tempfile::TempDir::new().unwrap();
+    let complex = |spec| {
+        construct_standard::<false, _, _>(spec, None)
+            .unwrap()
+            .target()
+    };
+
+    Resolution::new_with_save(complex("S_2@A(1)"), Some(tempdir.path().into())).unwrap();
+    Resolution::new_with_save(complex("S_2@A(1)"), Some(tempdir.path().into())).unwrap();
+    let Err(err) = Resolution::new_with_save(complex("S_2@A(2)"), Some(tempdir.path().into()))
+    else {
+        panic!("Expected an error");
+    };
+    assert!(
+        format!("{err:#}").contains("Saved data is for the profile"),
+        "{err:#}"
+    );
+}
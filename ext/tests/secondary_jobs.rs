@@ -0,0 +1,133 @@
+use std::{path::Path, sync::Arc};
+
+use algebra::module::Module;
+use ext::{
+    chain_complex::{ChainComplex, FreeChainComplex},
+    ext_algebra::{ExtAlgebra, SecondaryExtAlgebra},
+    secondary::{SecondaryJob, SecondaryLift, Shard, ShardPhase},
+    utils::{QueryModuleResolution, construct},
+};
+use sseq::coordinates::Bidegree;
+
+const MAX: Bidegree = Bidegree::n_s(20, 6);
+
+fn secondary(save_dir: Option<&Path>) -> SecondaryExtAlgebra<QueryModuleResolution> {
+    let resolution = construct("S_2@milnor", save_dir.map(Into::into)).unwrap();
+    resolution.compute_through_stem(MAX);
+    SecondaryExtAlgebra::new(Arc::new(ExtAlgebra::without_unit(Arc::new(resolution))))
+}
+
+/// All non-zero $d_2$ differentials, in the format of the `secondary` example.
+fn d2s(sec_e2: &SecondaryExtAlgebra<QueryModuleResolution>) -> Vec<String> {
+    sec_e2.extend_all();
+    let e2 = sec_e2.ext_algebra();
+    let d2_shift = Bidegree::n_s(-1, 2);
+
+    let mut result = Vec::new();
+    for b in e2.resolution().iter_nonzero_stem() {
+        if b.s() < 3 || b.t() - 1 > e2.resolution().module(b.s() - 2).max_computed_degree() {
+            continue;
+        }
+        for g in e2.basis(b - d2_shift) {
+            if let Some(dx) = sec_e2.d2(&e2.generator(g)) {
+                result.push(format!("d_2 x_{g} = {dx}"));
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn test_shard_parse() {
+    let shard: Shard = "composites:1/3".parse().unwrap();
+    assert_eq!(
+        shard,
+        Shard {
+            phase: ShardPhase::Composites,
+            index: 1,
+            count: 3
+        }
+    );
+    assert_eq!(shard.to_string(), "composites:1/3");
+    let jobs = (0..5)
+        .map(|t| SecondaryJob::Composite(Bidegree::s_t(2, t)))
+        .chain((0..5).map(|t| SecondaryJob::Intermediate(Bidegree::s_t(3, t))));
+    assert_eq!(
+        shard.select(jobs.clone()).collect::<Vec<_>>(),
+        vec![
+            SecondaryJob::Composite(Bidegree::s_t(2, 1)),
+            SecondaryJob::Composite(Bidegree::s_t(2, 4))
+        ]
+    );
+    let shard: Shard = "intermediates:0/3".parse().unwrap();
+    assert_eq!(
+        shard.select(jobs).collect::<Vec<_>>(),
+        vec![
+            SecondaryJob::Intermediate(Bidegree::s_t(3, 0)),
+            SecondaryJob::Intermediate(Bidegree::s_t(3, 3))
+        ]
+    );
+
+    assert!("composites:3/3".parse::<Shard>().is_err());
+    assert!("1/3".parse::<Shard>().is_err());
+    assert!("homotopies:1/3".parse::<Shard>().is_err());
+    assert!("composites:a/3".parse::<Shard>().is_err());
+}
+
+#[test]
+fn test_shard_and_merge() {
+    let main_dir = tempfile::TempDir::new().unwrap();
+    let sec_e2 = secondary(Some(main_dir.path()));
+    let lift = sec_e2.resolution_lift();
+    let jobs = lift.jobs();
+    assert!(!jobs.is_empty());
+    assert!(jobs.iter().all(|&job| !lift.is_job_done(job)));
+
+    // Run each shard in its own save directory, as if on separate machines. The composites are
+    // merged into the main save directory, and from there into the directories of the shards of
+    // the second phase.
+    let run_shards = |phase: ShardPhase| -> Vec<tempfile::TempDir> {
+        (0..2)
+            .map(|index| {
+                let dir = tempfile::TempDir::new().unwrap();
+                let shard_e2 = secondary(Some(dir.path()));
+                for &job in &jobs {
+                    if phase == ShardPhase::Intermediates && job.phase() == ShardPhase::Composites {
+                        shard_e2
+                            .resolution_lift()
+                            .merge_job(job, main_dir.path())
+                            .unwrap();
+                    }
+                }
+                shard_e2
+                    .run_shard(Shard {
+                        phase,
+                        index,
+                        count: 2,
+                    })
+                    .unwrap();
+                dir
+            })
+            .collect()
+    };
+
+    // The intermediates need the composites
+    let intermediate = jobs
+        .iter()
+        .find(|job| job.phase() == ShardPhase::Intermediates)
+        .unwrap();
+    assert!(lift.run_job(*intermediate).is_err());
+
+    for phase in [ShardPhase::Composites, ShardPhase::Intermediates] {
+        for dir in &run_shards(phase) {
+            for &job in &jobs {
+                lift.merge_job(job, dir.path()).unwrap();
+            }
+        }
+    }
+    assert!(jobs.iter().all(|&job| lift.is_job_done(job)));
+
+    let d2s_merged = d2s(&sec_e2);
+    assert!(!d2s_merged.is_empty());
+    assert_eq!(d2s_merged, d2s(&secondary(None)));
+}
@@ -0,0 +1,90 @@
+use ext::{chain_complex::FreeChainComplex, utils::construct};
+use serde_json::{Value, json};
+use sseq::coordinates::Bidegree;
+
+fn ext(json: Value, algebra: &str, max: Bidegree) -> String {
+    let res = construct((json, algebra), None).unwrap();
+    res.compute_through_stem(max);
+    res.graded_dimension_string()
+}
+
+/// $MO(1) = \Sigma^{-1} \mathbb{RP}^\infty$, but the Thom class is in degree 1.
+#[test]
+fn mo1() {
+    let max = Bidegree::n_s(20, 8);
+    let mo1 = json!({"p": 2, "type": "thom spectrum", "group": "O", "n": 1});
+    let rp = json!({"p": 2, "type": "real projective space", "min": 1});
+    assert_eq!(ext(mo1, "milnor", max), ext(rp, "milnor", max));
+}
+
+/// $MU(1) = \Sigma^{-2} \mathbb{CP}^\infty$, but the Thom class is in degree 2.
+#[test]
+fn mu1() {
+    for p in [2, 3] {
+        let max = Bidegree::n_s(30, 6);
+        let mu1 = json!({"p": p, "type": "thom spectrum", "group": "U", "n": 1});
+        let cp = json!({"p": p, "type": "complex projective space", "min": 2});
+        assert_eq!(ext(mu1, "adem", max), ext(cp, "milnor", max));
+    }
+}
+
+#[test]
+fn cp_inf() {
+    let max = Bidegree::n_s(30, 6);
+    let cp = json!({"p": 2, "type": "complex projective space", "min": 2});
+    let polynomial = json!({
+        "p": 2,
+        "type": "polynomial algebra",
+        "polynomial": {"y": 2},
+        "actions": ["Sq2 y = y^2"],
+        "reduced": true,
+    });
+    assert_eq!(ext(cp, "adem", max), ext(polynomial, "milnor", max));
+}
+
+/// $L_1^\infty = B\mathbb{Z}/3$.
+#[test]
+fn lens_space() {
+    let max = Bidegree::n_s(40, 6);
+    let lens = json!({"p": 3, "type": "lens space", "min": 1});
+    let em = json!({"p": 3, "type": "eilenberg maclane", "n": 1, "max": 50, "reduced": true});
+    assert_eq!(ext(lens, "adem", max), ext(em, "milnor", max));
+}
+
+#[test]
+fn milnor_vs_adem() {
+    let max = Bidegree::n_s(30, 6);
+    for json in [
+        json!({"p": 3, "type": "lens space", "min": -7, "max": 30}),
+        json!({"p": 5, "type": "complex projective space", "min": -8}),
+        json!({"p": 2, "type": "thom spectrum", "group": "O", "n": 3}),
+        json!({"p": 3, "type": "thom spectrum", "group": "U", "n": 2}),
+    ] {
+        assert_eq!(ext(json.clone(), "adem", max), ext(json, "milnor", max));
+    }
+}
+
+#[test]
+fn invalid() {
+    for json in [
+        json!({"p": 2, "type": "lens space", "min": 1}),
+        json!({"p": 2, "type": "complex projective space", "min": 1}),
+        json!({"p": 3, "type": "thom spectrum", "group": "O", "n": 1}),
+        json!({"p": 2, "type": "thom spectrum", "group": "Sp", "n": 1}),
+    ] {
+        assert!(construct((json, "milnor"), None).is_err());
+    }
+    let json = json!({"p": 2, "type": "thom spectrum", "group": "U", "n": 1});
+    assert!(construct((json, "c-motivic"), None).is_err());
+    let json = json!({
+        "p": 3,
+        "type": "lens space",
+        "min": 1,
+        "algebra": {
+            "name": "E(1)",
+            "generators": {"Q0": 1, "Q1": 5},
+            "relations": ["Q0^2", "Q1^2", "Q0 Q1 + Q1 Q0"],
+        },
+    });
+    assert!(construct(json, None).is_err());
+}
@@ -73,7 +73,7 @@ impl Resolution<ext::CCC> {
         sender: crate::Sender,
     ) -> anyhow::Result<Self> {
         let inner = Arc::new(
-            ext::utils::construct((json.clone(), algebra_name), None)
+            ext::utils::construct_standard::<false, _, _>((json.clone(), algebra_name), None)
                 .context("Failed to construct resolution from module json")?,
         );
         let algebra = inner.algebra();
@@ -359,7 +359,7 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
             self.add_differential_propagate(r, source, product_index + 1);
         }
 
-        let product = self.products.values().nth(product_index).unwrap();
+        let (name, product) = self.products.iter().nth(product_index).unwrap();
         let target = if product.permanent {
             None
         } else if let Some((_, true, target_name)) = &product.differential {
@@ -369,7 +369,9 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
         };
 
         // Separate this to new line to make code easier to read.
-        let new_d = self.inner.leibniz(r, source, &product.inner, target);
+        let new_d = self
+            .inner
+            .leibniz_named(r, source, Some(name), &product.inner, target);
 
         if let Some((r, source)) = new_d {
             self.add_differential_propagate(r, &source, product_index);
@@ -437,8 +439,13 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
         for r in self.inner.differentials(b).range() {
             let pairs = self.inner.differentials(b)[r].get_source_target_pairs();
             for (source, _) in pairs {
-                self.inner
-                    .leibniz(r, &BidegreeElement::new(b, source), &product.inner, target);
+                self.inner.leibniz_named(
+                    r,
+                    &BidegreeElement::new(b, source),
+                    Some(name),
+                    &product.inner,
+                    target,
+                );
             }
         }
 
@@ -449,9 +456,10 @@ impl<P: SseqProfile<2>> SseqWrapper<P> {
             .map(FpSlice::to_owned)
             .collect::<Vec<_>>();
         for class in permanent_classes {
-            self.inner.leibniz(
+            self.inner.leibniz_named(
                 i32::MAX,
                 &BidegreeElement::new(b, class),
+                Some(name),
                 &product.inner,
                 target,
             );